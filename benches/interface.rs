@@ -0,0 +1,120 @@
+use std::io::{sink, Sink};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tty_interface::{pos, Color, Interface, Position, Vector, WriterDevice};
+
+/// The terminal size of the frame benchmarks.
+const FRAME_WIDTH: u16 = 200;
+const FRAME_HEIGHT: u16 = 60;
+
+/// A line of 100 box-drawing graphemes.
+const BORDER: &str = "────────────────────────────────────────────────────────────────────────────────────────────────────";
+
+/// A device discarding everything written to it.
+fn device(size: Vector) -> WriterDevice<Sink> {
+    WriterDevice::new(sink(), size)
+}
+
+/// Stage a line of the grapheme across each of the interface's lines.
+fn fill(interface: &mut Interface, grapheme: &str) {
+    let size = interface.size();
+    let line = grapheme.repeat(size.x().into());
+    for y in 0..size.y() {
+        interface.set(pos!(0, y), &line);
+    }
+}
+
+fn staging(c: &mut Criterion) {
+    let mut device = device(Vector::new(100, 100));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    c.bench_function("stage 10k cells", |b| {
+        b.iter(|| fill(&mut interface, black_box("a")));
+    });
+
+    c.bench_function("stage 10k styled cells", |b| {
+        let style = Color::Red.as_style().set_bold(true);
+        let line = "a".repeat(100);
+        b.iter(|| {
+            for y in 0..100 {
+                interface.set_styled(pos!(0, y), black_box(&line), style);
+            }
+        });
+    });
+
+    c.bench_function("stage 10k static cells", |b| {
+        b.iter(|| {
+            for y in 0..100 {
+                interface.set_static(pos!(0, y), black_box(BORDER), None);
+            }
+        });
+    });
+}
+
+fn applying(c: &mut Criterion) {
+    let mut device = device(Vector::new(FRAME_WIDTH, FRAME_HEIGHT));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    c.bench_function("apply full 200x60 frame", |b| {
+        let mut graphemes = ["a", "b"].into_iter().cycle();
+        b.iter_batched(
+            || graphemes.next().unwrap(),
+            |grapheme| {
+                fill(&mut interface, grapheme);
+                interface.apply().unwrap();
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    // 1% of the frame's 12,000 cells, spread over its lines
+    let diff_positions: Vec<Position> = (0..120)
+        .map(|index| pos!((index * 37) % FRAME_WIDTH, (index * 7) % FRAME_HEIGHT))
+        .collect();
+
+    fill(&mut interface, "a");
+    interface.apply().unwrap();
+
+    c.bench_function("apply 1% diff of 200x60 frame", |b| {
+        let mut graphemes = ["b", "a"].into_iter().cycle();
+        b.iter_batched(
+            || graphemes.next().unwrap(),
+            |grapheme| {
+                for position in &diff_positions {
+                    interface.set(*position, grapheme);
+                }
+                interface.apply().unwrap();
+            },
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+fn unicode(c: &mut Criterion) {
+    let mut device = device(Vector::new(FRAME_WIDTH, FRAME_HEIGHT));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    // Wide graphemes, emoji sequences, combining marks, and right-to-left text
+    let texts = [
+        "日本語のテキスト 🇯🇵 👩‍👩‍👧 e\u{301}a\u{308} שלום עולם ".repeat(4),
+        "中文文本 🎉🎉 नमस्ते दुनिया مرحبا بالعالم ".repeat(4),
+    ];
+
+    c.bench_function("stage and apply unicode-heavy frame", |b| {
+        let mut texts = texts.iter().cycle();
+        b.iter_batched(
+            || texts.next().unwrap(),
+            |text| {
+                for y in 0..FRAME_HEIGHT {
+                    interface.clear_line(y);
+                    interface.set(pos!(0, y), text);
+                }
+                interface.apply().unwrap();
+            },
+            BatchSize::SmallInput,
+        );
+    });
+}
+
+criterion_group!(benches, staging, applying, unicode);
+criterion_main!(benches);
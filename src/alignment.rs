@@ -0,0 +1,55 @@
+/// Horizontal placement of text within the available columns.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(from_py_object, eq, eq_int, module = "tty_interface")
+)]
+pub enum Alignment {
+    /// Placed against the leftmost column.
+    Left,
+    /// Placed with equal space on either side, favoring the left if uneven.
+    Center,
+    /// Placed against the rightmost column.
+    Right,
+}
+
+impl Alignment {
+    /// The column offset at which content of the specified width is placed within the available
+    /// width. Content wider than the available width is placed at the leftmost column.
+    pub(crate) fn get_offset(self, content_width: u16, available_width: u16) -> u16 {
+        let remaining_width = available_width.saturating_sub(content_width);
+        match self {
+            Alignment::Left => 0,
+            Alignment::Center => remaining_width / 2,
+            Alignment::Right => remaining_width,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Alignment;
+
+    #[test]
+    fn alignment_offset() {
+        let cases = [
+            (Alignment::Left, 4, 10, 0),
+            (Alignment::Center, 4, 10, 3),
+            (Alignment::Center, 5, 10, 2),
+            (Alignment::Right, 4, 10, 6),
+            (Alignment::Center, 12, 10, 0),
+            (Alignment::Right, 12, 10, 0),
+        ];
+
+        for (alignment, content_width, available_width, expected) in cases {
+            assert_eq!(
+                expected,
+                alignment.get_offset(content_width, available_width),
+                "{:?} {} in {}",
+                alignment,
+                content_width,
+                available_width
+            );
+        }
+    }
+}
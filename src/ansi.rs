@@ -0,0 +1,295 @@
+//! A terminal device emitting ANSI escape sequences directly to file descriptors, with raw mode
+//! and size queries through termios. Enabled by the `ansi` feature, it's an alternative to the
+//! crossterm-backed [std::io::Stdout] device for builds without the default `crossterm` feature.
+
+use std::{
+    fs::File,
+    io::{Read, Write},
+    mem::ManuallyDrop,
+    os::{raw::c_int, unix::io::FromRawFd},
+};
+
+use crate::{
+    environment, pos, CommandEncoder, CursorQuery, EventSource, Position, RawModeControl, Result,
+    SizedTarget, Vector,
+};
+
+/// How long to wait for each byte of a cursor position report.
+const CURSOR_REPORT_TIMEOUT_MS: c_int = 2000;
+
+/// A terminal device writing ANSI escape sequences to a file descriptor and reading cursor
+/// position reports from another, which may be the same. The file descriptors remain owned by
+/// the caller.
+///
+/// # Examples
+/// ```no_run
+/// # use tty_interface::Error;
+/// use tty_interface::{ansi::AnsiTerminal, Interface, Position, pos};
+///
+/// let mut device = AnsiTerminal::new();
+/// let mut interface = Interface::new_relative(&mut device)?;
+/// interface.set(pos!(0, 0), "Hello, terminal!");
+/// interface.apply()?;
+/// interface.exit()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct AnsiTerminal {
+    input_fd: c_int,
+    input: ManuallyDrop<File>,
+    output_fd: c_int,
+    output: ManuallyDrop<File>,
+    original_termios: Option<libc::termios>,
+}
+
+impl AnsiTerminal {
+    /// Create a device writing to the process's standard output and reading from its standard
+    /// input.
+    pub fn new() -> AnsiTerminal {
+        unsafe { AnsiTerminal::from_raw_fds(libc::STDIN_FILENO, libc::STDOUT_FILENO) }
+    }
+
+    /// Create a device both writing to and reading from the specified terminal file descriptor.
+    ///
+    /// # Safety
+    /// `fd` must be a valid, open file descriptor for a terminal for the device's lifetime.
+    pub unsafe fn from_raw_fd(fd: c_int) -> AnsiTerminal {
+        AnsiTerminal::from_raw_fds(fd, fd)
+    }
+
+    /// Create a device writing to the output file descriptor and reading from the input.
+    ///
+    /// # Safety
+    /// Both file descriptors must be valid and open for the device's lifetime, and the input must
+    /// be a terminal.
+    pub unsafe fn from_raw_fds(input_fd: c_int, output_fd: c_int) -> AnsiTerminal {
+        AnsiTerminal {
+            input_fd,
+            input: ManuallyDrop::new(File::from_raw_fd(input_fd)),
+            output_fd,
+            output: ManuallyDrop::new(File::from_raw_fd(output_fd)),
+            original_termios: None,
+        }
+    }
+}
+
+impl Default for AnsiTerminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SizedTarget for AnsiTerminal {
+    fn get_terminal_size(&mut self) -> Result<Vector> {
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(self.output_fd, libc::TIOCGWINSZ, &mut size) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok(Vector::new(size.ws_col, size.ws_row))
+    }
+}
+
+impl RawModeControl for AnsiTerminal {
+    fn enable_raw_mode(&mut self) -> Result<()> {
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(self.input_fd, &mut termios) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        let original_termios = termios;
+        unsafe { libc::cfmakeraw(&mut termios) };
+        if unsafe { libc::tcsetattr(self.input_fd, libc::TCSANOW, &termios) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        self.original_termios.get_or_insert(original_termios);
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> Result<()> {
+        if let Some(termios) = self.original_termios.take() {
+            if unsafe { libc::tcsetattr(self.input_fd, libc::TCSANOW, &termios) } != 0 {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_terminal(&self) -> bool {
+        let is_tty = unsafe { libc::isatty(self.output_fd) == 1 };
+        is_tty && !environment::is_dumb_terminal()
+    }
+}
+
+impl CommandEncoder for AnsiTerminal {
+    fn styling_preference(&self) -> Option<bool> {
+        environment::styling_preference()
+    }
+}
+
+impl EventSource for AnsiTerminal {}
+
+impl CursorQuery for AnsiTerminal {
+    fn get_cursor_position(&mut self) -> Result<Position> {
+        self.output.write_all(b"\x1B[6n")?;
+        self.output.flush()?;
+
+        // Read the "ESC [ line ; column R" report, waiting briefly for each byte
+        let mut report = Vec::new();
+        let mut byte = [0; 1];
+        while !report.ends_with(b"R") {
+            let mut poll_fd = libc::pollfd {
+                fd: self.input_fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            if unsafe { libc::poll(&mut poll_fd, 1, CURSOR_REPORT_TIMEOUT_MS) } <= 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::TimedOut).into());
+            }
+
+            self.input.read_exact(&mut byte)?;
+            report.push(byte[0]);
+        }
+
+        parse_cursor_report(&report)
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidData).into())
+    }
+}
+
+impl Write for AnsiTerminal {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Parse a terminal's "ESC [ line ; column R" cursor position report into a zero-based position.
+fn parse_cursor_report(report: &[u8]) -> Option<Position> {
+    let report = std::str::from_utf8(report).ok()?;
+    let start = report.rfind("\x1B[")?;
+    let (line, column) = report[start + 2..].strip_suffix('R')?.split_once(';')?;
+
+    let line: u16 = line.parse().ok()?;
+    let column: u16 = column.parse().ok()?;
+
+    Some(pos!(column.checked_sub(1)?, line.checked_sub(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, os::unix::io::FromRawFd, ptr, thread};
+
+    use super::*;
+    use crate::{test::VirtualDevice, Color, Interface, Style};
+
+    /// Open a pseudo-terminal, returning its controller and the fd of its terminal end.
+    fn open_pty() -> (File, c_int) {
+        let (mut controller, mut terminal) = (0, 0);
+        let size = libc::winsize {
+            ws_row: 24,
+            ws_col: 80,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        let result = unsafe {
+            libc::openpty(
+                &mut controller,
+                &mut terminal,
+                ptr::null_mut(),
+                ptr::null(),
+                &size,
+            )
+        };
+        assert_eq!(0, result);
+
+        (unsafe { File::from_raw_fd(controller) }, terminal)
+    }
+
+    #[test]
+    fn ansi_styles_render_to_virtual_device() {
+        let mut device = VirtualDevice::new();
+        let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+        let style = Style::new()
+            .set_foreground(Color::Blue)
+            .set_background(Color::DarkYellow)
+            .set_bold(true)
+            .set_underline(true);
+        interface.set_styled(pos!(2, 1), "ab", style);
+        interface.set(pos!(4, 1), "c");
+        interface.apply().unwrap();
+
+        let screen = device.parser().screen();
+        assert_eq!("\n  abc", &screen.contents());
+
+        let styled = screen.cell(1, 2).unwrap();
+        assert_eq!(vt100::Color::Idx(12), styled.fgcolor());
+        assert_eq!(vt100::Color::Idx(3), styled.bgcolor());
+        assert!(styled.bold() && styled.underline() && !styled.italic());
+
+        let plain = screen.cell(1, 4).unwrap();
+        assert_eq!(vt100::Color::Default, plain.fgcolor());
+        assert!(!plain.bold() && !plain.underline());
+    }
+
+    #[test]
+    fn ansi_relative_moves_render_to_virtual_device() {
+        let mut device = VirtualDevice::new();
+        let mut interface = Interface::new_relative(&mut device).unwrap();
+        interface.set(pos!(0, 0), "first");
+        interface.set(pos!(0, 2), "third");
+        interface.apply().unwrap();
+        interface.set(pos!(1, 0), "F");
+        interface.set_cursor(Some(pos!(3, 1)));
+        interface.apply().unwrap();
+
+        let screen = device.parser().screen();
+        assert_eq!("fFrst\n\nthird", &screen.contents());
+        assert_eq!((1, 3), screen.cursor_position());
+        assert!(!screen.hide_cursor());
+    }
+
+    #[test]
+    fn ansi_terminal_over_pty() {
+        let (mut controller, terminal) = open_pty();
+        let mut device = unsafe { AnsiTerminal::from_raw_fd(terminal) };
+
+        assert_eq!(Vector::new(80, 24), device.get_terminal_size().unwrap());
+
+        // Answer the cursor position query as a terminal would
+        let mut responder = controller.try_clone().unwrap();
+        let response = thread::spawn(move || {
+            let mut query = [0; 4];
+            responder.read_exact(&mut query).unwrap();
+            responder.write_all(b"\x1B[5;3R").unwrap();
+            query
+        });
+
+        device.enable_raw_mode().unwrap();
+        assert_eq!(pos!(2, 4), device.get_cursor_position().unwrap());
+        assert_eq!(b"\x1B[6n", &response.join().unwrap());
+        device.disable_raw_mode().unwrap();
+
+        device.write_all(b"done").unwrap();
+        device.flush().unwrap();
+        unsafe { libc::close(terminal) };
+
+        let mut output = Vec::new();
+        let _ = controller.read_to_end(&mut output);
+        assert_eq!(b"done", output.as_slice());
+    }
+
+    #[test]
+    fn ansi_parse_cursor_report() {
+        assert_eq!(Some(pos!(4, 2)), parse_cursor_report(b"\x1B[3;5R"));
+        assert_eq!(Some(pos!(0, 0)), parse_cursor_report(b"junk\x1B[1;1R"));
+        assert_eq!(None, parse_cursor_report(b"\x1B[0;1R"));
+        assert_eq!(None, parse_cursor_report(b"\x1B[3R"));
+    }
+}
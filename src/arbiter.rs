@@ -0,0 +1,372 @@
+use std::{cell::RefCell, io::Write, ops::Range, rc::Rc, time::Duration};
+
+#[cfg(feature = "images")]
+use crate::ImageProtocol;
+use crate::{
+    cursor_position_or, pos, CommandEncoder, CursorQuery, Device, DeviceCommand, DeviceHandle,
+    Event, EventSource, Position, RawModeControl, Result, SizedTarget, Vector,
+};
+
+/// The shared device and what each claimant has done with it.
+struct Arbitration<'a> {
+    device: DeviceHandle<'a>,
+    /// A mirror of the terminal's screen tracking where the device's output leaves its cursor.
+    parser: vt100::Parser,
+    claims: Vec<Claim>,
+    next_id: usize,
+    last_writer: Option<usize>,
+    raw_mode_claimants: usize,
+    alternate_screen_claimants: usize,
+}
+
+/// A claimant's rows and terminal configuration.
+struct Claim {
+    id: usize,
+    rows: Range<u16>,
+    cursor: Position,
+    is_raw: bool,
+    is_alternate: bool,
+}
+
+impl Arbitration<'_> {
+    fn claim(&mut self, id: usize) -> &mut Claim {
+        self.claims
+            .iter_mut()
+            .find(|claim| claim.id == id)
+            .expect("claims live as long as their devices")
+    }
+}
+
+/// Shares one device between several interfaces, such as a pinned status area and a transient
+/// prompt, each claiming a band of the screen's rows. Each claim is a device of its own whose
+/// interface sees a terminal as tall as its rows, positioned at their top. Writes are serialized
+/// and the cursor is returned to where each interface left it before it writes again. Scrolling is
+/// confined to the writing claimant's rows, so relative interfaces and output printed above them
+/// scroll within their claim without disturbing the others.
+///
+/// The alternate screen is entered once the first claimant enters it and left once the last
+/// leaves it. Raw mode is shared likewise.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{DeviceArbiter, Interface, Position, pos};
+///
+/// let arbiter = DeviceArbiter::new(&mut device)?;
+/// let mut content_device = arbiter.claim(0..22).unwrap();
+/// let mut footer_device = arbiter.claim(22..24).unwrap();
+///
+/// let mut content = Interface::new_alternate(&mut content_device)?;
+/// let mut footer = Interface::new_alternate(&mut footer_device)?;
+///
+/// content.set(pos!(0, 0), "Building...");
+/// content.apply()?;
+///
+/// // Displayed on the screen's second-to-last line
+/// footer.set(pos!(0, 0), "Press q to quit");
+/// footer.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct DeviceArbiter<'a> {
+    arbitration: Rc<RefCell<Arbitration<'a>>>,
+}
+
+impl<'a> DeviceArbiter<'a> {
+    /// Create an arbiter sharing the specified device.
+    pub fn new(device: &'a mut dyn Device) -> Result<DeviceArbiter<'a>> {
+        DeviceArbiter::initialize(DeviceHandle::Borrowed(device))
+    }
+
+    /// Create an arbiter which takes ownership of the specified device to share it.
+    pub fn new_owned(device: Box<dyn Device>) -> Result<DeviceArbiter<'static>> {
+        DeviceArbiter::initialize(DeviceHandle::Owned(device))
+    }
+
+    fn initialize(mut device: DeviceHandle) -> Result<DeviceArbiter> {
+        let size = device.get_terminal_size()?;
+        let cursor = cursor_position_or(&mut *device, pos!(0, 0))?;
+
+        let mut parser = vt100::Parser::new(size.y(), size.x(), 0);
+        parser.process(format!("\x1B[{};{}H", cursor.y() + 1, cursor.x() + 1).as_bytes());
+
+        let arbitration = Arbitration {
+            device,
+            parser,
+            claims: Vec::new(),
+            next_id: 0,
+            last_writer: None,
+            raw_mode_claimants: 0,
+            alternate_screen_claimants: 0,
+        };
+
+        Ok(DeviceArbiter {
+            arbitration: Rc::new(RefCell::new(arbitration)),
+        })
+    }
+
+    /// Claim the specified rows of the screen, returning a device for an interface displayed in
+    /// them. Returns `None` if the rows are empty or overlap another claim. The rows are released
+    /// when the device is dropped.
+    pub fn claim(&self, rows: Range<u16>) -> Option<ArbitratedDevice<'a>> {
+        let mut arbitration = self.arbitration.borrow_mut();
+        let overlaps = |claim: &Claim| claim.rows.start < rows.end && rows.start < claim.rows.end;
+        if rows.is_empty() || arbitration.claims.iter().any(overlaps) {
+            return None;
+        }
+
+        let id = arbitration.next_id;
+        arbitration.next_id += 1;
+        arbitration.claims.push(Claim {
+            id,
+            rows: rows.clone(),
+            cursor: pos!(0, rows.start),
+            is_raw: false,
+            is_alternate: false,
+        });
+
+        Some(ArbitratedDevice {
+            arbitration: self.arbitration.clone(),
+            id,
+            rows,
+        })
+    }
+
+    /// The rows currently claimed, in the order they were claimed.
+    pub fn claimed_rows(&self) -> Vec<Range<u16>> {
+        let arbitration = self.arbitration.borrow();
+        arbitration
+            .claims
+            .iter()
+            .map(|claim| claim.rows.clone())
+            .collect()
+    }
+}
+
+/// A device for the rows of a screen claimed from a [DeviceArbiter]. Its terminal is as wide as
+/// the screen and as tall as its rows, and commands are translated to display within them.
+pub struct ArbitratedDevice<'a> {
+    arbitration: Rc<RefCell<Arbitration<'a>>>,
+    id: usize,
+    rows: Range<u16>,
+}
+
+impl ArbitratedDevice<'_> {
+    /// The screen rows this device displays.
+    pub fn rows(&self) -> Range<u16> {
+        self.rows.clone()
+    }
+}
+
+impl Drop for ArbitratedDevice<'_> {
+    /// Release the claim, restoring the terminal's configuration if it was the last claimant of
+    /// raw mode or the alternate screen, such as when its interface was dropped without exiting.
+    fn drop(&mut self) {
+        let mut arbitration = self.arbitration.borrow_mut();
+        let arbitration = &mut *arbitration;
+        let claim = arbitration.claim(self.id);
+        let (is_raw, is_alternate) = (claim.is_raw, claim.is_alternate);
+
+        if is_alternate {
+            arbitration.alternate_screen_claimants -= 1;
+            if arbitration.alternate_screen_claimants == 0 {
+                let mut buffer = Vec::new();
+                let command = DeviceCommand::LeaveAlternateScreen;
+                if arbitration.device.encode(&command, &mut buffer).is_ok() {
+                    let _ = arbitration.device.write_all(&buffer);
+                    let _ = arbitration.device.flush();
+                    arbitration.parser.process(&buffer);
+                }
+            }
+        }
+
+        if is_raw {
+            arbitration.raw_mode_claimants -= 1;
+            if arbitration.raw_mode_claimants == 0 {
+                let _ = arbitration.device.disable_raw_mode();
+            }
+        }
+
+        arbitration.claims.retain(|claim| claim.id != self.id);
+        if arbitration.last_writer == Some(self.id) {
+            arbitration.last_writer = None;
+
+            // Scrolling was confined to this claim's rows
+            let (rows, _) = arbitration.parser.screen().size();
+            let (row, column) = arbitration.parser.screen().cursor_position();
+            let commands = [
+                DeviceCommand::SetScrollRegion(0, rows.saturating_sub(1)),
+                DeviceCommand::MoveTo(pos!(column, row)),
+            ];
+
+            let mut buffer = Vec::new();
+            if commands
+                .iter()
+                .all(|command| arbitration.device.encode(command, &mut buffer).is_ok())
+            {
+                let _ = arbitration.device.write_all(&buffer);
+                let _ = arbitration.device.flush();
+                arbitration.parser.process(&buffer);
+            }
+        }
+    }
+}
+
+impl SizedTarget for ArbitratedDevice<'_> {
+    fn get_terminal_size(&mut self) -> Result<Vector> {
+        let size = self.arbitration.borrow_mut().device.get_terminal_size()?;
+        let end = self.rows.end.min(size.y());
+        Ok(Vector::new(size.x(), end.saturating_sub(self.rows.start)))
+    }
+}
+
+impl RawModeControl for ArbitratedDevice<'_> {
+    fn enable_raw_mode(&mut self) -> Result<()> {
+        let mut arbitration = self.arbitration.borrow_mut();
+        if arbitration.claim(self.id).is_raw {
+            return Ok(());
+        }
+
+        if arbitration.raw_mode_claimants == 0 {
+            arbitration.device.enable_raw_mode()?;
+        }
+
+        arbitration.raw_mode_claimants += 1;
+        arbitration.claim(self.id).is_raw = true;
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> Result<()> {
+        let mut arbitration = self.arbitration.borrow_mut();
+        if !arbitration.claim(self.id).is_raw {
+            return Ok(());
+        }
+
+        if arbitration.raw_mode_claimants == 1 {
+            arbitration.device.disable_raw_mode()?;
+        }
+
+        arbitration.raw_mode_claimants -= 1;
+        arbitration.claim(self.id).is_raw = false;
+        Ok(())
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.arbitration.borrow().device.is_terminal()
+    }
+}
+
+impl CursorQuery for ArbitratedDevice<'_> {
+    fn get_cursor_position(&mut self) -> Result<Position> {
+        let mut arbitration = self.arbitration.borrow_mut();
+        let cursor = arbitration.claim(self.id).cursor;
+        Ok(pos!(cursor.x(), cursor.y().saturating_sub(self.rows.start)))
+    }
+}
+
+impl CommandEncoder for ArbitratedDevice<'_> {
+    fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+        let mut arbitration = self.arbitration.borrow_mut();
+        let arbitration = &mut *arbitration;
+        match *command {
+            DeviceCommand::MoveTo(position) => {
+                let position = pos!(position.x(), position.y() + self.rows.start);
+                arbitration
+                    .device
+                    .encode(&DeviceCommand::MoveTo(position), buffer)?;
+            }
+            // Only this device's rows are cleared
+            DeviceCommand::ClearAll => {
+                for row in self.rows.clone() {
+                    let commands = [
+                        DeviceCommand::MoveTo(pos!(0, row)),
+                        DeviceCommand::ClearToEndOfLine,
+                    ];
+                    for command in commands {
+                        arbitration.device.encode(&command, buffer)?;
+                    }
+                }
+            }
+            // The screen is switched for the first claimant entering it and the last leaving it
+            DeviceCommand::EnterAlternateScreen => {
+                let claim = arbitration.claim(self.id);
+                if !claim.is_alternate {
+                    claim.is_alternate = true;
+                    arbitration.alternate_screen_claimants += 1;
+                    if arbitration.alternate_screen_claimants == 1 {
+                        arbitration.device.encode(command, buffer)?;
+                    }
+                }
+            }
+            DeviceCommand::LeaveAlternateScreen => {
+                let claim = arbitration.claim(self.id);
+                if claim.is_alternate {
+                    claim.is_alternate = false;
+                    arbitration.alternate_screen_claimants -= 1;
+                    if arbitration.alternate_screen_claimants == 0 {
+                        arbitration.device.encode(command, buffer)?;
+                    }
+                }
+            }
+            _ => arbitration.device.encode(command, buffer)?,
+        }
+
+        Ok(())
+    }
+
+    fn styling_preference(&self) -> Option<bool> {
+        self.arbitration.borrow().device.styling_preference()
+    }
+
+    #[cfg(feature = "images")]
+    fn image_protocol(&self) -> ImageProtocol {
+        self.arbitration.borrow().device.image_protocol()
+    }
+}
+
+impl EventSource for ArbitratedDevice<'_> {
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        self.arbitration.borrow_mut().device.read_event(timeout)
+    }
+}
+
+impl Write for ArbitratedDevice<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut arbitration = self.arbitration.borrow_mut();
+        let arbitration = &mut *arbitration;
+
+        // Another claimant may have moved the cursor and scroll region since this one last wrote
+        if arbitration.last_writer != Some(self.id) {
+            let (rows, _) = arbitration.parser.screen().size();
+            let last = self.rows.end.min(rows).max(self.rows.start + 1) - 1;
+            let cursor = arbitration.claim(self.id).cursor;
+            let commands = [
+                DeviceCommand::SetScrollRegion(self.rows.start, last),
+                DeviceCommand::MoveTo(cursor),
+            ];
+
+            let mut restore = Vec::new();
+            for command in commands {
+                arbitration
+                    .device
+                    .encode(&command, &mut restore)
+                    .map_err(|error| std::io::Error::other(error.to_string()))?;
+            }
+            arbitration.device.write_all(&restore)?;
+            arbitration.parser.process(&restore);
+            arbitration.last_writer = Some(self.id);
+        }
+
+        arbitration.device.write_all(buf)?;
+        arbitration.parser.process(buf);
+
+        let (row, column) = arbitration.parser.screen().cursor_position();
+        arbitration.claim(self.id).cursor = pos!(column, row);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.arbitration.borrow_mut().device.flush()
+    }
+}
@@ -0,0 +1,59 @@
+use unicode_bidi::ParagraphBidiInfo;
+
+use crate::text::split_graphemes;
+
+/// Splits the specified text into graphemes in visual order, reversing right-to-left runs per the
+/// Unicode Bidirectional Algorithm. Graphemes are kept intact so combining marks are not detached.
+pub(crate) fn get_visual_graphemes(text: &str) -> Vec<&str> {
+    let bidi_info = ParagraphBidiInfo::new(text, None);
+    if !bidi_info.has_rtl() {
+        return split_graphemes(text).collect();
+    }
+
+    let (levels, runs) = bidi_info.visual_runs(0..text.len());
+
+    let mut graphemes = Vec::new();
+    for run in runs {
+        let run_graphemes = split_graphemes(&text[run.clone()]);
+        if levels[run.start].is_rtl() {
+            graphemes.extend(run_graphemes.rev());
+        } else {
+            graphemes.extend(run_graphemes);
+        }
+    }
+
+    graphemes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_visual_graphemes;
+
+    #[test]
+    fn bidi_left_to_right_unchanged() {
+        assert_eq!(vec!["a", "b", "c"], get_visual_graphemes("abc"));
+    }
+
+    #[test]
+    fn bidi_right_to_left_reversed() {
+        assert_eq!(vec!["ג", "ב", "א"], get_visual_graphemes("אבג"));
+    }
+
+    #[test]
+    fn bidi_mixed_direction() {
+        let visual: String = get_visual_graphemes("abc אבג def").concat();
+        assert_eq!("abc גבא def", visual);
+    }
+
+    #[test]
+    fn bidi_right_to_left_paragraph_with_numbers() {
+        let visual: String = get_visual_graphemes("אב 12").concat();
+        assert_eq!("12 בא", visual);
+    }
+
+    #[test]
+    fn bidi_keeps_combining_marks_attached() {
+        let visual = get_visual_graphemes("שָׁלוֹם");
+        assert_eq!(vec!["ם", "וֹ", "ל", "שָׁ"], visual);
+    }
+}
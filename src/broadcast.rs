@@ -0,0 +1,172 @@
+use crate::{pos, Grid, GridChange, Interface, Position, Rect, Result, Style, Vector};
+
+/// An interface receiving a broadcaster's frames.
+struct Target<'a> {
+    interface: Interface<'a>,
+    /// The size the frame was last clipped to for this interface, if it's been rendered.
+    clip: Option<Vector>,
+}
+
+/// Applies the same frames to several interfaces, such as mirroring a session to an observer's
+/// terminal or a recording device as it's displayed. Each interface diffs the frames against what
+/// its own device displays, so devices may join late, fall behind or differ in size, with each
+/// frame clipped to its interface's size.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// # let mut observer_device = VirtualDevice::new();
+/// use tty_interface::{Broadcaster, Interface, Position, pos};
+///
+/// let mut broadcaster = Broadcaster::new();
+/// broadcaster.add(Interface::new_alternate(&mut device)?);
+/// broadcaster.add(Interface::new_alternate(&mut observer_device)?);
+///
+/// broadcaster.set(pos!(0, 0), "Displayed on both devices");
+/// broadcaster.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Broadcaster<'a> {
+    frame: Grid,
+    targets: Vec<Target<'a>>,
+}
+
+impl<'a> Broadcaster<'a> {
+    /// Create a broadcaster without any interfaces.
+    pub fn new() -> Broadcaster<'a> {
+        Broadcaster {
+            frame: Grid::new(),
+            targets: Vec::new(),
+        }
+    }
+
+    /// Add an interface to receive frames, returning its index. The whole frame is rendered to it
+    /// when next applied.
+    pub fn add(&mut self, interface: Interface<'a>) -> usize {
+        self.targets.push(Target {
+            interface,
+            clip: None,
+        });
+
+        self.targets.len() - 1
+    }
+
+    /// Stop broadcasting to the interface at the specified index, returning it so it may be
+    /// exited. Later interfaces' indices shift down by one.
+    pub fn remove(&mut self, index: usize) -> Option<Interface<'a>> {
+        if index >= self.targets.len() {
+            return None;
+        }
+
+        Some(self.targets.remove(index).interface)
+    }
+
+    /// The number of interfaces receiving frames.
+    pub fn interface_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Access the interface at the specified index, such as to read its events.
+    pub fn interface_mut(&mut self, index: usize) -> Option<&mut Interface<'a>> {
+        self.targets
+            .get_mut(index)
+            .map(|target| &mut target.interface)
+    }
+
+    /// Update the frame's text at the specified position, as with [Interface::set].
+    pub fn set(&mut self, position: Position, text: &str) -> Rect {
+        self.frame.set(position, text)
+    }
+
+    /// Update the frame's styled text at the specified position, as with
+    /// [Interface::set_styled].
+    pub fn set_styled(&mut self, position: Position, text: &str, style: Style) -> Rect {
+        self.frame.set_styled(position, text, style)
+    }
+
+    /// Clear the specified line of the frame.
+    pub fn clear_line(&mut self, line: u16) {
+        self.frame.clear_line(line);
+    }
+
+    /// Clear the frame's cells within the specified area.
+    pub fn clear_rect(&mut self, rect: Rect) {
+        self.frame.clear_rect(rect);
+    }
+
+    /// Apply the frame's changes to each interface. Every interface is updated even if another
+    /// fails, such as an observer disconnecting, in which case the first error is returned.
+    pub fn apply(&mut self) -> Result<()> {
+        let mut result = Ok(());
+        for target in &mut self.targets {
+            let size = target.interface.size();
+            if target.clip == Some(size) {
+                for change in self.frame.changes() {
+                    match change {
+                        GridChange::Set(position, grapheme, style) => {
+                            set_clipped(&mut target.interface, size, position, grapheme, style)
+                        }
+                        GridChange::Cleared(position) => {
+                            if is_within(size, position) {
+                                let cell = Rect::new(position, Vector::new(1, 1));
+                                target.interface.clear_rect(cell);
+                            }
+                        }
+                    }
+                }
+            } else {
+                // Interfaces which are new or resized are given the whole frame
+                target.interface.clear_rest_of_interface(pos!(0, 0));
+                for (position, grapheme, style) in self.frame.cells() {
+                    set_clipped(&mut target.interface, size, position, grapheme, style);
+                }
+                target.clip = Some(size);
+            }
+
+            let applied = target.interface.apply();
+            if result.is_ok() {
+                result = applied;
+            }
+        }
+
+        self.frame.clear_changes();
+        result
+    }
+}
+
+impl Default for Broadcaster<'_> {
+    fn default() -> Self {
+        Broadcaster::new()
+    }
+}
+
+/// Whether the position is within an interface of the specified size.
+fn is_within(size: Vector, position: Position) -> bool {
+    position.x() < size.x() && position.y() < size.y()
+}
+
+/// Set the grapheme at the position in the interface if it's within the interface's size. A wide
+/// grapheme cut by the interface's last column is replaced with a space rather than wrapping.
+fn set_clipped(
+    interface: &mut Interface,
+    size: Vector,
+    position: Position,
+    grapheme: &str,
+    style: Option<Style>,
+) {
+    if !is_within(size, position) {
+        return;
+    }
+
+    let width = interface.widths().grapheme_width(grapheme);
+    let grapheme = match position.x() as u32 + width as u32 > size.x() as u32 {
+        true => " ",
+        false => grapheme,
+    };
+
+    match style {
+        Some(style) => interface.set_styled(position, grapheme, style),
+        None => interface.set(position, grapheme),
+    };
+}
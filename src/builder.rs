@@ -0,0 +1,331 @@
+use std::time::Duration;
+
+use crate::{
+    interface::DEFAULT_REPAINT_THRESHOLD, AmbiguousWidth, Clock, CommandEncoder, CursorMode,
+    Device, DeviceHandle, Interface, Mode, Overflow, RenderStrategy, Result, Style, Theme,
+};
+
+/// Configures and creates an interface, as an alternative to the individual constructors and
+/// setters. Interfaces render on the alternate screen unless configured otherwise.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Color, Interface, Overflow};
+///
+/// let interface = Interface::builder()
+///     .relative()
+///     .default_style(Color::Green.as_style())
+///     .overflow(Overflow::Clip)
+///     .mouse(true)
+///     .build(&mut device)?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct InterfaceBuilder {
+    mode: Mode,
+    default_style: Option<Style>,
+    styling: Option<bool>,
+    assume_terminal: bool,
+    theme: Theme,
+    overflow: Overflow,
+    mouse: bool,
+    cursor_mode: CursorMode,
+    ambiguous_width: AmbiguousWidth,
+    width_calibration: bool,
+    tab_width: Option<u16>,
+    bidi: bool,
+    byte_budget: Option<usize>,
+    repaint_threshold: Option<f32>,
+    auto_apply: Option<Duration>,
+    history_limit: usize,
+    clock: Option<Box<dyn Clock>>,
+    encoder: Option<Box<dyn CommandEncoder>>,
+    render_strategy: RenderStrategy,
+    #[cfg(all(unix, feature = "signals"))]
+    signals: bool,
+    #[cfg(all(unix, feature = "signals"))]
+    termination: bool,
+}
+
+impl InterfaceBuilder {
+    /// Create a builder for an interface with the default configuration.
+    pub fn new() -> InterfaceBuilder {
+        InterfaceBuilder {
+            mode: Mode::Alternate,
+            default_style: None,
+            styling: None,
+            assume_terminal: false,
+            theme: Theme::new(),
+            overflow: Overflow::Wrap,
+            mouse: false,
+            cursor_mode: CursorMode::Managed,
+            ambiguous_width: AmbiguousWidth::Narrow,
+            width_calibration: false,
+            tab_width: None,
+            bidi: true,
+            byte_budget: None,
+            repaint_threshold: Some(DEFAULT_REPAINT_THRESHOLD),
+            auto_apply: None,
+            history_limit: 0,
+            clock: None,
+            encoder: None,
+            render_strategy: RenderStrategy::Minimal,
+            #[cfg(all(unix, feature = "signals"))]
+            signals: false,
+            #[cfg(all(unix, feature = "signals"))]
+            termination: false,
+        }
+    }
+
+    /// Render the interface on the terminal's alternate screen.
+    pub fn alternate(mut self) -> InterfaceBuilder {
+        self.mode = Mode::Alternate;
+        self
+    }
+
+    /// Render the interface relative to the cursor's position in the terminal's buffer.
+    pub fn relative(mut self) -> InterfaceBuilder {
+        self.mode = Mode::Relative;
+        self
+    }
+
+    /// Print the interface's changed lines without cursor control, e.g. if the user requested
+    /// plain output. Interfaces for devices which aren't terminals are printed this way
+    /// regardless of their mode.
+    pub fn line_printer(mut self) -> InterfaceBuilder {
+        self.mode = Mode::LinePrinter;
+        self
+    }
+
+    /// Render the interface in the specified mode.
+    pub fn mode(mut self, mode: Mode) -> InterfaceBuilder {
+        self.mode = mode;
+        self
+    }
+
+    /// Render the interface in its configured mode even if the device doesn't report being a
+    /// terminal, e.g. `TERM=dumb`, rather than printing its lines.
+    pub fn assume_terminal(mut self, assume: bool) -> InterfaceBuilder {
+        self.assume_terminal = assume;
+        self
+    }
+
+    /// Print text with or without its styles regardless of the device's preference, such as the
+    /// user's `NO_COLOR` setting. See [Interface::set_styling_enabled].
+    pub fn styling(mut self, enabled: bool) -> InterfaceBuilder {
+        self.styling = Some(enabled);
+        self
+    }
+
+    /// Apply a style to text staged without one. See [Interface::set_default_style].
+    pub fn default_style(mut self, style: Style) -> InterfaceBuilder {
+        self.default_style = Some(style);
+        self
+    }
+
+    /// Style named text with the theme. See [Interface::set_theme].
+    pub fn theme(mut self, theme: Theme) -> InterfaceBuilder {
+        self.theme = theme;
+        self
+    }
+
+    /// Handle text extending beyond the last column as specified. See [Interface::set_overflow].
+    pub fn overflow(mut self, overflow: Overflow) -> InterfaceBuilder {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Translate applied changes into output as specified. See [Interface::set_render_strategy].
+    pub fn render_strategy(mut self, strategy: RenderStrategy) -> InterfaceBuilder {
+        self.render_strategy = strategy;
+        self
+    }
+
+    /// Capture mouse events from the terminal while the interface is active.
+    pub fn mouse(mut self, capture: bool) -> InterfaceBuilder {
+        self.mouse = capture;
+        self
+    }
+
+    /// Control the cursor's visibility as specified. See [Interface::set_cursor_mode].
+    pub fn cursor_mode(mut self, mode: CursorMode) -> InterfaceBuilder {
+        self.cursor_mode = mode;
+        self
+    }
+
+    /// Measure East Asian ambiguous-width characters as specified, probing the terminal for
+    /// [AmbiguousWidth::Auto]. See [Interface::set_ambiguous_width].
+    pub fn ambiguous_width(mut self, ambiguous_width: AmbiguousWidth) -> InterfaceBuilder {
+        self.ambiguous_width = ambiguous_width;
+        self
+    }
+
+    /// Measure graphemes from the terminal the first time they're rendered. See
+    /// [Interface::set_width_calibration].
+    pub fn width_calibration(mut self, enabled: bool) -> InterfaceBuilder {
+        self.width_calibration = enabled;
+        self
+    }
+
+    /// Expand tabs to the specified stops. See [Interface::set_tab_width].
+    pub fn tab_width(mut self, tab_width: u16) -> InterfaceBuilder {
+        self.tab_width = Some(tab_width);
+        self
+    }
+
+    /// Reorder bidirectional text for display. See [Interface::set_bidi_enabled].
+    pub fn bidi(mut self, enabled: bool) -> InterfaceBuilder {
+        self.bidi = enabled;
+        self
+    }
+
+    /// Limit the bytes written by each apply. See [Interface::set_byte_budget].
+    pub fn byte_budget(mut self, budget: usize) -> InterfaceBuilder {
+        self.byte_budget = Some(budget);
+        self
+    }
+
+    /// Repaint the interface when the specified fraction of cells change, or never if unspecified.
+    /// See [Interface::set_repaint_threshold].
+    pub fn repaint_threshold(mut self, threshold: Option<f32>) -> InterfaceBuilder {
+        self.repaint_threshold = threshold;
+        self
+    }
+
+    /// Apply staged changes automatically once they settle. See [Interface::set_auto_apply].
+    pub fn auto_apply(mut self, debounce: Duration) -> InterfaceBuilder {
+        self.auto_apply = Some(debounce);
+        self
+    }
+
+    /// Remember applied frames for undoing. See [Interface::set_history_limit].
+    pub fn history_limit(mut self, limit: usize) -> InterfaceBuilder {
+        self.history_limit = limit;
+        self
+    }
+
+    /// Measure time using the specified clock. See [Interface::set_clock].
+    pub fn clock(mut self, clock: Box<dyn Clock>) -> InterfaceBuilder {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Encode the interface's terminal commands with the specified encoder rather than the
+    /// device's, e.g. a [crate::terminfo::TerminfoEncoder] for terminals which don't support the
+    /// default ANSI sequences.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{encode_ansi, CommandEncoder, DeviceCommand, Interface, Result};
+    ///
+    /// /// Encodes commands without showing or hiding the cursor.
+    /// struct FixedCursorEncoder;
+    ///
+    /// impl CommandEncoder for FixedCursorEncoder {
+    ///     fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+    ///         match command {
+    ///             DeviceCommand::HideCursor | DeviceCommand::ShowCursor => Ok(()),
+    ///             command => encode_ansi(command, buffer),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let interface = Interface::builder()
+    ///     .encoder(Box::new(FixedCursorEncoder))
+    ///     .build(&mut device)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn encoder(mut self, encoder: Box<dyn CommandEncoder>) -> InterfaceBuilder {
+        self.encoder = Some(encoder);
+        self
+    }
+
+    /// Create the configured interface for the specified device.
+    pub fn build(self, device: &mut dyn Device) -> Result<Interface<'_>> {
+        self.initialize(DeviceHandle::Borrowed(device))
+    }
+
+    /// Create the configured interface, taking ownership of the specified device.
+    pub fn build_owned(self, device: Box<dyn Device>) -> Result<Interface<'static>> {
+        self.initialize(DeviceHandle::Owned(device))
+    }
+
+    /// Handle the signals affecting terminal applications while the interface is active: resizes
+    /// are reported as [crate::Event::Resize], and suspending with Ctrl+Z restores the terminal
+    /// until the process is continued and reports [crate::Event::Resume]. Termination signals
+    /// keep their usual behavior unless also handled, see [InterfaceBuilder::handle_termination].
+    /// Every interface handling signals is reported each one received, and the process's previous
+    /// handlers are restored once the last of them is dropped. Requires the `signals` feature.
+    #[cfg(all(unix, feature = "signals"))]
+    pub fn handle_signals(mut self, handle: bool) -> InterfaceBuilder {
+        self.signals = handle;
+        self
+    }
+
+    /// Intercept SIGTERM, SIGINT, and SIGHUP while the interface is active, reporting them as
+    /// [crate::Event::Terminate] so the application may exit cleanly, and handle the other
+    /// signals as with [InterfaceBuilder::handle_signals].
+    ///
+    /// While intercepted, these signals no longer terminate the process: they're only reported
+    /// once the application reads or polls for events, and the process continues until it exits
+    /// in response. Applications which may go long without reading events, or which don't exit
+    /// on [crate::Event::Terminate], shouldn't intercept them. Requires the `signals` feature.
+    #[cfg(all(unix, feature = "signals"))]
+    pub fn handle_termination(mut self, handle: bool) -> InterfaceBuilder {
+        self.termination = handle;
+        self.signals |= handle;
+        self
+    }
+
+    /// Create the configured interface for the specified device handle.
+    fn initialize(self, device: DeviceHandle) -> Result<Interface> {
+        let mut interface =
+            Interface::initialize_mode(device, self.encoder, self.mode, self.assume_terminal)?;
+
+        interface.set_default_style(self.default_style);
+        if let Some(styling) = self.styling {
+            interface.set_styling_enabled(styling);
+        }
+        interface.set_theme(self.theme);
+        interface.set_overflow(self.overflow);
+        interface.set_render_strategy(self.render_strategy);
+        interface.set_bidi_enabled(self.bidi);
+        interface.set_byte_budget(self.byte_budget);
+        interface.set_repaint_threshold(self.repaint_threshold);
+        interface.set_auto_apply(self.auto_apply);
+        interface.set_history_limit(self.history_limit);
+
+        if let Some(tab_width) = self.tab_width {
+            interface.set_tab_width(tab_width);
+        }
+
+        if let Some(clock) = self.clock {
+            interface.set_clock(clock);
+        }
+
+        if self.mouse {
+            interface.enable_mouse_capture()?;
+        }
+
+        interface.set_cursor_mode(self.cursor_mode)?;
+        interface.set_ambiguous_width(self.ambiguous_width)?;
+        interface.set_width_calibration(self.width_calibration);
+
+        #[cfg(all(unix, feature = "signals"))]
+        if self.signals {
+            let signals = crate::signals::Signals::install(self.termination)?;
+            interface.handle_signals(signals);
+        }
+
+        Ok(interface)
+    }
+}
+
+impl Default for InterfaceBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
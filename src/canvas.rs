@@ -0,0 +1,251 @@
+use crate::{pos, Color, Interface, Position, Style, Vector};
+
+/// The characters a canvas draws its pixels with.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum CanvasMarker {
+    /// Braille patterns, each cell showing two columns and four rows of dots.
+    #[default]
+    Braille,
+    /// Half-block characters, each cell showing two vertically-stacked pixels.
+    HalfBlock,
+}
+
+impl CanvasMarker {
+    /// The columns and rows of pixels each cell shows.
+    fn resolution(self) -> (u16, u16) {
+        match self {
+            CanvasMarker::Braille => (2, 4),
+            CanvasMarker::HalfBlock => (1, 2),
+        }
+    }
+}
+
+/// A grid of colored pixels drawn with braille or half-block characters into a rectangle of
+/// cells, such as for sparklines and scatter plots. Pixels are addressed from the top-left, and
+/// those outside the canvas are ignored.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Canvas, Color, Interface, Position, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// // A 40x16 pixel plot in 20x4 cells of braille
+/// let mut canvas = Canvas::new(Vector::new(20, 4));
+/// canvas.line((0, 15), (39, 0), Color::Green);
+/// canvas.point(20, 12, Color::Red);
+///
+/// canvas.render(&mut interface, pos!(0, 0));
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    size: Vector,
+    marker: CanvasMarker,
+    pixels: Vec<Option<Color>>,
+}
+
+impl Canvas {
+    /// Create a blank braille canvas occupying the specified columns and lines.
+    pub fn new(size: Vector) -> Canvas {
+        Canvas::with_marker(size, CanvasMarker::Braille)
+    }
+
+    /// Create a blank canvas occupying the specified columns and lines, drawn with the marker.
+    pub fn with_marker(size: Vector, marker: CanvasMarker) -> Canvas {
+        let (columns, rows) = marker.resolution();
+        let pixels =
+            vec![None; size.x() as usize * columns as usize * size.y() as usize * rows as usize];
+
+        Canvas {
+            size,
+            marker,
+            pixels,
+        }
+    }
+
+    /// The columns and lines of cells this canvas occupies.
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    /// The characters this canvas draws its pixels with.
+    pub fn marker(&self) -> CanvasMarker {
+        self.marker
+    }
+
+    /// This canvas's width in pixels.
+    pub fn width(&self) -> u32 {
+        self.size.x() as u32 * self.marker.resolution().0 as u32
+    }
+
+    /// This canvas's height in pixels.
+    pub fn height(&self) -> u32 {
+        self.size.y() as u32 * self.marker.resolution().1 as u32
+    }
+
+    /// Set the pixel at the specified column and row to the color.
+    pub fn point(&mut self, x: u32, y: u32, color: Color) {
+        if x < self.width() && y < self.height() {
+            let index = (y * self.width() + x) as usize;
+            self.pixels[index] = Some(color);
+        }
+    }
+
+    /// Set the pixels along the line between the specified columns and rows to the color.
+    pub fn line(&mut self, from: (u32, u32), to: (u32, u32), color: Color) {
+        let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+        let (to_x, to_y) = (to.0 as i64, to.1 as i64);
+
+        let (dx, dy) = ((to_x - x).abs(), -(to_y - y).abs());
+        let (step_x, step_y) = ((to_x - x).signum(), (to_y - y).signum());
+        let mut error = dx + dy;
+
+        loop {
+            self.point(x as u32, y as u32, color);
+            if x == to_x && y == to_y {
+                break;
+            }
+
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Clear all of this canvas's pixels.
+    pub fn clear(&mut self) {
+        self.pixels.fill(None);
+    }
+
+    /// Stage this canvas's cells on the interface with their top-left at the specified position.
+    /// Cells without pixels are staged blank, clearing previous renders. Braille cells take the
+    /// color of their last pixel in row order.
+    pub fn render(&self, interface: &mut Interface, position: Position) {
+        for line in 0..self.size.y() {
+            for column in 0..self.size.x() {
+                let (grapheme, style) = self.cell(column, line);
+                let position = pos!(
+                    position.x().saturating_add(column),
+                    position.y().saturating_add(line)
+                );
+                match style {
+                    Some(style) => interface.set_styled(position, &grapheme, style),
+                    None => interface.set(position, &grapheme),
+                };
+            }
+        }
+    }
+
+    /// The pixel at the specified column and row, if set.
+    fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        self.pixels[(y * self.width() + x) as usize]
+    }
+
+    /// The grapheme and style showing the pixels of the cell at the specified column and line.
+    fn cell(&self, column: u16, line: u16) -> (String, Option<Style>) {
+        let (columns, rows) = self.marker.resolution();
+        let (left, top) = (column as u32 * columns as u32, line as u32 * rows as u32);
+
+        match self.marker {
+            CanvasMarker::Braille => {
+                let mut dots = 0;
+                let mut color = None;
+                for row in 0..4 {
+                    for offset in 0..2 {
+                        if let Some(pixel) = self.pixel(left + offset, top + row) {
+                            dots |= BRAILLE_DOTS[row as usize][offset as usize];
+                            color = Some(pixel);
+                        }
+                    }
+                }
+
+                match color {
+                    Some(color) => {
+                        let grapheme = char::from_u32(0x2800 + dots as u32).unwrap_or(' ');
+                        (grapheme.to_string(), Some(color.as_style()))
+                    }
+                    None => (" ".to_string(), None),
+                }
+            }
+            CanvasMarker::HalfBlock => match (self.pixel(left, top), self.pixel(left, top + 1)) {
+                (None, None) => (" ".to_string(), None),
+                (Some(upper), None) => ("▀".to_string(), Some(upper.as_style())),
+                (None, Some(lower)) => ("▄".to_string(), Some(lower.as_style())),
+                (Some(upper), Some(lower)) => (
+                    "▀".to_string(),
+                    Some(upper.as_style().set_background(lower)),
+                ),
+            },
+        }
+    }
+}
+
+/// The bit of a braille pattern for each dot, by row and column.
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Vector};
+
+    use super::{Canvas, CanvasMarker};
+
+    #[test]
+    fn canvas_braille_cells() {
+        let mut canvas = Canvas::new(Vector::new(2, 1));
+        assert_eq!((4, 4), (canvas.width(), canvas.height()));
+
+        canvas.point(0, 0, Color::Red);
+        canvas.point(1, 3, Color::Blue);
+        canvas.point(4, 0, Color::Green);
+
+        let (grapheme, style) = canvas.cell(0, 0);
+        assert_eq!("⢁", grapheme);
+        assert_eq!(Some(Color::Blue.as_style()), style);
+        assert_eq!((" ".to_string(), None), canvas.cell(1, 0));
+    }
+
+    #[test]
+    fn canvas_half_block_cells() {
+        let mut canvas = Canvas::with_marker(Vector::new(3, 1), CanvasMarker::HalfBlock);
+        assert_eq!((3, 2), (canvas.width(), canvas.height()));
+
+        canvas.point(0, 0, Color::Red);
+        canvas.point(0, 1, Color::Blue);
+        canvas.point(1, 1, Color::Green);
+
+        let red_over_blue = Color::Red.as_style().set_background(Color::Blue);
+        assert_eq!(("▀".to_string(), Some(red_over_blue)), canvas.cell(0, 0));
+        assert_eq!(
+            ("▄".to_string(), Some(Color::Green.as_style())),
+            canvas.cell(1, 0)
+        );
+        assert_eq!((" ".to_string(), None), canvas.cell(2, 0));
+    }
+
+    #[test]
+    fn canvas_line() {
+        let mut canvas = Canvas::with_marker(Vector::new(4, 2), CanvasMarker::HalfBlock);
+        canvas.line((3, 3), (0, 0), Color::Red);
+
+        let set: Vec<bool> = canvas.pixels.iter().map(Option::is_some).collect();
+        for y in 0..4 {
+            for x in 0..4 {
+                assert_eq!(x == y, set[y * 4 + x]);
+            }
+        }
+
+        canvas.clear();
+        canvas.line((0, 1), (3, 2), Color::Red);
+        assert_eq!(4, canvas.pixels.iter().flatten().count());
+    }
+}
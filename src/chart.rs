@@ -0,0 +1,289 @@
+use crate::{pos, Interface, Position, Rect, Style, Vector};
+
+/// Partial blocks filling a cell from the bottom by eighths, indexed by eighths filled.
+const BLOCKS: [&str; 9] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+
+/// A compact line chart of values drawn with block characters, such as for recent samples of a
+/// metric. The values are scaled between their minimum and maximum to the rectangle's height; when
+/// there are more values than columns, the most recent are shown.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Color, Interface, Position, Rect, Sparkline, Style, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let sparkline = Sparkline::new().set_style(Color::Green.as_style()).set_labels(true);
+/// let latency = [12.0, 15.0, 11.0, 30.0, 18.0];
+/// sparkline.render(&mut interface, Rect::new(pos!(0, 0), Vector::new(20, 2)), &latency);
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Sparkline {
+    style: Option<Style>,
+    has_labels: bool,
+}
+
+impl Sparkline {
+    /// Create an unstyled sparkline without labels.
+    pub fn new() -> Sparkline {
+        Sparkline {
+            style: None,
+            has_labels: false,
+        }
+    }
+
+    /// Create a new sparkline with the specified style.
+    pub fn set_style(self, style: Style) -> Sparkline {
+        Sparkline {
+            style: Some(style),
+            ..self
+        }
+    }
+
+    /// Create a new sparkline, labeled with its minimum value left of its last line and its
+    /// maximum value right of its first line if specified.
+    pub fn set_labels(self, has_labels: bool) -> Sparkline {
+        Sparkline { has_labels, ..self }
+    }
+
+    /// Stage the values charted within the rectangle, clearing its cells which aren't drawn.
+    pub fn render(&self, interface: &mut Interface, rect: Rect, values: &[f64]) {
+        let values: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let (min, max) = match values.is_empty() {
+            true => (0.0, 0.0),
+            false => (min, max),
+        };
+
+        let plot = stage_labels(interface, rect, self.has_labels, min, max);
+        let shown = &values[values.len().saturating_sub(plot.size().x() as usize)..];
+
+        // The lowest values are shown as the smallest block rather than omitted
+        let levels: Vec<u32> = shown
+            .iter()
+            .map(|value| level(*value, min, max, plot.size().y()).max(1))
+            .collect();
+        stage_columns(interface, plot, &levels, self.style);
+    }
+}
+
+/// A chart of values drawn as vertical bars of block characters from a baseline of zero, scaled
+/// so the greatest value fills the rectangle's height. Bars which don't fit are omitted.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{BarChart, Color, Interface, Position, Rect, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let chart = BarChart::new().set_bar_width(2).set_gap(1).set_labels(true);
+/// let requests = [4.0, 9.0, 6.0];
+/// chart.render(&mut interface, Rect::new(pos!(0, 0), Vector::new(20, 4)), &requests);
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BarChart {
+    style: Option<Style>,
+    has_labels: bool,
+    bar_width: u16,
+    gap: u16,
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BarChart {
+    /// Create an unstyled bar chart without labels of single-column bars separated by a column.
+    pub fn new() -> BarChart {
+        BarChart {
+            style: None,
+            has_labels: false,
+            bar_width: 1,
+            gap: 1,
+        }
+    }
+
+    /// Create a new bar chart with the specified style.
+    pub fn set_style(self, style: Style) -> BarChart {
+        BarChart {
+            style: Some(style),
+            ..self
+        }
+    }
+
+    /// Create a new bar chart, labeled with zero left of its last line and its maximum value
+    /// right of its first line if specified.
+    pub fn set_labels(self, has_labels: bool) -> BarChart {
+        BarChart { has_labels, ..self }
+    }
+
+    /// Create a new bar chart with bars of the specified number of columns, at least one.
+    pub fn set_bar_width(self, bar_width: u16) -> BarChart {
+        BarChart {
+            bar_width: bar_width.max(1),
+            ..self
+        }
+    }
+
+    /// Create a new bar chart with the specified number of columns between bars.
+    pub fn set_gap(self, gap: u16) -> BarChart {
+        BarChart { gap, ..self }
+    }
+
+    /// Stage the values charted within the rectangle, clearing its cells which aren't drawn.
+    /// Negative values are shown as empty bars.
+    pub fn render(&self, interface: &mut Interface, rect: Rect, values: &[f64]) {
+        let max = values
+            .iter()
+            .copied()
+            .filter(|v| v.is_finite())
+            .fold(0.0, f64::max);
+
+        let plot = stage_labels(interface, rect, self.has_labels, 0.0, max);
+
+        let mut levels = Vec::new();
+        for (index, value) in values.iter().enumerate() {
+            let level = match value.is_finite() {
+                true => level(*value, 0.0, max, plot.size().y()),
+                false => 0,
+            };
+
+            if index > 0 {
+                levels.extend(std::iter::repeat_n(0, self.gap as usize));
+            }
+            levels.extend(std::iter::repeat_n(level, self.bar_width as usize));
+        }
+
+        // Bars which don't fully fit are omitted along with their preceding gap
+        let stride = (self.bar_width + self.gap) as usize;
+        let fitting = (plot.size().x() as usize + self.gap as usize) / stride;
+        levels.truncate((fitting * stride).saturating_sub(self.gap as usize));
+
+        stage_columns(interface, plot, &levels, self.style);
+    }
+}
+
+/// The eighths of the height's cells filled by the value scaled between the minimum and maximum.
+fn level(value: f64, min: f64, max: f64, height: u16) -> u32 {
+    let eighths = height as f64 * 8.0;
+    let fraction = match max > min {
+        true => (value - min) / (max - min),
+        false => 0.0,
+    };
+
+    (fraction.clamp(0.0, 1.0) * eighths).round() as u32
+}
+
+/// Stage the minimum and maximum labels, if specified, returning the rectangle remaining for the
+/// chart between them.
+fn stage_labels(
+    interface: &mut Interface,
+    rect: Rect,
+    has_labels: bool,
+    min: f64,
+    max: f64,
+) -> Rect {
+    if !has_labels || rect.size().y() == 0 {
+        return rect;
+    }
+
+    let (min, max) = (format_value(min), format_value(max));
+    let widths = interface.widths();
+    let (min_width, max_width) = (widths.text_width(&min) + 1, widths.text_width(&max) + 1);
+    if min_width + max_width >= rect.size().x() {
+        return rect;
+    }
+
+    let (origin, size) = (rect.origin(), rect.size());
+    let bottom = origin.y() + size.y().saturating_sub(1);
+
+    // Each label's column is padded, clearing what was previously rendered there
+    let plot_width = size.x() - min_width - max_width;
+    for line in origin.y()..bottom + 1 {
+        let label = if line == bottom { min.as_str() } else { "" };
+        interface.set(
+            pos!(origin.x(), line),
+            &format!("{:>1$} ", label, min_width as usize - 1),
+        );
+
+        let label = if line == origin.y() { max.as_str() } else { "" };
+        let column = origin.x() + min_width + plot_width;
+        interface.set(
+            pos!(column, line),
+            &format!(" {:<1$}", label, max_width as usize - 1),
+        );
+    }
+
+    Rect::new(
+        pos!(origin.x() + min_width, origin.y()),
+        Vector::new(plot_width, size.y()),
+    )
+}
+
+/// Stage each column of the rectangle filled from the bottom by the number of eighths of its
+/// height, leaving columns beyond the levels blank.
+fn stage_columns(interface: &mut Interface, rect: Rect, levels: &[u32], style: Option<Style>) {
+    let (origin, size) = (rect.origin(), rect.size());
+
+    for line in 0..size.y() {
+        let eighths_below = (size.y() - 1 - line) as u32 * 8;
+
+        let mut text = String::new();
+        for column in 0..size.x() as usize {
+            let level = levels.get(column).copied().unwrap_or(0);
+            let fill = level.saturating_sub(eighths_below).min(8);
+            text.push_str(BLOCKS[fill as usize]);
+        }
+
+        let position = pos!(origin.x(), origin.y() + line);
+        match style {
+            Some(style) => interface.set_styled(position, &text, style),
+            None => interface.set(position, &text),
+        };
+    }
+}
+
+/// Format a value for a label with at most two decimal places, without trailing zeros.
+fn format_value(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_value, level};
+
+    #[test]
+    fn chart_level() {
+        assert_eq!(0, level(0.0, 0.0, 10.0, 1));
+        assert_eq!(4, level(5.0, 0.0, 10.0, 1));
+        assert_eq!(8, level(10.0, 0.0, 10.0, 1));
+        assert_eq!(12, level(7.5, 0.0, 10.0, 2));
+        assert_eq!(0, level(-5.0, 0.0, 10.0, 2));
+        assert_eq!(0, level(3.0, 3.0, 3.0, 2));
+    }
+
+    #[test]
+    fn chart_format_value() {
+        assert_eq!("10", format_value(10.0));
+        assert_eq!("0.5", format_value(0.5));
+        assert_eq!("0.33", format_value(1.0 / 3.0));
+        assert_eq!("0", format_value(-0.001));
+        assert_eq!("-2.25", format_value(-2.25));
+    }
+}
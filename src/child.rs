@@ -0,0 +1,58 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+/// The stream a child process wrote a line to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum ChildStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line read from one of a child process's streams.
+pub(crate) type ChildLine = (ChildStream, Vec<u8>);
+
+/// Spawn the command with its standard output and error piped, returning the child and its output
+/// lines from either stream as they're read. Each line ends with a line feed, even if the child's
+/// last didn't, and the lines end once both streams close.
+pub(crate) fn spawn_piped(command: &mut Command) -> io::Result<(Child, Receiver<ChildLine>)> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let (sender, receiver) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        read_lines(stdout, ChildStream::Stdout, sender.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        read_lines(stderr, ChildStream::Stderr, sender);
+    }
+
+    Ok((child, receiver))
+}
+
+/// Read lines from the stream on another thread, sending each until the stream closes.
+fn read_lines(stream: impl Read + Send + 'static, kind: ChildStream, sender: Sender<ChildLine>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut line = Vec::new();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if !line.ends_with(b"\n") {
+                        line.push(b'\n');
+                    }
+
+                    if sender.send((kind, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
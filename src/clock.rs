@@ -0,0 +1,17 @@
+use std::time::Instant;
+
+/// A source of the current time, allowing time-dependent behavior to be controlled in tests.
+pub trait Clock {
+    /// The current instant.
+    fn now(&self) -> Instant;
+}
+
+/// A clock reporting the system's monotonic time.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
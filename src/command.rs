@@ -0,0 +1,153 @@
+use std::io::Write;
+
+#[cfg(feature = "images")]
+use crate::ImageProtocol;
+use crate::{Position, Result, Style};
+
+/// A terminal operation emitted by an interface, which its device translates into output.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DeviceCommand<'a> {
+    /// Move the cursor to an absolute position.
+    MoveTo(Position),
+    /// Move the cursor left by a number of columns.
+    MoveLeft(u16),
+    /// Move the cursor right by a number of columns.
+    MoveRight(u16),
+    /// Move the cursor up by a number of lines.
+    MoveUp(u16),
+    /// Print unstyled text at the cursor, which may include line feeds.
+    Print(&'a str),
+    /// Print styled text at the cursor.
+    PrintStyled(&'a str, Style),
+    /// Hide the cursor.
+    HideCursor,
+    /// Show the cursor.
+    ShowCursor,
+    /// Switch to the terminal's alternate screen.
+    EnterAlternateScreen,
+    /// Return from the terminal's alternate screen.
+    LeaveAlternateScreen,
+    /// Clear the terminal's entire screen.
+    ClearAll,
+    /// Clear from the cursor to the end of its line.
+    ClearToEndOfLine,
+    /// Insert a number of blank columns at the cursor, shifting the rest of its line right.
+    InsertCharacters(u16),
+    /// Confine scrolling to the lines between the first and last, inclusive, moving the cursor to
+    /// the screen's top-left corner.
+    SetScrollRegion(u16, u16),
+    /// Begin reporting mouse events as input.
+    EnableMouseCapture,
+    /// Stop reporting mouse events as input.
+    DisableMouseCapture,
+}
+
+/// Translates commands into a device's output. By default, commands are encoded as ANSI escape
+/// sequences, which most terminals understand; backends for other protocols may override this.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{encode_ansi, CommandEncoder, DeviceCommand, Result};
+///
+/// /// A device which renders styled text without its styling.
+/// struct PlainDevice;
+///
+/// impl CommandEncoder for PlainDevice {
+///     fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+///         match command {
+///             DeviceCommand::PrintStyled(text, _) => encode_ansi(&DeviceCommand::Print(text), buffer),
+///             command => encode_ansi(command, buffer),
+///         }
+///     }
+/// }
+/// ```
+pub trait CommandEncoder {
+    /// Append the output performing the specified command to the buffer.
+    fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+        encode_ansi(command, buffer)
+    }
+
+    /// Whether the device's output should be styled, if it has a preference such as the user's
+    /// `NO_COLOR` setting. Otherwise, interfaces style their output unless they're printing lines
+    /// to a device which isn't a terminal.
+    fn styling_preference(&self) -> Option<bool> {
+        None
+    }
+
+    /// The protocol the device's terminal supports for displaying inline images. By default,
+    /// images are approximated with block characters.
+    #[cfg(feature = "images")]
+    fn image_protocol(&self) -> ImageProtocol {
+        ImageProtocol::Blocks
+    }
+}
+
+/// Append the ANSI escape sequences performing the specified command to the buffer.
+pub fn encode_ansi(command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+    match *command {
+        DeviceCommand::MoveTo(position) => {
+            write!(buffer, "\x1B[{};{}H", position.y() + 1, position.x() + 1)?
+        }
+        DeviceCommand::MoveLeft(columns) => write!(buffer, "\x1B[{}D", columns)?,
+        DeviceCommand::MoveRight(columns) => write!(buffer, "\x1B[{}C", columns)?,
+        DeviceCommand::MoveUp(lines) => write!(buffer, "\x1B[{}A", lines)?,
+        DeviceCommand::Print(text) => buffer.extend_from_slice(text.as_bytes()),
+        DeviceCommand::PrintStyled(text, style) => {
+            if style == Style::new() {
+                buffer.extend_from_slice(text.as_bytes());
+            } else {
+                write!(buffer, "\x1B[{}m{}\x1B[0m", style.to_sgr(), text)?;
+            }
+        }
+        DeviceCommand::HideCursor => buffer.extend_from_slice(b"\x1B[?25l"),
+        DeviceCommand::ShowCursor => buffer.extend_from_slice(b"\x1B[?25h"),
+        DeviceCommand::EnterAlternateScreen => buffer.extend_from_slice(b"\x1B[?1049h"),
+        DeviceCommand::LeaveAlternateScreen => buffer.extend_from_slice(b"\x1B[?1049l"),
+        DeviceCommand::ClearAll => buffer.extend_from_slice(b"\x1B[2J"),
+        DeviceCommand::ClearToEndOfLine => buffer.extend_from_slice(b"\x1B[K"),
+        DeviceCommand::InsertCharacters(columns) => write!(buffer, "\x1B[{}@", columns)?,
+        DeviceCommand::SetScrollRegion(first, last) => {
+            write!(buffer, "\x1B[{};{}r", first + 1, last + 1)?
+        }
+        DeviceCommand::EnableMouseCapture => {
+            // Normal, button-event, and any-event tracking, reported in the SGR and urxvt formats
+            for mode in MOUSE_MODES {
+                write!(buffer, "\x1B[?{}h", mode)?;
+            }
+        }
+        DeviceCommand::DisableMouseCapture => {
+            for mode in MOUSE_MODES.iter().rev() {
+                write!(buffer, "\x1B[?{}l", mode)?;
+            }
+        }
+    };
+
+    Ok(())
+}
+
+/// The private modes enabling mouse reporting, in the order they're enabled.
+const MOUSE_MODES: [u16; 5] = [1000, 1002, 1003, 1015, 1006];
+
+#[cfg(test)]
+mod tests {
+    use crate::{encode_ansi, pos, Color, DeviceCommand, Position};
+
+    #[test]
+    fn command_encode_ansi() {
+        let mut buffer = Vec::new();
+        encode_ansi(&DeviceCommand::MoveTo(pos!(2, 3)), &mut buffer).unwrap();
+        encode_ansi(&DeviceCommand::Print("a"), &mut buffer).unwrap();
+        encode_ansi(
+            &DeviceCommand::PrintStyled("b", Color::Red.as_style()),
+            &mut buffer,
+        )
+        .unwrap();
+        encode_ansi(&DeviceCommand::InsertCharacters(2), &mut buffer).unwrap();
+        encode_ansi(&DeviceCommand::SetScrollRegion(0, 19), &mut buffer).unwrap();
+
+        assert_eq!(
+            b"\x1B[4;3Ha\x1B[91mb\x1B[0m\x1B[2@\x1B[1;20r",
+            buffer.as_slice()
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! A compatibility layer providing the line-oriented API of the crate's 0.x releases on top of
+//! [Interface], so applications written against it may upgrade without a rewrite. New code should
+//! use [Interface] directly.
+
+use crate::{pos, Device, Interface, Position, Result, Style};
+
+/// A relative interface updated in batches of whole lines.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::compat::{Line, Segment, TTYInterface};
+///
+/// let mut tty = TTYInterface::new(&mut device)?;
+///
+/// let mut batch = tty.start_update();
+/// batch.set_line(0, Line::new(vec![Segment::new("Hello, world!".to_string())]));
+/// tty.perform_update(batch)?;
+///
+/// tty.end()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct TTYInterface<'a> {
+    interface: Interface<'a>,
+}
+
+impl<'a> TTYInterface<'a> {
+    /// Create a new interface rendering relative to the device's cursor.
+    pub fn new(device: &'a mut dyn Device) -> Result<TTYInterface<'a>> {
+        Ok(TTYInterface {
+            interface: Interface::new_relative(device)?,
+        })
+    }
+
+    /// Begin a batch of updates, which are rendered together by [TTYInterface::perform_update].
+    pub fn start_update(&self) -> UpdateBatch {
+        UpdateBatch { steps: Vec::new() }
+    }
+
+    /// Render a batch of updates, in the order they were added.
+    pub fn perform_update(&mut self, batch: UpdateBatch) -> Result<()> {
+        for step in batch.steps {
+            match step {
+                UpdateStep::SetLine(line, content) => {
+                    self.interface.clear_line(line);
+
+                    let mut column = 0;
+                    for segment in content.segments {
+                        let position = pos!(column, line);
+                        match segment.style {
+                            Some(style) => {
+                                self.interface.set_styled(position, &segment.text, style)
+                            }
+                            None => self.interface.set(position, &segment.text),
+                        };
+
+                        column += self.interface.widths().text_width(&segment.text);
+                    }
+                }
+                UpdateStep::DeleteLine(line) => self.interface.delete_line(line),
+                UpdateStep::SetCursor(position) => self
+                    .interface
+                    .set_cursor(Some(pos!(position.x, position.y))),
+            }
+        }
+
+        self.interface.apply()
+    }
+
+    /// When finished using this interface, uninitialize its terminal configuration.
+    pub fn end(self) -> Result<()> {
+        self.interface.exit()
+    }
+}
+
+/// A set of line and cursor updates to be rendered together.
+pub struct UpdateBatch {
+    steps: Vec<UpdateStep>,
+}
+
+/// An update in a batch.
+enum UpdateStep {
+    SetLine(u16, Line),
+    DeleteLine(u16),
+    SetCursor(CursorPosition),
+}
+
+impl UpdateBatch {
+    /// Replace the content of the specified line.
+    pub fn set_line(&mut self, line_index: usize, line: Line) {
+        if let Ok(line_index) = u16::try_from(line_index) {
+            self.steps.push(UpdateStep::SetLine(line_index, line));
+        }
+    }
+
+    /// Remove the specified line, moving the following lines up to take its place.
+    pub fn delete_line(&mut self, line_index: usize) {
+        if let Ok(line_index) = u16::try_from(line_index) {
+            self.steps.push(UpdateStep::DeleteLine(line_index));
+        }
+    }
+
+    /// Show the cursor at the specified position.
+    pub fn set_cursor(&mut self, position: CursorPosition) {
+        self.steps.push(UpdateStep::SetCursor(position));
+    }
+}
+
+/// A line's content, composed of consecutive segments.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Line {
+    segments: Vec<Segment>,
+}
+
+impl Line {
+    /// Create a line from the specified segments.
+    pub fn new(segments: Vec<Segment>) -> Line {
+        Line { segments }
+    }
+
+    /// This line's segments.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+/// A run of text in a line with an optional style.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Segment {
+    text: String,
+    style: Option<Style>,
+}
+
+impl Segment {
+    /// Create an unstyled segment.
+    pub fn new(text: String) -> Segment {
+        Segment { text, style: None }
+    }
+
+    /// Create a segment with the specified style.
+    pub fn new_styled(text: String, style: Style) -> Segment {
+        Segment {
+            text,
+            style: Some(style),
+        }
+    }
+
+    /// This segment's text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// This segment's style, if any.
+    pub fn style(&self) -> Option<Style> {
+        self.style
+    }
+}
+
+/// A cursor position within the interface, as a column and line.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CursorPosition {
+    x: u16,
+    y: u16,
+}
+
+impl CursorPosition {
+    /// Create a cursor position at the specified column and line.
+    pub fn init(x: u16, y: u16) -> CursorPosition {
+        CursorPosition { x, y }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CursorPosition, Line, Segment, TTYInterface};
+    use crate::{test::VirtualDevice, Color};
+
+    #[test]
+    fn compat_batch_updates() {
+        let mut device = VirtualDevice::new();
+        let mut tty = TTYInterface::new(&mut device).unwrap();
+
+        let mut batch = tty.start_update();
+        batch.set_line(0, Line::new(vec![Segment::new("First".to_string())]));
+        batch.set_line(1, Line::new(vec![Segment::new("Second".to_string())]));
+        batch.set_line(
+            2,
+            Line::new(vec![
+                Segment::new("Thi".to_string()),
+                Segment::new_styled("rd".to_string(), Color::Red.as_style()),
+            ]),
+        );
+        tty.perform_update(batch).unwrap();
+
+        let mut batch = tty.start_update();
+        batch.set_line(0, Line::new(vec![Segment::new("1st".to_string())]));
+        batch.delete_line(1);
+        batch.set_cursor(CursorPosition::init(2, 1));
+        tty.perform_update(batch).unwrap();
+
+        let screen = device.parser().screen();
+        assert_eq!("1st  \nThird \n     ", &screen.contents());
+        assert_eq!(vt100::Color::Idx(9), screen.cell(1, 3).unwrap().fgcolor());
+        assert_eq!((1, 2), screen.cursor_position());
+    }
+}
@@ -0,0 +1,13 @@
+/// Whether an interface controls the terminal cursor's visibility.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum CursorMode {
+    /// The cursor is hidden while each frame is written and shown afterward only if a position
+    /// was staged with [crate::Interface::set_cursor].
+    #[default]
+    Managed,
+    /// The cursor remains visible and is never hidden or shown by the interface, e.g. for an
+    /// application reading input with a line editor beneath a relative interface. After writing a
+    /// frame, the cursor is moved to the staged position or, if none, back to where it was before
+    /// the frame.
+    Visible,
+}
@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+/// When an interface's changes were last staged, and how long they may wait before they're
+/// applied automatically, if at all.
+pub(crate) struct DebounceState {
+    window: Option<Duration>,
+    last_staged: Option<Instant>,
+}
+
+impl DebounceState {
+    /// Create a state with automatic applies disabled and no changes staged.
+    pub(crate) fn new() -> DebounceState {
+        DebounceState {
+            window: None,
+            last_staged: None,
+        }
+    }
+
+    /// The debounce window after which staged changes are applied automatically, if enabled.
+    pub(crate) fn window(&self) -> Option<Duration> {
+        self.window
+    }
+
+    /// Apply staged changes automatically after the window, or disable automatic applies.
+    pub(crate) fn set_window(&mut self, window: Option<Duration>) {
+        self.window = window;
+    }
+
+    /// Record that changes were staged at the specified instant.
+    pub(crate) fn mark_staged(&mut self, now: Instant) {
+        self.last_staged = Some(now);
+    }
+
+    /// Record that the staged changes were applied.
+    pub(crate) fn mark_applied(&mut self) {
+        self.last_staged = None;
+    }
+
+    /// Whether automatic applies are enabled and the window has elapsed since changes were last
+    /// staged, as of the specified instant.
+    pub(crate) fn is_due(&self, now: Instant) -> bool {
+        match (self.window, self.last_staged) {
+            (Some(window), Some(last_staged)) => {
+                now.saturating_duration_since(last_staged) >= window
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,26 +1,96 @@
-use crate::{pos, Position, Result, Vector};
+use std::{io::Write, thread, time::Duration};
 
-/// An output device to be controlled for displaying an interface.
-pub trait Device: std::io::Write {
+#[cfg(feature = "crossterm")]
+use crate::pos;
+#[cfg(feature = "images")]
+use crate::ImageProtocol;
+use crate::{CommandEncoder, Event, Position, Result, Vector};
+
+/// An output device to be controlled for displaying an interface. Implemented for any writer
+/// providing the size, raw mode, cursor, command encoding, and input capabilities.
+pub trait Device:
+    Write + SizedTarget + RawModeControl + CursorQuery + CommandEncoder + EventSource
+{
+}
+
+impl<T> Device for T where
+    T: Write + SizedTarget + RawModeControl + CursorQuery + CommandEncoder + EventSource + ?Sized
+{
+}
+
+/// A target with a known viewport size.
+pub trait SizedTarget {
     /// Retrieve the device's terminal viewport size.
     fn get_terminal_size(&mut self) -> Result<Vector>;
+}
 
+/// A target whose terminal may be placed in "raw mode". By default, a target has no terminal
+/// configuration to change, so enabling and disabling raw mode do nothing.
+pub trait RawModeControl {
     /// Enable "raw mode" in the terminal.
-    fn enable_raw_mode(&mut self) -> Result<()>;
+    fn enable_raw_mode(&mut self) -> Result<()> {
+        Ok(())
+    }
 
     /// Restore the configuration before the terminal was placed in "raw mode".
-    fn disable_raw_mode(&mut self) -> Result<()>;
+    fn disable_raw_mode(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Whether the target is an interactive terminal. Interfaces for targets which aren't, such
+    /// as a file or pipe, print their lines without cursor control. By default, targets are
+    /// assumed to be terminals.
+    fn is_terminal(&self) -> bool {
+        true
+    }
+}
+
+/// A target able to report its cursor's position.
+pub trait CursorQuery {
+    /// Retrieve the cursor's absolute position in the device's buffer. By default, a target can't
+    /// report its cursor and the query fails as unsupported, for which interfaces fall back to
+    /// tracking the cursor themselves.
+    fn get_cursor_position(&mut self) -> Result<Position> {
+        Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into())
+    }
+}
 
-    /// Retrieve the cursor's absolute position in the device's buffer.
-    fn get_cursor_position(&mut self) -> Result<Position>;
+/// Retrieve the device's cursor position, or the fallback if the device can't report it.
+pub(crate) fn cursor_position_or(
+    device: &mut (impl CursorQuery + ?Sized),
+    fallback: Position,
+) -> Result<Position> {
+    match device.get_cursor_position() {
+        Err(error) if error.is_unsupported() => Ok(fallback),
+        position => position,
+    }
 }
 
-impl Device for std::io::Stdout {
+/// A target reporting its terminal's input as events.
+pub trait EventSource {
+    /// Wait up to the timeout, or indefinitely if there is none, for the next input event. By
+    /// default, a device reports no input: it waits out the timeout, and fails without one.
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        match timeout {
+            Some(timeout) => {
+                thread::sleep(timeout);
+                Ok(None)
+            }
+            None => Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into()),
+        }
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl SizedTarget for std::io::Stdout {
     fn get_terminal_size(&mut self) -> Result<Vector> {
         let (columns, lines) = crossterm::terminal::size()?;
         Ok(Vector::new(columns, lines))
     }
+}
 
+#[cfg(feature = "crossterm")]
+impl RawModeControl for std::io::Stdout {
     fn enable_raw_mode(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
         Ok(())
@@ -31,8 +101,131 @@ impl Device for std::io::Stdout {
         Ok(())
     }
 
+    fn is_terminal(&self) -> bool {
+        std::io::IsTerminal::is_terminal(self) && !crate::environment::is_dumb_terminal()
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl CommandEncoder for std::io::Stdout {
+    fn styling_preference(&self) -> Option<bool> {
+        crate::environment::styling_preference()
+    }
+
+    #[cfg(feature = "images")]
+    fn image_protocol(&self) -> ImageProtocol {
+        ImageProtocol::detect()
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl EventSource for std::io::Stdout {
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        if let Some(timeout) = timeout {
+            if !crossterm::event::poll(timeout)? {
+                return Ok(None);
+            }
+        }
+
+        Ok(crate::event::from_crossterm(crossterm::event::read()?))
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl CursorQuery for std::io::Stdout {
     fn get_cursor_position(&mut self) -> Result<Position> {
         let (column, row) = crossterm::cursor::position()?;
         Ok(pos!(column, row))
     }
 }
+
+/// A device generating terminal output into any writer, such as a file or pipe, for a terminal of
+/// a fixed size. There is no terminal to place in raw mode or cursor to query, so interfaces
+/// begin their output at the top-left corner.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::Error;
+/// use tty_interface::{Interface, Position, Vector, WriterDevice, pos};
+///
+/// let mut device = WriterDevice::new(Vec::new(), Vector::new(80, 24));
+///
+/// let mut interface = Interface::new_relative(&mut device)?;
+/// interface.set(pos!(0, 0), "Hello, file!");
+/// interface.apply()?;
+/// interface.exit()?;
+///
+/// let output = device.into_inner();
+/// # Ok::<(), Error>(())
+/// ```
+pub struct WriterDevice<W: Write> {
+    writer: W,
+    size: Vector,
+}
+
+impl<W: Write> WriterDevice<W> {
+    /// Create a device writing to the specified writer for a terminal of the specified size.
+    pub fn new(writer: W, size: Vector) -> WriterDevice<W> {
+        WriterDevice { writer, size }
+    }
+
+    /// Access the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Unwrap this device, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> SizedTarget for WriterDevice<W> {
+    fn get_terminal_size(&mut self) -> Result<Vector> {
+        Ok(self.size)
+    }
+}
+
+impl<W: Write> RawModeControl for WriterDevice<W> {}
+
+impl<W: Write> CursorQuery for WriterDevice<W> {}
+
+impl<W: Write> CommandEncoder for WriterDevice<W> {}
+
+impl<W: Write> EventSource for WriterDevice<W> {}
+
+impl<W: Write> Write for WriterDevice<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// A device controlled by an interface, either borrowed from or owned by the interface.
+pub(crate) enum DeviceHandle<'a> {
+    Borrowed(&'a mut dyn Device),
+    Owned(Box<dyn Device>),
+}
+
+impl<'a> std::ops::Deref for DeviceHandle<'a> {
+    type Target = dyn Device + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            DeviceHandle::Borrowed(device) => *device,
+            DeviceHandle::Owned(device) => device.as_ref(),
+        }
+    }
+}
+
+impl std::ops::DerefMut for DeviceHandle<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            DeviceHandle::Borrowed(device) => *device,
+            DeviceHandle::Owned(device) => device.as_mut(),
+        }
+    }
+}
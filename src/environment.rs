@@ -0,0 +1,75 @@
+use std::ffi::OsString;
+
+/// Whether the user's terminal output should be styled per their environment, if they've
+/// expressed a preference: a `CLICOLOR_FORCE` other than "0" forces styling, and otherwise a
+/// non-empty `NO_COLOR` disables it.
+pub(crate) fn styling_preference() -> Option<bool> {
+    styling_preference_from(|name| std::env::var_os(name))
+}
+
+/// Whether the user's terminal is declared "dumb" with `TERM=dumb`, lacking cursor addressing.
+pub(crate) fn is_dumb_terminal() -> bool {
+    is_dumb_terminal_from(|name| std::env::var_os(name))
+}
+
+/// The styling preference expressed by the variables the lookup returns.
+fn styling_preference_from(var: impl Fn(&str) -> Option<OsString>) -> Option<bool> {
+    let is_forced = var("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+    let is_disabled = var("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    match (is_forced, is_disabled) {
+        (true, _) => Some(true),
+        (false, true) => Some(false),
+        (false, false) => None,
+    }
+}
+
+/// Whether the variables the lookup returns declare a dumb terminal.
+fn is_dumb_terminal_from(var: impl Fn(&str) -> Option<OsString>) -> bool {
+    var("TERM").is_some_and(|term| term == "dumb")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use super::{is_dumb_terminal_from, styling_preference_from};
+
+    /// A lookup of the specified variables.
+    fn vars<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        |name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| OsString::from(value))
+        }
+    }
+
+    #[test]
+    fn environment_styling_preference() {
+        assert_eq!(None, styling_preference_from(vars(&[])));
+        assert_eq!(
+            Some(false),
+            styling_preference_from(vars(&[("NO_COLOR", "1")]))
+        );
+        assert_eq!(None, styling_preference_from(vars(&[("NO_COLOR", "")])));
+        assert_eq!(
+            Some(true),
+            styling_preference_from(vars(&[("CLICOLOR_FORCE", "1")]))
+        );
+        assert_eq!(
+            None,
+            styling_preference_from(vars(&[("CLICOLOR_FORCE", "0")]))
+        );
+
+        // Forcing styling takes precedence
+        let both = [("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")];
+        assert_eq!(Some(true), styling_preference_from(vars(&both)));
+    }
+
+    #[test]
+    fn environment_dumb_terminal() {
+        assert!(is_dumb_terminal_from(vars(&[("TERM", "dumb")])));
+        assert!(!is_dumb_terminal_from(vars(&[("TERM", "xterm-256color")])));
+        assert!(!is_dumb_terminal_from(vars(&[])));
+    }
+}
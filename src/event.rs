@@ -0,0 +1,341 @@
+use crate::{Position, Vector};
+
+/// Input reported by a terminal while an interface is active.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Event {
+    /// A key was pressed.
+    Key(KeyEvent),
+    /// The mouse was used while mouse events are captured.
+    Mouse(MouseEvent),
+    /// The terminal was resized to the specified columns and lines. By the time an interface
+    /// returns this event, its size has been updated to match.
+    Resize(Vector),
+    /// The terminal gained focus.
+    FocusGained,
+    /// The terminal lost focus.
+    FocusLost,
+    /// The process was asked to terminate, e.g. by SIGTERM, and the application should exit the
+    /// interface. Only reported while intercepting termination signals, see
+    /// [crate::InterfaceBuilder::handle_termination].
+    Terminate,
+    /// The process was continued after being suspended, e.g. by Ctrl+Z, and the interface has
+    /// been restored and will be fully redrawn by the next apply. Only reported while handling
+    /// signals.
+    Resume,
+}
+
+/// A key press along with the modifiers held during it.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{KeyCode, KeyEvent, Modifiers};
+///
+/// let event = KeyEvent::new(KeyCode::Char('c'), Modifiers::new().set_control(true));
+/// assert!(event.modifiers().is_control());
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyEvent {
+    code: KeyCode,
+    modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    /// Create a new, immutable key event.
+    pub fn new(code: KeyCode, modifiers: Modifiers) -> KeyEvent {
+        KeyEvent { code, modifiers }
+    }
+
+    /// The key which was pressed.
+    pub fn code(&self) -> KeyCode {
+        self.code
+    }
+
+    /// The modifiers held while the key was pressed.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Parse a key chord such as "ctrl+s", "shift+tab", or "f5": any of the "ctrl", "alt", and
+    /// "shift" modifiers followed by a key, separated by "+". Keys are a single character or a
+    /// name such as "enter", "esc", "pageup", or "f1". Returns `None` if the chord isn't valid.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{KeyCode, KeyEvent, Modifiers};
+    ///
+    /// let chord = KeyEvent::parse("ctrl+alt+x").unwrap();
+    /// assert_eq!(KeyCode::Char('x'), chord.code());
+    /// assert_eq!(Modifiers::new().set_control(true).set_alt(true), chord.modifiers());
+    ///
+    /// assert_eq!(None, KeyEvent::parse("hyper+x"));
+    /// ```
+    pub fn parse(chord: &str) -> Option<KeyEvent> {
+        let (modifier_names, key) = match chord.rsplit_once('+') {
+            _ if chord == "+" => ("", "+"),
+            Some((modifiers, "")) => (modifiers.strip_suffix('+')?, "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", chord),
+        };
+
+        let mut modifiers = Modifiers::new();
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers = match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.set_control(true),
+                "alt" => modifiers.set_alt(true),
+                "shift" => modifiers.set_shift(true),
+                _ => return None,
+            };
+        }
+
+        let mut characters = key.chars();
+        let code = match (characters.next(), characters.next()) {
+            (Some(character), None) => KeyCode::Char(character),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "enter" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Escape,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "space" => KeyCode::Char(' '),
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+
+        Some(KeyEvent::new(code, modifiers))
+    }
+}
+
+/// A key on the keyboard.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum KeyCode {
+    /// A key producing a character.
+    Char(char),
+    Enter,
+    Escape,
+    Backspace,
+    Tab,
+    /// Tab pressed with shift.
+    BackTab,
+    Delete,
+    Insert,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// A numbered function key, e.g. `F(1)` for F1.
+    F(u8),
+}
+
+/// The modifier keys held during a key press or mouse event.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Modifiers {
+    is_shift: bool,
+    is_control: bool,
+    is_alt: bool,
+}
+
+impl Modifiers {
+    /// Create a new set of modifiers with none held.
+    pub fn new() -> Modifiers {
+        Modifiers::default()
+    }
+
+    /// Create a new set of modifiers with shift held or not.
+    pub fn set_shift(&self, is_shift: bool) -> Modifiers {
+        Modifiers { is_shift, ..*self }
+    }
+
+    /// Whether shift is held.
+    pub fn is_shift(&self) -> bool {
+        self.is_shift
+    }
+
+    /// Create a new set of modifiers with control held or not.
+    pub fn set_control(&self, is_control: bool) -> Modifiers {
+        Modifiers {
+            is_control,
+            ..*self
+        }
+    }
+
+    /// Whether control is held.
+    pub fn is_control(&self) -> bool {
+        self.is_control
+    }
+
+    /// Create a new set of modifiers with alt held or not.
+    pub fn set_alt(&self, is_alt: bool) -> Modifiers {
+        Modifiers { is_alt, ..*self }
+    }
+
+    /// Whether alt is held.
+    pub fn is_alt(&self) -> bool {
+        self.is_alt
+    }
+}
+
+/// A use of the mouse at a position in the terminal.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct MouseEvent {
+    kind: MouseEventKind,
+    position: Position,
+    modifiers: Modifiers,
+}
+
+impl MouseEvent {
+    /// Create a new, immutable mouse event.
+    pub fn new(kind: MouseEventKind, position: Position, modifiers: Modifiers) -> MouseEvent {
+        MouseEvent {
+            kind,
+            position,
+            modifiers,
+        }
+    }
+
+    /// How the mouse was used.
+    pub fn kind(&self) -> MouseEventKind {
+        self.kind
+    }
+
+    /// The terminal cell at which the mouse was used, relative to the top-left of the screen.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The modifiers held while the mouse was used.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+}
+
+/// A way the mouse may be used.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MouseEventKind {
+    Press(MouseButton),
+    Release(MouseButton),
+    /// The mouse moved with a button held.
+    Drag(MouseButton),
+    /// The mouse moved with no buttons held.
+    Move,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// A mouse button.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Translate a crossterm event into an interface event, if it has an equivalent. Key releases
+/// are ignored.
+#[cfg(feature = "crossterm")]
+pub(crate) fn from_crossterm(event: crossterm::event::Event) -> Option<Event> {
+    use crossterm::event::{self as ct, KeyEventKind};
+
+    let modifiers = |modifiers: ct::KeyModifiers| {
+        Modifiers::new()
+            .set_shift(modifiers.contains(ct::KeyModifiers::SHIFT))
+            .set_control(modifiers.contains(ct::KeyModifiers::CONTROL))
+            .set_alt(modifiers.contains(ct::KeyModifiers::ALT))
+    };
+
+    let button = |button: ct::MouseButton| match button {
+        ct::MouseButton::Left => MouseButton::Left,
+        ct::MouseButton::Right => MouseButton::Right,
+        ct::MouseButton::Middle => MouseButton::Middle,
+    };
+
+    let event = match event {
+        ct::Event::Key(event) if event.kind != KeyEventKind::Release => {
+            let code = match event.code {
+                ct::KeyCode::Char(character) => KeyCode::Char(character),
+                ct::KeyCode::Enter => KeyCode::Enter,
+                ct::KeyCode::Esc => KeyCode::Escape,
+                ct::KeyCode::Backspace => KeyCode::Backspace,
+                ct::KeyCode::Tab => KeyCode::Tab,
+                ct::KeyCode::BackTab => KeyCode::BackTab,
+                ct::KeyCode::Delete => KeyCode::Delete,
+                ct::KeyCode::Insert => KeyCode::Insert,
+                ct::KeyCode::Left => KeyCode::Left,
+                ct::KeyCode::Right => KeyCode::Right,
+                ct::KeyCode::Up => KeyCode::Up,
+                ct::KeyCode::Down => KeyCode::Down,
+                ct::KeyCode::Home => KeyCode::Home,
+                ct::KeyCode::End => KeyCode::End,
+                ct::KeyCode::PageUp => KeyCode::PageUp,
+                ct::KeyCode::PageDown => KeyCode::PageDown,
+                ct::KeyCode::F(number) => KeyCode::F(number),
+                _ => return None,
+            };
+
+            Event::Key(KeyEvent::new(code, modifiers(event.modifiers)))
+        }
+        ct::Event::Mouse(event) => {
+            let kind = match event.kind {
+                ct::MouseEventKind::Down(pressed) => MouseEventKind::Press(button(pressed)),
+                ct::MouseEventKind::Up(released) => MouseEventKind::Release(button(released)),
+                ct::MouseEventKind::Drag(held) => MouseEventKind::Drag(button(held)),
+                ct::MouseEventKind::Moved => MouseEventKind::Move,
+                ct::MouseEventKind::ScrollUp => MouseEventKind::ScrollUp,
+                ct::MouseEventKind::ScrollDown => MouseEventKind::ScrollDown,
+            };
+
+            let position = Position::new(event.column, event.row);
+            Event::Mouse(MouseEvent::new(kind, position, modifiers(event.modifiers)))
+        }
+        ct::Event::Resize(columns, lines) => Event::Resize(Vector::new(columns, lines)),
+        ct::Event::FocusGained => Event::FocusGained,
+        ct::Event::FocusLost => Event::FocusLost,
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+#[cfg(all(test, feature = "crossterm"))]
+mod tests {
+    use crossterm::event as ct;
+
+    use super::from_crossterm;
+    use crate::{Event, KeyCode, KeyEvent, Modifiers, Vector};
+
+    #[test]
+    fn event_from_crossterm() {
+        let key = ct::KeyEvent::new(ct::KeyCode::Char('c'), ct::KeyModifiers::CONTROL);
+        assert_eq!(
+            Some(Event::Key(KeyEvent::new(
+                KeyCode::Char('c'),
+                Modifiers::new().set_control(true)
+            ))),
+            from_crossterm(ct::Event::Key(key))
+        );
+
+        assert_eq!(
+            Some(Event::Resize(Vector::new(100, 30))),
+            from_crossterm(ct::Event::Resize(100, 30))
+        );
+
+        let release = ct::KeyEvent {
+            kind: ct::KeyEventKind::Release,
+            ..ct::KeyEvent::new(ct::KeyCode::Enter, ct::KeyModifiers::NONE)
+        };
+        assert_eq!(None, from_crossterm(ct::Event::Key(release)));
+    }
+}
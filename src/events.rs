@@ -0,0 +1,419 @@
+//! Recording of the events a device reports and their deterministic replay, so interactive
+//! sessions may be captured once and replayed by tests of applications built on the crate.
+//!
+//! Recordings are text with one event per line: the seconds since recording started, the event's
+//! kind, and its details, e.g. `1.250000 key ctrl+s` or `2.000000 resize 100x30`. A read which
+//! timed out without an event is recorded as `timeout`, so polling loops replay identically.
+//! Blank lines and lines starting with `#` are ignored, so recordings may also be written by hand.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "images")]
+use crate::ImageProtocol;
+use crate::{
+    pos, CommandEncoder, CursorQuery, Device, DeviceCommand, DeviceHandle, Event, EventSource,
+    KeyCode, KeyEvent, Modifiers, MouseButton, MouseEvent, MouseEventKind, Position,
+    RawModeControl, Result, SizedTarget, Vector,
+};
+
+/// Records the events reported by a device as they're read, writing each to the recording
+/// immediately so a test which panics keeps what came before. Otherwise, the device is used as
+/// it would be directly.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Event, EventRecorder, Interface, KeyCode, KeyEvent, Modifiers};
+///
+/// device.push_event(Event::Key(KeyEvent::new(KeyCode::Char('q'), Modifiers::new())));
+///
+/// let mut recording = Vec::new();
+/// let mut recorder = EventRecorder::new(&mut device, Box::new(&mut recording));
+/// let mut interface = Interface::new_alternate(&mut recorder)?;
+/// interface.read_event()?;
+/// interface.exit()?;
+/// drop(recorder);
+///
+/// assert!(String::from_utf8(recording).unwrap().ends_with(" key q\n"));
+/// # Ok::<(), Error>(())
+/// ```
+pub struct EventRecorder<'a> {
+    device: DeviceHandle<'a>,
+    writer: Box<dyn Write + 'a>,
+    start: Instant,
+}
+
+impl<'a> EventRecorder<'a> {
+    /// Create a recorder of the specified device's events, writing them to the writer.
+    pub fn new(device: &'a mut dyn Device, writer: Box<dyn Write + 'a>) -> EventRecorder<'a> {
+        EventRecorder::initialize(DeviceHandle::Borrowed(device), writer)
+    }
+
+    /// Create a recorder which takes ownership of the specified device to record its events.
+    pub fn new_owned(device: Box<dyn Device>, writer: Box<dyn Write>) -> EventRecorder<'static> {
+        EventRecorder::initialize(DeviceHandle::Owned(device), writer)
+    }
+
+    fn initialize(device: DeviceHandle<'a>, writer: Box<dyn Write + 'a>) -> EventRecorder<'a> {
+        EventRecorder {
+            device,
+            writer,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl EventSource for EventRecorder<'_> {
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        let event = self.device.read_event(timeout)?;
+
+        let time = self.start.elapsed().as_secs_f64();
+        let line = match &event {
+            Some(event) => format!("{:.6} {}\n", time, format_event(event)),
+            None => format!("{:.6} timeout\n", time),
+        };
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+
+        Ok(event)
+    }
+}
+
+/// Replays recorded events in place of those a device would report, in order and regardless of
+/// their recorded times, while output goes to the device as usual, e.g. a
+/// [crate::test::VirtualDevice] whose screen the test inspects. Once the recording is exhausted,
+/// reads time out, or fail if waiting indefinitely since no further input will arrive.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Event, EventReplayer, Interface, KeyCode, KeyEvent, Modifiers, Vector};
+///
+/// let recording = "0.100000 resize 100x30\n0.500000 key ctrl+c\n";
+/// let mut replayer = EventReplayer::new(&mut device, recording.as_bytes())?;
+/// let mut interface = Interface::new_alternate(&mut replayer)?;
+///
+/// assert_eq!(Event::Resize(Vector::new(100, 30)), interface.read_event()?);
+/// let key = KeyEvent::new(KeyCode::Char('c'), Modifiers::new().set_control(true));
+/// assert_eq!(Event::Key(key), interface.read_event()?);
+/// # Ok::<(), Error>(())
+/// ```
+pub struct EventReplayer<'a> {
+    device: DeviceHandle<'a>,
+    events: VecDeque<Option<Event>>,
+}
+
+impl<'a> EventReplayer<'a> {
+    /// Create a replayer of the recording, read from the reader, e.g. a file, for the specified
+    /// device. Fails if a line of the recording isn't a valid event.
+    pub fn new(device: &'a mut dyn Device, recording: impl Read) -> Result<EventReplayer<'a>> {
+        EventReplayer::initialize(DeviceHandle::Borrowed(device), recording)
+    }
+
+    /// Create a replayer which takes ownership of the specified device.
+    pub fn new_owned(
+        device: Box<dyn Device>,
+        recording: impl Read,
+    ) -> Result<EventReplayer<'static>> {
+        EventReplayer::initialize(DeviceHandle::Owned(device), recording)
+    }
+
+    fn initialize(device: DeviceHandle<'a>, mut recording: impl Read) -> Result<EventReplayer<'a>> {
+        let mut text = String::new();
+        recording.read_to_string(&mut text)?;
+
+        let mut events = VecDeque::new();
+        for (number, line) in (1..).zip(text.lines()) {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let event = parse_line(line).ok_or_else(|| invalid_recording(number))?;
+            events.push_back(event);
+        }
+
+        Ok(EventReplayer { device, events })
+    }
+
+    /// The number of recorded reads yet to be replayed.
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+}
+
+impl EventSource for EventReplayer<'_> {
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        match (self.events.pop_front(), timeout) {
+            (Some(event), _) => Ok(event),
+            (None, Some(_)) => Ok(None),
+            (None, None) => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+        }
+    }
+}
+
+/// Implements the device traits other than [EventSource] by delegating to the wrapped device.
+macro_rules! delegate_device {
+    ($device:ident) => {
+        impl SizedTarget for $device<'_> {
+            fn get_terminal_size(&mut self) -> Result<Vector> {
+                self.device.get_terminal_size()
+            }
+        }
+
+        impl RawModeControl for $device<'_> {
+            fn enable_raw_mode(&mut self) -> Result<()> {
+                self.device.enable_raw_mode()
+            }
+
+            fn disable_raw_mode(&mut self) -> Result<()> {
+                self.device.disable_raw_mode()
+            }
+
+            fn is_terminal(&self) -> bool {
+                self.device.is_terminal()
+            }
+        }
+
+        impl CursorQuery for $device<'_> {
+            fn get_cursor_position(&mut self) -> Result<Position> {
+                self.device.get_cursor_position()
+            }
+        }
+
+        impl CommandEncoder for $device<'_> {
+            fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+                self.device.encode(command, buffer)
+            }
+
+            fn styling_preference(&self) -> Option<bool> {
+                self.device.styling_preference()
+            }
+
+            #[cfg(feature = "images")]
+            fn image_protocol(&self) -> ImageProtocol {
+                self.device.image_protocol()
+            }
+        }
+
+        impl Write for $device<'_> {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.device.write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.device.flush()
+            }
+        }
+    };
+}
+
+delegate_device!(EventRecorder);
+delegate_device!(EventReplayer);
+
+fn invalid_recording(line: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid event recording: malformed line {}", line),
+    )
+}
+
+/// Parse a recorded line: its time, which is ignored, and the event read, if any.
+fn parse_line(line: &str) -> Option<Option<Event>> {
+    let mut parts = line.splitn(3, ' ');
+    parts.next()?.parse::<f64>().ok()?;
+    let kind = parts.next()?;
+    let details = parts.next().unwrap_or_default();
+
+    let event = match kind {
+        "timeout" => return Some(None),
+        "key" => Event::Key(KeyEvent::parse(details)?),
+        "mouse" => Event::Mouse(parse_mouse(details)?),
+        "resize" => {
+            let (columns, lines) = details.split_once('x')?;
+            Event::Resize(Vector::new(columns.parse().ok()?, lines.parse().ok()?))
+        }
+        "focus-gained" => Event::FocusGained,
+        "focus-lost" => Event::FocusLost,
+        "terminate" => Event::Terminate,
+        "resume" => Event::Resume,
+        _ => return None,
+    };
+
+    Some(Some(event))
+}
+
+/// Format an event as its kind and details.
+fn format_event(event: &Event) -> String {
+    match event {
+        Event::Key(key) => {
+            let name = match key.code() {
+                KeyCode::Char(' ') => "space".to_string(),
+                KeyCode::Char(character) => character.to_string(),
+                KeyCode::Enter => "enter".to_string(),
+                KeyCode::Escape => "esc".to_string(),
+                KeyCode::Backspace => "backspace".to_string(),
+                KeyCode::Tab => "tab".to_string(),
+                KeyCode::BackTab => "backtab".to_string(),
+                KeyCode::Delete => "delete".to_string(),
+                KeyCode::Insert => "insert".to_string(),
+                KeyCode::Left => "left".to_string(),
+                KeyCode::Right => "right".to_string(),
+                KeyCode::Up => "up".to_string(),
+                KeyCode::Down => "down".to_string(),
+                KeyCode::Home => "home".to_string(),
+                KeyCode::End => "end".to_string(),
+                KeyCode::PageUp => "pageup".to_string(),
+                KeyCode::PageDown => "pagedown".to_string(),
+                KeyCode::F(number) => format!("f{}", number),
+            };
+
+            let mut chord = format_modifiers(key.modifiers());
+            if !chord.is_empty() {
+                chord.push('+');
+            }
+            format!("key {}{}", chord, name)
+        }
+        Event::Mouse(mouse) => {
+            let button = |button| match button {
+                MouseButton::Left => "left",
+                MouseButton::Right => "right",
+                MouseButton::Middle => "middle",
+            };
+            let kind = match mouse.kind() {
+                MouseEventKind::Press(pressed) => format!("press:{}", button(pressed)),
+                MouseEventKind::Release(released) => format!("release:{}", button(released)),
+                MouseEventKind::Drag(held) => format!("drag:{}", button(held)),
+                MouseEventKind::Move => "move".to_string(),
+                MouseEventKind::ScrollUp => "scroll-up".to_string(),
+                MouseEventKind::ScrollDown => "scroll-down".to_string(),
+            };
+
+            let position = mouse.position();
+            let modifiers = format_modifiers(mouse.modifiers());
+            format!(
+                "mouse {} {} {} {}",
+                kind,
+                position.x(),
+                position.y(),
+                modifiers
+            )
+            .trim_end()
+            .to_string()
+        }
+        Event::Resize(size) => format!("resize {}x{}", size.x(), size.y()),
+        Event::FocusGained => "focus-gained".to_string(),
+        Event::FocusLost => "focus-lost".to_string(),
+        Event::Terminate => "terminate".to_string(),
+        Event::Resume => "resume".to_string(),
+    }
+}
+
+/// Format the held modifiers as they're written in key chords, e.g. "ctrl+alt".
+fn format_modifiers(modifiers: Modifiers) -> String {
+    let names = [
+        (modifiers.is_control(), "ctrl"),
+        (modifiers.is_alt(), "alt"),
+        (modifiers.is_shift(), "shift"),
+    ];
+
+    let held: Vec<&str> = names
+        .into_iter()
+        .filter_map(|(is_held, name)| is_held.then_some(name))
+        .collect();
+    held.join("+")
+}
+
+/// Parse a mouse event's details: its kind, column, line and any modifiers held.
+fn parse_mouse(details: &str) -> Option<MouseEvent> {
+    let mut parts = details.split_whitespace();
+    let kind = parts.next()?;
+    let position = pos!(parts.next()?.parse().ok()?, parts.next()?.parse().ok()?);
+
+    let mut modifiers = Modifiers::new();
+    for name in parts.next().unwrap_or_default().split('+') {
+        modifiers = match name {
+            "" => modifiers,
+            "ctrl" => modifiers.set_control(true),
+            "alt" => modifiers.set_alt(true),
+            "shift" => modifiers.set_shift(true),
+            _ => return None,
+        };
+    }
+
+    let button = |name| match name {
+        "left" => Some(MouseButton::Left),
+        "right" => Some(MouseButton::Right),
+        "middle" => Some(MouseButton::Middle),
+        _ => None,
+    };
+    let kind = match kind.split_once(':') {
+        Some(("press", name)) => MouseEventKind::Press(button(name)?),
+        Some(("release", name)) => MouseEventKind::Release(button(name)?),
+        Some(("drag", name)) => MouseEventKind::Drag(button(name)?),
+        None if kind == "move" => MouseEventKind::Move,
+        None if kind == "scroll-up" => MouseEventKind::ScrollUp,
+        None if kind == "scroll-down" => MouseEventKind::ScrollDown,
+        _ => return None,
+    };
+
+    Some(MouseEvent::new(kind, position, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pos, Event, KeyCode, KeyEvent, Modifiers, MouseButton, MouseEvent, MouseEventKind,
+        Position, Vector,
+    };
+
+    use super::{format_event, parse_line};
+
+    #[test]
+    fn events_format_and_parse() {
+        let modifiers = Modifiers::new().set_control(true).set_shift(true);
+        let events = [
+            Event::Key(KeyEvent::new(KeyCode::Char('x'), Modifiers::new())),
+            Event::Key(KeyEvent::new(KeyCode::Char(' '), modifiers)),
+            Event::Key(KeyEvent::new(
+                KeyCode::Char('+'),
+                Modifiers::new().set_alt(true),
+            )),
+            Event::Key(KeyEvent::new(KeyCode::F(12), Modifiers::new())),
+            Event::Key(KeyEvent::new(KeyCode::PageDown, Modifiers::new())),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::Drag(MouseButton::Middle),
+                pos!(3, 4),
+                modifiers,
+            )),
+            Event::Mouse(MouseEvent::new(
+                MouseEventKind::ScrollUp,
+                pos!(0, 0),
+                Modifiers::new(),
+            )),
+            Event::Resize(Vector::new(100, 30)),
+            Event::FocusLost,
+            Event::Resume,
+        ];
+
+        for event in events {
+            let line = format!("0.500000 {}", format_event(&event));
+            assert_eq!(Some(Some(event)), parse_line(&line), "{}", line);
+        }
+
+        assert_eq!(
+            "key ctrl+shift+space",
+            format_event(&Event::Key(KeyEvent::new(KeyCode::Char(' '), modifiers)))
+        );
+        assert_eq!(Some(None), parse_line("1.0 timeout"));
+        assert_eq!(None, parse_line("1.0 key"));
+        assert_eq!(None, parse_line("soon key x"));
+        assert_eq!(None, parse_line("1.0 mouse press:thumb 1 1"));
+    }
+}
@@ -0,0 +1,377 @@
+//! A minimal C ABI for embedding the renderer in non-Rust tools. Build a shared or static library
+//! with the `ffi` feature, e.g. `cargo rustc --release --features ffi --crate-type cdylib`, and
+//! declare the functions as follows:
+//!
+//! ```c
+//! typedef struct TtyInterface TtyInterface;
+//!
+//! TtyInterface *tty_interface_new_alternate(int fd);
+//! TtyInterface *tty_interface_new_relative(int fd);
+//! int tty_interface_set(TtyInterface *interface, uint16_t x, uint16_t y, const char *text);
+//! int tty_interface_clear_line(TtyInterface *interface, uint16_t line);
+//! int tty_interface_apply(TtyInterface *interface);
+//! int tty_interface_exit(TtyInterface *interface);
+//! ```
+//!
+//! Functions returning `int` return [TTY_INTERFACE_OK] on success or a negative error code. An
+//! interface is freed by `tty_interface_exit` regardless of its result.
+//!
+//! An interface may be moved to and used from any thread, but not from multiple threads
+//! concurrently: calls taking the same interface must be serialized by the caller, such as with a
+//! mutex. Separate interfaces may be used concurrently, although they shouldn't share a terminal.
+//! Each interface renders from a thread of its own, which performs its calls as they're made.
+
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    ptr,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+use crate::{ansi::AnsiTerminal, pos, Device, Error, Interface, Position, Result};
+
+/// The operation completed successfully.
+pub const TTY_INTERFACE_OK: c_int = 0;
+
+/// A required argument was null.
+pub const TTY_INTERFACE_NULL_ARGUMENT: c_int = -1;
+
+/// A text argument was not valid UTF-8.
+pub const TTY_INTERFACE_INVALID_TEXT: c_int = -2;
+
+/// A low-level terminal interaction failed.
+pub const TTY_INTERFACE_TERMINAL_ERROR: c_int = -3;
+
+/// Map an interface error to its C error code.
+fn get_error_code(error: &Error) -> c_int {
+    match error {
+        Error::Terminal(_) | Error::Apply(_) => TTY_INTERFACE_TERMINAL_ERROR,
+    }
+}
+
+/// Map an interface result to its C result code.
+fn get_result_code(result: Result<()>) -> c_int {
+    match result {
+        Ok(()) => TTY_INTERFACE_OK,
+        Err(error) => get_error_code(&error),
+    }
+}
+
+/// An interface rendering to a file descriptor, owned by a C caller. Since interfaces may hold
+/// devices and callbacks which can't be sent between threads, the interface lives on a thread of
+/// its own which performs each call, leaving a handle which may be moved between threads.
+pub struct TtyInterface {
+    requests: Sender<Request>,
+    results: Receiver<c_int>,
+    worker: JoinHandle<()>,
+}
+
+/// A call for an interface's thread to perform, responding with its result code.
+enum Request {
+    Call(Box<dyn FnOnce(&mut Interface<'static>) -> c_int + Send>),
+    Exit,
+}
+
+impl TtyInterface {
+    /// Perform the call on the interface's thread, returning its result code.
+    fn call(&self, call: impl FnOnce(&mut Interface<'static>) -> c_int + Send + 'static) -> c_int {
+        match self.requests.send(Request::Call(Box::new(call))) {
+            Ok(()) => self.results.recv().unwrap_or(TTY_INTERFACE_TERMINAL_ERROR),
+            Err(_) => TTY_INTERFACE_TERMINAL_ERROR,
+        }
+    }
+
+    /// Exit the interface and wait for its thread to finish, returning the exit's result code.
+    fn exit(self) -> c_int {
+        let code = match self.requests.send(Request::Exit) {
+            Ok(()) => self.results.recv().unwrap_or(TTY_INTERFACE_TERMINAL_ERROR),
+            Err(_) => TTY_INTERFACE_TERMINAL_ERROR,
+        };
+
+        let _ = self.worker.join();
+        code
+    }
+}
+
+/// Create the interface with the constructor, then perform the requested calls until it's exited
+/// or its handle's dropped.
+fn run_interface(
+    device: AnsiTerminal,
+    constructor: fn(Box<dyn Device>) -> Result<Interface<'static>>,
+    requests: Receiver<Request>,
+    results: Sender<c_int>,
+) {
+    let mut interface = match constructor(Box::new(device)) {
+        Ok(interface) => interface,
+        Err(error) => {
+            let _ = results.send(get_error_code(&error));
+            return;
+        }
+    };
+
+    let _ = results.send(TTY_INTERFACE_OK);
+    for request in requests {
+        match request {
+            Request::Call(call) => {
+                let _ = results.send(call(&mut interface));
+            }
+            Request::Exit => {
+                let _ = results.send(get_result_code(interface.exit()));
+                return;
+            }
+        }
+    }
+}
+
+/// Create an interface rendering to the alternate screen of the terminal at `fd`, or null on
+/// failure. The caller retains ownership of the file descriptor.
+///
+/// # Safety
+/// `fd` must be a valid, open file descriptor for a terminal for the interface's lifetime.
+#[no_mangle]
+pub unsafe extern "C" fn tty_interface_new_alternate(fd: c_int) -> *mut TtyInterface {
+    create_interface(fd, Interface::new_alternate_owned)
+}
+
+/// Create an interface rendering relative to the cursor in the terminal at `fd`, or null on
+/// failure. The caller retains ownership of the file descriptor.
+///
+/// # Safety
+/// `fd` must be a valid, open file descriptor for a terminal for the interface's lifetime.
+#[no_mangle]
+pub unsafe extern "C" fn tty_interface_new_relative(fd: c_int) -> *mut TtyInterface {
+    create_interface(fd, Interface::new_relative_owned)
+}
+
+/// Create a C-owned interface for the specified file descriptor using a constructor.
+unsafe fn create_interface(
+    fd: c_int,
+    constructor: fn(Box<dyn Device>) -> Result<Interface<'static>>,
+) -> *mut TtyInterface {
+    let device = AnsiTerminal::from_raw_fd(fd);
+    let (requests, pending_requests) = mpsc::channel();
+    let (pending_results, results) = mpsc::channel();
+    let worker = thread::spawn(move || {
+        run_interface(device, constructor, pending_requests, pending_results)
+    });
+
+    match results.recv() {
+        Ok(TTY_INTERFACE_OK) => Box::into_raw(Box::new(TtyInterface {
+            requests,
+            results,
+            worker,
+        })),
+        _ => {
+            let _ = worker.join();
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Stage NUL-terminated UTF-8 text at the specified position.
+///
+/// # Safety
+/// `interface` must be a live interface pointer and `text` a NUL-terminated string, if non-null.
+#[no_mangle]
+pub unsafe extern "C" fn tty_interface_set(
+    interface: *mut TtyInterface,
+    x: u16,
+    y: u16,
+    text: *const c_char,
+) -> c_int {
+    let interface = match get_interface(interface) {
+        Some(interface) => interface,
+        None => return TTY_INTERFACE_NULL_ARGUMENT,
+    };
+
+    if text.is_null() {
+        return TTY_INTERFACE_NULL_ARGUMENT;
+    }
+
+    match CStr::from_ptr(text).to_str() {
+        Ok(text) => {
+            let text = text.to_string();
+            interface.call(move |interface| {
+                interface.set(pos!(x, y), &text);
+                TTY_INTERFACE_OK
+            })
+        }
+        Err(_) => TTY_INTERFACE_INVALID_TEXT,
+    }
+}
+
+/// Stage clearing all text on the specified line.
+///
+/// # Safety
+/// `interface` must be a live interface pointer, if non-null.
+#[no_mangle]
+pub unsafe extern "C" fn tty_interface_clear_line(
+    interface: *mut TtyInterface,
+    line: u16,
+) -> c_int {
+    match get_interface(interface) {
+        Some(interface) => interface.call(move |interface| {
+            interface.clear_line(line);
+            TTY_INTERFACE_OK
+        }),
+        None => TTY_INTERFACE_NULL_ARGUMENT,
+    }
+}
+
+/// Apply staged changes to the terminal.
+///
+/// # Safety
+/// `interface` must be a live interface pointer, if non-null.
+#[no_mangle]
+pub unsafe extern "C" fn tty_interface_apply(interface: *mut TtyInterface) -> c_int {
+    match get_interface(interface) {
+        Some(interface) => interface.call(|interface| get_result_code(interface.apply())),
+        None => TTY_INTERFACE_NULL_ARGUMENT,
+    }
+}
+
+/// Restore the terminal's configuration and free the interface.
+///
+/// # Safety
+/// `interface` must be a live interface pointer, if non-null, and is invalid after this call.
+#[no_mangle]
+pub unsafe extern "C" fn tty_interface_exit(interface: *mut TtyInterface) -> c_int {
+    if interface.is_null() {
+        return TTY_INTERFACE_NULL_ARGUMENT;
+    }
+
+    Box::from_raw(interface).exit()
+}
+
+/// Resolve a C interface pointer to its interface, if non-null.
+unsafe fn get_interface<'a>(interface: *mut TtyInterface) -> Option<&'a TtyInterface> {
+    interface.as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::CString, io::Read, os::unix::io::FromRawFd, ptr};
+
+    use super::*;
+
+    /// Open a pseudo-terminal, returning its controller and the fd of its terminal end.
+    fn open_pty() -> (std::fs::File, c_int) {
+        let (mut controller, mut terminal) = (0, 0);
+        let size = libc::winsize {
+            ws_row: 24,
+            ws_col: 80,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        let result = unsafe {
+            libc::openpty(
+                &mut controller,
+                &mut terminal,
+                ptr::null_mut(),
+                ptr::null(),
+                &size,
+            )
+        };
+        assert_eq!(0, result);
+
+        (unsafe { std::fs::File::from_raw_fd(controller) }, terminal)
+    }
+
+    #[test]
+    fn ffi_render_to_pty() {
+        let (mut controller, terminal) = open_pty();
+
+        unsafe {
+            let interface = tty_interface_new_alternate(terminal);
+            assert!(!interface.is_null());
+
+            let text = CString::new("Hello, FFI!").unwrap();
+            assert_eq!(
+                TTY_INTERFACE_OK,
+                tty_interface_set(interface, 2, 1, text.as_ptr())
+            );
+            assert_eq!(TTY_INTERFACE_OK, tty_interface_apply(interface));
+            assert_eq!(TTY_INTERFACE_OK, tty_interface_exit(interface));
+
+            libc::close(terminal);
+        }
+
+        let mut output = Vec::new();
+        let _ = controller.read_to_end(&mut output);
+
+        assert!(output.windows(11).any(|window| window == b"Hello, FFI!"));
+    }
+
+    #[test]
+    fn ffi_interfaces_used_across_threads() {
+        let (mut controller, terminal) = open_pty();
+
+        let interface = unsafe { tty_interface_new_alternate(terminal) };
+        assert!(!interface.is_null());
+
+        let interface = unsafe { Box::from_raw(interface) };
+        let text = CString::new("Moved").unwrap();
+        let applied = std::thread::spawn(move || unsafe {
+            let interface = Box::into_raw(interface);
+            assert_eq!(
+                TTY_INTERFACE_OK,
+                tty_interface_set(interface, 0, 0, text.as_ptr())
+            );
+            assert_eq!(TTY_INTERFACE_OK, tty_interface_apply(interface));
+            Box::from_raw(interface)
+        });
+
+        let interface = Box::into_raw(applied.join().unwrap());
+        unsafe {
+            assert_eq!(TTY_INTERFACE_OK, tty_interface_clear_line(interface, 0));
+            assert_eq!(TTY_INTERFACE_OK, tty_interface_exit(interface));
+            libc::close(terminal);
+        }
+
+        let mut output = Vec::new();
+        let _ = controller.read_to_end(&mut output);
+
+        assert!(output.windows(5).any(|window| window == b"Moved"));
+    }
+
+    #[test]
+    fn ffi_invalid_arguments() {
+        unsafe {
+            let text = CString::new("text").unwrap();
+            assert_eq!(
+                TTY_INTERFACE_NULL_ARGUMENT,
+                tty_interface_set(ptr::null_mut(), 0, 0, text.as_ptr())
+            );
+            assert_eq!(
+                TTY_INTERFACE_NULL_ARGUMENT,
+                tty_interface_apply(ptr::null_mut())
+            );
+            assert_eq!(
+                TTY_INTERFACE_NULL_ARGUMENT,
+                tty_interface_exit(ptr::null_mut())
+            );
+        }
+    }
+
+    #[test]
+    fn ffi_invalid_text() {
+        let (_controller, terminal) = open_pty();
+
+        unsafe {
+            let interface = tty_interface_new_alternate(terminal);
+            let text = [0xFFu8 as c_char, 0];
+            assert_eq!(
+                TTY_INTERFACE_INVALID_TEXT,
+                tty_interface_set(interface, 0, 0, text.as_ptr())
+            );
+            assert_eq!(
+                TTY_INTERFACE_NULL_ARGUMENT,
+                tty_interface_set(interface, 0, 0, ptr::null())
+            );
+            tty_interface_exit(interface);
+            libc::close(terminal);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{Event, Interface, KeyCode, KeyEvent, Position};
+
+/// A region of an interface, such as an input field, which may own the cursor and keyboard
+/// input while focused by a [FocusManager].
+pub trait Focusable {
+    /// Where the cursor is shown while this is focused, or `None` to hide it.
+    fn cursor_position(&self) -> Option<Position>;
+
+    /// Handle a key pressed while this is focused, returning whether it was consumed. Keys are
+    /// ignored by default.
+    fn handle_key(&mut self, _event: KeyEvent) -> bool {
+        false
+    }
+}
+
+impl<T: Focusable + ?Sized> Focusable for Box<T> {
+    fn cursor_position(&self) -> Option<Position> {
+        (**self).cursor_position()
+    }
+
+    fn handle_key(&mut self, event: KeyEvent) -> bool {
+        (**self).handle_key(event)
+    }
+}
+
+/// Tracks which of an ordered set of regions is focused, cycling focus between them with Tab and
+/// Shift+Tab and directing other keys to the focused region. Regions of different types may be
+/// managed together as `Box<dyn Focusable>`.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{
+///     Event, FocusManager, Focusable, Interface, KeyCode, KeyEvent, Modifiers, Position, pos,
+/// };
+///
+/// struct Field {
+///     line: u16,
+///     value: String,
+/// }
+///
+/// impl Focusable for Field {
+///     fn cursor_position(&self) -> Option<Position> {
+///         Some(pos!(self.value.len() as u16, self.line))
+///     }
+///
+///     fn handle_key(&mut self, event: KeyEvent) -> bool {
+///         match event.code() {
+///             KeyCode::Char(character) => self.value.push(character),
+///             _ => return false,
+///         }
+///
+///         true
+///     }
+/// }
+///
+/// let mut focus = FocusManager::new();
+/// focus.add(Field { line: 0, value: String::new() });
+/// focus.add(Field { line: 1, value: String::new() });
+///
+/// let tab = Event::Key(KeyEvent::new(KeyCode::Tab, Modifiers::new()));
+/// let key = Event::Key(KeyEvent::new(KeyCode::Char('a'), Modifiers::new()));
+/// assert!(focus.handle_event(&tab));
+/// assert!(focus.handle_event(&key));
+/// assert_eq!("a", focus.widget(1).unwrap().value);
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+/// focus.update_cursor(&mut interface);
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct FocusManager<W> {
+    widgets: Vec<W>,
+    focused: Option<usize>,
+}
+
+impl<W: Focusable> FocusManager<W> {
+    /// Create a manager with no regions.
+    pub fn new() -> FocusManager<W> {
+        FocusManager {
+            widgets: Vec::new(),
+            focused: None,
+        }
+    }
+
+    /// Add a region after those already added, returning its index. The first region added is
+    /// focused.
+    pub fn add(&mut self, widget: W) -> usize {
+        self.widgets.push(widget);
+        self.focused.get_or_insert(0);
+        self.widgets.len() - 1
+    }
+
+    /// The managed regions, in focus order.
+    pub fn widgets(&self) -> &[W] {
+        &self.widgets
+    }
+
+    /// The region at the specified index, if any.
+    pub fn widget(&self, index: usize) -> Option<&W> {
+        self.widgets.get(index)
+    }
+
+    /// The region at the specified index, if any, for modification.
+    pub fn widget_mut(&mut self, index: usize) -> Option<&mut W> {
+        self.widgets.get_mut(index)
+    }
+
+    /// The focused region's index, if any regions have been added.
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// The focused region, if any.
+    pub fn focused_widget(&self) -> Option<&W> {
+        self.widgets.get(self.focused?)
+    }
+
+    /// Focus the region at the specified index. Has no effect if there's no such region.
+    pub fn focus(&mut self, index: usize) {
+        if index < self.widgets.len() {
+            self.focused = Some(index);
+        }
+    }
+
+    /// Focus the region following the focused one, wrapping to the first.
+    pub fn focus_next(&mut self) {
+        if let Some(focused) = self.focused {
+            self.focused = Some((focused + 1) % self.widgets.len());
+        }
+    }
+
+    /// Focus the region preceding the focused one, wrapping to the last.
+    pub fn focus_previous(&mut self) {
+        if let Some(focused) = self.focused {
+            let count = self.widgets.len();
+            self.focused = Some((focused + count - 1) % count);
+        }
+    }
+
+    /// Handle an input event, cycling focus for Tab and Shift+Tab and otherwise passing keys to
+    /// the focused region. Returns whether the event was consumed.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let event = match event {
+            Event::Key(event) => *event,
+            _ => return false,
+        };
+
+        match event.code() {
+            KeyCode::Tab if event.modifiers().is_shift() => self.focus_previous(),
+            KeyCode::Tab => self.focus_next(),
+            KeyCode::BackTab => self.focus_previous(),
+            _ => {
+                return match self.focused {
+                    Some(focused) => self.widgets[focused].handle_key(event),
+                    None => false,
+                }
+            }
+        }
+
+        self.focused.is_some()
+    }
+
+    /// Stage the interface's cursor at the focused region's cursor position, hiding it if there's
+    /// no focused region or it has no cursor.
+    pub fn update_cursor(&self, interface: &mut Interface) {
+        let position = self.focused_widget().and_then(Focusable::cursor_position);
+        interface.set_cursor(position);
+    }
+}
+
+impl<W: Focusable> Default for FocusManager<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FocusManager, Focusable};
+    use crate::{pos, Event, KeyCode, KeyEvent, Modifiers, Position};
+
+    struct Label;
+
+    impl Focusable for Label {
+        fn cursor_position(&self) -> Option<Position> {
+            None
+        }
+    }
+
+    struct Field(u16, usize);
+
+    impl Focusable for Field {
+        fn cursor_position(&self) -> Option<Position> {
+            Some(pos!(self.1 as u16, self.0))
+        }
+
+        fn handle_key(&mut self, _event: KeyEvent) -> bool {
+            self.1 += 1;
+            true
+        }
+    }
+
+    fn key(code: KeyCode, modifiers: Modifiers) -> Event {
+        Event::Key(KeyEvent::new(code, modifiers))
+    }
+
+    #[test]
+    fn focus_cycles() {
+        let mut focus: FocusManager<Box<dyn Focusable>> = FocusManager::new();
+        assert_eq!(None, focus.focused());
+        assert!(!focus.handle_event(&key(KeyCode::Tab, Modifiers::new())));
+
+        focus.add(Box::new(Field(0, 0)));
+        focus.add(Box::new(Label));
+        focus.add(Box::new(Field(2, 3)));
+        assert_eq!(Some(0), focus.focused());
+
+        assert!(focus.handle_event(&key(KeyCode::BackTab, Modifiers::new())));
+        assert_eq!(Some(2), focus.focused());
+        assert!(focus.handle_event(&key(KeyCode::Tab, Modifiers::new())));
+        assert_eq!(Some(0), focus.focused());
+        assert!(focus.handle_event(&key(KeyCode::Tab, Modifiers::new().set_shift(true))));
+        assert_eq!(Some(2), focus.focused());
+
+        focus.focus(1);
+        assert_eq!(None, focus.focused_widget().unwrap().cursor_position());
+        focus.focus(5);
+        assert_eq!(Some(1), focus.focused());
+    }
+
+    #[test]
+    fn focus_directs_keys() {
+        let mut focus = FocusManager::new();
+        focus.add(Field(0, 0));
+        focus.add(Field(1, 0));
+
+        let character = key(KeyCode::Char('a'), Modifiers::new());
+        assert!(focus.handle_event(&character));
+        focus.focus_next();
+        assert!(focus.handle_event(&character));
+        assert!(focus.handle_event(&character));
+        assert!(!focus.handle_event(&Event::FocusLost));
+
+        assert_eq!(1, focus.widget(0).unwrap().1);
+        assert_eq!(2, focus.widget(1).unwrap().1);
+        assert_eq!(
+            Some(pos!(2, 1)),
+            focus.focused_widget().unwrap().cursor_position()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{
+    pos,
+    text::{split_graphemes, Widths},
+    Color, Interface, Position, Rect, Style,
+};
+
+/// Partial blocks filling a cell from the left by eighths, indexed by eighths filled.
+const BLOCKS: [&str; 8] = [" ", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+
+/// A bar filled from the left in proportion to a ratio, such as for a task's progress, labeled
+/// with centered text. The label takes the filled or unfilled style of the cells beneath it, so it
+/// remains legible where the bar's fill ends.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Color, Gauge, Interface, Position, Rect, Style, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let gauge = Gauge::new().set_filled_style(Style::new().set_background(Color::Blue));
+/// let bar = Rect::new(pos!(0, 0), Vector::new(20, 1));
+///
+/// // Labeled "42%"
+/// gauge.render(&mut interface, bar, 0.42);
+///
+/// // Labeled with custom text
+/// gauge.render_labeled(&mut interface, bar, 0.5, "3/6 files");
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Gauge {
+    filled_style: Style,
+    unfilled_style: Style,
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Gauge {
+    /// Create a gauge filled with black text on green and unstyled where unfilled.
+    pub fn new() -> Gauge {
+        Gauge {
+            filled_style: Style::new()
+                .set_foreground(Color::Black)
+                .set_background(Color::Green),
+            unfilled_style: Style::new(),
+        }
+    }
+
+    /// Create a new gauge with the specified style where filled. Its background colors the fill.
+    pub fn set_filled_style(self, filled_style: Style) -> Gauge {
+        Gauge {
+            filled_style,
+            ..self
+        }
+    }
+
+    /// Create a new gauge with the specified style where unfilled.
+    pub fn set_unfilled_style(self, unfilled_style: Style) -> Gauge {
+        Gauge {
+            unfilled_style,
+            ..self
+        }
+    }
+
+    /// This gauge's style where filled.
+    pub fn filled_style(&self) -> Style {
+        self.filled_style
+    }
+
+    /// This gauge's style where unfilled.
+    pub fn unfilled_style(&self) -> Style {
+        self.unfilled_style
+    }
+
+    /// Stage the gauge filling the rectangle in proportion to the ratio, between zero and one,
+    /// labeled with the ratio as a percentage.
+    pub fn render(&self, interface: &mut Interface, rect: Rect, ratio: f64) {
+        let percentage = (ratio.clamp(0.0, 1.0) * 100.0).round();
+        self.render_labeled(interface, rect, ratio, &format!("{}%", percentage));
+    }
+
+    /// Stage the gauge filling the rectangle in proportion to the ratio, between zero and one,
+    /// labeled with the text centered on its middle line. Text which doesn't fit is truncated.
+    pub fn render_labeled(&self, interface: &mut Interface, rect: Rect, ratio: f64, label: &str) {
+        let (origin, size) = (rect.origin(), rect.size());
+        let ratio = match ratio.is_nan() {
+            true => 0.0,
+            false => ratio.clamp(0.0, 1.0),
+        };
+
+        let eighths = (ratio * size.x() as f64 * 8.0).round() as u32;
+        let (filled_columns, partial_eighths) = ((eighths / 8) as u16, (eighths % 8) as usize);
+
+        // A cell's style is filled if at least half of it is
+        let style_at = |column: u16| match column < filled_columns
+            || column == filled_columns && partial_eighths >= 4
+        {
+            true => self.filled_style,
+            false => self.unfilled_style,
+        };
+
+        // The partial cell shows its fill as a block colored like the filled background
+        let mut partial_style = self.unfilled_style;
+        if let Some(color) = self.filled_style.background() {
+            partial_style = partial_style.set_foreground(color);
+        }
+
+        let widths = interface.widths().clone();
+        let label = fit_label(label, size.x(), &widths);
+        let label_width: u16 = label.iter().map(|g| widths.grapheme_width(g)).sum();
+        let label_start = (size.x() - label_width) / 2;
+        let label_line = size.y().saturating_sub(1) / 2;
+
+        for line in 0..size.y() {
+            let y = origin.y().saturating_add(line);
+
+            for column in 0..size.x() {
+                let position = pos!(origin.x().saturating_add(column), y);
+                if column == filled_columns && partial_eighths > 0 {
+                    interface.set_styled(position, BLOCKS[partial_eighths], partial_style);
+                } else if column < filled_columns {
+                    interface.set_styled(position, " ", self.filled_style);
+                } else {
+                    interface.set_styled(position, " ", self.unfilled_style);
+                }
+            }
+
+            if line == label_line {
+                let mut column = label_start;
+                for grapheme in &label {
+                    let position = pos!(origin.x().saturating_add(column), y);
+                    interface.set_styled(position, grapheme, style_at(column));
+                    column += widths.grapheme_width(grapheme);
+                }
+            }
+        }
+    }
+}
+
+/// The label's graphemes fitting within the width, measured with the widths.
+fn fit_label<'a>(label: &'a str, width: u16, widths: &Widths) -> Vec<&'a str> {
+    let label = label.lines().next().unwrap_or_default();
+
+    let mut used = 0;
+    let mut graphemes = Vec::new();
+    for grapheme in split_graphemes(label) {
+        used += widths.grapheme_width(grapheme);
+        if used > width {
+            break;
+        }
+
+        graphemes.push(grapheme);
+    }
+
+    graphemes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::Widths;
+
+    use super::fit_label;
+
+    #[test]
+    fn gauge_fit_label() {
+        assert_eq!(
+            vec!["5", "0", "%"],
+            fit_label("50%", 10, &Widths::default())
+        );
+        assert_eq!(vec!["a", "b"], fit_label("abc", 2, &Widths::default()));
+        assert_eq!(vec!["日"], fit_label("日本", 3, &Widths::default()));
+        assert_eq!(vec!["a"], fit_label("a\nb", 3, &Widths::default()));
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    text::{grapheme_width, normalize, split_graphemes},
+    Cell, Position, State, Style,
+};
+
+/// Content rendered above the interface's cells which can follow a position without disturbing
+/// them, such as a preview of an item being dragged. Cells obscured by a ghost are restored when
+/// it moves or is cleared.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{Ghost, Style};
+///
+/// let ghost = Ghost::new("Item 3").set_style(Style::new().set_italic(true));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Ghost {
+    graphemes: Vec<(Position, String)>,
+    style: Option<Style>,
+}
+
+impl Ghost {
+    /// Create a new ghost from the specified text, which may span multiple lines.
+    pub fn new(text: &str) -> Ghost {
+        let mut graphemes = Vec::new();
+
+        for (line, text) in normalize(text).lines().enumerate() {
+            let mut column = 0;
+            for grapheme in split_graphemes(text) {
+                graphemes.push((Position::new(column, line as u16), grapheme.to_string()));
+                column += grapheme_width(grapheme);
+            }
+        }
+
+        Ghost {
+            graphemes,
+            style: None,
+        }
+    }
+
+    /// Create a new ghost with the specified style applied to its content.
+    pub fn set_style(self, style: Style) -> Ghost {
+        Ghost {
+            style: Some(style),
+            ..self
+        }
+    }
+
+    /// This ghost's style, if specified.
+    pub fn style(&self) -> Option<Style> {
+        self.style
+    }
+
+    /// This ghost's graphemes positioned relative to the specified origin, omitting any which
+    /// would lie beyond the terminal's coordinate space.
+    pub(crate) fn graphemes_at(&self, origin: Position) -> impl Iterator<Item = (Position, &str)> {
+        self.graphemes.iter().filter_map(move |(offset, grapheme)| {
+            let x = origin.x().checked_add(offset.x())?;
+            let y = origin.y().checked_add(offset.y())?;
+            Some((Position::new(x, y), grapheme.as_str()))
+        })
+    }
+}
+
+/// An interface's ghost, if any, and the overlay last composited above its content, including its
+/// marks and notifications, so the content they obscure may be restored.
+pub(crate) struct GhostState {
+    ghost: Option<(Position, Ghost)>,
+    rendered: BTreeMap<Position, Cell>,
+    is_changed: bool,
+}
+
+impl GhostState {
+    /// Create a state without a ghost or a rendered overlay.
+    pub(crate) fn new() -> GhostState {
+        GhostState {
+            ghost: None,
+            rendered: BTreeMap::new(),
+            is_changed: false,
+        }
+    }
+
+    /// Show the ghost at the specified position, replacing any existing ghost.
+    pub(crate) fn set(&mut self, position: Position, ghost: Ghost) {
+        self.ghost = Some((position, ghost));
+        self.is_changed = true;
+    }
+
+    /// Move the ghost, if any, to the specified position. Returns whether there was a ghost.
+    pub(crate) fn move_to(&mut self, position: Position) -> bool {
+        match &mut self.ghost {
+            Some((ghost_position, _)) => {
+                *ghost_position = position;
+                self.is_changed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the ghost, if any. Returns whether there was a ghost.
+    pub(crate) fn clear(&mut self) -> bool {
+        let is_cleared = self.ghost.take().is_some();
+        self.is_changed |= is_cleared;
+        is_cleared
+    }
+
+    /// Whether a ghost is shown.
+    pub(crate) fn is_shown(&self) -> bool {
+        self.ghost.is_some()
+    }
+
+    /// The ghost's cells, if any, at its position.
+    pub(crate) fn cells(&self) -> impl Iterator<Item = (Position, Cell)> + '_ {
+        self.ghost.iter().flat_map(|(position, ghost)| {
+            ghost
+                .graphemes_at(*position)
+                .map(|(position, grapheme)| (position, Cell::new(grapheme, ghost.style())))
+        })
+    }
+
+    /// Record that the overlay changed and must be composited with the next frame.
+    pub(crate) fn mark_changed(&mut self) {
+        self.is_changed = true;
+    }
+
+    /// Whether the overlay changed since it was last composited.
+    pub(crate) fn is_changed(&self) -> bool {
+        self.is_changed
+    }
+
+    /// The overlay's cells as last composited.
+    pub(crate) fn rendered(&self) -> &BTreeMap<Position, Cell> {
+        &self.rendered
+    }
+
+    /// Composite the overlay onto the cell updates, restoring the content from the state where
+    /// the previous overlay's cells are no longer covered, and rendering the overlay's cells
+    /// which changed or were overwritten by the updates.
+    pub(crate) fn composite(
+        &mut self,
+        overlay: BTreeMap<Position, Cell>,
+        state: &State,
+        cells: &mut BTreeMap<Position, Option<Cell>>,
+    ) {
+        for position in self.rendered.keys() {
+            if !overlay.contains_key(position) {
+                cells.insert(*position, state.get_cell(*position).cloned());
+            }
+        }
+
+        for (position, cell) in &overlay {
+            let is_rendered = self.rendered.get(position) == Some(cell);
+            if !is_rendered || cells.contains_key(position) {
+                cells.insert(*position, Some(cell.clone()));
+            }
+        }
+
+        self.rendered = overlay;
+        self.is_changed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pos, Position};
+
+    use super::Ghost;
+
+    #[test]
+    fn ghost_graphemes() {
+        let ghost = Ghost::new("A漢B\nCD");
+
+        let graphemes: Vec<_> = ghost.graphemes_at(pos!(2, 3)).collect();
+        assert_eq!(
+            vec![
+                (pos!(2, 3), "A"),
+                (pos!(3, 3), "漢"),
+                (pos!(5, 3), "B"),
+                (pos!(2, 4), "C"),
+                (pos!(3, 4), "D"),
+            ],
+            graphemes
+        );
+    }
+
+    #[test]
+    fn ghost_graphemes_clipped() {
+        let ghost = Ghost::new("AB");
+
+        let graphemes: Vec<_> = ghost.graphemes_at(pos!(u16::MAX, 0)).collect();
+        assert_eq!(vec![(pos!(u16::MAX, 0), "A")], graphemes);
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::string::String;
+
+use crate::{
+    pos,
+    text::{grapheme_width, normalize, split_graphemes},
+    Position, Rect, State, Style, Vector,
+};
+
+/// A grid of styled text which records its changed cells, the rendering core beneath the
+/// interface. It needs only `alloc`, so an application may diff frames for output the crate
+/// doesn't drive itself, such as an embedded device's serial console, and render just the
+/// changes.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{pos, Grid, GridChange, Position};
+///
+/// let mut grid = Grid::new();
+/// grid.set(pos!(0, 0), "abc");
+/// grid.clear_changes();
+///
+/// grid.set(pos!(0, 0), "abd");
+/// let changes: Vec<GridChange> = grid.changes().collect();
+/// assert_eq!(vec![GridChange::Set(pos!(2, 0), "d", None)], changes);
+/// ```
+#[derive(Clone)]
+pub struct Grid {
+    state: State,
+}
+
+/// A change to one of a [Grid]'s cells since its changes were last cleared.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GridChange<'a> {
+    /// The cell at the position displays the grapheme, with its style if any. Wide graphemes
+    /// cover the cells following them.
+    Set(Position, &'a str, Option<Style>),
+    /// The cell at the position was cleared.
+    Cleared(Position),
+}
+
+impl Grid {
+    /// Create an empty grid.
+    pub fn new() -> Grid {
+        Grid {
+            state: State::new(),
+        }
+    }
+
+    /// Set the text at the specified position, placing each of its lines from the position's
+    /// column on consecutive lines. Returns the area the text covers.
+    pub fn set(&mut self, position: Position, text: &str) -> Rect {
+        self.set_text(position, text, None)
+    }
+
+    /// Set the styled text at the specified position, as with [Grid::set].
+    pub fn set_styled(&mut self, position: Position, text: &str, style: Style) -> Rect {
+        self.set_text(position, text, Some(style))
+    }
+
+    fn set_text(&mut self, position: Position, text: &str, style: Option<Style>) -> Rect {
+        let mut size = Vector::new(0, 0);
+        for (offset, text) in (0..).zip(normalize(text).lines()) {
+            let line = position.y().saturating_add(offset);
+            let mut column = position.x();
+            for grapheme in split_graphemes(text) {
+                match style {
+                    Some(style) => self
+                        .state
+                        .set_styled_text(pos!(column, line), grapheme, style),
+                    None => self.state.set_text(pos!(column, line), grapheme),
+                }
+                column = column.saturating_add(grapheme_width(grapheme));
+            }
+
+            size = Vector::new(size.x().max(column - position.x()), offset + 1);
+        }
+
+        Rect::new(position, size)
+    }
+
+    /// Clear the specified line.
+    pub fn clear_line(&mut self, line: u16) {
+        self.state.clear_line(line);
+    }
+
+    /// Clear the cells within the specified area.
+    pub fn clear_rect(&mut self, rect: Rect) {
+        self.state.clear_rect(rect);
+    }
+
+    /// The grapheme at the specified position and its style, if the cell isn't empty.
+    pub fn get(&self, position: Position) -> Option<(&str, Option<Style>)> {
+        let cell = self.state.get_cell(position)?;
+        Some((cell.grapheme(), cell.rendered_style()))
+    }
+
+    /// The text of the specified line, with empty cells between its graphemes as spaces.
+    pub fn line_text(&self, line: u16) -> String {
+        self.state.line_text(line)
+    }
+
+    /// The grid's cells in row-major order, with their graphemes and styles.
+    #[cfg(feature = "std")]
+    pub(crate) fn cells(&self) -> impl Iterator<Item = (Position, &str, Option<Style>)> {
+        self.state
+            .cells()
+            .map(|(position, cell)| (position, cell.grapheme(), cell.rendered_style()))
+    }
+
+    /// Whether any cells changed since the changes were last cleared.
+    pub fn has_changes(&self) -> bool {
+        self.state.is_dirty()
+    }
+
+    /// The cells changed since the changes were last cleared, in row-major order.
+    pub fn changes(&self) -> impl Iterator<Item = GridChange<'_>> {
+        self.state.dirty_positions().into_iter().map(|position| {
+            match self.state.get_cell(position) {
+                Some(cell) => GridChange::Set(position, cell.grapheme(), cell.rendered_style()),
+                None => GridChange::Cleared(position),
+            }
+        })
+    }
+
+    /// Clear the record of changed cells, such as once they've been rendered.
+    pub fn clear_changes(&mut self) {
+        self.state.clear_dirty();
+    }
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use crate::{pos, Color, Position, Rect, Vector};
+
+    use super::{Grid, GridChange};
+
+    #[test]
+    fn grid_changes() {
+        let mut grid = Grid::new();
+        let style = Color::Red.as_style();
+        assert_eq!(
+            Rect::new(pos!(1, 1), Vector::new(3, 2)),
+            grid.set_styled(pos!(1, 1), "ab\n漢c", style)
+        );
+        assert!(grid.has_changes());
+        assert_eq!(Some(("漢", Some(style))), grid.get(pos!(1, 2)));
+        assert_eq!(" 漢c", grid.line_text(2));
+
+        grid.clear_changes();
+        assert!(!grid.has_changes());
+
+        grid.set(pos!(2, 1), "b");
+        grid.clear_line(2);
+        let changes: Vec<GridChange> = grid.changes().collect();
+        assert_eq!(
+            vec![
+                GridChange::Set(pos!(2, 1), "b", None),
+                GridChange::Cleared(pos!(1, 2)),
+                GridChange::Cleared(pos!(2, 2)),
+                GridChange::Cleared(pos!(3, 2)),
+            ],
+            changes
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{pos, Color, Interface, Position, Rect, Style};
+
+/// Line numbers along the left of a region, such as an editor's or pager's, sized to fit the
+/// greatest number. The rest of the region is left for content, which is addressed relative to
+/// the gutter so it needn't know the gutter's width as it grows.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Gutter, Interface, Position, Rect, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let lines = ["fn main() {", "    println!(\"Hello\");", "}"];
+/// let mut gutter = Gutter::new(Rect::new(pos!(0, 0), Vector::new(40, 10)));
+/// gutter.set_line_count(lines.len());
+///
+/// // "1 fn main() {" and so on
+/// gutter.render(&mut interface);
+/// for (line, text) in lines.iter().enumerate() {
+///     interface.set(gutter.content_position(pos!(0, line as u16)), text);
+/// }
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Gutter {
+    region: Rect,
+    first_number: usize,
+    line_count: usize,
+    min_digits: u16,
+    style: Style,
+    rendered: Option<(Rect, u16)>,
+}
+
+impl Gutter {
+    /// Create a gutter along the left of the region numbering its lines from one, in dark grey.
+    pub fn new(region: Rect) -> Gutter {
+        Gutter {
+            region,
+            first_number: 1,
+            line_count: 0,
+            min_digits: 1,
+            style: Color::DarkGrey.as_style(),
+            rendered: None,
+        }
+    }
+
+    /// Create a new gutter with the specified style for its numbers.
+    pub fn set_style(self, style: Style) -> Gutter {
+        Gutter { style, ..self }
+    }
+
+    /// Create a new gutter with room for at least the specified number of digits.
+    pub fn set_min_digits(self, min_digits: u16) -> Gutter {
+        Gutter { min_digits, ..self }
+    }
+
+    /// Move the gutter to the specified region.
+    pub fn set_region(&mut self, region: Rect) {
+        self.region = region;
+    }
+
+    /// The region holding the gutter and its content.
+    pub fn region(&self) -> Rect {
+        self.region
+    }
+
+    /// Set the number of lines being numbered, such as a document's. Lines of the region past
+    /// the last aren't numbered, and the gutter widens to fit the last line's number.
+    pub fn set_line_count(&mut self, line_count: usize) {
+        self.line_count = line_count;
+    }
+
+    /// The number of lines being numbered.
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Set the number shown on the region's first line, such as one more than the number of
+    /// lines scrolled past.
+    pub fn set_first_number(&mut self, first_number: usize) {
+        self.first_number = first_number;
+    }
+
+    /// The number shown on the region's first line.
+    pub fn first_number(&self) -> usize {
+        self.first_number
+    }
+
+    /// The columns the gutter occupies: its digits and a separating space, though no more than
+    /// the region's width.
+    pub fn width(&self) -> u16 {
+        let digits = (self.line_count.max(1).ilog10() + 1) as u16;
+        (digits.max(self.min_digits) + 1).min(self.region.size().x())
+    }
+
+    /// The region's area right of the gutter, for content.
+    pub fn content_rect(&self) -> Rect {
+        let (_, content) = self.region.split_columns(self.width());
+        content
+    }
+
+    /// The interface position of the specified position relative to the content's top-left.
+    pub fn content_position(&self, position: Position) -> Position {
+        let origin = self.content_rect().origin();
+        pos!(origin.x() + position.x(), origin.y() + position.y())
+    }
+
+    /// Stage the line numbers, right-aligned within the gutter. If the gutter moved or its width
+    /// changed since it was last rendered, the region is cleared first and `true` returned, in
+    /// which case the content should be restaged at its new positions.
+    pub fn render(&mut self, interface: &mut Interface) -> bool {
+        let width = self.width();
+        let is_moved = self.rendered != Some((self.region, width));
+        if is_moved {
+            if let Some((region, _)) = self.rendered {
+                interface.clear_rect(region);
+            }
+            interface.clear_rect(self.region);
+        }
+
+        let digits = width.saturating_sub(1) as usize;
+        for line in 0..self.region.size().y() {
+            let number = self.first_number + line as usize;
+            let text = match number <= self.line_count {
+                true => format!("{:>1$} ", number, digits),
+                false => " ".repeat(width as usize),
+            };
+
+            let position = pos!(self.region.origin().x(), self.region.origin().y() + line);
+            interface.set_styled(position, &text[text.len() - width as usize..], self.style);
+        }
+
+        self.rendered = Some((self.region, width));
+        is_moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pos, Position, Rect, Vector};
+
+    use super::Gutter;
+
+    #[test]
+    fn gutter_width() {
+        let mut gutter = Gutter::new(Rect::new(pos!(2, 1), Vector::new(20, 5)));
+        assert_eq!(2, gutter.width());
+
+        gutter.set_line_count(9);
+        assert_eq!(2, gutter.width());
+        gutter.set_line_count(10);
+        assert_eq!(3, gutter.width());
+
+        gutter.set_line_count(1000);
+        gutter.set_first_number(998);
+        assert_eq!(5, gutter.width());
+        assert_eq!(pos!(7, 1), gutter.content_rect().origin());
+        assert_eq!(Vector::new(15, 5), gutter.content_rect().size());
+        assert_eq!(pos!(10, 3), gutter.content_position(pos!(3, 2)));
+
+        let gutter = gutter.set_min_digits(6);
+        assert_eq!(7, gutter.width());
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use crate::{Interface, Position, Rect, Style, Vector};
+
+/// A hook run before each frame is applied.
+pub(crate) type BeforeApplyHook = Box<dyn FnMut(&mut Staging)>;
+
+/// A hook run after each frame is applied.
+pub(crate) type AfterApplyHook = Box<dyn FnMut(&mut Staging, &ApplyStats)>;
+
+/// A hook run when the terminal is resized.
+pub(crate) type ResizeHook = Box<dyn FnMut(&mut Staging, Vector)>;
+
+/// The hooks registered with an interface.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    pub(crate) before_apply: Vec<BeforeApplyHook>,
+    pub(crate) after_apply: Vec<AfterApplyHook>,
+    pub(crate) resize: Vec<ResizeHook>,
+}
+
+/// A summary of a frame written by an apply, provided to hooks registered with
+/// [Interface::on_after_apply].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ApplyStats {
+    cells: usize,
+    deferred_cells: usize,
+    bytes: usize,
+    write_duration: Duration,
+    is_repaint: bool,
+}
+
+impl ApplyStats {
+    /// Create a summary of a written frame.
+    pub(crate) fn new(
+        cells: usize,
+        deferred_cells: usize,
+        bytes: usize,
+        write_duration: Duration,
+        is_repaint: bool,
+    ) -> ApplyStats {
+        ApplyStats {
+            cells,
+            deferred_cells,
+            bytes,
+            write_duration,
+            is_repaint,
+        }
+    }
+
+    /// The number of changed cells written.
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    /// The number of changed cells deferred to a later apply by the byte budget.
+    pub fn deferred_cells(&self) -> usize {
+        self.deferred_cells
+    }
+
+    /// The number of bytes written to the device.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    /// How long writing the frame to the device took.
+    pub fn write_duration(&self) -> Duration {
+        self.write_duration
+    }
+
+    /// Whether the frame cleared and repainted the interface rather than updating its changes.
+    pub fn is_repaint(&self) -> bool {
+        self.is_repaint
+    }
+}
+
+/// The staging operations available to hooks, which may add content such as a footer to each
+/// frame but can't apply or reconfigure the interface.
+pub struct Staging<'i, 'a> {
+    interface: &'i mut Interface<'a>,
+}
+
+impl<'i, 'a> Staging<'i, 'a> {
+    /// Create a staging API for the specified interface.
+    pub(crate) fn new(interface: &'i mut Interface<'a>) -> Staging<'i, 'a> {
+        Staging { interface }
+    }
+
+    /// The terminal's size as last retrieved from the device.
+    pub fn size(&self) -> Vector {
+        self.interface.size()
+    }
+
+    /// Stage the text at the specified position, as with [Interface::set].
+    pub fn set(&mut self, position: Position, text: &str) -> Rect {
+        self.interface.set(position, text)
+    }
+
+    /// Stage the styled text at the specified position, as with [Interface::set_styled].
+    pub fn set_styled(&mut self, position: Position, text: &str, style: Style) -> Rect {
+        self.interface.set_styled(position, text, style)
+    }
+
+    /// Clear the specified line, as with [Interface::clear_line].
+    pub fn clear_line(&mut self, line: u16) {
+        self.interface.clear_line(line);
+    }
+
+    /// Clear the specified region, as with [Interface::clear_rect].
+    pub fn clear_rect(&mut self, rect: Rect) {
+        self.interface.clear_rect(rect);
+    }
+}
@@ -0,0 +1,609 @@
+use std::{collections::BTreeSet, io::Write, mem::take};
+
+use crate::{pos, Cell, Position, Quantization, Rect, Result, Style, Vector};
+
+/// The protocol a terminal supports for displaying inline images.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum ImageProtocol {
+    /// The kitty graphics protocol, also supported by WezTerm, Ghostty, and Konsole.
+    Kitty,
+    /// iTerm2's inline images protocol, also supported by WezTerm and mintty.
+    ITerm2,
+    /// DEC sixel graphics, supported by xterm when enabled, mlterm, foot, and others. Images are
+    /// scaled assuming cells of [SIXEL_CELL_WIDTH] by [SIXEL_CELL_HEIGHT] pixels.
+    Sixel,
+    /// No image protocol, so images are approximated with colored half-block characters.
+    #[default]
+    Blocks,
+}
+
+impl ImageProtocol {
+    /// Detect the protocol supported by the current terminal from its environment variables,
+    /// falling back to block characters if it isn't recognized.
+    pub fn detect() -> ImageProtocol {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+
+        if var("TERM") == "xterm-kitty" || !var("KITTY_WINDOW_ID").is_empty() {
+            return ImageProtocol::Kitty;
+        }
+
+        match var("TERM_PROGRAM").as_str() {
+            "ghostty" => ImageProtocol::Kitty,
+            "iTerm.app" | "WezTerm" | "mintty" => ImageProtocol::ITerm2,
+            _ if var("TERM").starts_with("mlterm") || var("TERM").starts_with("foot") => {
+                ImageProtocol::Sixel
+            }
+            _ => ImageProtocol::Blocks,
+        }
+    }
+}
+
+/// The assumed width in pixels of cells displaying sixel images.
+pub const SIXEL_CELL_WIDTH: u16 = 10;
+
+/// The assumed height in pixels of cells displaying sixel images.
+pub const SIXEL_CELL_HEIGHT: u16 = 20;
+
+/// An image's pixels, which an interface scales to fill the cells it's displayed in.
+///
+/// # Examples
+/// ```
+/// use tty_interface::ImageSource;
+///
+/// // A 2x1 image of a red pixel beside a blue one
+/// let pixels = vec![255, 0, 0, 255, 0, 0, 255, 255];
+/// let image = ImageSource::from_rgba(2, 1, pixels).unwrap();
+/// assert_eq!((2, 1), (image.width(), image.height()));
+///
+/// // The pixels must match the image's dimensions
+/// assert!(ImageSource::from_rgba(2, 2, vec![0; 8]).is_none());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ImageSource {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl ImageSource {
+    /// Create an image from its rows of pixels, each four bytes of red, green, blue, and alpha.
+    /// Returns `None` if the pixels don't match the dimensions.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Option<ImageSource> {
+        let length = (width as usize)
+            .checked_mul(height as usize)?
+            .checked_mul(4)?;
+
+        match pixels.len() == length && length > 0 {
+            true => Some(ImageSource {
+                width,
+                height,
+                pixels,
+            }),
+            false => None,
+        }
+    }
+
+    /// This image's width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// This image's height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// This image's pixels, each four bytes of red, green, blue, and alpha.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The color of the pixel nearest the specified fraction of this image's width and height,
+    /// or `None` if it's mostly transparent.
+    fn sample(&self, x: f32, y: f32) -> Option<(u8, u8, u8)> {
+        let column = ((x * self.width as f32) as u32).min(self.width - 1);
+        let row = ((y * self.height as f32) as u32).min(self.height - 1);
+
+        let offset = (row as usize * self.width as usize + column as usize) * 4;
+        match &self.pixels[offset..offset + 4] {
+            [red, green, blue, alpha] if *alpha >= 128 => Some((*red, *green, *blue)),
+            _ => None,
+        }
+    }
+
+    /// Approximate this image in cells of the specified extent with half-block characters, each
+    /// showing two vertically-stacked pixels. Positions are relative to the extent's top-left.
+    pub(crate) fn to_blocks(&self, size: Vector) -> Vec<(Position, Cell)> {
+        let color =
+            |rgb: Option<(u8, u8, u8)>| rgb.map(|rgb| Quantization::Perceptual.to_color(rgb));
+
+        let mut cells = Vec::new();
+        for line in 0..size.y() {
+            for column in 0..size.x() {
+                let x = (column as f32 + 0.5) / size.x() as f32;
+                let top =
+                    color(self.sample(x, (line as f32 * 2.0 + 0.5) / (size.y() as f32 * 2.0)));
+                let bottom =
+                    color(self.sample(x, (line as f32 * 2.0 + 1.5) / (size.y() as f32 * 2.0)));
+
+                let cell = match (top, bottom) {
+                    (None, None) => Cell::new(" ", None),
+                    (Some(top), None) => Cell::new("▀", Some(Style::new().set_foreground(top))),
+                    (None, Some(bottom)) => {
+                        Cell::new("▄", Some(Style::new().set_foreground(bottom)))
+                    }
+                    (Some(top), Some(bottom)) => {
+                        let style = Style::new().set_foreground(top).set_background(bottom);
+                        Cell::new("▀", Some(style))
+                    }
+                };
+
+                cells.push((pos!(column, line), cell));
+            }
+        }
+
+        cells
+    }
+
+    /// Append the escape sequence displaying this image with the protocol in cells of the
+    /// specified extent, from the cursor without moving it. Kitty images are identified by `id`
+    /// so they may be replaced or deleted.
+    pub(crate) fn encode(
+        &self,
+        protocol: ImageProtocol,
+        size: Vector,
+        id: u32,
+        buffer: &mut Vec<u8>,
+    ) -> Result<()> {
+        match protocol {
+            ImageProtocol::Kitty => {
+                let data = base64(&self.pixels);
+                let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+                for (index, chunk) in chunks.iter().enumerate() {
+                    let more = (index + 1 < chunks.len()) as u8;
+                    buffer.extend_from_slice(b"\x1B_G");
+                    if index == 0 {
+                        write!(
+                            buffer,
+                            "a=T,f=32,s={},v={},c={},r={},i={},C=1,q=2,",
+                            self.width,
+                            self.height,
+                            size.x(),
+                            size.y(),
+                            id
+                        )?;
+                    }
+                    write!(buffer, "m={};", more)?;
+                    buffer.extend_from_slice(chunk);
+                    buffer.extend_from_slice(b"\x1B\\");
+                }
+            }
+            ImageProtocol::ITerm2 => {
+                let png = self.to_png();
+                write!(
+                    buffer,
+                    "\x1B]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0;doNotMoveCursor=1:{}\x07",
+                    png.len(),
+                    size.x(),
+                    size.y(),
+                    base64(&png)
+                )?;
+            }
+            ImageProtocol::Sixel => {
+                // The cursor is saved and restored since sixels move it below the image
+                buffer.extend_from_slice(b"\x1B7");
+                self.encode_sixel(size, buffer)?;
+                buffer.extend_from_slice(b"\x1B8");
+            }
+            ImageProtocol::Blocks => {}
+        }
+
+        Ok(())
+    }
+
+    /// Append the sixel sequence displaying this image scaled to cells of the specified extent,
+    /// with colors from a 6x6x6 cube and transparent pixels left unpainted.
+    fn encode_sixel(&self, size: Vector, buffer: &mut Vec<u8>) -> Result<()> {
+        let width = size.x() as u32 * SIXEL_CELL_WIDTH as u32;
+        let height = size.y() as u32 * SIXEL_CELL_HEIGHT as u32;
+
+        // Each pixel's color register, the index of its level of red, green, and blue in the cube
+        let level = |component: u8| (component as u16 * 5 + 127) / 255;
+        let registers: Vec<Option<u16>> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let rgb = self.sample(
+                    (x as f32 + 0.5) / width as f32,
+                    (y as f32 + 0.5) / height as f32,
+                );
+                rgb.map(|(r, g, b)| level(r) * 36 + level(g) * 6 + level(b))
+            })
+            .collect();
+
+        write!(buffer, "\x1BP0;1;0q\"1;1;{};{}", width, height)?;
+
+        let used: BTreeSet<u16> = registers.iter().flatten().copied().collect();
+        for register in &used {
+            let (r, g, b) = (register / 36, register / 6 % 6, register % 6);
+            write!(buffer, "#{};2;{};{};{}", register, r * 20, g * 20, b * 20)?;
+        }
+
+        // Each band of six rows is painted one color at a time, returning to its start between
+        for band in (0..height).step_by(6) {
+            let rows = band..(band + 6).min(height);
+            let register_at = |x: u32, y: u32| registers[(y * width + x) as usize];
+
+            let band_used: BTreeSet<u16> = rows
+                .clone()
+                .flat_map(|y| (0..width).filter_map(move |x| register_at(x, y)))
+                .collect();
+
+            for (index, register) in band_used.iter().enumerate() {
+                if index > 0 {
+                    buffer.push(b'$');
+                }
+                write!(buffer, "#{}", register)?;
+
+                let sixels = (0..width).map(|x| {
+                    let bits =
+                        rows.clone().enumerate().fold(0u8, |bits, (bit, y)| {
+                            match register_at(x, y) == Some(*register) {
+                                true => bits | 1 << bit,
+                                false => bits,
+                            }
+                        });
+                    b'?' + bits
+                });
+
+                encode_runs(sixels, buffer)?;
+            }
+
+            buffer.push(b'-');
+        }
+
+        buffer.extend_from_slice(b"\x1B\\");
+        Ok(())
+    }
+
+    /// Encode this image as an uncompressed PNG.
+    fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8-bit RGBA without interlacing
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        // Each row is preceded by its filter type, none
+        let row_length = self.width as usize * 4;
+        let mut rows = Vec::with_capacity(self.pixels.len() + self.height as usize);
+        for row in self.pixels.chunks(row_length) {
+            rows.push(0);
+            rows.extend_from_slice(row);
+        }
+
+        let mut png = b"\x89PNG\r\n\x1A\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&rows));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// An image displayed by an interface with an image protocol over blank cells.
+pub(crate) struct PlacedImage {
+    pub(crate) rect: Rect,
+    pub(crate) source: ImageSource,
+    /// Identifies the image to the terminal so it may be replaced or deleted.
+    pub(crate) id: u32,
+    pub(crate) is_rendered: bool,
+}
+
+impl PlacedImage {
+    /// Whether this image's cells overlap the rectangle.
+    pub(crate) fn overlaps(&self, rect: Rect) -> bool {
+        self.rect.intersection(rect).is_some()
+    }
+}
+
+/// The images an interface displays with an image protocol, and those removed since its last
+/// frame which must be deleted from the terminal.
+pub(crate) struct ImageState {
+    placed: Vec<PlacedImage>,
+    deleted: Vec<u32>,
+    next_id: u32,
+}
+
+impl ImageState {
+    /// Create a state without any images.
+    pub(crate) fn new() -> ImageState {
+        ImageState {
+            placed: Vec::new(),
+            deleted: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// The displayed images, in the order they were placed.
+    pub(crate) fn placed(&self) -> &[PlacedImage] {
+        &self.placed
+    }
+
+    /// Place an image over the rectangle's cells, to be displayed with the next frame.
+    pub(crate) fn place(&mut self, rect: Rect, source: ImageSource) {
+        self.placed.push(PlacedImage {
+            rect,
+            source,
+            id: self.next_id,
+            is_rendered: false,
+        });
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+    }
+
+    /// Remove and return the images overlapping the rectangle.
+    pub(crate) fn remove_overlapping(&mut self, rect: Rect) -> Vec<PlacedImage> {
+        let (removed, kept) = take(&mut self.placed)
+            .into_iter()
+            .partition(|image| image.overlaps(rect));
+        self.placed = kept;
+        removed
+    }
+
+    /// Delete a removed image from the terminal with the next frame.
+    pub(crate) fn delete(&mut self, id: u32) {
+        self.deleted.push(id);
+    }
+
+    /// Append the escape sequences deleting the removed images.
+    pub(crate) fn encode_deletions(&self, buffer: &mut Vec<u8>) -> Result<()> {
+        for id in &self.deleted {
+            encode_kitty_delete(*id, buffer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Record that a frame displayed the images at the specified indices and its deletions.
+    pub(crate) fn mark_rendered(&mut self, indices: Vec<usize>) {
+        self.deleted.clear();
+        for index in indices {
+            self.placed[index].is_rendered = true;
+        }
+    }
+}
+
+/// Append the escape sequence deleting a kitty image and freeing its data.
+fn encode_kitty_delete(id: u32, buffer: &mut Vec<u8>) -> Result<()> {
+    write!(buffer, "\x1B_Ga=d,d=I,i={},q=2\x1B\\", id)?;
+    Ok(())
+}
+
+/// The most base64-encoded bytes kitty accepts in one escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Append the sixel characters, compressing runs of more than three with repeat introducers.
+fn encode_runs(sixels: impl Iterator<Item = u8>, buffer: &mut Vec<u8>) -> Result<()> {
+    let mut sixels = sixels.peekable();
+    while let Some(sixel) = sixels.next() {
+        let mut count = 1;
+        while sixels.next_if_eq(&sixel).is_some() {
+            count += 1;
+        }
+
+        match count {
+            1..=3 => buffer.extend(std::iter::repeat_n(sixel, count)),
+            _ => write!(buffer, "!{}{}", count, sixel as char)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Encode bytes as padded base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (index, byte)| {
+            value | (*byte as u32) << (16 - index * 8)
+        });
+
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => encoded.push(ALPHABET[(value >> (18 - index * 6)) as usize & 0x3F] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Append a PNG chunk of the specified type and data, with its length and checksum.
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let checksum = crc32(&png[start..]);
+    png.extend_from_slice(&checksum.to_be_bytes());
+}
+
+/// Wrap data in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+
+    let blocks: Vec<&[u8]> = data.chunks(u16::MAX as usize).collect();
+    for (index, block) in blocks.iter().enumerate() {
+        let is_final = index + 1 == blocks.len();
+        let length = block.len() as u16;
+
+        stream.push(is_final as u8);
+        stream.extend_from_slice(&length.to_le_bytes());
+        stream.extend_from_slice(&(!length).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+
+    if blocks.is_empty() {
+        stream.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    stream
+}
+
+/// The CRC-32 checksum of the data, as used by PNG.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+
+    !crc
+}
+
+/// The Adler-32 checksum of the data, as used by zlib.
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pos, Color, Position, Style, Vector};
+
+    use super::{adler32, base64, crc32, ImageProtocol, ImageSource};
+
+    #[test]
+    fn image_base64() {
+        assert_eq!("", base64(b""));
+        assert_eq!("Zg==", base64(b"f"));
+        assert_eq!("Zm8=", base64(b"fo"));
+        assert_eq!("Zm9v", base64(b"foo"));
+        assert_eq!("Zm9vYmFy", base64(b"foobar"));
+    }
+
+    #[test]
+    fn image_checksums() {
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+        assert_eq!(0x11E6_0398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn image_to_png() {
+        let image = ImageSource::from_rgba(1, 1, vec![255, 0, 0, 255]).unwrap();
+        let png = image.to_png();
+
+        assert_eq!(b"\x89PNG\r\n\x1A\n", &png[..8]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(b"IEND", &png[png.len() - 8..png.len() - 4]);
+    }
+
+    #[test]
+    fn image_to_blocks() {
+        // Red over dark blue in the left column, transparent over green in the right
+        let pixels = vec![
+            255, 0, 0, 255, 0, 0, 0, 0, //
+            0, 0, 255, 255, 0, 255, 0, 255,
+        ];
+        let image = ImageSource::from_rgba(2, 2, pixels).unwrap();
+
+        let cells = image.to_blocks(Vector::new(2, 1));
+        assert_eq!(2, cells.len());
+
+        let (position, cell) = &cells[0];
+        assert_eq!(pos!(0, 0), *position);
+        assert_eq!("▀", cell.grapheme());
+        assert_eq!(
+            Some(
+                &Style::new()
+                    .set_foreground(Color::Red)
+                    .set_background(Color::DarkBlue)
+            ),
+            cell.style()
+        );
+
+        let (position, cell) = &cells[1];
+        assert_eq!(pos!(1, 0), *position);
+        assert_eq!("▄", cell.grapheme());
+        assert_eq!(
+            Some(&Style::new().set_foreground(Color::Green)),
+            cell.style()
+        );
+    }
+
+    #[test]
+    fn image_encode_kitty() {
+        let image = ImageSource::from_rgba(1, 1, vec![1, 2, 3, 4]).unwrap();
+
+        let mut buffer = Vec::new();
+        image
+            .encode(ImageProtocol::Kitty, Vector::new(2, 1), 7, &mut buffer)
+            .unwrap();
+
+        assert_eq!(
+            "\x1B_Ga=T,f=32,s=1,v=1,c=2,r=1,i=7,C=1,q=2,m=0;AQIDBA==\x1B\\",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+
+    #[test]
+    fn image_encode_sixel() {
+        let image = ImageSource::from_rgba(1, 1, vec![255, 0, 0, 255]).unwrap();
+
+        let mut buffer = Vec::new();
+        image
+            .encode(ImageProtocol::Sixel, Vector::new(1, 1), 1, &mut buffer)
+            .unwrap();
+
+        // Twenty rows in three full bands and a final band of two rows
+        assert_eq!(
+            "\x1B7\x1BP0;1;0q\"1;1;10;20#180;2;100;0;0#180!10~-#180!10~-#180!10~-#180!10B-\x1B\\\x1B8",
+            String::from_utf8(buffer).unwrap()
+        );
+    }
+
+    #[test]
+    fn image_encode_sixel_colors() {
+        // Red over blue, meeting in the second band of six rows
+        let pixels = vec![255, 0, 0, 255, 0, 0, 255, 255];
+        let image = ImageSource::from_rgba(1, 2, pixels).unwrap();
+
+        let mut buffer = Vec::new();
+        image.encode_sixel(Vector::new(1, 1), &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        // Both colors are defined, and the second band paints one after returning from the other
+        assert!(output.contains("#5;2;0;0;100#180;2;100;0;0"));
+        assert!(output.contains("-#5!10o$#180!10N-"));
+    }
+
+    #[test]
+    fn image_encode_kitty_chunked() {
+        let image = ImageSource::from_rgba(64, 64, vec![0; 64 * 64 * 4]).unwrap();
+
+        let mut buffer = Vec::new();
+        image
+            .encode(ImageProtocol::Kitty, Vector::new(8, 4), 1, &mut buffer)
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        let chunks: Vec<&str> = output.split("\x1B\\").filter(|c| !c.is_empty()).collect();
+        assert_eq!(6, chunks.len());
+        assert!(chunks[0].contains("m=1;"));
+        assert!(chunks[5].starts_with("\x1B_Gm=0;"));
+    }
+}
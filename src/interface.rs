@@ -1,26 +1,136 @@
-use std::mem::swap;
+use std::{
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    fs::File,
+    hash::{Hash, Hasher},
+    io::{BufWriter, ErrorKind, Write},
+    mem::{swap, take},
+    ops::Range,
+    path::Path,
+    process::{Command, ExitStatus},
+    time::{Duration, Instant},
+};
 
-use crossterm::{
-    cursor,
-    style::{self, Attribute, ContentStyle, StyledContent},
-    terminal, QueueableCommand,
+use crate::{
+    bidi::get_visual_graphemes,
+    child::{spawn_piped, ChildStream},
+    cursor_position_or,
+    logging::log_command,
+    markdown::render_markdown,
+    motion::{CursorMove, MAX_REPRINTED_COLUMNS},
+    panel::draw_panel,
+    pos,
+    text::{grapheme_indices, normalize, split_graphemes, Widths},
+    Alignment, AmbiguousWidth, AppendedLine, ApplyError, ApplyStats, Cell, Clock, Color,
+    CommandEncoder, Corner, CursorMode, DebounceState, Device, DeviceCommand, DeviceHandle, Error,
+    Event, Ghost, GhostState, Hooks, InterfaceBuilder, InterfaceWriter, LineHandle, Mode,
+    NotificationLevel, Notifications, Overflow, PanelStyle, Position, Recorder, Rect,
+    RenderStrategy, Result, Staging, State, Style, SystemClock, Theme, Timer, Vector,
 };
-use unicode_segmentation::UnicodeSegmentation;
 
-use crate::{pos, Cell, Color, Device, Position, Result, State, Style, Vector};
+#[cfg(feature = "images")]
+use crate::{ImageProtocol, ImageSource, ImageState};
+
+#[cfg(all(unix, feature = "signals"))]
+use crate::signals::{self, Signal, Signals};
+
+/// The default number of columns between tab stops.
+const DEFAULT_TAB_WIDTH: u16 = 8;
+
+/// The default fraction of the terminal's cells which must change for an apply to repaint it.
+pub(crate) const DEFAULT_REPAINT_THRESHOLD: f32 = 0.6;
+
+/// An East Asian ambiguous-width character printed to measure how the terminal displays them.
+const AMBIGUOUS_WIDTH_PROBE: &str = "→";
+
+/// The size assumed for output which isn't a terminal and doesn't report one.
+const LINE_PRINTER_SIZE: Vector = Vector::new(80, 24);
 
 /// A TTY-based user-interface providing optimized update rendering.
 pub struct Interface<'a> {
-    device: &'a mut dyn Device,
+    device: DeviceHandle<'a>,
     size: Vector,
     current: State,
     alternate: Option<State>,
     staged_cursor: Option<Position>,
     cursor: Position,
-    relative: bool,
+    mode: Mode,
+    origin: Position,
+    scrolled_lines: u16,
+    viewport: Position,
+    rendered_viewport: Position,
+    byte_budget: Option<usize>,
+    tab_width: u16,
+    tab_placeholder: Option<(String, Style)>,
+    wrap_indicator: Option<(String, Style)>,
+    ambiguous_width: AmbiguousWidth,
+    calibrated_graphemes: Option<HashSet<String>>,
+    bidi: bool,
+    ghost: GhostState,
+    marks: Vec<Position>,
+    mark_style: Style,
+    highlights: Vec<Rect>,
+    #[cfg(feature = "images")]
+    images: ImageState,
+    notifications: Notifications,
+    clock: Box<dyn Clock>,
+    debounce: DebounceState,
+    default_style: Option<Style>,
+    styling: bool,
+    overflow: Overflow,
+    mouse_capture: bool,
+    encoder: Option<Box<dyn CommandEncoder>>,
+    render_strategy: RenderStrategy,
+    last_frame: Option<(Instant, Duration)>,
+    repaint_threshold: Option<f32>,
+    wrapped: BTreeMap<Position, WrappedText>,
+    aligned: BTreeMap<u16, AlignedText>,
+    theme: Theme,
+    named: BTreeMap<Position, String>,
+    appended: Vec<AppendedLine>,
+    history: VecDeque<State>,
+    history_limit: usize,
+    recorder: Option<Recorder>,
+    line_hashes: HashMap<u16, u64>,
+    pending_output: Vec<u8>,
+    hooks: Hooks,
+    cursor_mode: CursorMode,
+    is_invalidated: bool,
+    #[cfg(all(unix, feature = "signals"))]
+    signals: Option<Signals>,
+}
+
+/// Text staged to wrap at the terminal's width, remembered so it may be rewrapped on resize.
+struct WrappedText {
+    text: String,
+    style: Option<Style>,
+    positions: BTreeSet<Position>,
+}
+
+/// Text staged aligned within the terminal's width, remembered so it may be realigned on resize.
+struct AlignedText {
+    text: String,
+    alignment: Alignment,
+    style: Option<Style>,
+    positions: BTreeSet<Position>,
 }
 
 impl Interface<'_> {
+    /// Create a builder for configuring a new interface.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::Interface;
+    ///
+    /// let interface = Interface::builder().relative().tab_width(4).build(&mut device)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn builder() -> InterfaceBuilder {
+        InterfaceBuilder::new()
+    }
+
     /// Create a new interface for the specified device on the alternate screen.
     ///
     /// # Examples
@@ -33,27 +143,22 @@ impl Interface<'_> {
     /// # Ok::<(), Error>(())
     /// ```
     pub fn new_alternate<'a>(device: &'a mut dyn Device) -> Result<Interface<'a>> {
-        let size = device.get_terminal_size()?;
-
-        let mut interface = Interface {
-            device,
-            size,
-            current: State::new(),
-            alternate: None,
-            staged_cursor: None,
-            cursor: pos!(0, 0),
-            relative: false,
-        };
-
-        let device = &mut interface.device;
-        device.enable_raw_mode()?;
-        device.queue(terminal::EnterAlternateScreen)?;
-        device.queue(terminal::Clear(terminal::ClearType::All))?;
-        device.queue(cursor::Hide)?;
-        device.queue(cursor::MoveTo(0, 0))?;
-        device.flush()?;
+        Interface::initialize_mode(DeviceHandle::Borrowed(device), None, Mode::Alternate, false)
+    }
 
-        Ok(interface)
+    /// Create a new interface which takes ownership of the specified device, on the alternate
+    /// screen. Useful where the interface must outlive the scope which created its device.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// use tty_interface::Interface;
+    ///
+    /// let interface = Interface::new_alternate_owned(Box::new(VirtualDevice::new()))?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn new_alternate_owned(device: Box<dyn Device>) -> Result<Interface<'static>> {
+        Interface::initialize_mode(DeviceHandle::Owned(device), None, Mode::Alternate, false)
     }
 
     /// Create a new interface for the specified device which renders relatively in the buffer.
@@ -68,22 +173,156 @@ impl Interface<'_> {
     /// # Ok::<(), Error>(())
     /// ```
     pub fn new_relative<'a>(device: &'a mut dyn Device) -> Result<Interface<'a>> {
-        let size = device.get_terminal_size()?;
+        Interface::initialize_mode(DeviceHandle::Borrowed(device), None, Mode::Relative, false)
+    }
+
+    /// Create a new interface which takes ownership of the specified device and renders relatively
+    /// in its buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// use tty_interface::Interface;
+    ///
+    /// let interface = Interface::new_relative_owned(Box::new(VirtualDevice::new()))?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn new_relative_owned(device: Box<dyn Device>) -> Result<Interface<'static>> {
+        Interface::initialize_mode(DeviceHandle::Owned(device), None, Mode::Relative, false)
+    }
+
+    /// Initialize an interface in the specified mode, or as a line printer if the device isn't a
+    /// terminal unless one is assumed, optionally encoding its commands with an encoder other
+    /// than the device's.
+    pub(crate) fn initialize_mode(
+        device: DeviceHandle,
+        encoder: Option<Box<dyn CommandEncoder>>,
+        mode: Mode,
+        assume_terminal: bool,
+    ) -> Result<Interface> {
+        let mode = match assume_terminal || device.is_terminal() {
+            true => mode,
+            false => Mode::LinePrinter,
+        };
+
+        match mode {
+            Mode::Alternate => Interface::initialize_alternate(device, encoder),
+            Mode::Relative => Interface::initialize_relative(device, encoder),
+            Mode::LinePrinter => Interface::initialize_line_printer(device, encoder),
+        }
+    }
+
+    /// Initialize an interface on the device's alternate screen, optionally encoding its commands
+    /// with an encoder other than the device's.
+    fn initialize_alternate(
+        device: DeviceHandle,
+        encoder: Option<Box<dyn CommandEncoder>>,
+    ) -> Result<Interface> {
+        let mut interface = Interface::initialize(device, encoder, Mode::Alternate, pos!(0, 0))?;
+
+        interface.device.enable_raw_mode()?;
+        interface.write_commands(&[
+            DeviceCommand::EnterAlternateScreen,
+            DeviceCommand::ClearAll,
+            DeviceCommand::HideCursor,
+            DeviceCommand::MoveTo(pos!(0, 0)),
+        ])?;
+
+        Ok(interface)
+    }
+
+    /// Initialize an interface relative to the device's current cursor position, optionally
+    /// encoding its commands with an encoder other than the device's.
+    fn initialize_relative(
+        mut device: DeviceHandle,
+        encoder: Option<Box<dyn CommandEncoder>>,
+    ) -> Result<Interface> {
+        device.enable_raw_mode()?;
 
-        let mut interface = Interface {
+        // Devices which can't report their cursor, such as writers, begin output at the top-left
+        let origin = cursor_position_or(&mut *device, pos!(0, 0))?;
+
+        Interface::initialize(device, encoder, Mode::Relative, origin)
+    }
+
+    /// Initialize an interface printing its changed lines to the device without cursor control,
+    /// optionally encoding its commands with an encoder other than the device's.
+    fn initialize_line_printer(
+        device: DeviceHandle,
+        encoder: Option<Box<dyn CommandEncoder>>,
+    ) -> Result<Interface> {
+        Interface::initialize(device, encoder, Mode::LinePrinter, pos!(0, 0))
+    }
+
+    /// Create an interface in its default configuration for the specified device.
+    fn initialize(
+        mut device: DeviceHandle,
+        encoder: Option<Box<dyn CommandEncoder>>,
+        mode: Mode,
+        origin: Position,
+    ) -> Result<Interface> {
+        let size = match device.get_terminal_size() {
+            Err(_) if mode == Mode::LinePrinter => LINE_PRINTER_SIZE,
+            size => size?,
+        };
+
+        // Lines printed to a file or pipe are plain unless styling was requested
+        let styling = device
+            .styling_preference()
+            .unwrap_or(mode != Mode::LinePrinter);
+
+        Ok(Interface {
             device,
             size,
             current: State::new(),
             alternate: None,
             staged_cursor: None,
             cursor: pos!(0, 0),
-            relative: true,
-        };
-
-        let device = &mut interface.device;
-        device.enable_raw_mode()?;
-
-        Ok(interface)
+            mode,
+            origin,
+            scrolled_lines: 0,
+            viewport: pos!(0, 0),
+            rendered_viewport: pos!(0, 0),
+            byte_budget: None,
+            tab_width: DEFAULT_TAB_WIDTH,
+            tab_placeholder: None,
+            wrap_indicator: None,
+            ambiguous_width: AmbiguousWidth::Narrow,
+            calibrated_graphemes: None,
+            bidi: true,
+            ghost: GhostState::new(),
+            marks: Vec::new(),
+            mark_style: Style::new(),
+            highlights: Vec::new(),
+            #[cfg(feature = "images")]
+            images: ImageState::new(),
+            notifications: Notifications::new(),
+            appended: Vec::new(),
+            history: VecDeque::new(),
+            history_limit: 0,
+            recorder: None,
+            line_hashes: HashMap::new(),
+            cursor_mode: CursorMode::Managed,
+            clock: Box::new(SystemClock),
+            debounce: DebounceState::new(),
+            default_style: None,
+            styling,
+            overflow: Overflow::Wrap,
+            mouse_capture: false,
+            encoder,
+            render_strategy: RenderStrategy::Minimal,
+            last_frame: None,
+            repaint_threshold: Some(DEFAULT_REPAINT_THRESHOLD),
+            wrapped: BTreeMap::new(),
+            aligned: BTreeMap::new(),
+            theme: Theme::new(),
+            named: BTreeMap::new(),
+            is_invalidated: false,
+            pending_output: Vec::new(),
+            hooks: Hooks::default(),
+            #[cfg(all(unix, feature = "signals"))]
+            signals: None,
+        })
     }
 
     /// When finished using this interface, uninitialize its terminal configuration.
@@ -99,305 +338,4423 @@ impl Interface<'_> {
     /// # Ok::<(), Error>(())
     /// ```
     pub fn exit(mut self) -> Result<()> {
-        if !self.relative {
-            self.device.queue(terminal::LeaveAlternateScreen)?;
-            self.device.flush()?;
+        if self.mode == Mode::LinePrinter {
+            return self.finish_line_printer();
+        }
+
+        if self.mouse_capture {
+            self.write_commands(&[DeviceCommand::DisableMouseCapture])?;
+        }
+
+        if self.mode == Mode::Alternate {
+            self.write_commands(&[DeviceCommand::LeaveAlternateScreen])?;
         } else {
             if let Some(last_position) = self.current.get_last_position() {
-                self.move_cursor_to(pos!(0, last_position.y()))?;
+                let line = last_position.y().saturating_sub(self.viewport.y());
+                let line = line.max(self.scrolled_lines);
+                self.move_cursor_to(pos!(0, line))?;
             }
         }
 
         self.device.disable_raw_mode()?;
 
-        println!();
+        self.device.write_all(b"\n")?;
+        self.device.write_all(&self.pending_output)?;
+        self.device.flush()?;
+
+        if let Some(recorder) = self.recorder.take() {
+            recorder.finish()?;
+        }
+
         Ok(())
     }
 
-    /// Update the interface's text at the specified position. Changes are staged until applied.
+    /// When finished using this interface, uninitialize its terminal configuration and print its
+    /// last applied content as plain, unstyled lines, e.g. so output captured in logs or CI is
+    /// readable. Alternate interfaces print their content after returning to the main screen,
+    /// while relative interfaces reprint their displayed lines in place.
     ///
     /// # Examples
     /// ```
     /// # use tty_interface::{Error, test::VirtualDevice};
     /// # let mut device = VirtualDevice::new();
-    /// use tty_interface::{Interface, Position, pos};
+    /// use tty_interface::{Color, Interface, Position, pos};
     ///
     /// let mut interface = Interface::new_alternate(&mut device)?;
-    /// interface.set(pos!(1, 1), "Hello, world!");
+    /// interface.set_styled(pos!(0, 0), "Done!", Color::Green.as_style());
+    /// interface.apply()?;
+    ///
+    /// // Prints "Done!" without styling
+    /// interface.finish_with_plain_output()?;
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn set(&mut self, position: Position, text: &str) {
-        self.stage_text(position, text, None)
+    pub fn finish_with_plain_output(mut self) -> Result<()> {
+        if self.mode == Mode::LinePrinter {
+            return self.finish_line_printer();
+        }
+
+        if self.mouse_capture {
+            self.write_commands(&[DeviceCommand::DisableMouseCapture])?;
+        }
+
+        // Relative interfaces' lines after the first are displayed from the origin's column
+        let (first_line, indent) = match self.mode {
+            Mode::Alternate => {
+                self.write_commands(&[DeviceCommand::LeaveAlternateScreen])?;
+                (0, String::new())
+            }
+            Mode::Relative => {
+                self.move_cursor_to(pos!(0, self.scrolled_lines))?;
+                let first_line = self.viewport.y().saturating_add(self.scrolled_lines);
+                (first_line, " ".repeat(self.origin.x().into()))
+            }
+            Mode::LinePrinter => unreachable!("line printers are finished separately"),
+        };
+
+        self.device.disable_raw_mode()?;
+
+        let mut output = String::new();
+        if let Some(last_position) = self.current.get_last_position() {
+            for line in first_line..=last_position.y() {
+                if line > first_line {
+                    output.push_str(&indent);
+                }
+
+                output.push_str(self.current.line_text(line).trim_end());
+                output.push('\n');
+            }
+        }
+
+        if output.is_empty() {
+            output.push('\n');
+        }
+
+        self.device.write_all(output.as_bytes())?;
+        self.device.write_all(&self.pending_output)?;
+        self.device.flush()?;
+
+        if let Some(recorder) = self.recorder.take() {
+            recorder.finish()?;
+        }
+
+        Ok(())
+    }
+
+    /// Finish a line printer, whose applied lines have already been printed.
+    fn finish_line_printer(mut self) -> Result<()> {
+        self.device.flush()?;
+
+        if let Some(recorder) = self.recorder.take() {
+            recorder.finish()?;
+        }
+
+        Ok(())
     }
 
-    /// Update the interface's text at the specified position. Changes are staged until applied.
+    /// Record each frame subsequently applied, with its timestamp, to an asciinema cast v2 file
+    /// at the specified path, replacing any recording in progress. The file is created
+    /// immediately and each frame is written to it as it's applied.
     ///
     /// # Examples
-    /// ```
+    /// ```no_run
     /// # use tty_interface::{Error, test::VirtualDevice};
     /// # let mut device = VirtualDevice::new();
-    /// use tty_interface::{Interface, Style, Position, pos};
+    /// use tty_interface::{Interface, Position, pos};
     ///
     /// let mut interface = Interface::new_alternate(&mut device)?;
-    /// interface.set_styled(pos!(1, 1), "Hello, world!", Style::new().set_bold(true));
+    /// interface.record_to("demo.cast")?;
+    ///
+    /// interface.set(pos!(0, 0), "Hello, world!");
+    /// interface.apply()?;
+    /// interface.exit()?;
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn set_styled(&mut self, position: Position, text: &str, style: Style) {
-        self.stage_text(position, text, Some(style))
+    pub fn record_to(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let file = File::create(path)?;
+        self.record_to_writer(Box::new(BufWriter::new(file)));
+        Ok(())
     }
 
-    /// Clear all text on the specified line. Changes are staged until applied.
+    /// Record each frame subsequently applied, with its timestamp, as an asciinema cast v2 streamed
+    /// to the writer, replacing any recording in progress. The writer is flushed when the interface
+    /// exits or is dropped, and exiting reports any error writing the recording.
+    pub fn record_to_writer(&mut self, writer: Box<dyn Write>) {
+        self.recorder = Some(Recorder::new(writer, self.size, self.clock.now()));
+    }
+
+    /// This interface's render mode.
     ///
     /// # Examples
     /// ```
     /// # use tty_interface::{Error, test::VirtualDevice};
     /// # let mut device = VirtualDevice::new();
-    /// use tty_interface::{Interface, Style, Position, pos};
+    /// use tty_interface::{Interface, Mode};
     ///
-    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// let interface = Interface::new_relative(&mut device)?;
+    /// assert_eq!(Mode::Relative, interface.mode());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The absolute position in the terminal's buffer which this interface's positions are
+    /// relative to. This is the top-left corner for alternate interfaces and the cursor's
+    /// starting position for relative interfaces, which moves up as rendering below the screen's
+    /// last line scrolls the terminal.
     ///
-    /// // Write "Hello," and "world!" on two different lines
-    /// interface.set(pos!(0, 0), "Hello,");
-    /// interface.set(pos!(0, 1), "world!");
-    /// interface.apply()?;
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
     ///
-    /// // Clear the second line, "world!"
-    /// interface.clear_line(1);
-    /// interface.apply()?;
+    /// let interface = Interface::new_alternate(&mut device)?;
+    /// assert_eq!(pos!(0, 0), interface.origin());
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn clear_line(&mut self, line: u16) {
-        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
-        alternate.clear_line(line);
+    pub fn origin(&self) -> Position {
+        self.origin
     }
 
-    /// Clear the remainder of the line from the specified position. Changes are staged until
-    /// applied.
+    /// Scroll the interface's content so the specified line is displayed at its top, e.g. to page
+    /// through content taller than the terminal. Lines above it aren't rendered, nor are lines
+    /// below the screen in alternate mode. Changes are staged until applied.
     ///
     /// # Examples
     /// ```
-    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # use tty_interface::{Error, test::VirtualDevice, Vector};
     /// # let mut device = VirtualDevice::new();
-    /// use tty_interface::{Interface, Style, Position, pos};
+    /// # device.resize(Vector::new(80, 10));
+    /// use tty_interface::{Interface, Position, pos};
     ///
     /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// for line in 0..100 {
+    ///     interface.set(pos!(0, line), &format!("Line {}", line));
+    /// }
     ///
-    /// // Write "Hello, world!" to the first line
-    /// interface.set(pos!(0, 0), "Hello, world!");
-    /// interface.apply()?;
-    ///
-    /// // Clear everything after "Hello"
-    /// interface.clear_rest_of_line(pos!(5, 0));
+    /// interface.scroll_to(50);
     /// interface.apply()?;
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn clear_rest_of_line(&mut self, from: Position) {
-        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
-        alternate.clear_rest_of_line(from);
+    pub fn scroll_to(&mut self, line: u16) {
+        self.viewport = pos!(self.viewport.x(), line);
     }
 
-    /// Clear the remainder of the interface from the specified position. Changes are staged until
-    /// applied.
+    /// Scroll the interface's content by the specified number of lines, down if positive and up
+    /// if negative, stopping at the first line. Changes are staged until applied.
     ///
     /// # Examples
     /// ```
     /// # use tty_interface::{Error, test::VirtualDevice};
     /// # let mut device = VirtualDevice::new();
-    /// use tty_interface::{Interface, Style, Position, pos};
+    /// use tty_interface::Interface;
     ///
     /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.scroll_by(5);
+    /// interface.scroll_by(-2);
+    /// assert_eq!(3, interface.scroll_offset());
     ///
-    /// // Write two lines of content
-    /// interface.set(pos!(0, 0), "Hello, world!");
-    /// interface.set(pos!(0, 1), "Another line");
-    /// interface.apply()?;
-    ///
-    /// // Clear everything after "Hello", including the second line
-    /// interface.clear_rest_of_interface(pos!(5, 0));
-    /// interface.apply()?;
+    /// interface.scroll_by(-10);
+    /// assert_eq!(0, interface.scroll_offset());
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn clear_rest_of_interface(&mut self, from: Position) {
-        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
-        alternate.clear_rest_of_interface(from);
+    pub fn scroll_by(&mut self, delta: i32) {
+        self.scroll_to(offset_by(self.viewport.y(), delta));
     }
 
-    /// Update the interface's cursor to the specified position, or hide it if unspecified.
+    /// The line displayed at the top of the interface, as staged by [Interface::scroll_to] and
+    /// [Interface::scroll_by].
+    pub fn scroll_offset(&self) -> u16 {
+        self.viewport.y()
+    }
+
+    /// Pan the interface's content so the specified column is displayed at its left edge, e.g. to
+    /// view content wider than the terminal staged with [Overflow::Extend]. Columns left of it and
+    /// beyond the terminal's width aren't rendered. Changes are staged until applied.
     ///
     /// # Examples
     /// ```
     /// # use tty_interface::{Error, test::VirtualDevice};
     /// # let mut device = VirtualDevice::new();
-    /// use tty_interface::{Interface, Position, pos};
+    /// use tty_interface::{Interface, Overflow, Position, pos};
     ///
     /// let mut interface = Interface::new_alternate(&mut device)?;
-    /// interface.set_cursor(Some(pos!(1, 2)));
+    /// interface.set_overflow(Overflow::Extend);
+    /// interface.set(pos!(0, 0), &"0123456789".repeat(20));
+    ///
+    /// interface.pan_to(100);
+    /// interface.apply()?;
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn set_cursor(&mut self, position: Option<Position>) {
-        self.alternate.get_or_insert_with(|| self.current.clone());
-        self.staged_cursor = position;
+    pub fn pan_to(&mut self, column: u16) {
+        self.viewport = pos!(column, self.viewport.y());
     }
 
-    /// Stages the specified text and optional style at a position in the terminal.
-    fn stage_text(&mut self, position: Position, text: &str, style: Option<Style>) {
-        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
-
-        let mut line = position.y().into();
-        let mut column = position.x().into();
-
-        for grapheme in text.graphemes(true) {
-            if column > self.size.x().into() {
-                column = 0;
-                line += 1;
-            }
-
-            let cell_position = pos!(column, line);
-            match style {
-                Some(style) => alternate.set_styled_text(cell_position, grapheme, style),
-                None => alternate.set_text(cell_position, grapheme),
-            }
+    /// Pan the interface's content by the specified number of columns, right if positive and left
+    /// if negative, stopping at the first column. Changes are staged until applied.
+    pub fn pan_by(&mut self, delta: i32) {
+        self.pan_to(offset_by(self.viewport.x(), delta));
+    }
 
-            column += 1;
-        }
+    /// The column displayed at the left edge of the interface, as staged by [Interface::pan_to]
+    /// and [Interface::pan_by].
+    pub fn pan_offset(&self) -> u16 {
+        self.viewport.x()
     }
 
-    /// Applies staged changes to the terminal.
+    /// Scroll the terminal as needed so the specified number of lines, up to the screen's height,
+    /// are available from a relative interface's origin downward, moving its origin up. Rendering
+    /// a tall interface started near the bottom of the screen otherwise scrolls it as it renders.
+    /// Has no effect on alternate interfaces.
     ///
     /// # Examples
     /// ```
-    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # use tty_interface::{Error, test::VirtualDevice, Vector};
     /// # let mut device = VirtualDevice::new();
+    /// # device.resize(Vector::new(80, 10));
+    /// # device.parser().process(b"\n\n\n\n\n\n\n\n");
     /// use tty_interface::{Interface, Position, pos};
     ///
-    /// let mut interface = Interface::new_alternate(&mut device)?;
-    /// interface.set(pos!(1, 1), "Hello, world!");
-    /// interface.apply()?;
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    /// assert_eq!(pos!(0, 8), interface.origin());
+    ///
+    /// // Make room for a five-line menu
+    /// interface.reserve_lines(5)?;
+    /// assert_eq!(pos!(0, 5), interface.origin());
     /// # Ok::<(), Error>(())
     /// ```
-    pub fn apply(&mut self) -> Result<()> {
-        if self.alternate.is_none() {
+    pub fn reserve_lines(&mut self, lines: u16) -> Result<()> {
+        let lines = lines.min(self.size.y());
+        if self.mode != Mode::Relative || self.origin.y().saturating_add(lines) <= self.size.y() {
             return Ok(());
         }
 
-        let mut alternate = self.alternate.take().unwrap();
-        swap(&mut self.current, &mut alternate);
+        // Line feeds from the screen's last line scroll the terminal, after which the cursor is
+        // returned to where it was within the interface
+        let cursor = self.cursor;
+        let mut buffer = Vec::new();
+        self.queue_cursor_move(&mut buffer, pos!(cursor.x(), lines - 1), false)?;
+        self.cursor = pos!(cursor.x(), lines - 1);
+        self.track_scroll();
 
-        let dirty_cells: Vec<(Position, Option<Cell>)> = self.current.dirty_iter().collect();
+        self.queue_cursor_move(&mut buffer, cursor, false)?;
+        self.cursor = cursor;
 
-        self.device.queue(cursor::Hide)?;
+        self.device.write_all(&buffer)?;
+        self.device.flush()?;
 
-        for (position, cell) in dirty_cells {
-            if self.cursor != position {
-                self.move_cursor_to(position)?;
-            }
+        Ok(())
+    }
 
-            match cell {
-                Some(cell) => {
-                    let mut content_style = ContentStyle::default();
-                    if let Some(style) = cell.style() {
-                        content_style = get_content_style(*style);
-                    }
+    /// Run the command to completion, printing its standard output and error through the
+    /// interface as with [Interface::writer] so a relative interface, such as a build's progress,
+    /// stays pinned below them. The lines of either stream are printed as they're read.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::process::Command;
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    /// interface.set(pos!(0, 0), "Building...");
+    /// interface.apply()?;
+    ///
+    /// let status = interface.pipe_child(Command::new("cargo").arg("build"))?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn pipe_child(&mut self, command: &mut Command) -> Result<ExitStatus> {
+        self.pipe_child_styled(command, None, None)
+    }
 
-                    let styled_content = StyledContent::new(content_style, cell.grapheme());
-                    let print_styled_content = style::PrintStyledContent(styled_content);
-                    self.device.queue(print_styled_content)?;
-                }
-                None => {
-                    let clear_content = style::Print(' ');
-                    self.device.queue(clear_content)?;
-                }
-            }
+    /// Run the command to completion like [Interface::pipe_child], styling the lines of its
+    /// standard output and error with the respective styles, if specified.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::process::Command;
+    /// use tty_interface::{Color, Interface};
+    ///
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    ///
+    /// // Errors are printed in red
+    /// let mut command = Command::new("make");
+    /// interface.pipe_child_styled(&mut command, None, Some(Color::Red.as_style()))?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn pipe_child_styled(
+        &mut self,
+        command: &mut Command,
+        stdout_style: Option<Style>,
+        stderr_style: Option<Style>,
+    ) -> Result<ExitStatus> {
+        let (mut child, lines) = spawn_piped(command)?;
+        for (stream, line) in lines {
+            let style = match stream {
+                ChildStream::Stdout => stdout_style,
+                ChildStream::Stderr => stderr_style,
+            };
+
+            let Some(style) = style else {
+                self.write_output(&line)?;
+                continue;
+            };
+
+            let line = String::from_utf8_lossy(&line);
+            let text = line.trim_end_matches(['\r', '\n']);
 
-            self.cursor = self.cursor.translate(1, 0);
+            let mut output = Vec::new();
+            self.encode(&DeviceCommand::PrintStyled(text, style), &mut output)?;
+            output.push(b'\n');
+            self.write_output(&output)?;
         }
 
-        if let Some(position) = self.staged_cursor {
-            self.move_cursor_to(position)?;
-            self.device.queue(cursor::Show)?;
+        Ok(child.wait()?)
+    }
+
+    /// Queue application output written through [Interface::writer], printing any complete lines.
+    pub(crate) fn write_output(&mut self, output: &[u8]) -> Result<()> {
+        match self.mode {
+            Mode::Alternate => {
+                self.pending_output.extend_from_slice(output);
+                return Ok(());
+            }
+            Mode::LinePrinter => {
+                self.device.write_all(output)?;
+                return Ok(());
+            }
+            Mode::Relative => self.pending_output.extend_from_slice(output),
         }
 
-        self.device.flush()?;
+        let Some(end) = self.pending_output.iter().rposition(|byte| *byte == b'\n') else {
+            return Ok(());
+        };
 
-        self.current.clear_dirty();
+        let lines: Vec<u8> = self.pending_output.drain(..=end).collect();
+        self.print_above(&lines)
+    }
 
+    /// Flush application output written through [Interface::writer] to the terminal.
+    pub(crate) fn flush_output(&mut self) -> Result<()> {
+        self.device.flush()?;
         Ok(())
     }
 
-    /// Move the cursor to the specified position and update it in state.
-    fn move_cursor_to(&mut self, position: Position) -> Result<()> {
-        if self.relative {
-            let diff_x = position.x() as i32 - self.cursor.x() as i32;
-            let diff_y = position.y() as i32 - self.cursor.y() as i32;
-
-            if diff_x > 0 {
-                self.device.queue(cursor::MoveRight(diff_x as u16))?;
-            } else if diff_x < 0 {
-                self.device.queue(cursor::MoveLeft(diff_x.abs() as u16))?;
-            }
+    /// Print lines in a relative interface's place, then repaint the applied interface below them.
+    fn print_above(&mut self, lines: &[u8]) -> Result<()> {
+        let mut buffer = Vec::new();
+        self.encode(&DeviceCommand::MoveTo(self.origin), &mut buffer)?;
+        self.encode(&DeviceCommand::ClearToEndOfLine, &mut buffer)?;
+        for line in self.origin.y().saturating_add(1)..self.size.y() {
+            self.encode(&DeviceCommand::MoveTo(pos!(0, line)), &mut buffer)?;
+            self.encode(&DeviceCommand::ClearToEndOfLine, &mut buffer)?;
+        }
+        self.encode(&DeviceCommand::MoveTo(self.origin), &mut buffer)?;
 
-            if diff_y > 0 {
-                self.device
-                    .queue(style::Print("\n".repeat(diff_y as usize)))?;
-            } else if diff_y < 0 {
-                self.device.queue(cursor::MoveUp(diff_y.abs() as u16))?;
+        // Raw mode doesn't return the carriage on line feeds
+        let mut previous = 0;
+        for byte in lines.iter().copied() {
+            if byte == b'\n' && previous != b'\r' {
+                buffer.push(b'\r');
             }
-        } else {
-            let move_cursor = cursor::MoveTo(position.x(), position.y());
-            self.device.queue(move_cursor)?;
+            buffer.push(byte);
+            previous = byte;
         }
 
+        self.device.write_all(&buffer)?;
+        self.device.flush()?;
+
+        // Without a cursor to query, the lines are assumed to scroll the terminal once they reach
+        // its last line
+        let line_count = lines.iter().filter(|byte| **byte == b'\n').count();
+        let line = (self.origin.y() as usize + line_count).min(self.size.y().max(1) as usize - 1);
+        self.origin = cursor_position_or(&mut *self.device, pos!(0, line as u16))?;
+        self.scrolled_lines = 0;
+        self.cursor = pos!(0, 0);
+        self.is_invalidated = true;
+
+        let staged = self.alternate.take();
+        let result = self.apply_frame(true);
+        self.alternate = staged;
+        result
+    }
+
+    /// How many of a relative interface's first lines have scrolled above the top of the screen.
+    /// These lines are left in the terminal's scrollback and further changes to them aren't
+    /// rendered.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice, Vector};
+    /// # let mut device = VirtualDevice::new();
+    /// # device.resize(Vector::new(80, 3));
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    /// for line in 0..5 {
+    ///     interface.set(pos!(0, line), "Line");
+    /// }
+    /// interface.apply()?;
+    ///
+    /// assert_eq!(2, interface.scrolled_lines());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn scrolled_lines(&self) -> u16 {
+        self.scrolled_lines
+    }
+
+    /// The terminal's size as last retrieved from the device.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Vector};
+    ///
+    /// let interface = Interface::new_alternate(&mut device)?;
+    /// assert_eq!(Vector::new(80, 24), interface.size());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    /// Whether this interface has switched the terminal to its alternate screen.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::Interface;
+    ///
+    /// let interface = Interface::new_alternate(&mut device)?;
+    /// assert!(interface.is_alternate_screen_active());
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn is_alternate_screen_active(&self) -> bool {
+        self.mode == Mode::Alternate
+    }
+
+    /// Update the interface's text at the specified position, returning the bounding box of the
+    /// cells it occupies once wrapped or clipped. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// let bounds = interface.set(pos!(1, 1), "Hello, world!");
+    /// assert_eq!(Vector::new(13, 1), bounds.size());
+    ///
+    /// // Place the next element beneath the text
+    /// let next_line = bounds.origin().y() + bounds.size().y();
+    /// interface.set(pos!(1, next_line), "Goodbye!");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set(&mut self, position: Position, text: &str) -> Rect {
+        self.stage_text(position, text, None)
+    }
+
+    /// Update the interface's text at the specified position, returning the bounding box of the
+    /// cells it occupies once wrapped or clipped. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Style, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_styled(pos!(1, 1), "Hello, world!", Style::new().set_bold(true));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_styled(&mut self, position: Position, text: &str, style: Style) -> Rect {
+        self.stage_text(position, text, Some(style))
+    }
+
+    /// Update the interface's text at the specified position from a static string, such as a
+    /// label or border, returning its bounding box like [Interface::set]. The cells borrow their
+    /// graphemes from the string rather than allocating them, so constant text may be restaged
+    /// every frame cheaply. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// for frame in 0..3 {
+    ///     interface.set_static(pos!(0, 0), "┌─ Status ─┐", None);
+    ///     interface.set_static(pos!(0, 1), "Connected", Some(Color::Green.as_style()));
+    ///     interface.apply()?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_static(
+        &mut self,
+        position: Position,
+        text: &'static str,
+        style: Option<Style>,
+    ) -> Rect {
+        // Normalized text no longer borrows from the string
+        let Cow::Borrowed(text) = normalize(text) else {
+            return self.stage_text(position, text, style);
+        };
+
+        self.mark_staged();
+
+        let mut alternate = self
+            .alternate
+            .take()
+            .unwrap_or_else(|| self.current.clone());
+        let graphemes = self.visual_graphemes(text);
+        let (overflow, width) = (self.overflow, self.size.x());
+        let (bounds, _) = self.layout_graphemes(
+            &mut alternate,
+            position,
+            graphemes,
+            Cell::new_static,
+            style,
+            overflow,
+            width,
+        );
+        self.alternate = Some(alternate);
+
+        bounds
+    }
+
+    /// Update the interface's text at the specified position, styled by the theme's style for the
+    /// name, returning the bounding box of the cells it occupies. The text is restyled if the
+    /// name's style is later redefined, unless it's been overwritten. Text is styled by the default
+    /// style while its name is undefined. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.define_style("error", Color::Red.as_style());
+    /// interface.set_named(pos!(0, 0), "Failed to connect", "error");
+    /// interface.apply()?;
+    ///
+    /// // Restyles the error text
+    /// interface.define_style("error", Color::DarkRed.as_style().set_bold(true));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_named(&mut self, position: Position, text: &str, name: &str) -> Rect {
+        self.mark_staged();
+
+        let style = self.theme.get(name);
+        let mut layout = self.layout_state();
+        let (overflow, width) = (self.overflow, self.size.x());
+        let (bounds, _) = self.layout_text(&mut layout, position, text, style, overflow, width);
+
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+        alternate.set_cells(&layout);
+
+        for (position, _) in layout.cells() {
+            self.named.insert(position, name.to_string());
+        }
+
+        bounds
+    }
+
+    /// Append text on the line below the last line with content, scrolling an alternate
+    /// interface down if needed to display it, and return a handle for updating it later. Long
+    /// text is wrapped or clipped like [Interface::set], and rewrapped when a resize event changing
+    /// the terminal's width is read. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface};
+    ///
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    /// let step = interface.append_line("Compiling...", None);
+    /// interface.append_line("warning: unused variable", Some(Color::Yellow.as_style()));
+    /// interface.apply()?;
+    ///
+    /// interface.update_line(step, "Compiled", Some(Color::Green.as_style()));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn append_line(&mut self, text: &str, style: Option<Style>) -> LineHandle {
+        let staged = self.alternate.as_ref().unwrap_or(&self.current);
+        let line = staged
+            .get_last_position()
+            .map_or(0, |position| position.y() + 1);
+
+        let bounds = self.stage_line(line, text, style);
+        self.appended.push(AppendedLine {
+            text: text.to_string(),
+            style,
+            bounds: Some(bounds),
+        });
+
+        let bottom = bounds.origin().y() + bounds.size().y().max(1);
+        if self.mode == Mode::Alternate && bottom > self.viewport.y() + self.size.y() {
+            self.scroll_to(bottom - self.size.y());
+        }
+
+        LineHandle::new(self.appended.len() - 1)
+    }
+
+    /// Replace an appended line's text wherever it's since been moved. If it wraps onto more or
+    /// fewer lines than before, the lines beneath it are shifted down or up to make room. Has no
+    /// effect if the handle wasn't returned by this interface or the line was deleted. Changes are
+    /// staged until applied.
+    pub fn update_line(&mut self, handle: LineHandle, text: &str, style: Option<Style>) {
+        self.restage_appended(handle.index(), text, style);
+    }
+
+    /// The appended line identified by the handle, if it was returned by this interface.
+    pub(crate) fn appended_line(&self, handle: LineHandle) -> Option<&AppendedLine> {
+        self.appended.get(handle.index())
+    }
+
+    /// Restages an appended line with the text, inserting or deleting lines beneath it so it
+    /// occupies exactly the lines it's wrapped onto.
+    fn restage_appended(&mut self, index: usize, text: &str, style: Option<Style>) {
+        let Some(bounds) = self
+            .appended
+            .get(index)
+            .and_then(|appended| appended.bounds)
+        else {
+            return;
+        };
+
+        let line = bounds.origin().y();
+        let (previous_height, height) = (bounds.size().y(), self.line_height(text));
+        for _ in previous_height..height {
+            self.insert_line(line + previous_height);
+        }
+        for _ in height..previous_height {
+            self.delete_line(line + height);
+        }
+
+        for cleared in line..line.saturating_add(height) {
+            self.clear_line(cleared);
+        }
+
+        let bounds = self.stage_line(line, text, style);
+        self.appended[index] = AppendedLine {
+            text: text.to_string(),
+            style,
+            bounds: Some(bounds),
+        };
+    }
+
+    /// Rewraps the appended lines to the terminal's current width from the first line down,
+    /// shifting the content beneath each which grows or shrinks.
+    fn reflow_appended(&mut self) {
+        let mut indices: Vec<usize> = (0..self.appended.len()).collect();
+        indices.sort_by_key(|index| self.appended[*index].bounds.map(|bounds| bounds.origin()));
+
+        for index in indices {
+            let AppendedLine { text, style, .. } = self.appended[index].clone();
+            self.restage_appended(index, &text, style);
+        }
+    }
+
+    /// The number of lines the text occupies when staged at the start of a line.
+    fn line_height(&self, text: &str) -> u16 {
+        let (overflow, width) = (self.overflow, self.size.x());
+        let (bounds, _) = self.layout_text(
+            &mut self.layout_state(),
+            pos!(0, 0),
+            text,
+            None,
+            overflow,
+            width,
+        );
+        bounds.size().y().max(1)
+    }
+
+    /// Stages the text at the start of the line, returning its bounds.
+    fn stage_line(&mut self, line: u16, text: &str, style: Option<Style>) -> Rect {
+        let bounds = self.stage_text(pos!(0, line), text, style);
+        Rect::new(
+            pos!(0, line),
+            Vector::new(self.size.x(), bounds.size().y().max(1)),
+        )
+    }
+
+    /// The position following the specified text if it were set at a position, accounting for
+    /// grapheme widths, tabs, and the interface's overflow handling, e.g. to place the cursor
+    /// after it. Text ending at the last column is followed by the next line's first column,
+    /// unless it overflows without wrapping. Nothing is staged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// let prompt = "名前: ";
+    /// interface.set(pos!(0, 0), prompt);
+    /// interface.set_cursor(Some(interface.cursor_after(pos!(0, 0), prompt)));
+    /// assert_eq!(pos!(6, 0), interface.cursor_after(pos!(0, 0), prompt));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn cursor_after(&self, position: Position, text: &str) -> Position {
+        let (_, end) = self.layout_text(
+            &mut self.layout_state(),
+            position,
+            text,
+            None,
+            self.overflow,
+            self.size.x(),
+        );
+
+        end
+    }
+
+    /// The columns and lines the specified text would occupy if set on a single line, using the
+    /// same grapheme widths and tab stops as staging. Nothing is staged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Vector};
+    ///
+    /// let interface = Interface::new_alternate(&mut device)?;
+    /// assert_eq!(Vector::new(10, 1), interface.measure("日本語 abc"));
+    /// assert_eq!(Vector::new(0, 0), interface.measure(""));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn measure(&self, text: &str) -> Vector {
+        let (bounds, _) = self.layout_text(
+            &mut self.layout_state(),
+            pos!(0, 0),
+            text,
+            None,
+            Overflow::Extend,
+            self.size.x(),
+        );
+
+        bounds.size()
+    }
+
+    /// The columns and lines the specified text would occupy if wrapped within the width, using
+    /// the same grapheme widths, tab stops, and wrapping as staging. Nothing is staged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Vector};
+    ///
+    /// let interface = Interface::new_alternate(&mut device)?;
+    /// assert_eq!(Vector::new(5, 3), interface.measure_wrapped("Hello, world!", 5));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn measure_wrapped(&self, text: &str, width: u16) -> Vector {
+        let (bounds, _) = self.layout_text(
+            &mut self.layout_state(),
+            pos!(0, 0),
+            text,
+            None,
+            Overflow::Wrap,
+            width,
+        );
+
+        bounds.size()
+    }
+
+    /// Update the interface's text with a multi-line block, such as a banner, placing each line
+    /// from the specified column of consecutive lines. Spacing is kept verbatim, overwriting the
+    /// text beneath it, and lines are neither reordered nor wrapped; content beyond the terminal's
+    /// last column is discarded. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_block(pos!(0, 0), "+-------+\n| Ready |\n+-------+");
+    /// interface.apply()?;
+    ///
+    /// // Only the changed cells are re-rendered
+    /// interface.set(pos!(2, 1), "Done!");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_block(&mut self, position: Position, text: &str) {
+        self.stage_block(position, text, None)
+    }
+
+    /// Update the interface's text with a styled multi-line block. See [Interface::set_block].
+    /// Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_block_styled(pos!(0, 0), " _ _ \n( v )", Color::Yellow.as_style());
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_block_styled(&mut self, position: Position, text: &str, style: Style) {
+        self.stage_block(position, text, Some(style))
+    }
+
+    /// Update the interface's text at the specified position, wrapping it at the terminal's last
+    /// column regardless of the overflow setting. The text is remembered and rewrapped to fit when
+    /// a resize event is read, until replaced by other wrapped text at the same position or
+    /// cleared with [Interface::clear_wrapped]. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_wrapped(pos!(0, 0), &"Lorem ipsum dolor sit amet. ".repeat(10));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_wrapped(&mut self, position: Position, text: &str) {
+        self.stage_wrapped(position, text, None);
+    }
+
+    /// Update the interface's styled text at the specified position, wrapping it at the terminal's
+    /// last column and rewrapping it on resize. See [Interface::set_wrapped].
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// let text = "Lorem ipsum dolor sit amet. ".repeat(10);
+    /// interface.set_wrapped_styled(pos!(0, 0), &text, Color::Cyan.as_style());
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_wrapped_styled(&mut self, position: Position, text: &str, style: Style) {
+        self.stage_wrapped(position, text, Some(style));
+    }
+
+    /// Clear the wrapped text at the specified position, if any, and stop rewrapping it on resize.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_wrapped(pos!(0, 0), &"Lorem ipsum dolor sit amet. ".repeat(10));
+    /// interface.clear_wrapped(pos!(0, 0));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_wrapped(&mut self, position: Position) {
+        if let Some(wrapped) = self.wrapped.remove(&position) {
+            self.mark_staged();
+            let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+            alternate.clear_positions(&wrapped.positions);
+        }
+    }
+
+    /// Update the interface's text on the specified line, aligned within the terminal's width.
+    /// The text is remembered and realigned when a resize event changing the width is read, until
+    /// replaced by other aligned text on the same line or cleared with [Interface::clear_aligned].
+    /// Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Alignment, Interface};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_aligned(0, "Title", Alignment::Center);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_aligned(&mut self, line: u16, text: &str, alignment: Alignment) {
+        self.stage_aligned(line, text, alignment, None);
+    }
+
+    /// Update the interface's text on the specified line, aligned within the terminal's width
+    /// and styled, and realigned on resize. See [Interface::set_aligned].
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Alignment, Interface, Style};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_aligned_styled(0, "Title", Alignment::Right, Style::new().set_bold(true));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_aligned_styled(
+        &mut self,
+        line: u16,
+        text: &str,
+        alignment: Alignment,
+        style: Style,
+    ) {
+        self.stage_aligned(line, text, alignment, Some(style));
+    }
+
+    /// Clear the aligned text on the specified line, if any, and stop realigning it on resize.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Alignment, Interface};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_aligned(0, "Title", Alignment::Center);
+    /// interface.clear_aligned(0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_aligned(&mut self, line: u16) {
+        if let Some(aligned) = self.aligned.remove(&line) {
+            self.mark_staged();
+            let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+            alternate.clear_positions(&aligned.positions);
+        }
+    }
+
+    /// Update the interface's text in a field of exactly `width` columns at the specified
+    /// position, truncating the text or padding it with `pad` according to the alignment. Since
+    /// the padding is staged along with the text, it overwrites any remnants of longer previous
+    /// values. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Alignment, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Renders "  1,024"
+    /// interface.set_field(pos!(0, 0), "1,024", 7, Alignment::Right, ' ');
+    /// interface.apply()?;
+    ///
+    /// // Renders "     16", overwriting the longer previous value
+    /// interface.set_field(pos!(0, 0), "16", 7, Alignment::Right, ' ');
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_field(
+        &mut self,
+        position: Position,
+        text: &str,
+        width: u16,
+        alignment: Alignment,
+        pad: char,
+    ) {
+        self.stage_field(position, text, width, alignment, pad, None)
+    }
+
+    /// Update the interface's styled text in a field of exactly `width` columns at the specified
+    /// position, truncating the text or padding it with `pad` according to the alignment. The
+    /// style applies to both the text and its padding. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Alignment, Color, Interface, Position, Style, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Renders "Status....." with a blue background
+    /// let style = Style::new().set_background(Color::Blue);
+    /// interface.set_field_styled(pos!(0, 0), "Status", 11, Alignment::Left, '.', style);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_field_styled(
+        &mut self,
+        position: Position,
+        text: &str,
+        width: u16,
+        alignment: Alignment,
+        pad: char,
+        style: Style,
+    ) {
+        self.stage_field(position, text, width, alignment, pad, Some(style))
+    }
+
+    /// Update the interface's text at the specified position with a timer's current text and
+    /// style, as measured by the interface's clock. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::time::Instant;
+    /// use tty_interface::{Interface, Position, Timer, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// let timer = Timer::elapsed(Instant::now());
+    ///
+    /// // Rendered as "Elapsed: 00:00:00"
+    /// interface.set(pos!(0, 0), "Elapsed:");
+    /// interface.set_timer(pos!(9, 0), &timer);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_timer(&mut self, position: Position, timer: &Timer) {
+        let now = self.clock.now();
+        self.stage_text(position, &timer.text_at(now), timer.style_at(now));
+    }
+
+    /// Clear all text on the specified line. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Style, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write "Hello," and "world!" on two different lines
+    /// interface.set(pos!(0, 0), "Hello,");
+    /// interface.set(pos!(0, 1), "world!");
+    /// interface.apply()?;
+    ///
+    /// // Clear the second line, "world!"
+    /// interface.clear_line(1);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_line(&mut self, line: u16) {
+        self.staged_state().clear_line(line);
+    }
+
+    /// Clear the remainder of the line from the specified position. Changes are staged until
+    /// applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Style, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write "Hello, world!" to the first line
+    /// interface.set(pos!(0, 0), "Hello, world!");
+    /// interface.apply()?;
+    ///
+    /// // Clear everything after "Hello"
+    /// interface.clear_rest_of_line(pos!(5, 0));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_rest_of_line(&mut self, from: Position) {
+        self.staged_state().clear_rest_of_line(from);
+    }
+
+    /// Clear the remainder of the interface from the specified position. Changes are staged until
+    /// applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Style, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write two lines of content
+    /// interface.set(pos!(0, 0), "Hello, world!");
+    /// interface.set(pos!(0, 1), "Another line");
+    /// interface.apply()?;
+    ///
+    /// // Clear everything after "Hello", including the second line
+    /// interface.clear_rest_of_interface(pos!(5, 0));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_rest_of_interface(&mut self, from: Position) {
+        self.staged_state().clear_rest_of_interface(from);
+    }
+
+    /// Clear all text in the specified column. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write two lines of content
+    /// interface.set(pos!(0, 0), "ABC");
+    /// interface.set(pos!(0, 1), "DEF");
+    /// interface.apply()?;
+    ///
+    /// // Clear the middle column, "B" and "E"
+    /// interface.clear_column(1);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_column(&mut self, column: u16) {
+        self.staged_state().clear_column(column);
+    }
+
+    /// Clear all text within the specified rectangle. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, Rect, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write three lines of content
+    /// interface.set(pos!(0, 0), "ABC");
+    /// interface.set(pos!(0, 1), "DEF");
+    /// interface.set(pos!(0, 2), "GHI");
+    /// interface.apply()?;
+    ///
+    /// // Clear the bottom-right corner, "EF" and "HI"
+    /// interface.clear_rect(Rect::new(pos!(1, 1), Vector::new(2, 2)));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn clear_rect(&mut self, rect: Rect) {
+        self.staged_state().clear_rect(rect);
+    }
+
+    /// Compose the style on top of the styling of content within the rectangle, such as to show a
+    /// selection or focus, replacing any existing overlay. The content's own styling is preserved
+    /// and restored once the overlay is cleared. Content set within the rectangle afterward isn't
+    /// overlaid. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, Rect, Style, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_styled(pos!(0, 0), "Item 1", Style::new().set_bold(true));
+    /// interface.set(pos!(0, 1), "Item 2");
+    ///
+    /// // Select the first item
+    /// let row = Rect::new(pos!(0, 0), Vector::new(6, 1));
+    /// interface.set_overlay(row, Style::new().set_background(Color::Blue));
+    /// interface.apply()?;
+    ///
+    /// // Deselect it, restoring its bold styling
+    /// interface.clear_overlay(row);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_overlay(&mut self, rect: Rect, style: Style) {
+        self.staged_state().set_overlay(rect, Some(style));
+    }
+
+    /// Remove overlays from content within the rectangle, restoring its own styling. Changes are
+    /// staged until applied.
+    pub fn clear_overlay(&mut self, rect: Rect) {
+        self.staged_state().set_overlay(rect, None);
+    }
+
+    /// Overlay the style on each occurrence of the pattern in the displayed text, as of the last
+    /// apply, replacing any previous highlights, and return the number of occurrences. Matches are
+    /// found line by line, with empty cells matching spaces. Like other overlays, content set over
+    /// a match afterward isn't highlighted. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, Style, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set(pos!(0, 0), "ERROR: disk full");
+    /// interface.set(pos!(0, 1), "INFO: retrying");
+    /// interface.set(pos!(0, 2), "ERROR: disk full");
+    /// interface.apply()?;
+    ///
+    /// let count = interface.highlight_matches("ERROR", Style::new().set_background(Color::Red));
+    /// assert_eq!(2, count);
+    /// interface.apply()?;
+    ///
+    /// interface.clear_highlights();
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn highlight_matches(&mut self, pattern: &str, style: Style) -> usize {
+        self.clear_highlights();
+
+        let matches = self.current.find_matches(pattern);
+        let state = self.staged_state();
+        for rect in &matches {
+            state.set_overlay(*rect, Some(style));
+        }
+
+        self.highlights = matches;
+        self.highlights.len()
+    }
+
+    /// Remove the overlays of the matches highlighted by [Interface::highlight_matches]. Changes
+    /// are staged until applied.
+    pub fn clear_highlights(&mut self) {
+        if self.highlights.is_empty() {
+            return;
+        }
+
+        let highlights = take(&mut self.highlights);
+        let state = self.staged_state();
+        for rect in highlights {
+            state.set_overlay(rect, None);
+        }
+    }
+
+    /// Display the image scaled to fill the rectangle, replacing any images it overlaps. If the
+    /// device reports supporting the kitty, iTerm2, or sixel image protocol, the image is
+    /// transmitted over blank cells after each frame's text; otherwise, it's approximated with
+    /// colored half-block characters. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{ImageSource, Interface, Position, Rect, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set(pos!(0, 0), "preview.png");
+    ///
+    /// // A red square previewed beneath its file name
+    /// let pixels = [255, 0, 0, 255].repeat(16 * 16);
+    /// let image = ImageSource::from_rgba(16, 16, pixels).unwrap();
+    /// let preview = Rect::new(pos!(0, 1), Vector::new(8, 4));
+    /// interface.set_image(preview, image);
+    /// interface.apply()?;
+    ///
+    /// interface.clear_image(preview);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    #[cfg(feature = "images")]
+    pub fn set_image(&mut self, rect: Rect, source: ImageSource) {
+        self.clear_image(rect);
+
+        let protocol = self.device.image_protocol();
+        let cells = match protocol {
+            ImageProtocol::Blocks => source.to_blocks(rect.size()),
+            _ => (0..rect.size().y())
+                .flat_map(|line| (0..rect.size().x()).map(move |column| pos!(column, line)))
+                .map(|offset| (offset, Cell::new(" ", None)))
+                .collect(),
+        };
+
+        let state = self.staged_state();
+        for (offset, cell) in cells {
+            state.set_cell(rect.origin().translate(offset.x(), offset.y()), cell);
+        }
+
+        if protocol != ImageProtocol::Blocks {
+            self.images.place(rect, source);
+        }
+    }
+
+    /// Remove images overlapping the rectangle, clearing the cells they occupied. Changes are
+    /// staged until applied.
+    #[cfg(feature = "images")]
+    pub fn clear_image(&mut self, rect: Rect) {
+        let protocol = self.device.image_protocol();
+        for image in self.images.remove_overlapping(rect) {
+            // Kitty images are deleted, while others are painted over by rewriting their cells
+            if protocol == ImageProtocol::Kitty {
+                self.images.delete(image.id);
+            } else {
+                let top = image.rect.origin().y();
+                let lines = top..top.saturating_add(image.rect.size().y());
+                self.line_hashes.retain(|line, _| !lines.contains(line));
+            }
+
+            self.staged_state().clear_rect(image.rect);
+        }
+    }
+
+    /// Draw a panel occupying the rectangle, such as a dialog box, filling its cells and drawing
+    /// its border, title, and shadow per the style. Text set within the panel afterward overlays
+    /// it. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Border, Color, Interface, PanelStyle, Position, Rect, Style, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// let fill = Style::new().set_background(Color::DarkBlue);
+    /// let style = PanelStyle::new()
+    ///     .set_border(Border::Double)
+    ///     .set_fill(fill)
+    ///     .set_title("Quit?")
+    ///     .set_shadow(true);
+    /// interface.draw_panel(Rect::new(pos!(10, 5), Vector::new(24, 5)), &style);
+    /// interface.set_styled(pos!(12, 7), "Unsaved changes remain", fill);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn draw_panel(&mut self, rect: Rect, style: &PanelStyle) {
+        draw_panel(self.staged_state(), rect, style);
+    }
+
+    /// Render a subset of Markdown within the rectangle, clearing it first, and return the number
+    /// of lines used. Headings, lists, and paragraphs are wrapped to the rectangle's width and
+    /// fenced code blocks are truncated to it; lines beyond the rectangle are discarded. Emphasis,
+    /// strong emphasis, inline code, code blocks, and headings are styled by the theme's
+    /// "markdown.emphasis", "markdown.strong", "markdown.code", and "markdown.heading" styles, or
+    /// italic, bold, yellow, and bold if undefined. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, Rect, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// let help = "# Help\n\nPress `q` to **quit**.\n\n- Arrows move\n- Enter selects";
+    /// let lines = interface.render_markdown(Rect::new(pos!(0, 0), Vector::new(40, 10)), help);
+    /// assert_eq!(6, lines);
+    ///
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn render_markdown(&mut self, rect: Rect, text: &str) -> u16 {
+        let theme = self.theme.clone();
+        render_markdown(self.staged_state(), &theme, rect, text)
+    }
+
+    /// A copy of the staged cells within the rectangle.
+    pub(crate) fn staged_region(&self, rect: Rect) -> State {
+        self.alternate
+            .as_ref()
+            .unwrap_or(&self.current)
+            .region(rect)
+    }
+
+    /// Replaces the staged cells within the rectangle with the region's.
+    pub(crate) fn restore_region(&mut self, rect: Rect, region: &State) {
+        self.staged_state().restore_region(rect, region);
+    }
+
+    /// Draw a vertical line of the specified grapheme downward from a position. Changes are
+    /// staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Separate two panes with a vertical line
+    /// interface.set(pos!(0, 0), "Left");
+    /// interface.draw_vline(pos!(5, 0), 3, "│");
+    /// interface.set(pos!(7, 0), "Right");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn draw_vline(&mut self, from: Position, length: u16, grapheme: &str) {
+        self.staged_state().draw_vline(from, length, grapheme, None);
+    }
+
+    /// Draw a styled vertical line of the specified grapheme downward from a position. Changes
+    /// are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.draw_vline_styled(pos!(5, 0), 3, "│", Color::Blue.as_style());
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn draw_vline_styled(&mut self, from: Position, length: u16, grapheme: &str, style: Style) {
+        self.staged_state()
+            .draw_vline(from, length, grapheme, Some(style));
+    }
+
+    /// Draw a two-line column ruler from the specified position, with column offsets labeled on
+    /// the first line and tick marks on the second line every `interval` columns. Useful for
+    /// aligning layouts and diagnosing width issues. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Renders:
+    /// // 0    5    10   15
+    /// // |....|....|....|....
+    /// interface.draw_ruler(pos!(0, 0), 20, 5);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn draw_ruler(&mut self, from: Position, length: u16, interval: u16) {
+        self.stage_ruler(from, length, interval, None);
+    }
+
+    /// Draw a styled two-line column ruler from the specified position, with column offsets
+    /// labeled on the first line and tick marks on the second line every `interval` columns.
+    /// Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.draw_ruler_styled(pos!(0, 0), 40, 10, Color::DarkGrey.as_style());
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn draw_ruler_styled(&mut self, from: Position, length: u16, interval: u16, style: Style) {
+        self.stage_ruler(from, length, interval, Some(style));
+    }
+
+    /// Stages a column ruler's labels and tick marks, clipped to the terminal's width.
+    fn stage_ruler(&mut self, from: Position, length: u16, interval: u16, style: Option<Style>) {
+        if interval == 0 {
+            return;
+        }
+
+        self.mark_staged();
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+
+        let length = length.min(self.size.x().saturating_sub(from.x()));
+        let tick_line = from.y().saturating_add(1);
+
+        // Labels are only drawn where they fit entirely before the next tick
+        let mut labels = vec![' '; length as usize];
+        for offset in (0..length).step_by(interval as usize) {
+            let label = offset.to_string();
+            let label_length = label.len() as u16;
+            if label_length < interval && offset + label_length <= length {
+                for (index, digit) in label.chars().enumerate() {
+                    labels[offset as usize + index] = digit;
+                }
+            }
+        }
+
+        let mut buffer = [0; 4];
+        for (offset, label) in labels.into_iter().enumerate() {
+            let offset = offset as u16;
+            let tick = if offset.is_multiple_of(interval) {
+                '|'
+            } else {
+                '.'
+            };
+
+            let cells = [(from.y(), label), (tick_line, tick)];
+            for (line, character) in cells {
+                let position = pos!(from.x() + offset, line);
+                let grapheme = character.encode_utf8(&mut buffer);
+                match style {
+                    Some(style) => alternate.set_styled_text(position, grapheme, style),
+                    None => alternate.set_text(position, grapheme),
+                }
+            }
+        }
+    }
+
+    /// Insert an empty line at the specified line, shifting it and all subsequent lines down.
+    /// Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write "Hello," and "world!" on two different lines
+    /// interface.set(pos!(0, 0), "Hello,");
+    /// interface.set(pos!(0, 1), "world!");
+    /// interface.apply()?;
+    ///
+    /// // Insert a line between them, moving "world!" to the third line
+    /// interface.insert_line(1);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn insert_line(&mut self, line: u16) {
+        self.staged_state().insert_line(line);
+        for appended in &mut self.appended {
+            appended.insert_line(line);
+        }
+    }
+
+    /// Delete the specified line, shifting all subsequent lines up. Changes are staged until
+    /// applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Write three lines of content
+    /// interface.set(pos!(0, 0), "First");
+    /// interface.set(pos!(0, 1), "Second");
+    /// interface.set(pos!(0, 2), "Third");
+    /// interface.apply()?;
+    ///
+    /// // Delete the second line, moving "Third" up to the second line
+    /// interface.delete_line(1);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn delete_line(&mut self, line: u16) {
+        self.staged_state().delete_line(line);
+        for appended in &mut self.appended {
+            appended.delete_line(line);
+        }
+    }
+
+    /// Update whether the interface controls the cursor's visibility, showing or hiding it
+    /// immediately to match. The cursor is managed by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{CursorMode, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    /// interface.set_cursor_mode(CursorMode::Visible)?;
+    ///
+    /// // The cursor stays visible and returns to where it was after each frame
+    /// interface.set(pos!(0, 0), "Progress: 50%");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_cursor_mode(&mut self, mode: CursorMode) -> Result<()> {
+        if mode != self.cursor_mode {
+            self.cursor_mode = mode;
+            if self.mode == Mode::LinePrinter {
+                return Ok(());
+            }
+
+            self.write_commands(&[match mode {
+                CursorMode::Managed => DeviceCommand::HideCursor,
+                CursorMode::Visible => DeviceCommand::ShowCursor,
+            }])?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the interface controls the cursor's visibility.
+    pub fn cursor_mode(&self) -> CursorMode {
+        self.cursor_mode
+    }
+
+    /// Update the interface's cursor to the specified position, or hide it if unspecified.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_cursor(Some(pos!(1, 2)));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_cursor(&mut self, position: Option<Position>) {
+        self.staged_state();
+        self.staged_cursor = position;
+    }
+
+    /// Stage a multi-line block verbatim at the specified position, clipped to the terminal's width.
+    fn stage_block(&mut self, position: Position, text: &str, style: Option<Style>) {
+        let block = State::from_text(text, self.tab_width, self.widths().clone());
+        let style = style.or(self.default_style);
+        let width = self.size.x();
+
+        let alternate = self.staged_state();
+        for (offset, cell) in block.cells() {
+            let x = position.x().checked_add(offset.x());
+            let y = position.y().checked_add(offset.y());
+
+            if let (Some(x), Some(y)) = (x, y) {
+                if x.saturating_add(cell.width(block.widths())) > width {
+                    continue;
+                }
+
+                match style {
+                    Some(style) => alternate.set_styled_text(pos!(x, y), cell.grapheme(), style),
+                    None => alternate.set_text(pos!(x, y), cell.grapheme()),
+                }
+            }
+        }
+    }
+
+    /// Records that changes were staged and retrieves the staged state.
+    fn staged_state(&mut self) -> &mut State {
+        self.mark_staged();
+        self.alternate.get_or_insert_with(|| self.current.clone())
+    }
+
+    /// Records that changes were staged for automatic applies.
+    fn mark_staged(&mut self) {
+        self.debounce.mark_staged(self.clock.now());
+    }
+
+    /// Check the invariants of this interface's states, describing the first which is violated.
+    /// Checked after each operation by [crate::test::check_operations].
+    pub(crate) fn check_invariants(&self) -> std::result::Result<(), String> {
+        self.current.check_invariants()?;
+        if let Some(alternate) = &self.alternate {
+            alternate.check_invariants()?;
+        }
+
+        Ok(())
+    }
+
+    /// Compare the screen to this interface's applied state cell-for-cell, including styles,
+    /// describing the first position at which they diverge. The state is rendered onto a blank
+    /// screen with the same encoder for comparison. Only alternate interfaces whose frames are
+    /// fully applied, without content composited over their state such as ghosts, are compared.
+    pub(crate) fn find_screen_divergence(
+        &mut self,
+        screen: &vt100::Screen,
+    ) -> Result<Option<String>> {
+        let is_composited = self.ghost.is_shown()
+            || !self.marks.is_empty()
+            || self.notifications.len() > 0
+            || self.wrap_indicator.is_some();
+        #[cfg(feature = "images")]
+        let is_composited = is_composited || !self.images.placed().is_empty();
+        if self.mode != Mode::Alternate || is_composited || self.current.is_dirty() {
+            return Ok(None);
+        }
+
+        let cells: Vec<(Position, Cell)> = self
+            .current
+            .cells()
+            .filter(|(position, _)| self.is_in_viewport(*position))
+            .map(|(position, cell)| (self.to_display(position), cell.clone()))
+            .collect();
+
+        let mut expected_frame = Vec::new();
+        for (position, cell) in cells {
+            // Wide cells straddling the last column are displayed blank
+            if position.x().saturating_add(cell.width(self.widths())) > self.size.x() {
+                continue;
+            }
+
+            self.encode(&DeviceCommand::MoveTo(position), &mut expected_frame)?;
+            self.encode(&print_command(&cell), &mut expected_frame)?;
+        }
+
+        let (lines, columns) = screen.size();
+        let mut expected = vt100::Parser::new(lines, columns, 0);
+        expected.process(&expected_frame);
+
+        let describe = |cell: &vt100::Cell| {
+            let contents = match cell.contents().as_str() {
+                "" => " ".to_string(),
+                contents => contents.to_string(),
+            };
+
+            // Blank cells don't display their foreground color, boldness or slant
+            let is_blank = contents == " ";
+            let foreground = match is_blank {
+                true => vt100::Color::Default,
+                false => cell.fgcolor(),
+            };
+
+            let attributes = [
+                (cell.bold() && !is_blank, "bold"),
+                (cell.italic() && !is_blank, "italic"),
+                (cell.underline(), "underline"),
+                (cell.inverse(), "inverse"),
+            ];
+            let attributes: Vec<&str> = attributes
+                .into_iter()
+                .filter_map(|(is_set, name)| is_set.then_some(name))
+                .collect();
+
+            format!(
+                "{:?} ({:?} on {:?}{})",
+                contents,
+                foreground,
+                cell.bgcolor(),
+                attributes
+                    .iter()
+                    .map(|attribute| format!(", {}", attribute))
+                    .collect::<String>()
+            )
+        };
+
+        for line in 0..lines.min(self.size.y()) {
+            for column in 0..columns.min(self.size.x()) {
+                let displayed = screen.cell(line, column).map(describe);
+                let expected = expected.screen().cell(line, column).map(describe);
+                if displayed != expected {
+                    return Ok(Some(format!(
+                        "at {:?}: the screen displays {} where {} is expected",
+                        pos!(column, line),
+                        displayed.unwrap_or_default(),
+                        expected.unwrap_or_default()
+                    )));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// How many columns this interface's terminal displays graphemes with.
+    pub(crate) fn widths(&self) -> &Widths {
+        self.current.widths()
+    }
+
+    /// Update how many columns this interface's terminal displays graphemes with, for both its
+    /// rendered and staged states.
+    fn update_widths(&mut self, update: impl FnOnce(&mut Widths)) {
+        let mut widths = self.widths().clone();
+        update(&mut widths);
+
+        if let Some(alternate) = &mut self.alternate {
+            alternate.set_widths(widths.clone());
+        }
+        self.current.set_widths(widths);
+    }
+
+    /// An empty state for laying out text, measured with this interface's widths.
+    fn layout_state(&self) -> State {
+        State::with_widths(self.widths().clone())
+    }
+
+    /// The positions to be rendered by the next apply.
+    pub(crate) fn dirty_positions(&self) -> Vec<Position> {
+        self.alternate
+            .as_ref()
+            .unwrap_or(&self.current)
+            .dirty_positions()
+    }
+
+    /// Check the invariants which hold once changes are applied, given where the device's cursor
+    /// is, describing the first which is violated.
+    pub(crate) fn check_applied_invariants(
+        &self,
+        device_cursor: Position,
+    ) -> std::result::Result<(), String> {
+        // Without a byte budget, every change is rendered
+        if self.byte_budget.is_none() && self.current.is_dirty() {
+            return Err("changes remain dirty once applied".to_string());
+        }
+
+        // Relative interfaces may extend beyond the screen, scrolling the terminal
+        if self.mode != Mode::Alternate {
+            return Ok(());
+        }
+
+        if self.cursor.y() >= self.size.y() || self.cursor.x() > self.size.x() {
+            return Err(format!(
+                "cursor at {:?} is beyond the {:?} screen",
+                self.cursor, self.size
+            ));
+        }
+
+        // The cursor rests on the last column after printing into it, which terminals may report
+        // as either the last column or the one following it
+        let last_column = self.size.x() - 1;
+        let cursor = pos!(self.cursor.x().min(last_column), self.cursor.y());
+        let device_cursor = pos!(device_cursor.x().min(last_column), device_cursor.y());
+        if cursor != device_cursor {
+            return Err(format!(
+                "cursor tracked at {:?} but displayed at {:?}",
+                cursor, device_cursor
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Stages the specified text and optional style at a position in the terminal, returning the
+    /// bounding box of its cells.
+    fn stage_text(&mut self, position: Position, text: &str, style: Option<Style>) -> Rect {
+        self.mark_staged();
+
+        let mut alternate = self
+            .alternate
+            .take()
+            .unwrap_or_else(|| self.current.clone());
+        let (overflow, width) = (self.overflow, self.size.x());
+        let (bounds, _) = self.layout_text(&mut alternate, position, text, style, overflow, width);
+        self.alternate = Some(alternate);
+
+        bounds
+    }
+
+    /// Stages the specified wrapped text, replacing any wrapped text at the same position, and
+    /// remembers it to be rewrapped on resize.
+    fn stage_wrapped(&mut self, position: Position, text: &str, style: Option<Style>) {
+        self.mark_staged();
+
+        let mut layout = self.layout_state();
+        let width = self.size.x();
+        self.layout_text(&mut layout, position, text, style, Overflow::Wrap, width);
+        let positions: BTreeSet<Position> = layout.cells().map(|(position, _)| position).collect();
+
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+        if let Some(previous) = self.wrapped.remove(&position) {
+            alternate
+                .clear_positions(&previous.positions.difference(&positions).copied().collect());
+        }
+
+        alternate.set_cells(&layout);
+
+        let text = text.to_string();
+        let wrapped = WrappedText {
+            text,
+            style,
+            positions,
+        };
+        self.wrapped.insert(position, wrapped);
+    }
+
+    /// Rewraps the remembered wrapped text to the terminal's current width, restaging only the
+    /// cells which change.
+    fn reflow_wrapped(&mut self) {
+        if self.wrapped.is_empty() {
+            return;
+        }
+
+        self.mark_staged();
+
+        let mut layouts = Vec::new();
+        let mut stale_positions = BTreeSet::new();
+        for (position, wrapped) in &self.wrapped {
+            let mut layout = self.layout_state();
+            self.layout_text(
+                &mut layout,
+                *position,
+                &wrapped.text,
+                wrapped.style,
+                Overflow::Wrap,
+                self.size.x(),
+            );
+            stale_positions.extend(wrapped.positions.iter().copied());
+            layouts.push(layout);
+        }
+
+        // Text may be rewrapped into positions another text previously occupied
+        for layout in &layouts {
+            for (position, _) in layout.cells() {
+                stale_positions.remove(&position);
+            }
+        }
+
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+        alternate.clear_positions(&stale_positions);
+
+        for (wrapped, layout) in self.wrapped.values_mut().zip(&layouts) {
+            alternate.set_cells(layout);
+            wrapped.positions = layout.cells().map(|(position, _)| position).collect();
+        }
+    }
+
+    /// Stages the specified aligned text, replacing any aligned text on the same line, and
+    /// remembers it to be realigned on resize.
+    fn stage_aligned(&mut self, line: u16, text: &str, alignment: Alignment, style: Option<Style>) {
+        self.mark_staged();
+
+        let layout = self.layout_aligned(line, text, alignment, style);
+        let positions: BTreeSet<Position> = layout.cells().map(|(position, _)| position).collect();
+
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+        if let Some(previous) = self.aligned.remove(&line) {
+            alternate
+                .clear_positions(&previous.positions.difference(&positions).copied().collect());
+        }
+
+        alternate.set_cells(&layout);
+
+        let aligned = AlignedText {
+            text: text.to_string(),
+            alignment,
+            style,
+            positions,
+        };
+        self.aligned.insert(line, aligned);
+    }
+
+    /// Realigns the remembered aligned text within the terminal's current width, restaging only
+    /// the cells which change.
+    fn reflow_aligned(&mut self) {
+        if self.aligned.is_empty() {
+            return;
+        }
+
+        self.mark_staged();
+
+        let mut layouts = Vec::new();
+        let mut stale_positions = BTreeSet::new();
+        for (line, aligned) in &self.aligned {
+            let layout =
+                self.layout_aligned(*line, &aligned.text, aligned.alignment, aligned.style);
+            stale_positions.extend(aligned.positions.iter().copied());
+            layouts.push(layout);
+        }
+
+        for layout in &layouts {
+            for (position, _) in layout.cells() {
+                stale_positions.remove(&position);
+            }
+        }
+
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+        alternate.clear_positions(&stale_positions);
+
+        for (aligned, layout) in self.aligned.values_mut().zip(&layouts) {
+            alternate.set_cells(layout);
+            aligned.positions = layout.cells().map(|(position, _)| position).collect();
+        }
+    }
+
+    /// Lays out the specified text aligned on a line within the terminal's width.
+    fn layout_aligned(
+        &self,
+        line: u16,
+        text: &str,
+        alignment: Alignment,
+        style: Option<Style>,
+    ) -> State {
+        let width = self.size.x();
+        let column = alignment.get_offset(self.widths().text_width(&normalize(text)), width);
+
+        let mut layout = self.layout_state();
+        self.layout_text(
+            &mut layout,
+            pos!(column, line),
+            text,
+            style,
+            self.overflow,
+            width,
+        );
+        layout
+    }
+
+    /// Lays out the specified text and optional style at a position in the state, handling text
+    /// extending beyond the width as specified. Returns the bounding box of the staged cells and
+    /// the position following the text's last cell.
+    fn layout_text(
+        &self,
+        state: &mut State,
+        position: Position,
+        text: &str,
+        style: Option<Style>,
+        overflow: Overflow,
+        width: u16,
+    ) -> (Rect, Position) {
+        let text = normalize(text);
+        let graphemes = self.visual_graphemes(&text);
+        self.layout_graphemes(
+            state,
+            position,
+            graphemes,
+            Cell::new,
+            style,
+            overflow,
+            width,
+        )
+    }
+
+    /// The text's graphemes in the order they're displayed.
+    fn visual_graphemes<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        match self.bidi {
+            true => get_visual_graphemes(text),
+            false => split_graphemes(text).collect(),
+        }
+    }
+
+    /// Lays out the graphemes like [Interface::layout_text], creating each grapheme's cell with
+    /// the specified function.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_graphemes<'g>(
+        &self,
+        state: &mut State,
+        position: Position,
+        graphemes: Vec<&'g str>,
+        cell: impl Fn(&'g str, Option<Style>) -> Cell,
+        style: Option<Style>,
+        overflow: Overflow,
+        width: u16,
+    ) -> (Rect, Position) {
+        let mut line = position.y();
+        let mut column = position.x();
+        let mut bounds: Option<(Position, Position)> = None;
+        let indicator = self.wrap_indicator.as_ref();
+        let mut stage = |state: &mut State, line: &mut u16, column: &mut u16, cell| {
+            let start = stage_cell(state, width, overflow, indicator, line, column, cell);
+            if let Some(start) = start {
+                let end = pos!(*column, start.y().saturating_add(1));
+                bounds = Some(match bounds {
+                    Some((top_left, bottom_right)) => (
+                        pos!(top_left.x().min(start.x()), top_left.y()),
+                        pos!(bottom_right.x().max(end.x()), end.y()),
+                    ),
+                    None => (start, end),
+                });
+            }
+        };
+
+        let style = style.or(self.default_style);
+        for grapheme in graphemes {
+            if grapheme != "\t" {
+                stage(state, &mut line, &mut column, cell(grapheme, style));
+                continue;
+            }
+
+            if self.tab_width == 0 {
+                continue;
+            }
+
+            let tab_length = self.tab_width - column % self.tab_width;
+            for offset in 0..tab_length {
+                let tab_cell = match &self.tab_placeholder {
+                    Some((glyph, placeholder_style)) if offset == 0 => {
+                        Cell::new(glyph, Some(*placeholder_style))
+                    }
+                    Some((_, placeholder_style)) => Cell::new(" ", Some(*placeholder_style)),
+                    None => Cell::new(" ", style),
+                };
+
+                stage(state, &mut line, &mut column, tab_cell);
+            }
+        }
+
+        // Text ending at the last column continues from the following line
+        if overflow == Overflow::Wrap && column >= width && column > position.x() {
+            column = 0;
+            line += 1;
+        }
+
+        let bounds = match bounds {
+            Some((top_left, bottom_right)) => Rect::new(
+                top_left,
+                Vector::new(
+                    bottom_right.x() - top_left.x(),
+                    bottom_right.y() - top_left.y(),
+                ),
+            ),
+            None => Rect::new(position, Vector::new(0, 0)),
+        };
+
+        (bounds, pos!(column, line))
+    }
+
+    /// Stages the specified text in a fixed-width field, truncated or padded per the alignment.
+    fn stage_field(
+        &mut self,
+        position: Position,
+        text: &str,
+        width: u16,
+        alignment: Alignment,
+        pad: char,
+        style: Option<Style>,
+    ) {
+        self.mark_staged();
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+
+        let text = normalize(text);
+        let graphemes = match self.bidi {
+            true => get_visual_graphemes(&text),
+            false => split_graphemes(&text).collect(),
+        };
+
+        let mut content = Vec::new();
+        let mut content_width = 0;
+        for grapheme in graphemes {
+            let grapheme_width = alternate.widths().grapheme_width(grapheme);
+            if content_width + grapheme_width > width {
+                break;
+            }
+
+            content.push((grapheme, grapheme_width));
+            content_width += grapheme_width;
+        }
+
+        let mut buffer = [0; 4];
+        let pad: &str = pad.encode_utf8(&mut buffer);
+
+        let mut stage = |offset: u16, grapheme: &str| {
+            if let Some(column) = position.x().checked_add(offset) {
+                let cell_position = pos!(column, position.y());
+                match style {
+                    Some(style) => alternate.set_styled_text(cell_position, grapheme, style),
+                    None => alternate.set_text(cell_position, grapheme),
+                }
+            }
+        };
+
+        let content_offset = alignment.get_offset(content_width, width);
+        for offset in 0..content_offset {
+            stage(offset, pad);
+        }
+
+        let mut offset = content_offset;
+        for (grapheme, grapheme_width) in content {
+            stage(offset, grapheme);
+            offset += grapheme_width;
+        }
+
+        for offset in offset..width {
+            stage(offset, pad);
+        }
+    }
+
+    /// Update the number of columns between tab stops used to expand tabs in staged text. A
+    /// width of zero removes tabs from staged text.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_tab_width(4);
+    ///
+    /// // "world!" is staged at the fourth column
+    /// interface.set(pos!(0, 0), "Hi,\tworld!");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_tab_width(&mut self, tab_width: u16) {
+        self.tab_width = tab_width;
+    }
+
+    /// The number of columns between tab stops used to expand tabs in staged text.
+    pub fn tab_width(&self) -> u16 {
+        self.tab_width
+    }
+
+    /// Update the placeholder used to render expanded tabs, or render them as blank space if
+    /// unspecified. The glyph occupies the tab's first column and the style applies to all of the
+    /// tab's columns.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_tab_placeholder(Some(("→", Color::DarkGrey.as_style())));
+    ///
+    /// // The tab is rendered as "→       "
+    /// interface.set(pos!(0, 0), "\tindented");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_tab_placeholder(&mut self, placeholder: Option<(&str, Style)>) {
+        self.tab_placeholder = placeholder.map(|(glyph, style)| (glyph.to_string(), style));
+    }
+
+    /// Update the indicator beginning each line continuing wrapped text, such as "↪ ", to
+    /// distinguish it from text following a newline, or continue wrapped text from the first
+    /// column if unspecified. Wrapped text continues after the indicator, so it's included in
+    /// measurements such as [Interface::measure_wrapped].
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_wrap_indicator(Some(("↪ ", Color::DarkGrey.as_style())));
+    ///
+    /// // "Hello, world!" wrapped at five columns is "Hello", "↪ , w", "↪ orl", and "↪ d!"
+    /// assert_eq!(Vector::new(5, 4), interface.measure_wrapped("Hello, world!", 5));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_wrap_indicator(&mut self, indicator: Option<(&str, Style)>) {
+        self.wrap_indicator = indicator.map(|(text, style)| (text.to_string(), style));
+    }
+
+    /// Update how East Asian ambiguous-width characters, such as "→", are measured, probing the
+    /// terminal for [AmbiguousWidth::Auto]. They're narrow by default. Widths are shared by the
+    /// interfaces on the current thread, so this should be set before text is staged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{AmbiguousWidth, Interface, Position, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_ambiguous_width(AmbiguousWidth::Wide)?;
+    /// assert_eq!(Vector::new(4, 1), interface.measure("→→"));
+    ///
+    /// // Measure them as the terminal displays them
+    /// interface.set_ambiguous_width(AmbiguousWidth::Auto)?;
+    /// assert_eq!(Vector::new(2, 1), interface.measure("→→"));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_ambiguous_width(&mut self, ambiguous_width: AmbiguousWidth) -> Result<()> {
+        let is_wide = match ambiguous_width {
+            AmbiguousWidth::Narrow => false,
+            AmbiguousWidth::Wide => true,
+            AmbiguousWidth::Auto => self.probe_width(AMBIGUOUS_WIDTH_PROBE)? == 2,
+        };
+
+        self.update_widths(|widths| widths.set_ambiguous_wide(is_wide));
+        self.ambiguous_width = ambiguous_width;
+        Ok(())
+    }
+
+    /// How East Asian ambiguous-width characters are measured.
+    pub fn ambiguous_width(&self) -> AmbiguousWidth {
+        self.ambiguous_width
+    }
+
+    /// Update whether graphemes are measured from the terminal the first time they're rendered,
+    /// correcting their widths where the terminal displays them differently than unicode-width
+    /// reports, such as emoji with variation selectors. Measuring disturbs the display, so the
+    /// interface is redrawn after applying graphemes that haven't been measured before. Disabled by
+    /// default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_width_calibration(true);
+    ///
+    /// // The heart is measured as it's applied, shifting "Love" if the terminal disagrees
+    /// interface.set(pos!(0, 0), "❤️ Love");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_width_calibration(&mut self, enabled: bool) {
+        self.calibrated_graphemes = match enabled {
+            true => Some(self.calibrated_graphemes.take().unwrap_or_default()),
+            false => None,
+        };
+    }
+
+    /// Whether graphemes are measured from the terminal the first time they're rendered.
+    pub fn is_width_calibration_enabled(&self) -> bool {
+        self.calibrated_graphemes.is_some()
+    }
+
+    /// Update whether staged text is reordered for display using the Unicode Bidirectional
+    /// Algorithm, placing right-to-left runs (e.g. Hebrew or Arabic) in visual order. Enabled by
+    /// default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// // Stage text in its logical order, without reordering
+    /// interface.set_bidi_enabled(false);
+    /// interface.set(pos!(0, 0), "שלום");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_bidi_enabled(&mut self, enabled: bool) {
+        self.bidi = enabled;
+    }
+
+    /// Whether staged text is reordered for display using the Unicode Bidirectional Algorithm.
+    pub fn is_bidi_enabled(&self) -> bool {
+        self.bidi
+    }
+
+    /// Update the style applied to text staged without one, or leave such text unstyled if
+    /// unspecified.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_default_style(Some(Color::Green.as_style()));
+    ///
+    /// // Rendered in green
+    /// interface.set(pos!(0, 0), "Hello");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_default_style(&mut self, style: Option<Style>) {
+        self.default_style = style;
+    }
+
+    /// The style applied to text staged without one, if any.
+    pub fn default_style(&self) -> Option<Style> {
+        self.default_style
+    }
+
+    /// Update whether text is printed with its styles. If disabled, text is printed without
+    /// colors or attributes, e.g. to honor the user's `NO_COLOR` setting, but staged styles are
+    /// kept and printed again if it's reenabled. Enabled by default unless the device prefers
+    /// otherwise or the interface is printing lines to a device which isn't a terminal.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_styling_enabled(false);
+    ///
+    /// // Printed without its color
+    /// interface.set_styled(pos!(0, 0), "Error!", Color::Red.as_style());
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_styling_enabled(&mut self, enabled: bool) {
+        if enabled != self.styling {
+            self.styling = enabled;
+            self.is_invalidated = true;
+        }
+    }
+
+    /// Whether text is printed with its styles.
+    pub fn is_styling_enabled(&self) -> bool {
+        self.styling
+    }
+
+    /// Update the interface's text at the specified position, styling it by spans of byte ranges
+    /// into the text, such as those produced by syntax highlighters, and returning the bounding
+    /// box of the cells it occupies. Each grapheme takes the style of the last span overlapping
+    /// any of its bytes, so spans needn't align with grapheme or character boundaries; text not
+    /// covered by a span takes the default style. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// let line = "let café = \"☕\";";
+    /// let spans = [
+    ///     (0..3, Color::Magenta.as_style()),
+    ///     (12..17, Color::Green.as_style()),
+    /// ];
+    /// interface.set_highlighted(pos!(0, 0), line, &spans);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_highlighted(
+        &mut self,
+        position: Position,
+        text: &str,
+        spans: &[(Range<usize>, Style)],
+    ) -> Rect {
+        self.mark_staged();
+
+        // Graphemes are normalized individually so each's style is found by its offset
+        let mut normalized = String::new();
+        let mut styles = Vec::new();
+        for (start, grapheme) in grapheme_indices(text) {
+            let end = start + grapheme.len();
+            let style = spans
+                .iter()
+                .rev()
+                .find(|(range, _)| range.start < end && range.end > start)
+                .map(|(_, style)| *style);
+
+            styles.push((normalized.len(), style));
+            normalized.push_str(&normalize(grapheme));
+        }
+
+        let style_of = |grapheme: &str| {
+            let offset = grapheme.as_ptr() as usize - normalized.as_ptr() as usize;
+            let index = styles.partition_point(|(start, _)| *start <= offset);
+            styles[index - 1].1
+        };
+
+        let mut alternate = self
+            .alternate
+            .take()
+            .unwrap_or_else(|| self.current.clone());
+        let (overflow, width) = (self.overflow, self.size.x());
+        let graphemes = self.visual_graphemes(&normalized);
+        let (bounds, _) = self.layout_graphemes(
+            &mut alternate,
+            position,
+            graphemes,
+            |grapheme, style| Cell::new(grapheme, style_of(grapheme).or(style)),
+            None,
+            overflow,
+            width,
+        );
+        self.alternate = Some(alternate);
+
+        bounds
+    }
+
+    /// Update the interface's text at the specified position, coloring its cells along a gradient
+    /// between the colors, and returning the bounding box of the cells it occupies. Cells keep
+    /// the default style's other formatting. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_gradient(pos!(0, 0), "██████████", Color::DarkBlue, Color::Cyan);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_gradient(&mut self, position: Position, text: &str, from: Color, to: Color) -> Rect {
+        self.mark_staged();
+
+        let mut layout = self.layout_state();
+        let (overflow, width) = (self.overflow, self.size.x());
+        let (bounds, _) = self.layout_text(&mut layout, position, text, None, overflow, width);
+
+        let style = self.default_style.unwrap_or_default();
+        let steps = layout.cells().count().saturating_sub(1).max(1) as f32;
+        let alternate = self.alternate.get_or_insert_with(|| self.current.clone());
+        for (index, (position, cell)) in layout.cells().enumerate() {
+            let color = Color::lerp(from, to, index as f32 / steps);
+            alternate.set_styled_text(position, cell.grapheme(), style.set_foreground(color));
+        }
+
+        bounds
+    }
+
+    /// Define the style for a name in the interface's theme, restyling text staged with the name.
+    /// Changes are staged until applied. See [Interface::set_named].
+    pub fn define_style(&mut self, name: &str, style: Style) {
+        let previous = self.theme.clone();
+        self.theme.define(name, style);
+        self.restyle_named(&previous);
+    }
+
+    /// Replace the interface's theme, restyling text staged with names whose styles differ between
+    /// the themes. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, Theme, pos};
+    ///
+    /// let light: Theme = [("title", Color::Black.as_style())].into_iter().collect();
+    /// let dark: Theme = [("title", Color::White.as_style())].into_iter().collect();
+    ///
+    /// let mut interface = Interface::builder().theme(light).build(&mut device)?;
+    /// interface.set_named(pos!(0, 0), "Settings", "title");
+    /// interface.apply()?;
+    ///
+    /// interface.set_theme(dark);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_theme(&mut self, theme: Theme) {
+        let previous = std::mem::replace(&mut self.theme, theme);
+        self.restyle_named(&previous);
+    }
+
+    /// The interface's theme of named styles.
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /// Restages text staged with names whose styles changed from the previous theme, forgetting
+    /// text which has since been overwritten or cleared.
+    fn restyle_named(&mut self, previous: &Theme) {
+        let default_style = self.default_style;
+        let resolve = |theme: &Theme, name: &str| theme.get(name).or(default_style);
+
+        let staged = self.alternate.as_ref().unwrap_or(&self.current);
+        let mut restyled = Vec::new();
+        let mut overwritten = Vec::new();
+        for (position, name) in &self.named {
+            let (before, after) = (resolve(previous, name), resolve(&self.theme, name));
+            match staged.get_cell(*position) {
+                Some(cell) if cell.style().copied() == before => {
+                    if before != after {
+                        restyled.push((*position, cell.grapheme().to_string(), after));
+                    }
+                }
+                _ => overwritten.push(*position),
+            }
+        }
+
+        for position in overwritten {
+            self.named.remove(&position);
+        }
+
+        if restyled.is_empty() {
+            return;
+        }
+
+        let alternate = self.staged_state();
+        for (position, grapheme, style) in restyled {
+            match style {
+                Some(style) => alternate.set_styled_text(position, &grapheme, style),
+                None => alternate.set_text(position, &grapheme),
+            }
+        }
+    }
+
+    /// Update how staged text extending beyond the terminal's last column is handled. Text wraps
+    /// to the following line by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Overflow, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_overflow(Overflow::Clip);
+    ///
+    /// // Truncated at the last column rather than wrapping
+    /// interface.set(pos!(78, 0), "Hello");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// How staged text extending beyond the terminal's last column is handled.
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Update how applied changes are translated into terminal output. Only changed cells are
+    /// rewritten by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, RenderStrategy, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_render_strategy(RenderStrategy::Latency { skip_frames: true });
+    ///
+    /// // Rewrites the line from "a" to "b" rather than moving the cursor between them
+    /// interface.set(pos!(0, 0), "a");
+    /// interface.set(pos!(20, 0), "b");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_render_strategy(&mut self, strategy: RenderStrategy) {
+        self.render_strategy = strategy;
+    }
+
+    /// How applied changes are translated into terminal output.
+    pub fn render_strategy(&self) -> RenderStrategy {
+        self.render_strategy
+    }
+
+    /// Whether mouse events are captured from the terminal while this interface is active.
+    pub fn is_mouse_captured(&self) -> bool {
+        self.mouse_capture
+    }
+
+    /// Begin capturing mouse events from the terminal until this interface exits.
+    pub(crate) fn enable_mouse_capture(&mut self) -> Result<()> {
+        if self.mode == Mode::LinePrinter {
+            return Ok(());
+        }
+
+        self.write_commands(&[DeviceCommand::EnableMouseCapture])?;
+        self.mouse_capture = true;
+        Ok(())
+    }
+
+    /// Wait for the next input event from the terminal. Resize events update this interface's size
+    /// before they're returned, so the application may immediately restage its content to fit.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Event, Interface, Vector};
+    /// # device.resize(Vector::new(100, 30));
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// if let Event::Resize(size) = interface.read_event()? {
+    ///     assert_eq!(size, interface.size());
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn read_event(&mut self) -> Result<Event> {
+        loop {
+            #[cfg(all(unix, feature = "signals"))]
+            if self.signals.is_some() {
+                match self.poll_event(signals::POLL_INTERVAL)? {
+                    Some(event) => return Ok(event),
+                    None => continue,
+                }
+            }
+
+            if let Some(event) = self.device.read_event(None)? {
+                return Ok(self.handle_event(event));
+            }
+        }
+    }
+
+    /// Wait up to the timeout for the next input event from the terminal, returning `None` if none
+    /// arrived, so single-threaded applications may interleave input with periodic rendering. Like
+    /// [Interface::read_event], resize events update this interface's size before they're returned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::time::Duration;
+    /// use tty_interface::{Event, Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// let mut ticks = 0;
+    /// loop {
+    ///     match interface.poll_event(Duration::from_millis(10))? {
+    ///         Some(Event::Key(_)) => break,
+    ///         Some(_) => {}
+    ///         None => ticks += 1,
+    ///     }
+    ///
+    ///     interface.set(pos!(0, 0), &format!("Ticks: {}", ticks));
+    ///     interface.apply()?;
+    /// #   if ticks == 3 { break; }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        #[cfg(all(unix, feature = "signals"))]
+        if self.signals.is_some() {
+            return self.poll_event_or_signal(timeout);
+        }
+
+        let event = self.device.read_event(Some(timeout))?;
+        Ok(event.map(|event| self.handle_event(event)))
+    }
+
+    /// Handle the signals received by the process while active, see
+    /// [InterfaceBuilder::handle_signals].
+    #[cfg(all(unix, feature = "signals"))]
+    pub(crate) fn handle_signals(&mut self, signals: Signals) {
+        self.signals = Some(signals);
+    }
+
+    /// Wait up to the timeout for an input event or a received signal, checking for signals
+    /// periodically while waiting.
+    #[cfg(all(unix, feature = "signals"))]
+    fn poll_event_or_signal(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            if let Some(event) = self.take_signal_event()? {
+                return Ok(Some(event));
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let wait = remaining.min(signals::POLL_INTERVAL);
+            if let Some(event) = self.device.read_event(Some(wait))? {
+                return Ok(Some(self.handle_event(event)));
+            }
+
+            if remaining <= signals::POLL_INTERVAL {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Handle a signal received since the last check, if any, returning the event reporting it.
+    #[cfg(all(unix, feature = "signals"))]
+    fn take_signal_event(&mut self) -> Result<Option<Event>> {
+        let signal = match self.signals.as_mut().and_then(Signals::take_pending) {
+            Some(signal) => signal,
+            None => return Ok(None),
+        };
+
+        log_command!("received {:?} signal", signal);
+
+        let event = match signal {
+            Signal::Resize => {
+                let size = self.device.get_terminal_size()?;
+                self.handle_event(Event::Resize(size))
+            }
+            Signal::Suspend => {
+                self.suspend()?;
+                Event::Resume
+            }
+            Signal::Terminate => Event::Terminate,
+        };
+
+        Ok(Some(event))
+    }
+
+    /// Restore the terminal's configuration and stop the process, then reinitialize the terminal
+    /// once it's continued so the next apply redraws the interface.
+    #[cfg(all(unix, feature = "signals"))]
+    fn suspend(&mut self) -> Result<()> {
+        if self.mode == Mode::LinePrinter {
+            return signals::stop_process();
+        }
+
+        let mut commands = Vec::new();
+        if self.mouse_capture {
+            commands.push(DeviceCommand::DisableMouseCapture);
+        }
+        if self.mode == Mode::Alternate {
+            commands.push(DeviceCommand::LeaveAlternateScreen);
+        }
+        commands.push(DeviceCommand::ShowCursor);
+
+        self.write_commands(&commands)?;
+        self.device.disable_raw_mode()?;
+
+        signals::stop_process()?;
+
+        self.device.enable_raw_mode()?;
+        let size = self.device.get_terminal_size()?;
+        self.handle_event(Event::Resize(size));
+
+        let mut commands = Vec::new();
+        if self.mode == Mode::Alternate {
+            commands.push(DeviceCommand::EnterAlternateScreen);
+            commands.push(DeviceCommand::ClearAll);
+            commands.push(DeviceCommand::MoveTo(pos!(0, 0)));
+        } else {
+            // The shell's output while suspended is left above a new rendering
+            self.origin = cursor_position_or(&mut *self.device, self.origin)?;
+            self.scrolled_lines = 0;
+        }
+        if self.mouse_capture {
+            commands.push(DeviceCommand::EnableMouseCapture);
+        }
+        if self.cursor_mode == CursorMode::Managed {
+            commands.push(DeviceCommand::HideCursor);
+        }
+
+        self.write_commands(&commands)?;
+        self.cursor = pos!(0, 0);
+        self.is_invalidated = true;
+
+        Ok(())
+    }
+
+    /// Update this interface's state to reflect an input event before it's returned.
+    fn handle_event(&mut self, event: Event) -> Event {
+        if let Event::Resize(size) = event {
+            log_command!("resize to {:?}", size);
+
+            let is_width_changed = size.x() != self.size.x();
+
+            // Cells the new size reveals must be rendered, as must wide cells it cuts at the last
+            // column, which are blanked
+            let displayed_positions: BTreeSet<Position> = self
+                .current
+                .cells()
+                .filter(|(position, cell)| self.is_displayed(*position, cell))
+                .map(|(position, _)| position)
+                .collect();
+
+            self.size = size;
+
+            let changed_positions: Vec<Position> = self
+                .current
+                .cells()
+                .filter(|(position, cell)| {
+                    self.is_in_viewport(*position)
+                        && self.is_displayed(*position, cell)
+                            != displayed_positions.contains(position)
+                })
+                .map(|(position, _)| position)
+                .collect();
+            for position in &changed_positions {
+                self.line_hashes.remove(&position.y());
+            }
+            if let Some(alternate) = &mut self.alternate {
+                alternate.mark_dirty(changed_positions.clone());
+            }
+            self.current.mark_dirty(changed_positions);
+
+            // The terminal keeps its cursor on the screen when it shrinks
+            if self.mode == Mode::Alternate {
+                self.cursor = pos!(
+                    self.cursor.x().min(size.x().saturating_sub(1)),
+                    self.cursor.y().min(size.y().saturating_sub(1))
+                );
+            }
+
+            if let Some(recorder) = &mut self.recorder {
+                recorder.record_resize(self.clock.now(), size);
+            }
+
+            if is_width_changed {
+                self.reflow_wrapped();
+                self.reflow_aligned();
+                self.reflow_appended();
+            }
+
+            let mut hooks = take(&mut self.hooks.resize);
+            for hook in &mut hooks {
+                hook(&mut Staging::new(self), size);
+            }
+            self.hooks.resize = hooks;
+        }
+
+        event
+    }
+
+    /// Applies staged changes to the terminal. If a byte budget is configured and the changes
+    /// exceed it, the remaining changes are deferred to subsequent applies.
+    ///
+    /// Changes are always written in row-major order: each line's changes from left to right, and
+    /// all of a line's changes before any of the following line's.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set(pos!(1, 1), "Hello, world!");
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn apply(&mut self) -> Result<()> {
+        self.apply_frame(true)
+    }
+
+    /// Applies staged changes like [Interface::apply], but leaves the frame in the device's buffer
+    /// rather than flushing it, so several components' updates may be applied in turn and then
+    /// flushed to the terminal together with [Interface::flush].
+    ///
+    /// Unflushed frames are only written as the device's buffer fills, so the terminal is less
+    /// likely to display the intermediate states between them. This isn't a guarantee: the
+    /// interface doesn't wrap frames in the terminal's synchronized update sequences, so a large
+    /// buffer may still be displayed as it's written.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// interface.set(pos!(0, 0), "Header");
+    /// interface.apply_no_flush()?;
+    ///
+    /// interface.set(pos!(0, 1), "Body");
+    /// interface.apply_no_flush()?;
+    ///
+    /// interface.flush()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn apply_no_flush(&mut self) -> Result<()> {
+        self.apply_frame(false)
+    }
+
+    /// Flush frames applied with [Interface::apply_no_flush] from the device's buffer to the
+    /// terminal.
+    pub fn flush(&mut self) -> Result<()> {
+        self.device.flush()?;
+        Ok(())
+    }
+
+    /// Prints the staged lines whose content changed since they were last printed as plain text,
+    /// in order, flushing the device afterward if specified. Cleared lines aren't printed.
+    fn print_changed_lines(&mut self, flush: bool) -> Result<ApplyStats> {
+        if let Some(mut alternate) = self.alternate.take() {
+            swap(&mut self.current, &mut alternate);
+        }
+
+        self.debounce.mark_applied();
+
+        let dirty_positions: Vec<Position> = self
+            .current
+            .dirty_iter()
+            .map(|(position, _)| position)
+            .collect();
+        let lines: BTreeSet<u16> = dirty_positions
+            .iter()
+            .map(|position| position.y())
+            .collect();
+
+        let mut output = Vec::new();
+        for line in lines {
+            // Restyling a line doesn't change its printed text
+            let text = self.current.line_text(line);
+            let text = text.trim_end();
+            let mut hasher = DefaultHasher::new();
+            text.hash(&mut hasher);
+            if self.line_hashes.insert(line, hasher.finish()) == Some(hasher.finish()) {
+                continue;
+            }
+
+            if text.is_empty() {
+                continue;
+            }
+
+            match self.styling {
+                true => self.encode_line(line, self.widths().text_width(text), &mut output)?,
+                false => output.extend_from_slice(text.as_bytes()),
+            }
+            output.push(b'\n');
+        }
+
+        log_command!("apply: printed {} bytes of changed lines", output.len());
+
+        let write_started = self.clock.now();
+        self.device.write_all(&output)?;
+        if flush {
+            self.device.flush()?;
+        }
+        let write_duration = self.clock.now().saturating_duration_since(write_started);
+
+        self.current.clear_dirty();
+        self.record_history();
+
+        Ok(ApplyStats::new(
+            dirty_positions.len(),
+            0,
+            output.len(),
+            write_duration,
+            false,
+        ))
+    }
+
+    /// Append the styled cells of the line before the specified column to the buffer, with empty
+    /// columns as spaces and runs of identically styled cells printed together.
+    fn encode_line(&mut self, line: u16, end: u16, buffer: &mut Vec<u8>) -> Result<()> {
+        let mut runs: Vec<(String, Option<Style>)> = Vec::new();
+        let mut push = |text: &str, style: Option<Style>| match runs.last_mut() {
+            _ if text.is_empty() => {}
+            Some((run, run_style)) if *run_style == style => run.push_str(text),
+            _ => runs.push((text.to_string(), style)),
+        };
+
+        let mut column = 0;
+        for (position, cell) in self.current.get_line(line) {
+            if position.x() >= end {
+                break;
+            }
+
+            push(
+                &" ".repeat(position.x().saturating_sub(column).into()),
+                None,
+            );
+            push(cell.grapheme(), cell.rendered_style());
+            column = position.x().saturating_add(cell.width(self.widths()));
+        }
+
+        for (text, style) in runs {
+            let command = match style {
+                Some(style) => DeviceCommand::PrintStyled(&text, style),
+                None => DeviceCommand::Print(&text),
+            };
+            self.encode(&command, buffer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Applies staged changes, flushing the device afterward if specified.
+    fn apply_frame(&mut self, flush: bool) -> Result<()> {
+        let mut hooks = take(&mut self.hooks.before_apply);
+        for hook in &mut hooks {
+            hook(&mut Staging::new(self));
+        }
+        self.hooks.before_apply = hooks;
+
+        let stats = match self.mode {
+            Mode::LinePrinter => Some(self.print_changed_lines(flush)?),
+            _ => self.render_frame(flush)?,
+        };
+
+        if let Some(stats) = stats {
+            let mut hooks = take(&mut self.hooks.after_apply);
+            for hook in &mut hooks {
+                hook(&mut Staging::new(self), &stats);
+            }
+            self.hooks.after_apply = hooks;
+        }
+
+        Ok(())
+    }
+
+    /// Render staged changes to the terminal, returning a summary of the frame if one was written.
+    fn render_frame(&mut self, flush: bool) -> Result<Option<ApplyStats>> {
+        self.calibrate_widths()?;
+
+        if self.notifications.expire(self.clock.now()) {
+            self.ghost.mark_changed();
+        }
+
+        let is_redraw = self.viewport != self.rendered_viewport || self.is_invalidated;
+        if self.alternate.is_none()
+            && !self.current.is_dirty()
+            && !self.ghost.is_changed()
+            && !is_redraw
+        {
+            return Ok(None);
+        }
+
+        // The lines displayed before scrolling, which must be cleared if left empty
+        let displayed_lines = match self.current.get_last_position() {
+            Some(position) => (position.y() + 1).saturating_sub(self.rendered_viewport.y()),
+            None => 0,
+        };
+
+        if let Some(mut alternate) = self.alternate.take() {
+            swap(&mut self.current, &mut alternate);
+        }
+
+        self.debounce.mark_applied();
+
+        if self.is_previous_frame_draining() {
+            log_command!("apply: skipped while the previous frame drains");
+            return Ok(None);
+        }
+
+        let mut dirty_cells: BTreeMap<Position, Option<Cell>> = self.current.dirty_iter().collect();
+
+        // Lines restaged with the content they were last rendered with needn't be rendered again
+        let line_hashes: BTreeMap<u16, u64> = dirty_cells
+            .keys()
+            .map(|position| (position.y(), self.current.line_hash(position.y())))
+            .collect();
+        if !is_redraw {
+            dirty_cells.retain(|position, _| {
+                self.line_hashes.get(&position.y()) != line_hashes.get(&position.y())
+            });
+        }
+
+        let mut dirty_cells = self.composite_ghost(dirty_cells);
+
+        let is_repaint = is_redraw || self.is_repaint_needed(dirty_cells.len());
+        if is_repaint {
+            log_command!("apply: repainting for {} dirty cells", dirty_cells.len());
+            dirty_cells = self.repaint_cells(dirty_cells);
+        }
+
+        // Rewrite each displayed line in full, clearing what was displayed beyond its content
+        if is_redraw && self.mode == Mode::Relative {
+            for line in 0..displayed_lines {
+                let line = self.viewport.y().saturating_add(line);
+                for column in [self.viewport.x(), self.line_content_end(line)] {
+                    dirty_cells.entry(pos!(column, line)).or_insert(None);
+                }
+            }
+        }
+
+        // Lines scrolled above the screen can't be reached by the cursor
+        dirty_cells.retain(|position, _| {
+            self.is_in_viewport(*position) && self.to_display(*position).y() >= self.scrolled_lines
+        });
+
+        // Wide cells straddling the last column would wrap onto the following line, so the
+        // column is blanked instead
+        for (position, cell) in dirty_cells.iter_mut() {
+            let width = cell.as_ref().map_or(1, |cell| cell.width(self.widths()));
+            if self.to_display(*position).x().saturating_add(width) > self.size.x() {
+                *cell = None;
+            }
+        }
+
+        let mut line_clears = BTreeMap::new();
+        if is_repaint || matches!(self.render_strategy, RenderStrategy::Latency { .. }) {
+            line_clears = self.coalesce_lines(&mut dirty_cells);
+        }
+
+        // If the repaint fails, all of its cells must be rendered again
+        if is_repaint {
+            self.current
+                .mark_dirty(dirty_cells.keys().copied().collect());
+        }
+
+        let dirty_cells_count = dirty_cells.len();
+        log_command!("apply: {} dirty cells", dirty_cells_count);
+
+        let (initial_cursor, initial_origin) = (self.cursor, self.origin);
+        let initial_scrolled_lines = self.scrolled_lines;
+        let initial_rendered_viewport = self.rendered_viewport;
+        let initial_is_invalidated = self.is_invalidated;
+        self.rendered_viewport = self.viewport;
+        self.is_invalidated = false;
+        let mut frame = Vec::new();
+        let is_cursor_managed = self.cursor_mode == CursorMode::Managed;
+        if is_cursor_managed {
+            self.encode(&DeviceCommand::HideCursor, &mut frame)?;
+        }
+
+        if is_repaint && self.mode == Mode::Alternate {
+            self.encode(&DeviceCommand::ClearAll, &mut frame)?;
+            self.encode(&DeviceCommand::MoveTo(pos!(0, 0)), &mut frame)?;
+            self.cursor = pos!(0, 0);
+        }
+
+        let mut has_emitted_cells = false;
+        let mut cell_ends = Vec::new();
+        let mut deferred_positions = Vec::new();
+        for (position, cell) in dirty_cells {
+            // A clear to the end of the line stands in for several cleared cells
+            let cleared_positions = line_clears.remove(&position);
+            let clears_line = cleared_positions.is_some();
+
+            if !deferred_positions.is_empty() {
+                deferred_positions.extend(cleared_positions.unwrap_or_else(|| vec![position]));
+                continue;
+            }
+
+            let display_position = self.to_display(position);
+
+            let blank = Cell::new(" ", None);
+            let corner_cell = match &cell {
+                Some(cell) => Some(cell),
+                None if clears_line => None,
+                None => Some(&blank),
+            };
+
+            let mut cell_buffer = Vec::new();
+            let is_corner = match corner_cell {
+                Some(corner_cell) => {
+                    self.queue_corner_cell(&mut cell_buffer, display_position, corner_cell)?
+                }
+                None => false,
+            };
+
+            if !is_corner && self.cursor != display_position {
+                self.queue_cursor_move(&mut cell_buffer, display_position, true)?;
+            }
+
+            // Printing into the corner leaves the cursor on it, having reprinted its predecessor
+            let mut cell_width = 1;
+            match &cell {
+                _ if is_corner => cell_width = 0,
+                Some(cell) => {
+                    cell_width = cell.width(self.widths());
+                    self.encode(&print_command(cell), &mut cell_buffer)?;
+                }
+                None if clears_line => {
+                    cell_width = 0;
+                    self.encode(&DeviceCommand::ClearToEndOfLine, &mut cell_buffer)?;
+                }
+                None => {
+                    self.encode(&DeviceCommand::Print(" "), &mut cell_buffer)?;
+                }
+            }
+
+            // Always make progress by emitting at least one cell, even if it exceeds the budget
+            if let Some(budget) = self.byte_budget {
+                if has_emitted_cells && frame.len() + cell_buffer.len() > budget {
+                    deferred_positions.extend(cleared_positions.unwrap_or_else(|| vec![position]));
+                    continue;
+                }
+            }
+
+            if self.cursor != display_position {
+                log_command!(
+                    "move cursor from {:?} to {:?}",
+                    self.cursor,
+                    display_position
+                );
+            }
+
+            match cell {
+                Some(cell) => log_command!("print {:?} at {:?}", cell.grapheme(), position),
+                None if clears_line => log_command!("clear line from {:?}", position),
+                None => log_command!("clear {:?}", position),
+            }
+
+            frame.extend(cell_buffer);
+            cell_ends.push((frame.len(), position));
+            has_emitted_cells = true;
+            self.cursor = display_position.translate(cell_width, 0);
+            self.track_scroll();
+        }
+
+        #[cfg(feature = "images")]
+        let rendered_images = match deferred_positions.is_empty() {
+            true => self.queue_images(&mut frame, is_repaint || is_redraw)?,
+            false => Vec::new(),
+        };
+
+        let staged_cursor = self
+            .staged_cursor
+            .filter(|position| self.is_in_viewport(*position));
+        if let Some(position) = staged_cursor {
+            let position = self.to_display(position);
+            let position = pos!(position.x(), position.y().max(self.scrolled_lines));
+            if self.cursor != position {
+                log_command!("move cursor from {:?} to {:?}", self.cursor, position);
+            }
+            log_command!("show cursor at {:?}", position);
+
+            let may_reprint = deferred_positions.is_empty();
+            self.queue_cursor_move(&mut frame, position, may_reprint)?;
+            self.cursor = position;
+            self.track_scroll();
+
+            if is_cursor_managed {
+                self.encode(&DeviceCommand::ShowCursor, &mut frame)?;
+            }
+        } else if !is_cursor_managed && has_emitted_cells {
+            // The visible cursor is returned to where the application left it
+            let position = pos!(
+                initial_cursor.x(),
+                initial_cursor.y().max(self.scrolled_lines)
+            );
+            if self.cursor != position {
+                log_command!("return cursor from {:?} to {:?}", self.cursor, position);
+                let may_reprint = deferred_positions.is_empty();
+                self.queue_cursor_move(&mut frame, position, may_reprint)?;
+                self.cursor = position;
+            }
+        }
+
+        log_command!(
+            "apply: wrote {} cells ({} deferred)",
+            dirty_cells_count - deferred_positions.len(),
+            deferred_positions.len()
+        );
+
+        let write_started = self.clock.now();
+        if let Err((error, bytes_written)) = write_frame(&mut *self.device, &frame, flush) {
+            // The frame's cells remain dirty for a retry, which starts from the original cursor if
+            // nothing was written
+            if bytes_written == 0 {
+                self.cursor = initial_cursor;
+                self.origin = initial_origin;
+                self.scrolled_lines = initial_scrolled_lines;
+                self.rendered_viewport = initial_rendered_viewport;
+                self.is_invalidated = initial_is_invalidated;
+            }
+
+            let position = cell_ends
+                .iter()
+                .find(|(end, _)| *end > bytes_written)
+                .map(|(_, position)| *position);
+
+            return Err(Error::Apply(ApplyError::new(
+                error,
+                position,
+                bytes_written,
+            )));
+        }
+
+        let write_finished = self.clock.now();
+        let write_duration = write_finished.saturating_duration_since(write_started);
+        self.last_frame = Some((write_finished, write_duration));
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_frame(write_started, &frame);
+        }
+
+        // Lines with deferred changes no longer display what was last rendered
+        for (line, hash) in line_hashes {
+            match deferred_positions
+                .iter()
+                .any(|position| position.y() == line)
+            {
+                true => self.line_hashes.remove(&line),
+                false => self.line_hashes.insert(line, hash),
+            };
+        }
+
+        #[cfg(feature = "images")]
+        self.images.mark_rendered(rendered_images);
+
+        self.current.clear_dirty();
+        let deferred_cells = deferred_positions.len();
+        self.current.mark_dirty(deferred_positions);
+        self.record_history();
+
+        Ok(Some(ApplyStats::new(
+            dirty_cells_count - deferred_cells,
+            deferred_cells,
+            frame.len(),
+            write_duration,
+            is_repaint,
+        )))
+    }
+
+    /// Queues deleting removed images and displaying images which haven't been rendered or must
+    /// be redrawn, returning the indices of those displayed. Images outside the viewport await
+    /// being scrolled into view.
+    #[cfg(feature = "images")]
+    fn queue_images(&mut self, frame: &mut Vec<u8>, is_redraw: bool) -> Result<Vec<usize>> {
+        self.images.encode_deletions(frame)?;
+
+        let protocol = self.device.image_protocol();
+        let mut rendered = Vec::new();
+        for index in 0..self.images.placed().len() {
+            let image = &self.images.placed()[index];
+            let (origin, size) = (image.rect.origin(), image.rect.size());
+            if image.is_rendered && !is_redraw || !self.is_in_viewport(origin) {
+                continue;
+            }
+
+            let position = self.to_display(origin);
+            if position.y() < self.scrolled_lines {
+                continue;
+            }
+
+            log_command!("display image at {:?}", origin);
+            self.queue_cursor_move(frame, position, true)?;
+            self.cursor = position;
+
+            let image = &self.images.placed()[index];
+            image.source.encode(protocol, size, image.id, frame)?;
+            rendered.push(index);
+        }
+
+        Ok(rendered)
+    }
+
+    /// Remembers the applied frame for undoing, unless it's unchanged from the last one.
+    fn record_history(&mut self) {
+        if self.history_limit == 0 {
+            return;
+        }
+
+        let last = self.history.back();
+        if last.is_some_and(|last| last.has_same_cells(&self.current)) {
+            return;
+        }
+
+        self.history.push_back(self.current.snapshot());
+        while self.history.len() > self.history_limit {
+            self.history.pop_front();
+        }
+    }
+
+    /// Whether enough of the terminal's cells have changed to repaint it rather than update each.
+    fn is_repaint_needed(&self, dirty_cells_count: usize) -> bool {
+        let threshold = match self.repaint_threshold {
+            Some(threshold) if self.byte_budget.is_none() => threshold,
+            _ => return false,
+        };
+
+        let cell_count = self.size.x() as usize * self.size.y() as usize;
+        cell_count > 0 && dirty_cells_count as f32 / cell_count as f32 > threshold
+    }
+
+    /// Every cell to be displayed after this frame, replacing the dirty cells for a repaint. On
+    /// the alternate screen the repaint begins by clearing it, but otherwise cleared cells must
+    /// still be rendered.
+    fn repaint_cells(
+        &self,
+        dirty_cells: BTreeMap<Position, Option<Cell>>,
+    ) -> BTreeMap<Position, Option<Cell>> {
+        let mut cells: BTreeMap<Position, Option<Cell>> = self
+            .current
+            .cells()
+            .map(|(position, cell)| (position, Some(cell.clone())))
+            .collect();
+
+        for (position, cell) in self.ghost.rendered() {
+            cells.insert(*position, Some(cell.clone()));
+        }
+
+        if self.mode == Mode::Relative {
+            for (position, cell) in dirty_cells {
+                cells.entry(position).or_insert(cell);
+            }
+        }
+
+        cells
+    }
+
+    /// Whether frames may be skipped and the previous frame's write finished more recently than
+    /// it took, suggesting the link may still be transmitting it.
+    fn is_previous_frame_draining(&self) -> bool {
+        match (self.render_strategy, self.last_frame) {
+            (RenderStrategy::Latency { skip_frames: true }, Some((finished, duration))) => {
+                self.clock.now() < finished + duration
+            }
+            _ => false,
+        }
+    }
+
+    /// Extend each line's updates to a single run from its first to its last change, and replace
+    /// cleared cells at the end of the line with one clear to its end. Returns the positions at
+    /// which lines are cleared, each with the cleared positions it replaces.
+    fn coalesce_lines(
+        &self,
+        cells: &mut BTreeMap<Position, Option<Cell>>,
+    ) -> BTreeMap<Position, Vec<Position>> {
+        let lines: BTreeSet<u16> = cells.keys().map(|position| position.y()).collect();
+
+        let mut line_clears = BTreeMap::new();
+        for line in lines {
+            let line_range = pos!(0, line)..=pos!(u16::MAX, line);
+            let content_end = self.line_content_end(line);
+
+            let trailing_clears: Vec<Position> = cells
+                .range(line_range.clone())
+                .filter(|(position, _)| position.x() >= content_end)
+                .map(|(position, _)| *position)
+                .collect();
+
+            if let Some(first_clear) = trailing_clears.first() {
+                for position in &trailing_clears[1..] {
+                    cells.remove(position);
+                }
+
+                line_clears.insert(*first_clear, trailing_clears);
+            }
+
+            let first_x = match cells.range(line_range.clone()).next() {
+                Some((position, _)) => position.x(),
+                None => continue,
+            };
+            let last_x = cells
+                .range(line_range)
+                .next_back()
+                .map_or(first_x, |(p, _)| p.x());
+
+            // Rewrite unchanged cells between changes rather than moving the cursor over them
+            let mut x = first_x;
+            while x < last_x {
+                let position = pos!(x, line);
+                let cell = match cells.get(&position) {
+                    Some(cell) => cell.clone(),
+                    None => {
+                        let cell = self
+                            .ghost
+                            .rendered()
+                            .get(&position)
+                            .or_else(|| self.current.get_cell(position))
+                            .cloned();
+                        cells.insert(position, cell.clone());
+                        cell
+                    }
+                };
+
+                x = x.saturating_add(cell.map_or(1, |cell| cell.width(self.widths()).max(1)));
+            }
+        }
+
+        line_clears
+    }
+
+    /// The column following the line's last displayed cell, after this frame.
+    fn line_content_end(&self, line: u16) -> u16 {
+        let line_range = pos!(0, line)..=pos!(u16::MAX, line);
+        self.current
+            .get_line(line)
+            .chain(
+                self.ghost
+                    .rendered()
+                    .range(line_range)
+                    .map(|(p, c)| (*p, c)),
+            )
+            .map(|(position, cell)| position.x().saturating_add(cell.width(self.widths())))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Overlay marks, the ghost, if any, and notifications onto the specified cell updates and
+    /// restore cells which were obscured by their previous rendering.
+    fn composite_ghost(
+        &mut self,
+        dirty_cells: BTreeMap<Position, Option<Cell>>,
+    ) -> BTreeMap<Position, Option<Cell>> {
+        let mut cells = dirty_cells;
+
+        let mut overlay = BTreeMap::new();
+        for position in &self.marks {
+            let grapheme = self.current.get_cell(*position).map_or(" ", Cell::grapheme);
+            overlay.insert(*position, Cell::new(grapheme, Some(self.mark_style)));
+        }
+
+        overlay.extend(self.ghost.cells());
+
+        let notification_cells = self
+            .notifications
+            .cells(self.viewport, self.size, self.widths());
+        overlay.extend(notification_cells);
+
+        self.ghost.composite(overlay, &self.current, &mut cells);
+
+        cells
+    }
+
+    /// Show a ghost above the interface's content at the specified position, replacing any
+    /// existing ghost. Obscured content is preserved and restored when the ghost moves or is
+    /// cleared. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Ghost, Interface, Position, Style, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set(pos!(0, 0), "Item 1");
+    /// interface.set(pos!(0, 1), "Item 2");
+    ///
+    /// // Begin dragging the first item
+    /// let ghost = Ghost::new("Item 1").set_style(Style::new().set_italic(true));
+    /// interface.set_ghost(pos!(2, 0), ghost);
+    /// interface.apply()?;
+    ///
+    /// // Follow the pointer, restoring the content beneath the ghost's previous position
+    /// interface.move_ghost(pos!(2, 1));
+    /// interface.apply()?;
+    ///
+    /// // Drop the item
+    /// interface.clear_ghost();
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_ghost(&mut self, position: Position, ghost: Ghost) {
+        self.ghost.set(position, ghost);
+        self.mark_staged();
+    }
+
+    /// Move the current ghost, if any, to the specified position. Changes are staged until
+    /// applied.
+    pub fn move_ghost(&mut self, position: Position) {
+        if self.ghost.move_to(position) {
+            self.mark_staged();
+        }
+    }
+
+    /// Remove the current ghost, if any, restoring the content it obscured. Changes are staged
+    /// until applied.
+    pub fn clear_ghost(&mut self) {
+        if self.ghost.clear() {
+            self.mark_staged();
+        }
+    }
+
+    /// Mark the specified positions with secondary cursors, restyling their content with the
+    /// specified style and replacing any existing marks. Marks follow the content beneath them
+    /// and the content is restored when they move or are cleared. Changes are staged until
+    /// applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Color, Interface, Position, Style, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set(pos!(0, 0), "let x = 1;");
+    /// interface.set(pos!(0, 1), "let y = 2;");
+    ///
+    /// // Show collaborators' cursors
+    /// let style = Style::new().set_background(Color::Blue);
+    /// interface.set_marks(vec![pos!(4, 0), pos!(4, 1)], style);
+    /// interface.apply()?;
+    ///
+    /// // Move them, restoring the content beneath their previous positions
+    /// interface.set_marks(vec![pos!(5, 0), pos!(5, 1)], style);
+    /// interface.apply()?;
+    ///
+    /// interface.clear_marks();
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_marks(&mut self, positions: Vec<Position>, style: Style) {
+        if positions != self.marks || style != self.mark_style {
+            self.marks = positions;
+            self.mark_style = style;
+            self.ghost.mark_changed();
+            self.mark_staged();
+        }
+    }
+
+    /// Remove all marks, restoring the content beneath them. Changes are staged until applied.
+    pub fn clear_marks(&mut self) {
+        if !self.marks.is_empty() {
+            self.marks.clear();
+            self.ghost.mark_changed();
+            self.mark_staged();
+        }
+    }
+
+    /// The positions currently marked with secondary cursors.
+    pub fn marks(&self) -> &[Position] {
+        &self.marks
+    }
+
+    /// Show a transient notification above the interface's content for the specified duration,
+    /// stacked with other notifications in the notification corner. Obscured content is restored
+    /// by the first apply or pump after it expires. Only the text's first line is shown. Changes
+    /// are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::time::Duration;
+    /// use tty_interface::{Interface, NotificationLevel};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.notify("Saved", NotificationLevel::Success, Duration::from_secs(3));
+    /// interface.apply()?;
+    ///
+    /// // Periodically, clearing the notification once expired
+    /// interface.pump()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn notify(&mut self, text: &str, level: NotificationLevel, duration: Duration) {
+        let expires_at = self.clock.now() + duration;
+        self.notifications.push(text, level, expires_at);
+        self.ghost.mark_changed();
+        self.mark_staged();
+    }
+
+    /// Remove all notifications, restoring the content they obscured. Changes are staged until
+    /// applied.
+    pub fn clear_notifications(&mut self) {
+        if self.notifications.len() > 0 {
+            self.notifications.clear();
+            self.ghost.mark_changed();
+            self.mark_staged();
+        }
+    }
+
+    /// The number of notifications shown.
+    pub fn notification_count(&self) -> usize {
+        self.notifications.len()
+    }
+
+    /// Update the corner of the screen notifications are stacked in. Notifications are shown in
+    /// the bottom-right corner by default. Changes are staged until applied.
+    pub fn set_notification_corner(&mut self, corner: Corner) {
+        if self.notifications.corner() != corner {
+            self.notifications.set_corner(corner);
+            self.ghost.mark_changed();
+            self.mark_staged();
+        }
+    }
+
+    /// The corner of the screen notifications are stacked in.
+    pub fn notification_corner(&self) -> Corner {
+        self.notifications.corner()
+    }
+
+    /// Remember up to the specified number of applied frames so they may be restored with
+    /// [Interface::undo], or stop remembering frames if zero. Frames aren't remembered by default.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_history_limit(10);
+    ///
+    /// interface.set(pos!(0, 0), "Step 1: choose a name");
+    /// interface.apply()?;
+    ///
+    /// interface.clear_line(0);
+    /// interface.set(pos!(0, 0), "Step 2: choose a color");
+    /// interface.apply()?;
+    ///
+    /// // Back to step 1
+    /// assert!(interface.undo());
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_history_limit(&mut self, limit: usize) {
+        self.history_limit = limit;
+        while self.history.len() > limit {
+            self.history.pop_front();
+        }
+    }
+
+    /// The number of applied frames remembered for undoing.
+    pub fn history_limit(&self) -> usize {
+        self.history_limit
+    }
+
+    /// Stage the frame applied before the last one, replacing any staged changes, and forget the
+    /// last frame. Only the cells which differ are rendered once applied. Returns whether an
+    /// earlier frame was remembered; see [Interface::set_history_limit].
+    pub fn undo(&mut self) -> bool {
+        if self.history.len() < 2 {
+            return false;
+        }
+
+        self.history.pop_back();
+        if let Some(previous) = self.history.back() {
+            let mut state = self.current.clone();
+            state.restore(previous);
+            self.alternate = Some(state);
+            self.mark_staged();
+        }
+
+        true
+    }
+
+    /// Automatically apply staged changes once no further changes have been staged for the
+    /// specified debounce window, or disable automatic applies if unspecified. Automatic applies
+    /// occur during calls to [Interface::pump], and explicit applies remain available.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::time::Duration;
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_auto_apply(Some(Duration::from_millis(16)));
+    ///
+    /// for progress in 0..=100 {
+    ///     interface.set(pos!(0, 0), &format!("{}%", progress));
+    ///     interface.pump()?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_auto_apply(&mut self, debounce: Option<Duration>) {
+        self.debounce.set_window(debounce);
+    }
+
+    /// The debounce window after which staged changes are automatically applied, if enabled.
+    pub fn auto_apply(&self) -> Option<Duration> {
+        self.debounce.window()
+    }
+
+    /// Apply staged changes if automatic applies are enabled and the debounce window has elapsed
+    /// since changes were last staged. Returns whether changes were applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::time::Duration;
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_auto_apply(Some(Duration::ZERO));
+    ///
+    /// interface.set(pos!(0, 0), "Hello, world!");
+    /// assert!(interface.pump()?);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn pump(&mut self) -> Result<bool> {
+        if self.notifications.has_expired(self.clock.now()) {
+            self.apply()?;
+            return Ok(true);
+        }
+
+        if !self.debounce.is_due(self.clock.now()) {
+            return Ok(false);
+        }
+
+        self.apply()?;
+        Ok(true)
+    }
+
+    /// Register a hook run before each frame is applied, which may stage content such as a
+    /// footer into the frame.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// let mut frames = 0;
+    /// interface.on_before_apply(move |staging| {
+    ///     frames += 1;
+    ///     let line = staging.size().y() - 1;
+    ///     staging.set(pos!(0, line), &format!("Frame {}", frames));
+    /// });
+    ///
+    /// // Displays "Frame 1" on the last line
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn on_before_apply(&mut self, hook: impl FnMut(&mut Staging) + 'static) {
+        self.hooks.before_apply.push(Box::new(hook));
+    }
+
+    /// Register a hook run after each frame is written with a summary of the frame, such as to
+    /// collect metrics. Content it stages is rendered by the next apply.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::{cell::Cell, rc::Rc};
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    ///
+    /// let bytes = Rc::new(Cell::new(0));
+    /// let total = bytes.clone();
+    /// interface.on_after_apply(move |_, stats| total.set(total.get() + stats.bytes()));
+    ///
+    /// interface.set(pos!(0, 0), "Hello, world!");
+    /// interface.apply()?;
+    /// assert!(bytes.get() > 0);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn on_after_apply(&mut self, hook: impl FnMut(&mut Staging, &ApplyStats) + 'static) {
+        self.hooks.after_apply.push(Box::new(hook));
+    }
+
+    /// Register a hook run with the terminal's new size when a resize is read, such as to
+    /// restage content anchored to the bottom of the screen.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.on_resize(|staging, size| {
+    ///     staging.set(pos!(0, size.y() - 1), "Footer");
+    /// });
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn on_resize(&mut self, hook: impl FnMut(&mut Staging, Vector) + 'static) {
+        self.hooks.resize.push(Box::new(hook));
+    }
+
+    /// Remove all registered hooks.
+    pub fn clear_hooks(&mut self) {
+        self.hooks = Hooks::default();
+    }
+
+    /// Update the clock used for time-dependent behavior, such as automatic applies.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, test::VirtualClock};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_clock(Box::new(VirtualClock::new()));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Update the fraction of the terminal's cells which must change for an apply to clear and
+    /// repaint the interface line by line, rather than update each changed cell, or never repaint
+    /// if unspecified. Repainting is cheaper when most of the interface has changed. Applies
+    /// repaint once more than 60% of cells have changed by default, unless a byte budget is set.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_repaint_threshold(Some(0.25));
+    ///
+    /// // Changes more than a quarter of an 80x24 terminal's cells, so it's repainted
+    /// interface.set(pos!(0, 0), &"-".repeat(1000));
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_repaint_threshold(&mut self, threshold: Option<f32>) {
+        self.repaint_threshold = threshold;
+    }
+
+    /// The fraction of the terminal's cells which must change for an apply to repaint it, if any.
+    pub fn repaint_threshold(&self) -> Option<f32> {
+        self.repaint_threshold
+    }
+
+    /// Limit the number of bytes emitted for cell updates by each apply, or remove the limit if
+    /// unspecified. Updates exceeding the budget are deferred, in line-major order, to subsequent
+    /// applies.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set_byte_budget(Some(512));
+    ///
+    /// interface.set(pos!(0, 0), &"-".repeat(1000));
+    ///
+    /// // Renders the first portion of the line
+    /// interface.apply()?;
+    ///
+    /// // Renders the deferred remainder of the line
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn set_byte_budget(&mut self, budget: Option<usize>) {
+        self.byte_budget = budget;
+    }
+
+    /// The maximum number of bytes emitted for cell updates by each apply, if limited.
+    pub fn byte_budget(&self) -> Option<usize> {
+        self.byte_budget
+    }
+
+    /// Whether any applied changes were deferred and have yet to be rendered.
+    pub fn has_deferred_changes(&self) -> bool {
+        self.current.is_dirty()
+    }
+
+    /// Move the cursor to the specified position and update it in state.
+    fn move_cursor_to(&mut self, position: Position) -> Result<()> {
+        let mut buffer = Vec::new();
+        self.queue_cursor_move(&mut buffer, position, false)?;
+
+        self.device.write_all(&buffer)?;
         self.cursor = position;
+        self.track_scroll();
 
         Ok(())
     }
-}
 
-/// Converts a style from its internal representation to crossterm's.
-fn get_content_style(style: Style) -> ContentStyle {
-    let mut content_style = ContentStyle::default();
+    /// Whether the position is displayed at the current scroll and pan offsets. Relative
+    /// interfaces display every line from the scroll offset on.
+    fn is_in_viewport(&self, position: Position) -> bool {
+        let column = position.x().checked_sub(self.viewport.x());
+        let line = position.y().checked_sub(self.viewport.y());
+        match (column, line) {
+            (Some(column), Some(line)) => {
+                column < self.size.x() && (self.mode == Mode::Relative || line < self.size.y())
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the cell at the position is displayed in full, rather than being beyond the
+    /// viewport or cut by its last column.
+    fn is_displayed(&self, position: Position, cell: &Cell) -> bool {
+        self.is_in_viewport(position)
+            && self
+                .to_display(position)
+                .x()
+                .saturating_add(cell.width(self.widths()))
+                <= self.size.x()
+    }
+
+    /// The position at which a position in the viewport is displayed.
+    fn to_display(&self, position: Position) -> Position {
+        pos!(
+            position.x() - self.viewport.x(),
+            position.y() - self.viewport.y()
+        )
+    }
+
+    /// Account for the terminal scrolling if a relative interface's cursor moved below the
+    /// screen's last line, shifting its origin up and, once the origin reaches the top, its first
+    /// lines off the screen.
+    fn track_scroll(&mut self) {
+        if self.mode != Mode::Relative {
+            return;
+        }
+
+        let line = (self.origin.y() as u32 + self.cursor.y() as u32)
+            .saturating_sub(self.scrolled_lines as u32);
+        let last_line = self.size.y().max(1) as u32 - 1;
+        if line <= last_line {
+            return;
+        }
+
+        let shift = u16::try_from(line - last_line).unwrap_or(u16::MAX);
+        let origin_shift = shift.min(self.origin.y());
+        self.origin = pos!(self.origin.x(), self.origin.y() - origin_shift);
+        self.scrolled_lines = self.scrolled_lines.saturating_add(shift - origin_shift);
+
+        log_command!("scrolled by {} lines", shift);
+    }
+
+    /// Queue the cheapest commands to move the cursor from its current position to the specified
+    /// position. If `may_reprint` is set, the screen is known to match the interface's state and
+    /// the cells between the positions may be reprinted rather than moved across.
+    fn queue_cursor_move(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        position: Position,
+        may_reprint: bool,
+    ) -> Result<()> {
+        let gap = match may_reprint {
+            true => self.displayed_gap(position),
+            false => None,
+        };
+
+        let cursor_move = CursorMove::new(self.cursor, position, self.origin.x())
+            .set_absolute(self.mode == Mode::Alternate)
+            .set_gap(gap);
+
+        let commands = cursor_move.encode(|command, buffer| self.encode(command, buffer))?;
+        buffer.extend(commands);
+
+        Ok(())
+    }
+
+    /// The cells displayed from the cursor up to the specified position on its line, if they're
+    /// few enough to be worth reprinting and don't extend past the position.
+    fn displayed_gap(&self, position: Position) -> Option<Vec<Cell>> {
+        let columns = position.x().checked_sub(self.cursor.x())?;
+        if self.cursor.y() != position.y() || columns == 0 || columns > MAX_REPRINTED_COLUMNS {
+            return None;
+        }
+
+        // A cursor within a wide cell, such as one clamped to a narrowed screen, can't reprint it
+        if let Some(previous_column) = self.cursor.x().checked_sub(1) {
+            let previous_position = pos!(
+                previous_column + self.viewport.x(),
+                position.y() + self.viewport.y()
+            );
+            let previous_cell = self.current.get_cell(previous_position);
+            if previous_cell.is_some_and(|cell| cell.width(self.widths()) > 1) {
+                return None;
+            }
+        }
+
+        let mut gap = Vec::new();
+        let mut column = self.cursor.x();
+        while column < position.x() {
+            let gap_position = pos!(column + self.viewport.x(), position.y() + self.viewport.y());
+            let cell = match self.ghost.rendered().get(&gap_position) {
+                Some(cell) => Some(cell),
+                None => self.current.get_cell(gap_position),
+            };
+
+            let cell = cell.cloned().unwrap_or_else(|| Cell::new(" ", None));
+            if cell.width(self.widths()) == 0 || column + cell.width(self.widths()) > position.x() {
+                return None;
+            }
+
+            column += cell.width(self.widths());
+            gap.push(cell);
+        }
+
+        Some(gap)
+    }
+
+    /// Queue printing the cell if it ends in the screen's bottom-right corner, returning whether it
+    /// did. Some terminals scroll after printing into the last line's last column, so the cell is
+    /// instead printed where the cell preceding it is displayed, shifted into the corner by
+    /// inserting blank columns before it, and its predecessor reprinted.
+    fn queue_corner_cell(
+        &mut self,
+        buffer: &mut Vec<u8>,
+        display_position: Position,
+        cell: &Cell,
+    ) -> Result<bool> {
+        let line = (self.origin.y() as u32 + display_position.y() as u32)
+            .saturating_sub(self.scrolled_lines as u32);
+        let end =
+            self.origin.x() as u32 + display_position.x() as u32 + cell.width(self.widths()) as u32;
+        if line + 1 != self.size.y() as u32 || end != self.size.x() as u32 {
+            return Ok(false);
+        }
+
+        // The cell displayed before this one, which may be a wide cell covering two columns
+        let displayed_cell = |offset: u16| {
+            let column = display_position.x().checked_sub(offset)?;
+            let position = pos!(
+                column + self.viewport.x(),
+                display_position.y() + self.viewport.y()
+            );
+            self.ghost
+                .rendered()
+                .get(&position)
+                .or_else(|| self.current.get_cell(position))
+                .filter(|cell| cell.width(self.widths()) == offset)
+                .cloned()
+        };
+
+        let previous_cell = match displayed_cell(1).or_else(|| displayed_cell(2)) {
+            Some(previous_cell) => previous_cell,
+            None if display_position.x() > 0 => Cell::new(" ", None),
+            None => return Ok(false),
+        };
+
+        let previous_width = previous_cell.width(self.widths());
+        let previous_position = pos!(display_position.x() - previous_width, display_position.y());
+        if self.cursor != previous_position {
+            self.queue_cursor_move(buffer, previous_position, true)?;
+        }
+
+        for command in [
+            print_command(cell),
+            DeviceCommand::MoveLeft(cell.width(self.widths())),
+            DeviceCommand::InsertCharacters(previous_width),
+            print_command(&previous_cell),
+        ] {
+            self.encode(&command, buffer)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Append the command's output to the buffer, using the configured encoder if there is one.
+    fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+        let unstyled;
+        let command = match command {
+            DeviceCommand::PrintStyled(text, _) if !self.styling => {
+                unstyled = DeviceCommand::Print(text);
+                &unstyled
+            }
+            command => command,
+        };
+
+        match &mut self.encoder {
+            Some(encoder) => encoder.encode(command, buffer),
+            None => self.device.encode(command, buffer),
+        }
+    }
+
+    /// If width calibration is enabled, measure staged graphemes that haven't been measured
+    /// before, correcting the staged state where the terminal disagrees with unicode-width. The
+    /// interface is redrawn if any were measured since probing disturbs the display.
+    fn calibrate_widths(&mut self) -> Result<()> {
+        let (Some(calibrated), Some(staged)) = (&self.calibrated_graphemes, &self.alternate) else {
+            return Ok(());
+        };
+
+        let graphemes: BTreeSet<String> = staged
+            .dirty_iter()
+            .filter_map(|(_, cell)| cell)
+            .filter(|cell| !cell.grapheme().is_ascii() && !calibrated.contains(cell.grapheme()))
+            .map(|cell| cell.grapheme().to_string())
+            .collect();
+
+        for grapheme in graphemes {
+            let staged_width = self.widths().grapheme_width(&grapheme);
+            let width = self.probe_width(&grapheme)?.clamp(1, 2);
+            if width != staged_width {
+                log_command!("calibrate: {:?} is {} columns wide", grapheme, width);
+                self.update_widths(|widths| widths.set_measured(&grapheme, width));
+                if let Some(staged) = &mut self.alternate {
+                    staged.correct_width(&grapheme, staged_width);
+                }
+            }
+
+            if let Some(calibrated) = &mut self.calibrated_graphemes {
+                calibrated.insert(grapheme);
+            }
+            self.is_invalidated = true;
+        }
+
+        Ok(())
+    }
+
+    /// The columns the terminal advances its cursor when printing the grapheme, measured by
+    /// querying the cursor's position before and after. The grapheme is then erased and the
+    /// cursor restored.
+    fn probe_width(&mut self, grapheme: &str) -> Result<u16> {
+        if self.mode == Mode::LinePrinter {
+            return Ok(self.widths().grapheme_width(grapheme));
+        }
+
+        // Without a cursor to query, graphemes are assumed to have their expected widths
+        let start = match self.device.get_cursor_position() {
+            Err(error) if error.is_unsupported() => {
+                return Ok(self.widths().grapheme_width(grapheme));
+            }
+            start => start?,
+        };
+        self.write_commands(&[DeviceCommand::Print(grapheme)])?;
+        let end = self.device.get_cursor_position()?;
+
+        self.write_commands(&[
+            DeviceCommand::MoveTo(start),
+            DeviceCommand::Print("  "),
+            DeviceCommand::MoveTo(start),
+        ])?;
+
+        Ok(match end.y() == start.y() {
+            true => end.x().saturating_sub(start.x()),
+            false => 1,
+        })
+    }
 
-    if let Some(color) = style.foreground() {
-        content_style.foreground_color = Some(get_crossterm_color(color));
+    /// Write the commands to the device and flush them.
+    fn write_commands(&mut self, commands: &[DeviceCommand]) -> Result<()> {
+        let mut buffer = Vec::new();
+        for command in commands {
+            self.encode(command, &mut buffer)?;
+        }
+
+        self.device.write_all(&buffer)?;
+        self.device.flush()?;
+
+        Ok(())
     }
+}
 
-    if let Some(color) = style.background() {
-        content_style.background_color = Some(get_crossterm_color(color));
+impl<'a> Interface<'a> {
+    /// A writer for the application's own output, such as a build's log or a child process's,
+    /// which continues to scroll while a relative interface stays pinned below it. Each complete
+    /// line written is printed in the interface's place and the interface repainted beneath it,
+    /// leaving changes staged since the last apply pending.
+    ///
+    /// Incomplete lines, and all output written through an alternate interface, are printed after
+    /// the interface when it exits. Line printers print the output immediately.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use std::io::Write;
+    /// use tty_interface::{Interface, Position, pos};
+    ///
+    /// let mut interface = Interface::new_relative(&mut device)?;
+    /// interface.set(pos!(0, 0), "[=====     ] 50%");
+    /// interface.apply()?;
+    ///
+    /// // Printed above the progress bar, which is repainted on the following line
+    /// writeln!(interface.writer(), "Compiling tty-interface")?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn writer(&mut self) -> InterfaceWriter<'_, 'a> {
+        InterfaceWriter::new(self)
     }
+}
 
-    if style.is_bold() {
-        content_style.attributes.set(Attribute::Bold);
+/// The command printing the cell's grapheme in its rendered style.
+fn print_command(cell: &Cell) -> DeviceCommand<'_> {
+    match cell.rendered_style() {
+        Some(style) => DeviceCommand::PrintStyled(cell.grapheme(), style),
+        None => DeviceCommand::Print(cell.grapheme()),
     }
+}
 
-    if style.is_italic() {
-        content_style.attributes.set(Attribute::Italic);
+/// Write a frame to the device, flushing it if specified, returning the error and how many bytes
+/// the device accepted if it fails.
+fn write_frame(
+    device: &mut dyn Device,
+    frame: &[u8],
+    flush: bool,
+) -> std::result::Result<(), (std::io::Error, usize)> {
+    let mut bytes_written = 0;
+    while bytes_written < frame.len() {
+        match device.write(&frame[bytes_written..]) {
+            Ok(0) => return Err((ErrorKind::WriteZero.into(), bytes_written)),
+            Ok(length) => bytes_written += length,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err((error, bytes_written)),
+        }
     }
 
-    if style.is_underlined() {
-        content_style.attributes.set(Attribute::Underlined);
+    if flush {
+        device.flush().map_err(|error| (error, bytes_written))?;
     }
 
-    content_style
+    Ok(())
+}
+
+/// Offset the value by a signed delta, saturating within the `u16` range.
+fn offset_by(value: u16, delta: i32) -> u16 {
+    (value as i32)
+        .saturating_add(delta)
+        .clamp(0, u16::MAX as i32) as u16
 }
 
-fn get_crossterm_color(color: Color) -> crossterm::style::Color {
-    match color {
-        Color::Black => style::Color::Black,
-        Color::DarkGrey => style::Color::DarkGrey,
-        Color::Red => style::Color::Red,
-        Color::DarkRed => style::Color::DarkRed,
-        Color::Green => style::Color::Green,
-        Color::DarkGreen => style::Color::DarkGreen,
-        Color::Yellow => style::Color::Yellow,
-        Color::DarkYellow => style::Color::DarkYellow,
-        Color::Blue => style::Color::Blue,
-        Color::DarkBlue => style::Color::DarkBlue,
-        Color::Magenta => style::Color::Magenta,
-        Color::DarkMagenta => style::Color::DarkMagenta,
-        Color::Cyan => style::Color::Cyan,
-        Color::DarkCyan => style::Color::DarkCyan,
-        Color::White => style::Color::White,
-        Color::Grey => style::Color::Grey,
-        Color::Reset => style::Color::Reset,
+/// Stages a cell at the next position, wrapping to the following line if its grapheme would exceed
+/// the width. Returns the cell's position, unless it was clipped.
+fn stage_cell(
+    state: &mut State,
+    width: u16,
+    overflow: Overflow,
+    indicator: Option<&(String, Style)>,
+    line: &mut u16,
+    column: &mut u16,
+    cell: Cell,
+) -> Option<Position> {
+    let cell_width = cell.width(state.widths());
+    let mut start = None;
+    if column.saturating_add(cell_width) > width && *column > 0 && overflow != Overflow::Extend {
+        if overflow == Overflow::Clip {
+            *column = column.saturating_add(cell_width);
+            return None;
+        }
+
+        *column = 0;
+        *line = line.saturating_add(1);
+
+        // Wrapped lines begin with the indicator, unless it would leave no room for text
+        if let Some((indicator, style)) = indicator {
+            if state.widths().text_width(indicator) + cell_width <= width {
+                start = Some(pos!(0, *line));
+                for grapheme in split_graphemes(indicator) {
+                    state.set_cell(pos!(*column, *line), Cell::new(grapheme, Some(*style)));
+                    *column += state.widths().grapheme_width(grapheme);
+                }
+            }
+        }
     }
+
+    let cell_position = pos!(*column, *line);
+    state.set_cell(cell_position, cell);
+
+    *column = column.saturating_add(cell_width);
+
+    Some(start.unwrap_or(cell_position))
 }
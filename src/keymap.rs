@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use crate::{Event, Interface, KeyEvent, Result};
+
+/// Maps key chords to application-defined actions, which may be values such as an enum of
+/// commands or callbacks such as `fn(&mut App)`.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Event, Interface, KeyCode, KeyEvent, KeyMap, Modifiers};
+///
+/// #[derive(Debug, Copy, Clone, PartialEq)]
+/// enum Action {
+///     Save,
+///     Quit,
+/// }
+///
+/// let mut keys = KeyMap::new();
+/// keys.bind(KeyEvent::parse("ctrl+s").unwrap(), Action::Save);
+/// keys.bind(KeyEvent::parse("q").unwrap(), Action::Quit);
+///
+/// # device.push_event(Event::Key(KeyEvent::new(KeyCode::Char('x'), Modifiers::new())));
+/// # device.push_event(Event::Key(KeyEvent::new(KeyCode::Char('q'), Modifiers::new())));
+/// let mut interface = Interface::new_alternate(&mut device)?;
+/// loop {
+///     match keys.next_action(&mut interface)? {
+///         Action::Save => { /* ... */ }
+///         Action::Quit => break,
+///     }
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyMap<A> {
+    bindings: HashMap<KeyEvent, A>,
+}
+
+impl<A> KeyMap<A> {
+    /// Create a map with no bindings.
+    pub fn new() -> KeyMap<A> {
+        KeyMap {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Bind the chord to an action, returning the action it was previously bound to, if any.
+    pub fn bind(&mut self, chord: KeyEvent, action: A) -> Option<A> {
+        self.bindings.insert(chord, action)
+    }
+
+    /// Remove the chord's binding, returning its action if it was bound.
+    pub fn unbind(&mut self, chord: KeyEvent) -> Option<A> {
+        self.bindings.remove(&chord)
+    }
+
+    /// The action bound to the chord, if any.
+    pub fn get(&self, chord: KeyEvent) -> Option<&A> {
+        self.bindings.get(&chord)
+    }
+
+    /// The action bound to the key pressed in the event, if any.
+    pub fn action(&self, event: &Event) -> Option<&A> {
+        match event {
+            Event::Key(chord) => self.get(*chord),
+            _ => None,
+        }
+    }
+
+    /// The action bound to the key pressed in the event, if any, for modification, e.g. to invoke
+    /// a `FnMut` callback.
+    pub fn action_mut(&mut self, event: &Event) -> Option<&mut A> {
+        match event {
+            Event::Key(chord) => self.bindings.get_mut(chord),
+            _ => None,
+        }
+    }
+
+    /// Read events from the interface, discarding those without a binding, until a bound key is
+    /// pressed, returning its action.
+    pub fn next_action(&self, interface: &mut Interface) -> Result<&A> {
+        loop {
+            let event = interface.read_event()?;
+            if let Some(action) = self.action(&event) {
+                return Ok(action);
+            }
+        }
+    }
+}
+
+impl<A> Default for KeyMap<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> FromIterator<(KeyEvent, A)> for KeyMap<A> {
+    fn from_iter<T: IntoIterator<Item = (KeyEvent, A)>>(bindings: T) -> Self {
+        KeyMap {
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyMap;
+    use crate::{Event, KeyCode, KeyEvent, Modifiers};
+
+    fn chord(chord: &str) -> KeyEvent {
+        KeyEvent::parse(chord).unwrap()
+    }
+
+    #[test]
+    fn keymap_parse_chords() {
+        let control = Modifiers::new().set_control(true);
+        assert_eq!(KeyEvent::new(KeyCode::Char('c'), control), chord("ctrl+c"));
+        assert_eq!(KeyEvent::new(KeyCode::Char('+'), control), chord("Ctrl++"));
+        assert_eq!(
+            KeyEvent::new(KeyCode::Char('+'), Modifiers::new()),
+            chord("+")
+        );
+        assert_eq!(
+            KeyEvent::new(KeyCode::F(12), Modifiers::new()),
+            chord("F12")
+        );
+        assert_eq!(
+            KeyEvent::new(KeyCode::Tab, Modifiers::new().set_shift(true)),
+            chord("shift+tab")
+        );
+        assert_eq!(
+            KeyEvent::new(KeyCode::PageDown, Modifiers::new()),
+            chord("pagedown")
+        );
+
+        assert_eq!(None, KeyEvent::parse(""));
+        assert_eq!(None, KeyEvent::parse("ctrl+"));
+        assert_eq!(None, KeyEvent::parse("ctrl+foo"));
+        assert_eq!(None, KeyEvent::parse("super+a"));
+    }
+
+    #[test]
+    fn keymap_actions() {
+        let mut keys: KeyMap<&str> = [(chord("ctrl+s"), "save"), (chord("esc"), "cancel")]
+            .into_iter()
+            .collect();
+        assert_eq!(Some("save"), keys.bind(chord("ctrl+s"), "write"));
+        assert_eq!(Some("cancel"), keys.unbind(chord("esc")));
+
+        assert_eq!(Some(&"write"), keys.action(&Event::Key(chord("ctrl+s"))));
+        assert_eq!(None, keys.action(&Event::Key(chord("s"))));
+        assert_eq!(None, keys.action(&Event::Key(chord("esc"))));
+        assert_eq!(None, keys.action(&Event::FocusGained));
+    }
+
+    #[test]
+    fn keymap_callbacks() {
+        let mut count = 0;
+        let mut keys: KeyMap<fn(&mut i32)> = KeyMap::new();
+        keys.bind(chord("up"), |count| *count += 1);
+        keys.bind(chord("down"), |count| *count -= 1);
+
+        for key in ["up", "up", "down", "up"] {
+            if let Some(callback) = keys.action(&Event::Key(chord(key))) {
+                callback(&mut count);
+            }
+        }
+
+        assert_eq!(2, count);
+    }
+}
@@ -2,6 +2,32 @@
 //!
 //! Provides simple TTY-based user interface capabilities including partial re-renders of multi-line displays.
 //!
+//! ## Minimal builds
+//!
+//! Text is split into graphemes with Unicode's segmentation rules by the default `graphemes`
+//! feature. Building without it drops the `unicode-segmentation` dependency and its tables in
+//! favor of a simpler character-based approximation, which keeps combining marks, emoji modifiers
+//! and sequences, and flags intact but splits rarer clusters, such as Hangul syllables composed
+//! of jamo, into separate cells.
+//!
+//! Building without the default `std` feature leaves only the core for embedded targets, which
+//! needs just `alloc`: a [Grid] of styled text tracking its changed cells, with the positions,
+//! styles and text measurement it's built from, for rendering to devices of the application's.
+//!
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+/// Declares items requiring the standard library, which are omitted from `no_std` builds.
+macro_rules! cfg_std {
+    ($($item:item)*) => {
+        $(
+            #[cfg(feature = "std")]
+            $item
+        )*
+    };
+}
 
 mod position;
 pub use position::Position;
@@ -9,19 +35,196 @@ pub use position::Position;
 mod vector;
 pub use vector::Vector;
 
-mod interface;
-pub use interface::Interface;
-
-mod device;
-pub use device::Device;
-
-mod result;
-pub use result::{Error, Result};
+mod rect;
+pub use rect::Rect;
 
 mod style;
 pub use style::{Color, Style};
 
+mod quantize;
+pub use quantize::Quantization;
+
+mod text;
+pub use text::{grapheme_count, slice_graphemes};
+
 mod state;
-pub(crate) use state::{Cell, State};
+#[cfg(feature = "std")]
+pub(crate) use state::Cell;
+pub(crate) use state::State;
+
+mod grid;
+pub use grid::{Grid, GridChange};
+
+cfg_std! {
+    mod mode;
+    pub use mode::Mode;
+
+    mod overflow;
+    pub use overflow::Overflow;
+
+    mod cursor;
+    pub use cursor::CursorMode;
+
+    mod width;
+    pub use width::AmbiguousWidth;
+
+    mod strategy;
+    pub use strategy::RenderStrategy;
+
+    mod alignment;
+    pub use alignment::Alignment;
+
+    mod ghost;
+    pub use ghost::Ghost;
+    pub(crate) use ghost::GhostState;
+
+    mod panel;
+    pub use panel::{Border, PanelStyle};
+
+    mod modal;
+    pub use modal::Modal;
+
+    mod line;
+    pub(crate) use line::AppendedLine;
+    pub use line::LineHandle;
+
+    mod status;
+    pub use status::StatusLine;
+
+    mod canvas;
+    pub use canvas::{Canvas, CanvasMarker};
+
+    mod chart;
+    pub use chart::{BarChart, Sparkline};
+
+    mod gauge;
+    pub use gauge::Gauge;
+
+    mod tabs;
+    pub use tabs::Tabs;
+
+    mod tree;
+    pub use tree::{Tree, TreeNode};
+
+    mod pager;
+    pub use pager::{LineProvider, Pager};
+
+    mod gutter;
+    pub use gutter::Gutter;
+
+    mod notification;
+    pub(crate) use notification::Notifications;
+    pub use notification::{Corner, NotificationLevel};
+
+    mod clock;
+    pub use clock::{Clock, SystemClock};
+
+    mod timer;
+    pub use timer::Timer;
+
+    mod debounce;
+    pub(crate) use debounce::DebounceState;
+
+    mod interface;
+    pub use interface::Interface;
+
+    mod writer;
+    pub use writer::InterfaceWriter;
+
+    mod hooks;
+    pub(crate) use hooks::Hooks;
+    pub use hooks::{ApplyStats, Staging};
+
+    mod builder;
+    pub use builder::InterfaceBuilder;
+
+    mod command;
+    pub use command::{encode_ansi, CommandEncoder, DeviceCommand};
+
+    mod device;
+    pub(crate) use device::{cursor_position_or, DeviceHandle};
+    pub use device::{CursorQuery, Device, EventSource, RawModeControl, SizedTarget, WriterDevice};
+
+    mod arbiter;
+    pub use arbiter::{ArbitratedDevice, DeviceArbiter};
+
+    mod broadcast;
+    pub use broadcast::Broadcaster;
+
+    #[cfg(any(feature = "crossterm", all(feature = "ansi", unix)))]
+    mod environment;
+
+    mod event;
+    pub use event::{Event, KeyCode, KeyEvent, Modifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    mod events;
+    pub use events::{EventRecorder, EventReplayer};
+
+    mod focus;
+    pub use focus::{FocusManager, Focusable};
+
+    mod keymap;
+    pub use keymap::KeyMap;
+
+    mod result;
+    pub use result::{ApplyError, Error, Result};
+
+    mod theme;
+    pub use theme::Theme;
+
+    #[cfg(feature = "images")]
+    mod image;
+    #[cfg(feature = "images")]
+    pub(crate) use image::ImageState;
+    #[cfg(feature = "images")]
+    pub use image::{ImageProtocol, ImageSource, SIXEL_CELL_HEIGHT, SIXEL_CELL_WIDTH};
+
+    #[cfg(feature = "ls-colors")]
+    mod ls_colors;
+    #[cfg(feature = "ls-colors")]
+    pub use ls_colors::{FileType, LsColors};
+
+    mod bidi;
+
+    mod markdown;
+
+    mod logging;
+
+    mod watch;
+    #[cfg(feature = "crossterm")]
+    pub use watch::watch;
+    pub use watch::watch_until;
+
+    mod child;
+
+    mod motion;
+
+    mod recorder;
+    pub(crate) use recorder::Recorder;
+
+    mod player;
+    pub use player::Player;
+
+    pub mod compat;
+
+    pub mod prompt;
+
+    #[cfg(all(feature = "ansi", unix))]
+    pub mod ansi;
+
+    pub mod telnet;
+
+    #[cfg(all(feature = "ffi", unix))]
+    pub mod ffi;
+
+    #[cfg(feature = "python")]
+    pub mod python;
+
+    #[cfg(feature = "terminfo")]
+    pub mod terminfo;
+
+    #[cfg(all(unix, feature = "signals"))]
+    mod signals;
 
-pub mod test;
+    pub mod test;
+}
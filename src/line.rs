@@ -0,0 +1,137 @@
+use crate::{pos, Interface, Position, Rect, Style, Vector};
+
+/// Identifies a line appended to an interface with [crate::Interface::append_line], for updating
+/// it later wherever it's since been moved, e.g. by inserting or deleting lines above it. Handles
+/// are only meaningful to the interface which returned them.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Color, Interface};
+///
+/// let mut interface = Interface::new_relative(&mut device)?;
+/// let download = interface.append_line("Downloading: 0%", None);
+/// interface.insert_line(0);
+///
+/// download.set_text(&mut interface, "Downloading: 50%");
+/// download.set_style(&mut interface, Some(Color::Blue.as_style()));
+/// assert_eq!(Some(1), download.line(&interface));
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct LineHandle {
+    index: usize,
+}
+
+impl LineHandle {
+    /// Create a handle for the interface's appended line at the specified index.
+    pub(crate) fn new(index: usize) -> LineHandle {
+        LineHandle { index }
+    }
+
+    /// The index of this handle's line among those appended to its interface.
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The line this handle's text currently starts on, or `None` if it's been deleted.
+    pub fn line(&self, interface: &Interface) -> Option<u16> {
+        let bounds = interface.appended_line(*self)?.bounds?;
+        Some(bounds.origin().y())
+    }
+
+    /// Replace this line's text, keeping its style. Has no effect if the line was deleted.
+    /// Changes are staged until applied.
+    pub fn set_text(&self, interface: &mut Interface, text: &str) {
+        if let Some(appended) = interface.appended_line(*self) {
+            let style = appended.style;
+            interface.update_line(*self, text, style);
+        }
+    }
+
+    /// Restyle this line's text. Has no effect if the line was deleted. Changes are staged until
+    /// applied.
+    pub fn set_style(&self, interface: &mut Interface, style: Option<Style>) {
+        if let Some(appended) = interface.appended_line(*self) {
+            let text = appended.text.clone();
+            interface.update_line(*self, &text, style);
+        }
+    }
+}
+
+/// A line appended to an interface, as it was last staged.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct AppendedLine {
+    pub(crate) text: String,
+    pub(crate) style: Option<Style>,
+    /// The full-width lines the text occupies, or `None` if they've been deleted.
+    pub(crate) bounds: Option<Rect>,
+}
+
+impl AppendedLine {
+    /// Shift the line down if it's on or after the inserted line.
+    pub(crate) fn insert_line(&mut self, line: u16) {
+        if let Some(bounds) = &mut self.bounds {
+            if bounds.origin().y() >= line {
+                let origin = pos!(0, bounds.origin().y().saturating_add(1));
+                *bounds = Rect::new(origin, bounds.size());
+            }
+        }
+    }
+
+    /// Shift the line up if it's after the deleted line, or shrink it if it includes it.
+    pub(crate) fn delete_line(&mut self, line: u16) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+
+        let (origin, size) = (bounds.origin(), bounds.size());
+        if origin.y() > line {
+            self.bounds = Some(Rect::new(pos!(0, origin.y() - 1), size));
+        } else if line - origin.y() < size.y() {
+            self.bounds =
+                (size.y() > 1).then(|| Rect::new(origin, Vector::new(size.x(), size.y() - 1)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppendedLine;
+    use crate::{pos, Position, Rect, Vector};
+
+    fn appended(line: u16, height: u16) -> AppendedLine {
+        AppendedLine {
+            text: String::new(),
+            style: None,
+            bounds: Some(Rect::new(pos!(0, line), Vector::new(10, height))),
+        }
+    }
+
+    fn line_and_height(appended: &AppendedLine) -> Option<(u16, u16)> {
+        let bounds = appended.bounds?;
+        Some((bounds.origin().y(), bounds.size().y()))
+    }
+
+    #[test]
+    fn appended_line_shifts() {
+        let mut line = appended(3, 2);
+        line.insert_line(5);
+        assert_eq!(Some((3, 2)), line_and_height(&line));
+        line.insert_line(3);
+        assert_eq!(Some((4, 2)), line_and_height(&line));
+        line.delete_line(0);
+        assert_eq!(Some((3, 2)), line_and_height(&line));
+        line.delete_line(5);
+        assert_eq!(Some((3, 2)), line_and_height(&line));
+
+        line.delete_line(4);
+        assert_eq!(Some((3, 1)), line_and_height(&line));
+        line.delete_line(3);
+        assert_eq!(None, line_and_height(&line));
+        line.insert_line(0);
+        assert_eq!(None, line_and_height(&line));
+    }
+}
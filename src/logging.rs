@@ -0,0 +1,106 @@
+//! Debug-level logging of the terminal commands emitted by each apply, enabled by the `log`
+//! feature. Records use the `tty_interface` target, so they may be filtered independently of the
+//! application's own logging, and are forwarded by `tracing`'s log compatibility layer.
+
+/// Log a debug-level record describing an emitted terminal command. The arguments are only
+/// evaluated if the `log` feature is enabled and the record is.
+macro_rules! log_command {
+    ($($argument:tt)*) => {{
+        #[cfg(feature = "log")]
+        log::debug!(target: "tty_interface", $($argument)*);
+
+        // Type-check the arguments without evaluating them, so they're used regardless of features
+        #[cfg(not(feature = "log"))]
+        if false {
+            let _ = format_args!($($argument)*);
+        }
+    }};
+}
+
+pub(crate) use log_command;
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use std::{
+        sync::Mutex,
+        thread::{self, ThreadId},
+    };
+
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    use crate::{pos, test::VirtualDevice, Interface, Position};
+
+    /// Captures records with the thread which logged them, as tests run concurrently.
+    struct CapturingLogger(Mutex<Vec<(ThreadId, String)>>);
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.target() == "tty_interface" && metadata.level() <= Level::Debug
+        }
+
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                let message = record.args().to_string();
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((thread::current().id(), message));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+    /// Run the action, returning the messages it logged.
+    fn capture(action: impl FnOnce()) -> Vec<String> {
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(LevelFilter::Debug);
+
+        action();
+
+        let thread = thread::current().id();
+        let mut records = LOGGER.0.lock().unwrap();
+        let messages = records
+            .iter()
+            .filter(|(id, _)| *id == thread)
+            .map(|(_, message)| message.clone())
+            .collect();
+        records.retain(|(id, _)| *id != thread);
+
+        messages
+    }
+
+    #[test]
+    fn logging_apply_commands() {
+        let mut device = VirtualDevice::new();
+
+        let messages = capture(|| {
+            let mut interface = Interface::new_alternate(&mut device).unwrap();
+            interface.set(pos!(3, 1), "ab");
+            interface.apply().unwrap();
+            interface.clear_line(1);
+            interface.set_cursor(Some(pos!(0, 2)));
+            interface.apply().unwrap();
+        });
+
+        assert_eq!(
+            vec![
+                "apply: 2 dirty cells",
+                "move cursor from Position(0, 0) to Position(3, 1)",
+                "print \"a\" at Position(3, 1)",
+                "print \"b\" at Position(4, 1)",
+                "apply: wrote 2 cells (0 deferred)",
+                "apply: 2 dirty cells",
+                "move cursor from Position(5, 1) to Position(3, 1)",
+                "clear Position(3, 1)",
+                "clear Position(4, 1)",
+                "move cursor from Position(5, 1) to Position(0, 2)",
+                "show cursor at Position(0, 2)",
+                "apply: wrote 2 cells (0 deferred)",
+            ],
+            messages
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::Style;
+
+/// A kind of file distinguished by `LS_COLORS`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FileType {
+    /// A regular file, `fi`.
+    File,
+    /// A directory, `di`.
+    Directory,
+    /// A symbolic link, `ln`.
+    Symlink,
+    /// A symbolic link to a nonexistent file, `or`.
+    Orphan,
+    /// A named pipe, `pi`.
+    Pipe,
+    /// A socket, `so`.
+    Socket,
+    /// A block device, `bd`.
+    BlockDevice,
+    /// A character device, `cd`.
+    CharDevice,
+    /// A regular file with an executable permission, `ex`.
+    Executable,
+}
+
+impl FileType {
+    /// The two-letter `LS_COLORS` key for this file type.
+    pub fn key(self) -> &'static str {
+        match self {
+            FileType::File => "fi",
+            FileType::Directory => "di",
+            FileType::Symlink => "ln",
+            FileType::Orphan => "or",
+            FileType::Pipe => "pi",
+            FileType::Socket => "so",
+            FileType::BlockDevice => "bd",
+            FileType::CharDevice => "cd",
+            FileType::Executable => "ex",
+        }
+    }
+}
+
+/// File styles parsed from a GNU `dircolors`/`LS_COLORS` configuration.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{Color, FileType, LsColors};
+///
+/// let ls_colors = LsColors::parse("di=01;34:*.rs=33");
+/// assert_eq!(
+///     Some(Color::DarkBlue.as_style().set_bold(true)),
+///     ls_colors.style_for_type(FileType::Directory),
+/// );
+/// assert_eq!(Some(Color::DarkYellow.as_style()), ls_colors.style_for_name("lib.rs"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LsColors {
+    types: HashMap<String, Style>,
+    suffixes: Vec<(String, Style)>,
+}
+
+impl LsColors {
+    /// Parse a colon-separated `LS_COLORS` value. Malformed entries are ignored.
+    pub fn parse(value: &str) -> LsColors {
+        let mut ls_colors = LsColors::default();
+
+        for entry in value.split(':') {
+            let (key, sgr) = match entry.split_once('=') {
+                Some(pair) => pair,
+                None => continue,
+            };
+
+            let style = match Style::from_sgr(sgr) {
+                Some(style) => style,
+                None => continue,
+            };
+
+            match key.strip_prefix('*') {
+                Some(suffix) => ls_colors.suffixes.push((suffix.to_string(), style)),
+                None => {
+                    ls_colors.types.insert(key.to_string(), style);
+                }
+            }
+        }
+
+        ls_colors
+    }
+
+    /// Parse the `LS_COLORS` environment variable, if set.
+    pub fn from_env() -> Option<LsColors> {
+        std::env::var("LS_COLORS")
+            .ok()
+            .map(|value| LsColors::parse(&value))
+    }
+
+    /// The style configured for the specified file type, if any.
+    pub fn style_for_type(&self, file_type: FileType) -> Option<Style> {
+        self.types.get(file_type.key()).copied()
+    }
+
+    /// The style configured for a file name's suffix pattern (e.g. `*.tar`), if any. Later
+    /// patterns take precedence over earlier patterns, as with GNU `ls`.
+    pub fn style_for_name(&self, name: &str) -> Option<Style> {
+        self.suffixes
+            .iter()
+            .rev()
+            .find(|(suffix, _)| name.ends_with(suffix.as_str()))
+            .map(|(_, style)| *style)
+    }
+
+    /// The style for the file at the specified path, considering its type, permissions, and name.
+    /// Falls back to the regular file style, if any, when no more specific style applies.
+    pub fn style_for_path(&self, path: &Path) -> Option<Style> {
+        let file_type = get_file_type(path);
+
+        if file_type != FileType::File {
+            if let Some(style) = self.style_for_type(file_type) {
+                return Some(style);
+            }
+        }
+
+        let name = path.file_name().map(|name| name.to_string_lossy());
+        if let Some(style) = name.and_then(|name| self.style_for_name(&name)) {
+            return Some(style);
+        }
+
+        self.style_for_type(FileType::File)
+    }
+}
+
+/// Determine the type of the file at the specified path, treating missing files as regular.
+fn get_file_type(path: &Path) -> FileType {
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(_) => return FileType::File,
+    };
+
+    let file_type = metadata.file_type();
+    if file_type.is_symlink() {
+        return match path.metadata() {
+            Ok(_) => FileType::Symlink,
+            Err(_) => FileType::Orphan,
+        };
+    }
+
+    if file_type.is_dir() {
+        return FileType::Directory;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        if file_type.is_fifo() {
+            return FileType::Pipe;
+        }
+
+        if file_type.is_socket() {
+            return FileType::Socket;
+        }
+
+        if file_type.is_block_device() {
+            return FileType::BlockDevice;
+        }
+
+        if file_type.is_char_device() {
+            return FileType::CharDevice;
+        }
+
+        if metadata.permissions().mode() & 0o111 != 0 {
+            return FileType::Executable;
+        }
+    }
+
+    FileType::File
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{Color, Style};
+
+    use super::{FileType, LsColors};
+
+    #[test]
+    fn ls_colors_parse_types() {
+        let ls_colors = LsColors::parse("rs=0:di=01;34:ln=01;36:ex=01;32:fi=");
+
+        assert_eq!(
+            Some(Color::DarkBlue.as_style().set_bold(true)),
+            ls_colors.style_for_type(FileType::Directory)
+        );
+        assert_eq!(
+            Some(Color::DarkCyan.as_style().set_bold(true)),
+            ls_colors.style_for_type(FileType::Symlink)
+        );
+        assert_eq!(
+            Some(Color::DarkGreen.as_style().set_bold(true)),
+            ls_colors.style_for_type(FileType::Executable)
+        );
+        assert_eq!(Some(Style::new()), ls_colors.style_for_type(FileType::File));
+        assert_eq!(None, ls_colors.style_for_type(FileType::Pipe));
+    }
+
+    #[test]
+    fn ls_colors_parse_suffixes() {
+        let ls_colors = LsColors::parse("*.tar=01;31:*.gz=31:*.tar.gz=35:*README=4");
+
+        assert_eq!(
+            Some(Color::DarkRed.as_style().set_bold(true)),
+            ls_colors.style_for_name("archive.tar")
+        );
+        assert_eq!(
+            Some(Color::DarkMagenta.as_style()),
+            ls_colors.style_for_name("archive.tar.gz")
+        );
+        assert_eq!(
+            Some(Color::DarkRed.as_style()),
+            ls_colors.style_for_name("file.gz")
+        );
+        assert_eq!(
+            Some(Style::new().set_underline(true)),
+            ls_colors.style_for_name("README")
+        );
+        assert_eq!(None, ls_colors.style_for_name("main.rs"));
+    }
+
+    #[test]
+    fn ls_colors_ignore_malformed_entries() {
+        let ls_colors = LsColors::parse("di:ln=x;1::*.rs=33");
+
+        assert_eq!(None, ls_colors.style_for_type(FileType::Directory));
+        assert_eq!(None, ls_colors.style_for_type(FileType::Symlink));
+        assert_eq!(
+            Some(Color::DarkYellow.as_style()),
+            ls_colors.style_for_name("a.rs")
+        );
+    }
+
+    #[test]
+    fn ls_colors_style_for_path() {
+        let ls_colors = LsColors::parse("di=34:fi=37:*.toml=33");
+
+        assert_eq!(
+            Some(Color::DarkBlue.as_style()),
+            ls_colors.style_for_path(Path::new("src"))
+        );
+        assert_eq!(
+            Some(Color::DarkYellow.as_style()),
+            ls_colors.style_for_path(Path::new("Cargo.toml"))
+        );
+        assert_eq!(
+            Some(Color::Grey.as_style()),
+            ls_colors.style_for_path(Path::new("README.md"))
+        );
+    }
+}
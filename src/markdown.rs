@@ -0,0 +1,382 @@
+use crate::{
+    pos,
+    text::{split_graphemes, Widths},
+    Color, Position, Rect, State, Style, Theme,
+};
+
+/// The styles Markdown elements are rendered with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct MarkdownStyles {
+    heading: Style,
+    strong: Style,
+    emphasis: Style,
+    code: Style,
+}
+
+impl MarkdownStyles {
+    /// The styles named "markdown.heading", "markdown.strong", "markdown.emphasis", and
+    /// "markdown.code" in the theme, defaulting to bold, bold, italic, and yellow respectively.
+    fn from_theme(theme: &Theme) -> MarkdownStyles {
+        MarkdownStyles {
+            heading: theme
+                .get("markdown.heading")
+                .unwrap_or(Style::new().set_bold(true)),
+            strong: theme
+                .get("markdown.strong")
+                .unwrap_or(Style::new().set_bold(true)),
+            emphasis: theme
+                .get("markdown.emphasis")
+                .unwrap_or(Style::new().set_italic(true)),
+            code: theme
+                .get("markdown.code")
+                .unwrap_or(Color::Yellow.as_style()),
+        }
+    }
+}
+
+/// A grapheme and the style it's rendered with.
+type StyledGrapheme = (String, Style);
+
+/// Renders the Markdown text into the state within the rectangle, clearing it first, and returns
+/// the number of lines used. Lines beyond the rectangle are discarded.
+pub(crate) fn render_markdown(state: &mut State, theme: &Theme, rect: Rect, text: &str) -> u16 {
+    let styles = MarkdownStyles::from_theme(theme);
+    let width = rect.size().x();
+    let widths = state.widths().clone();
+
+    let mut lines: Vec<Vec<StyledGrapheme>> = Vec::new();
+    let mut paragraph: Option<(String, String, u16)> = None;
+    let mut is_fenced = false;
+
+    // Separates blocks with a single blank line, however many separate them in the source
+    let push_gap = |lines: &mut Vec<Vec<StyledGrapheme>>| {
+        if lines.last().is_some_and(|line| !line.is_empty()) {
+            lines.push(Vec::new());
+        }
+    };
+
+    for source_line in text.lines() {
+        let trimmed = source_line.trim_start();
+
+        if trimmed.starts_with("```") {
+            flush_paragraph(&mut lines, &mut paragraph, width, &widths, &styles);
+            if !is_fenced {
+                push_gap(&mut lines);
+            }
+            is_fenced = !is_fenced;
+            continue;
+        }
+
+        if is_fenced {
+            let code = source_line.replace('\t', "    ");
+            let graphemes = split_graphemes(&code)
+                .map(|grapheme| (grapheme.to_string(), styles.code))
+                .collect();
+            lines.push(graphemes);
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut lines, &mut paragraph, width, &widths, &styles);
+            push_gap(&mut lines);
+            continue;
+        }
+
+        if let Some((level, heading)) = parse_heading(trimmed) {
+            flush_paragraph(&mut lines, &mut paragraph, width, &widths, &styles);
+            let mut style = styles.heading;
+            if level == 1 {
+                style = style.set_underline(true);
+            }
+
+            let graphemes = parse_inline(heading, style, &styles);
+            lines.extend(wrap(Vec::new(), &graphemes, width, &widths, 0));
+            continue;
+        }
+
+        if let Some((marker, item)) = parse_list_item(source_line) {
+            flush_paragraph(&mut lines, &mut paragraph, width, &widths, &styles);
+            let indent = (source_line.len() - trimmed.len()) as u16 / 2 * 2;
+            let marker_width = split_graphemes(&marker).count() as u16;
+            paragraph = Some((
+                format!("{}{}", " ".repeat(indent as usize), marker),
+                item.to_string(),
+                indent + marker_width,
+            ));
+            continue;
+        }
+
+        // Plain lines continue the current paragraph or list item
+        match &mut paragraph {
+            Some((_, text, _)) => {
+                text.push(' ');
+                text.push_str(trimmed);
+            }
+            None => paragraph = Some((String::new(), trimmed.to_string(), 0)),
+        }
+    }
+    flush_paragraph(&mut lines, &mut paragraph, width, &widths, &styles);
+
+    while lines.last().is_some_and(Vec::is_empty) {
+        lines.pop();
+    }
+    lines.truncate(rect.size().y() as usize);
+
+    state.clear_rect(rect);
+    for (line, graphemes) in lines.iter().enumerate() {
+        let mut column = 0;
+        for (grapheme, style) in graphemes {
+            let grapheme_width = widths.grapheme_width(grapheme);
+            if column + grapheme_width > width {
+                break;
+            }
+
+            let position = pos!(rect.origin().x() + column, rect.origin().y() + line as u16);
+            match *style == Style::new() {
+                true => state.set_text(position, grapheme),
+                false => state.set_styled_text(position, grapheme, *style),
+            }
+            column += grapheme_width;
+        }
+    }
+
+    lines.len() as u16
+}
+
+/// Wrap the pending paragraph or list item, if any, into the lines. A paragraph is its prefix,
+/// such as a list item's indented marker, its text, and the indentation of its wrapped lines.
+fn flush_paragraph(
+    lines: &mut Vec<Vec<StyledGrapheme>>,
+    paragraph: &mut Option<(String, String, u16)>,
+    width: u16,
+    widths: &Widths,
+    styles: &MarkdownStyles,
+) {
+    let Some((prefix, text, indent)) = paragraph.take() else {
+        return;
+    };
+
+    let prefix = split_graphemes(&prefix)
+        .map(|grapheme| (grapheme.to_string(), Style::new()))
+        .collect();
+    let graphemes = parse_inline(&text, Style::new(), styles);
+
+    lines.extend(wrap(prefix, &graphemes, width, widths, indent));
+}
+
+/// The level and text of an ATX heading line, such as "## Usage".
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// The rendered marker and text of a list item line, such as "- item" or "2. item".
+fn parse_list_item(line: &str) -> Option<(String, &str)> {
+    let trimmed = line.trim_start();
+
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = trimmed.strip_prefix(bullet) {
+            return Some(("• ".to_string(), item.trim_start()));
+        }
+    }
+
+    let digits = trimmed.chars().take_while(char::is_ascii_digit).count();
+    let rest = &trimmed[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some((format!("{}. ", &trimmed[..digits]), rest[2..].trim_start()));
+    }
+
+    None
+}
+
+/// The text's graphemes styled by its emphasis, strong emphasis, and inline code spans on top of
+/// the base style. Markers are removed, and a backslash escapes the following character.
+fn parse_inline(text: &str, base: Style, styles: &MarkdownStyles) -> Vec<StyledGrapheme> {
+    let graphemes: Vec<&str> = split_graphemes(text).collect();
+    let (mut is_strong, mut is_emphasis, mut is_code) = (false, false, false);
+
+    let mut styled = Vec::new();
+    let mut index = 0;
+    while index < graphemes.len() {
+        let grapheme = graphemes[index];
+        let next = graphemes.get(index + 1).copied();
+
+        if is_code {
+            match grapheme {
+                "`" => is_code = false,
+                _ => styled.push((grapheme.to_string(), base.overlay(&styles.code))),
+            }
+            index += 1;
+            continue;
+        }
+
+        match grapheme {
+            "\\" if next.is_some_and(|next| next != " ") => {
+                index += 1;
+                let style = inline_style(base, styles, is_strong, is_emphasis);
+                styled.push((graphemes[index].to_string(), style));
+            }
+            "`" => is_code = true,
+            // Underscores within words, such as in "snake_case", are literal
+            "_" if index > 0 && is_word(graphemes[index - 1]) && next.is_some_and(is_word) => {
+                let style = inline_style(base, styles, is_strong, is_emphasis);
+                styled.push((grapheme.to_string(), style));
+            }
+            "*" | "_" if next == Some(grapheme) => {
+                is_strong = !is_strong;
+                index += 1;
+            }
+            // Markers followed by whitespace, such as in "2 * 3", are literal
+            "*" | "_" if is_emphasis || next.is_some_and(|next| next.trim() == next) => {
+                is_emphasis = !is_emphasis;
+            }
+            _ => {
+                let style = inline_style(base, styles, is_strong, is_emphasis);
+                styled.push((grapheme.to_string(), style));
+            }
+        }
+        index += 1;
+    }
+
+    styled
+}
+
+/// Whether the grapheme is part of a word.
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_alphanumeric)
+}
+
+/// The base style with strong emphasis and emphasis applied, if specified.
+fn inline_style(base: Style, styles: &MarkdownStyles, is_strong: bool, is_emphasis: bool) -> Style {
+    let mut style = base;
+    if is_strong {
+        style = style.overlay(&styles.strong);
+    }
+    if is_emphasis {
+        style = style.overlay(&styles.emphasis);
+    }
+
+    style
+}
+
+/// Wrap the graphemes into lines of the width at spaces, measured with the widths, following the
+/// prefix on the first line and indenting the rest. Words longer than a line are broken between
+/// graphemes.
+fn wrap(
+    prefix: Vec<StyledGrapheme>,
+    graphemes: &[StyledGrapheme],
+    width: u16,
+    widths: &Widths,
+    indent: u16,
+) -> Vec<Vec<StyledGrapheme>> {
+    let indent = indent.min(width.saturating_sub(1));
+
+    let mut line_start: u16 = prefix.iter().map(|(g, _)| widths.grapheme_width(g)).sum();
+    let mut line_width = line_start;
+    let mut lines = vec![prefix];
+    for word in graphemes.split(|(grapheme, _)| grapheme == " ") {
+        if word.is_empty() {
+            continue;
+        }
+
+        let word_width: u16 = word.iter().map(|(g, _)| widths.grapheme_width(g)).sum();
+        let has_content = line_width > line_start;
+        if has_content && line_width + 1 + word_width > width {
+            lines.push(indentation(indent));
+            (line_start, line_width) = (indent, indent);
+        } else if has_content {
+            lines
+                .last_mut()
+                .unwrap()
+                .push((" ".to_string(), Style::new()));
+            line_width += 1;
+        }
+
+        for (grapheme, style) in word {
+            let grapheme_width = widths.grapheme_width(grapheme);
+            if line_width + grapheme_width > width && line_width > line_start {
+                lines.push(indentation(indent));
+                (line_start, line_width) = (indent, indent);
+            }
+
+            lines.last_mut().unwrap().push((grapheme.clone(), *style));
+            line_width += grapheme_width;
+        }
+    }
+
+    lines
+}
+
+/// A line's leading spaces.
+fn indentation(indent: u16) -> Vec<StyledGrapheme> {
+    vec![(" ".to_string(), Style::new()); indent as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{text::Widths, Color, Style, Theme};
+
+    use super::{parse_heading, parse_inline, parse_list_item, wrap, MarkdownStyles};
+
+    fn text(graphemes: &[(String, Style)]) -> String {
+        graphemes.iter().map(|(g, _)| g.as_str()).collect()
+    }
+
+    #[test]
+    fn markdown_parse_blocks() {
+        assert_eq!(Some((2, "Usage")), parse_heading("## Usage ##"));
+        assert_eq!(None, parse_heading("#hashtag"));
+        assert_eq!(None, parse_heading("####### Seven"));
+
+        assert_eq!(
+            Some(("• ".to_string(), "item")),
+            parse_list_item("  - item")
+        );
+        assert_eq!(
+            Some(("12. ".to_string(), "item")),
+            parse_list_item("12) item")
+        );
+        assert_eq!(None, parse_list_item("-item"));
+        assert_eq!(None, parse_list_item("3.14 is pi"));
+    }
+
+    #[test]
+    fn markdown_parse_inline() {
+        let styles = MarkdownStyles::from_theme(&Theme::new());
+
+        let graphemes = parse_inline(
+            "a **b** _c_ `*d*` 2 * 3 \\*e snake_case",
+            Style::new(),
+            &styles,
+        );
+        assert_eq!("a b c *d* 2 * 3 *e snake_case", text(&graphemes));
+
+        let style_of = |index: usize| graphemes[index].1;
+        assert_eq!(Style::new(), style_of(0));
+        assert_eq!(Style::new().set_bold(true), style_of(2));
+        assert_eq!(Style::new().set_italic(true), style_of(4));
+        assert_eq!(Color::Yellow.as_style(), style_of(6));
+        assert_eq!(Style::new(), style_of(12));
+    }
+
+    #[test]
+    fn markdown_wrap() {
+        let styles = MarkdownStyles::from_theme(&Theme::new());
+        let graphemes = parse_inline("one two three fourteen", Style::new(), &styles);
+        let prefix = vec![
+            ("•".to_string(), Style::new()),
+            (" ".to_string(), Style::new()),
+        ];
+
+        let lines: Vec<String> = wrap(prefix, &graphemes, 9, &Widths::default(), 2)
+            .iter()
+            .map(|line| text(line))
+            .collect();
+        assert_eq!(vec!["• one two", "  three", "  fourtee", "  n"], lines);
+    }
+}
@@ -0,0 +1,79 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{Interface, Rect, State};
+
+impl<'a> Interface<'a> {
+    /// Render content over a region of the interface, such as a dialog, returning a guard which
+    /// restores the region's previous content when dropped. The guard may be used as the interface
+    /// in the meantime, e.g. to update and apply the modal's content. Only cells within the
+    /// region are restored, so content drawn outside it, such as a panel's shadow, should be
+    /// included in the region. Changes are staged until applied.
+    ///
+    /// # Examples
+    /// ```
+    /// # use tty_interface::{Error, test::VirtualDevice};
+    /// # let mut device = VirtualDevice::new();
+    /// use tty_interface::{Interface, PanelStyle, Position, Rect, Vector, pos};
+    ///
+    /// let mut interface = Interface::new_alternate(&mut device)?;
+    /// interface.set(pos!(0, 0), "Document text");
+    /// interface.apply()?;
+    ///
+    /// let rect = Rect::new(pos!(2, 0), Vector::new(12, 3));
+    /// let mut modal = interface.modal(rect, |interface| {
+    ///     interface.draw_panel(rect, &PanelStyle::new().set_title("Saving"));
+    ///     interface.set(pos!(3, 1), "Please wait");
+    /// });
+    /// modal.apply()?;
+    ///
+    /// // Restores the document text
+    /// drop(modal);
+    /// interface.apply()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn modal(&mut self, rect: Rect, render: impl FnOnce(&mut Interface<'a>)) -> Modal<'_, 'a> {
+        let underlying = self.staged_region(rect);
+        render(self);
+
+        Modal {
+            interface: self,
+            rect,
+            underlying,
+        }
+    }
+}
+
+/// Content rendered over a region of an interface by [Interface::modal], which restores the
+/// region's previous content when dropped. Dereferences to the interface.
+pub struct Modal<'i, 'a> {
+    interface: &'i mut Interface<'a>,
+    rect: Rect,
+    underlying: State,
+}
+
+impl Modal<'_, '_> {
+    /// The region the modal's content covers.
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+}
+
+impl<'a> Deref for Modal<'_, 'a> {
+    type Target = Interface<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.interface
+    }
+}
+
+impl DerefMut for Modal<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.interface
+    }
+}
+
+impl Drop for Modal<'_, '_> {
+    fn drop(&mut self) {
+        self.interface.restore_region(self.rect, &self.underlying);
+    }
+}
@@ -0,0 +1,12 @@
+/// How an interface is rendered within the terminal.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Mode {
+    /// Rendered on the terminal's alternate screen, which is discarded when the interface exits.
+    Alternate,
+    /// Rendered relative to the cursor's position in the terminal's buffer, leaving the contents
+    /// in the buffer's history when the interface exits.
+    Relative,
+    /// Printed as plain lines appended to the output without cursor control, each time they're
+    /// applied with changes, for output which isn't a terminal such as a file or pipe.
+    LinePrinter,
+}
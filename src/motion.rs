@@ -0,0 +1,177 @@
+use crate::{Cell, DeviceCommand, Position, Result};
+
+/// The widest gap between the cursor and its destination which may be reprinted rather than moved
+/// across. Wider gaps cost more than any cursor movement sequence.
+pub(crate) const MAX_REPRINTED_COLUMNS: u16 = 8;
+
+/// A cursor move from one interface position to another, along with what's known about the
+/// terminal to determine which sequences may perform it.
+pub(crate) struct CursorMove {
+    from: Position,
+    to: Position,
+    is_absolute: bool,
+    column_offset: u16,
+    gap: Option<Vec<Cell>>,
+}
+
+impl CursorMove {
+    /// Create a move between the positions using relative moves, line feeds, and carriage returns.
+    /// A carriage return's destination is offset from the interface's first column by the number of
+    /// columns preceding the interface.
+    pub(crate) fn new(from: Position, to: Position, column_offset: u16) -> CursorMove {
+        CursorMove {
+            from,
+            to,
+            is_absolute: false,
+            column_offset,
+            gap: None,
+        }
+    }
+
+    /// Permit moving to the absolute position, i.e. if the interface's positions are the screen's.
+    pub(crate) fn set_absolute(self, is_absolute: bool) -> CursorMove {
+        CursorMove {
+            is_absolute,
+            ..self
+        }
+    }
+
+    /// Permit reprinting the cells displayed between the positions, if they're on the same line,
+    /// instead of moving across them.
+    pub(crate) fn set_gap(self, gap: Option<Vec<Cell>>) -> CursorMove {
+        CursorMove { gap, ..self }
+    }
+
+    /// Encode the candidate sequences for this move, returning the one with the fewest bytes. Ties
+    /// prefer relative moves, then carriage returns, reprinting, and finally absolute moves.
+    pub(crate) fn encode(
+        &self,
+        mut encode: impl FnMut(&DeviceCommand, &mut Vec<u8>) -> Result<()>,
+    ) -> Result<Vec<u8>> {
+        let (from, to) = (self.from, self.to);
+
+        let line_feeds = "\n".repeat(to.y().saturating_sub(from.y()).into());
+        let mut vertical = Vec::new();
+        if to.y() > from.y() {
+            vertical.push(DeviceCommand::Print(&line_feeds));
+        } else if to.y() < from.y() {
+            vertical.push(DeviceCommand::MoveUp(from.y() - to.y()));
+        }
+
+        let mut candidates = Vec::new();
+
+        let mut relative = Vec::new();
+        if to.x() > from.x() {
+            relative.push(DeviceCommand::MoveRight(to.x() - from.x()));
+        } else if to.x() < from.x() {
+            relative.push(DeviceCommand::MoveLeft(from.x() - to.x()));
+        }
+        relative.extend(vertical.iter().cloned());
+        candidates.push(relative);
+
+        if to.x() != from.x() {
+            let mut carriage_return = vec![DeviceCommand::Print("\r")];
+            let column = self.column_offset.saturating_add(to.x());
+            if column > 0 {
+                carriage_return.push(DeviceCommand::MoveRight(column));
+            }
+            carriage_return.extend(vertical.iter().cloned());
+            candidates.push(carriage_return);
+        }
+
+        if let Some(gap) = &self.gap {
+            if from.y() == to.y() && from.x() < to.x() {
+                candidates.push(
+                    gap.iter()
+                        .map(|cell| match cell.rendered_style() {
+                            Some(style) => DeviceCommand::PrintStyled(cell.grapheme(), style),
+                            None => DeviceCommand::Print(cell.grapheme()),
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        if self.is_absolute {
+            candidates.push(vec![DeviceCommand::MoveTo(to)]);
+        }
+
+        let mut cheapest: Option<Vec<u8>> = None;
+        for candidate in candidates {
+            let mut buffer = Vec::new();
+            for command in &candidate {
+                encode(command, &mut buffer)?;
+            }
+
+            if cheapest
+                .as_ref()
+                .is_none_or(|cheapest| buffer.len() < cheapest.len())
+            {
+                cheapest = Some(buffer);
+            }
+        }
+
+        Ok(cheapest.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CursorMove;
+    use crate::{encode_ansi, pos, Cell, Color, Position};
+
+    /// Encode the move as ANSI escape sequences.
+    fn encode(cursor_move: CursorMove) -> String {
+        let buffer = cursor_move.encode(encode_ansi).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn motion_carriage_return_to_first_column() {
+        let cursor_move = CursorMove::new(pos!(5, 0), pos!(0, 1), 0);
+        assert_eq!("\r\n", encode(cursor_move));
+
+        let cursor_move = CursorMove::new(pos!(12, 3), pos!(0, 3), 0).set_absolute(true);
+        assert_eq!("\r", encode(cursor_move));
+    }
+
+    #[test]
+    fn motion_relative_when_offset() {
+        // Returning to the interface's first column requires moving past the preceding columns
+        let cursor_move = CursorMove::new(pos!(5, 0), pos!(0, 0), 3);
+        assert_eq!("\x1B[5D", encode(cursor_move));
+
+        let cursor_move = CursorMove::new(pos!(500, 0), pos!(0, 2), 3);
+        assert_eq!("\r\x1B[3C\n\n", encode(cursor_move));
+    }
+
+    #[test]
+    fn motion_absolute_when_distant() {
+        let cursor_move = CursorMove::new(pos!(0, 0), pos!(40, 10), 0);
+        assert_eq!(format!("\x1B[40C{}", "\n".repeat(10)), encode(cursor_move));
+
+        let cursor_move = CursorMove::new(pos!(0, 0), pos!(40, 10), 0).set_absolute(true);
+        assert_eq!("\x1B[11;41H", encode(cursor_move));
+
+        let cursor_move = CursorMove::new(pos!(30, 20), pos!(40, 0), 0).set_absolute(true);
+        assert_eq!("\x1B[1;41H", encode(cursor_move));
+    }
+
+    #[test]
+    fn motion_reprints_short_gaps() {
+        let gap = vec![Cell::new("a", None), Cell::new(" ", None)];
+        let cursor_move = CursorMove::new(pos!(1, 0), pos!(3, 0), 0).set_gap(Some(gap));
+        assert_eq!("a ", encode(cursor_move));
+
+        // Styled cells cost more to reprint than moving across them
+        let gap = vec![Cell::new("a", Some(Color::Red.as_style()))];
+        let cursor_move = CursorMove::new(pos!(1, 0), pos!(2, 0), 0).set_gap(Some(gap));
+        assert_eq!("\x1B[1C", encode(cursor_move));
+    }
+
+    #[test]
+    fn motion_stationary() {
+        let cursor_move = CursorMove::new(pos!(4, 2), pos!(4, 2), 0).set_absolute(true);
+        assert_eq!("", encode(cursor_move));
+    }
+}
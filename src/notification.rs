@@ -0,0 +1,222 @@
+use std::time::Instant;
+
+use crate::{
+    pos,
+    text::{normalize, split_graphemes, Widths},
+    Cell, Color, Position, Style, Vector,
+};
+
+/// The severity of a notification, which determines its style.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    /// The style notifications of this level are rendered with.
+    pub fn style(self) -> Style {
+        match self {
+            NotificationLevel::Info => Color::White.as_style().set_background(Color::DarkBlue),
+            NotificationLevel::Success => Color::Black.as_style().set_background(Color::Green),
+            NotificationLevel::Warning => Color::Black.as_style().set_background(Color::Yellow),
+            NotificationLevel::Error => Color::White
+                .as_style()
+                .set_background(Color::DarkRed)
+                .set_bold(true),
+        }
+    }
+}
+
+/// A corner of the screen, where notifications are stacked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A transient message shown until it expires.
+struct Notification {
+    text: String,
+    level: NotificationLevel,
+    expires_at: Instant,
+}
+
+/// The notifications shown above an interface's content, stacked outward from a corner in the
+/// order they were posted.
+pub(crate) struct Notifications {
+    notifications: Vec<Notification>,
+    corner: Corner,
+}
+
+impl Notifications {
+    /// Create an empty stack of notifications in the bottom-right corner.
+    pub(crate) fn new() -> Notifications {
+        Notifications {
+            notifications: Vec::new(),
+            corner: Corner::BottomRight,
+        }
+    }
+
+    /// Add a notification to the stack, shown until the specified instant.
+    pub(crate) fn push(&mut self, text: &str, level: NotificationLevel, expires_at: Instant) {
+        self.notifications.push(Notification {
+            text: normalize(text)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            level,
+            expires_at,
+        });
+    }
+
+    /// Remove all notifications.
+    pub(crate) fn clear(&mut self) {
+        self.notifications.clear();
+    }
+
+    /// The number of notifications shown.
+    pub(crate) fn len(&self) -> usize {
+        self.notifications.len()
+    }
+
+    /// The corner notifications are stacked in.
+    pub(crate) fn corner(&self) -> Corner {
+        self.corner
+    }
+
+    /// Update the corner notifications are stacked in.
+    pub(crate) fn set_corner(&mut self, corner: Corner) {
+        self.corner = corner;
+    }
+
+    /// Whether any notification has expired as of the specified instant.
+    pub(crate) fn has_expired(&self, now: Instant) -> bool {
+        self.notifications
+            .iter()
+            .any(|notification| notification.expires_at <= now)
+    }
+
+    /// Remove notifications which have expired as of the specified instant, returning whether any
+    /// were removed.
+    pub(crate) fn expire(&mut self, now: Instant) -> bool {
+        let count = self.notifications.len();
+        self.notifications
+            .retain(|notification| notification.expires_at > now);
+        self.notifications.len() != count
+    }
+
+    /// The notifications' cells on a screen of the specified size whose top-left corner is at the
+    /// specified position. Notifications which don't fit on the screen are omitted.
+    pub(crate) fn cells(
+        &self,
+        origin: Position,
+        size: Vector,
+        widths: &Widths,
+    ) -> Vec<(Position, Cell)> {
+        let mut cells = Vec::new();
+
+        let lines = self.notifications.iter().zip(0..size.y());
+        for (notification, offset) in lines {
+            let text = format!(" {} ", notification.text);
+            let line = match self.corner {
+                Corner::TopLeft | Corner::TopRight => offset,
+                Corner::BottomLeft | Corner::BottomRight => size.y() - 1 - offset,
+            };
+            let mut column = match self.corner {
+                Corner::TopLeft | Corner::BottomLeft => 0,
+                Corner::TopRight | Corner::BottomRight => {
+                    size.x().saturating_sub(widths.text_width(&text))
+                }
+            };
+
+            let style = Some(notification.level.style());
+            for grapheme in split_graphemes(&text) {
+                let width = widths.grapheme_width(grapheme);
+                if column + width > size.x() {
+                    break;
+                }
+
+                let position = pos!(origin.x() + column, origin.y() + line);
+                cells.push((position, Cell::new(grapheme, style)));
+                column += width;
+            }
+        }
+
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Corner, NotificationLevel, Notifications};
+    use crate::{pos, text::Widths, Position, Vector};
+
+    /// The text of the cells on each line, from the first line with a cell.
+    fn lines(notifications: &Notifications, origin: Position, size: Vector) -> Vec<String> {
+        let mut lines: Vec<(u16, String)> = Vec::new();
+        for (position, cell) in notifications.cells(origin, size, &Widths::default()) {
+            match lines.last_mut() {
+                Some((line, text)) if *line == position.y() => text.push_str(cell.grapheme()),
+                _ => lines.push((position.y(), cell.grapheme().to_string())),
+            }
+        }
+
+        lines.into_iter().map(|(_, text)| text).collect()
+    }
+
+    #[test]
+    fn notification_stacking() {
+        let now = Instant::now();
+        let mut notifications = Notifications::new();
+        notifications.push("Saved", NotificationLevel::Success, now);
+        notifications.push("Disk almost full", NotificationLevel::Warning, now);
+
+        let cells = notifications.cells(pos!(0, 0), Vector::new(20, 5), &Widths::default());
+        assert_eq!(pos!(13, 4), cells[0].0);
+        assert_eq!(pos!(2, 3), cells[7].0);
+
+        notifications.set_corner(Corner::TopLeft);
+        let cells = notifications.cells(pos!(0, 10), Vector::new(20, 5), &Widths::default());
+        assert_eq!(pos!(0, 10), cells[0].0);
+        assert_eq!(
+            vec![" Saved ", " Disk almost full "],
+            lines(&notifications, pos!(0, 10), Vector::new(20, 5))
+        );
+
+        // Notifications are truncated and omitted if they don't fit
+        assert_eq!(
+            vec![" Save"],
+            lines(&notifications, pos!(0, 0), Vector::new(5, 1))
+        );
+    }
+
+    #[test]
+    fn notification_expiry() {
+        let now = Instant::now();
+        let mut notifications = Notifications::new();
+        notifications.push(
+            "First",
+            NotificationLevel::Info,
+            now + Duration::from_secs(1),
+        );
+        notifications.push(
+            "Second",
+            NotificationLevel::Error,
+            now + Duration::from_secs(2),
+        );
+
+        assert!(!notifications.has_expired(now));
+        assert!(!notifications.expire(now));
+        assert!(notifications.has_expired(now + Duration::from_secs(1)));
+        assert!(notifications.expire(now + Duration::from_secs(1)));
+        assert_eq!(1, notifications.len());
+    }
+}
@@ -0,0 +1,11 @@
+/// How staged text extending beyond the terminal's last column is handled.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Overflow {
+    /// Continued from the first column of the following line.
+    Wrap,
+    /// Discarded, leaving the text truncated at the last column.
+    Clip,
+    /// Kept beyond the last column on the same line, to be displayed by panning the interface
+    /// with [crate::Interface::pan_to].
+    Extend,
+}
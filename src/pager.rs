@@ -0,0 +1,424 @@
+use std::{borrow::Cow, ops::Range};
+
+use crate::{
+    pos,
+    text::{split_graphemes, Widths},
+    Color, Event, Interface, KeyCode, MouseEventKind, Position, Rect, Style,
+};
+
+/// The lines scrolled by each turn of the mouse wheel.
+const WHEEL_LINES: i64 = 3;
+
+/// A source of a pager's lines, such as a file read on demand. Lines are only requested as
+/// they're displayed or searched.
+pub trait LineProvider {
+    /// The number of lines.
+    fn line_count(&self) -> usize;
+
+    /// The line at the specified index, less than the line count, without its line ending.
+    fn line(&self, index: usize) -> Cow<'_, str>;
+}
+
+impl LineProvider for Vec<String> {
+    fn line_count(&self) -> usize {
+        self.len()
+    }
+
+    fn line(&self, index: usize) -> Cow<'_, str> {
+        Cow::Borrowed(&self[index])
+    }
+}
+
+/// A rendered row's text and the byte ranges of its search matches.
+type PagerRow = (String, Vec<Range<usize>>);
+
+/// A scrollable view of many lines of text, like `less`, with a status line showing the visible
+/// lines' range and how far through the text they are. Matches of a search are highlighted, and
+/// re-rendering only stages the rows which changed.
+///
+/// Keys scroll by line (up, down, `j`, `k`, enter), by page (page up, page down, `b`, space), and
+/// to either end (home, end, `g`, `G`), and `n` and `N` move to the next and previous matches.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Interface, Pager, Position, Rect, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let log: String = (1..=500).map(|n| format!("request {} served\n", n)).collect();
+/// let mut pager = Pager::new(&log);
+/// pager.search("request 250 ");
+///
+/// pager.render(&mut interface, Rect::new(pos!(0, 0), Vector::new(40, 10)));
+/// interface.apply()?;
+/// assert_eq!(249, pager.top_line());
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Pager {
+    provider: Box<dyn LineProvider>,
+    top_line: usize,
+    page_height: usize,
+    query: Option<String>,
+    match_style: Style,
+    status_style: Style,
+    rendered: Option<(Rect, Vec<PagerRow>)>,
+}
+
+impl Pager {
+    /// Create a pager showing the lines of the text.
+    pub fn new(text: &str) -> Pager {
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        Pager::with_provider(Box::new(lines))
+    }
+
+    /// Create a pager showing the provider's lines. Matches are highlighted with a yellow
+    /// background and the status line is bold.
+    pub fn with_provider(provider: Box<dyn LineProvider>) -> Pager {
+        Pager {
+            provider,
+            top_line: 0,
+            page_height: 1,
+            query: None,
+            match_style: Style::new()
+                .set_foreground(Color::Black)
+                .set_background(Color::Yellow),
+            status_style: Style::new().set_bold(true),
+            rendered: None,
+        }
+    }
+
+    /// Create a new pager highlighting search matches with the specified style.
+    pub fn set_match_style(self, match_style: Style) -> Pager {
+        Pager {
+            match_style,
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// Create a new pager with the specified style for its status line.
+    pub fn set_status_style(self, status_style: Style) -> Pager {
+        Pager {
+            status_style,
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// The index of the first visible line.
+    pub fn top_line(&self) -> usize {
+        self.top_line
+    }
+
+    /// Scroll so the line at the specified index is the first visible, or as near as possible.
+    pub fn scroll_to(&mut self, line: usize) {
+        self.top_line = line.min(self.last_top_line());
+    }
+
+    /// Scroll by the specified number of lines, down if positive or up if negative.
+    pub fn scroll_by(&mut self, delta: i64) {
+        let line = (self.top_line as i64).saturating_add(delta).max(0);
+        self.scroll_to(line as usize);
+    }
+
+    /// How far through the text the last visible line is, as a percentage.
+    pub fn percentage(&self) -> u8 {
+        let count = self.provider.line_count();
+        if count == 0 {
+            return 100;
+        }
+
+        let bottom = (self.top_line + self.page_height).min(count);
+        (bottom * 100 / count) as u8
+    }
+
+    /// The text being searched for, if any.
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Highlight matches of the text and scroll to the first line containing one at or after the
+    /// first visible line, returning whether one was found.
+    pub fn search(&mut self, query: &str) -> bool {
+        self.query = match query.is_empty() {
+            true => None,
+            false => Some(query.to_string()),
+        };
+
+        self.find_match(self.top_line, true)
+    }
+
+    /// Stop highlighting search matches.
+    pub fn clear_search(&mut self) {
+        self.query = None;
+    }
+
+    /// Scroll to the next line below the first visible line containing a match, returning
+    /// whether one was found.
+    pub fn next_match(&mut self) -> bool {
+        self.find_match(self.top_line + 1, true)
+    }
+
+    /// Scroll to the previous line above the first visible line containing a match, returning
+    /// whether one was found.
+    pub fn previous_match(&mut self) -> bool {
+        match self.top_line.checked_sub(1) {
+            Some(line) => self.find_match(line, false),
+            None => false,
+        }
+    }
+
+    /// Handle an input event, scrolling for navigation keys and the mouse wheel. Returns whether
+    /// the event was consumed.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let page = self.page_height as i64;
+
+        match event {
+            Event::Key(event) => match event.code() {
+                KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+                KeyCode::Down | KeyCode::Char('j') | KeyCode::Enter => self.scroll_by(1),
+                KeyCode::PageUp | KeyCode::Char('b') => self.scroll_by(-page),
+                KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(page),
+                KeyCode::Home | KeyCode::Char('g') => self.scroll_to(0),
+                KeyCode::End | KeyCode::Char('G') => self.scroll_to(usize::MAX),
+                KeyCode::Char('n') => _ = self.next_match(),
+                KeyCode::Char('N') => _ = self.previous_match(),
+                _ => return false,
+            },
+            Event::Mouse(event) => match event.kind() {
+                MouseEventKind::ScrollUp => self.scroll_by(-WHEEL_LINES),
+                MouseEventKind::ScrollDown => self.scroll_by(WHEEL_LINES),
+                _ => return false,
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Stage the visible lines within the rectangle above a status line on its last line. Lines
+    /// are truncated to its width. Only rows which changed since the pager was last rendered are
+    /// staged, returning whether any were.
+    pub fn render(&mut self, interface: &mut Interface, rect: Rect) -> bool {
+        let (width, height) = (rect.size().x(), rect.size().y() as usize);
+        if height == 0 {
+            return false;
+        }
+
+        self.page_height = (height - 1).max(1);
+        self.scroll_to(self.top_line);
+
+        let tab_width = interface.tab_width();
+        let widths = interface.widths().clone();
+        let mut rows = Vec::new();
+        for index in self.top_line..self.top_line + height - 1 {
+            let row = match index < self.provider.line_count() {
+                true => {
+                    let source = self.provider.line(index);
+                    let line = expand_tabs(&source, tab_width, &widths);
+                    let matches = self.matches(&line);
+                    let (text, visible) = fit_width(&line, width, &widths);
+                    let matches = matches
+                        .into_iter()
+                        .filter(|range| range.start < visible)
+                        .map(|range| range.start..range.end.min(visible))
+                        .collect();
+                    (text, matches)
+                }
+                false => (fit_width("~", width, &widths).0, Vec::new()),
+            };
+            rows.push(row);
+        }
+        rows.push((fit_width(&self.status(), width, &widths).0, Vec::new()));
+
+        let previous = match self.rendered.take() {
+            Some((previous_rect, previous)) if previous_rect == rect => previous,
+            _ => Vec::new(),
+        };
+
+        let mut is_staged = false;
+        for (line, row) in rows.iter().enumerate() {
+            if previous.get(line) == Some(row) {
+                continue;
+            }
+
+            let position = pos!(rect.origin().x(), rect.origin().y() + line as u16);
+            let (text, matches) = row;
+            let spans: Vec<(Range<usize>, Style)> = match line == height - 1 {
+                true => vec![(0..text.len(), self.status_style)],
+                false => matches
+                    .iter()
+                    .map(|range| (range.clone(), self.match_style))
+                    .collect(),
+            };
+            interface.set_highlighted(position, text, &spans);
+            is_staged = true;
+        }
+
+        self.rendered = Some((rect, rows));
+        is_staged
+    }
+
+    /// The greatest first visible line, showing a full page of the last lines.
+    fn last_top_line(&self) -> usize {
+        self.provider.line_count().saturating_sub(self.page_height)
+    }
+
+    /// Scroll to the first line from the specified index, searching forward or backward, which
+    /// contains a match, returning whether one was found.
+    fn find_match(&mut self, from: usize, is_forward: bool) -> bool {
+        let Some(query) = &self.query else {
+            return false;
+        };
+
+        let count = self.provider.line_count();
+        let found = match is_forward {
+            true => (from..count).find(|index| self.provider.line(*index).contains(query.as_str())),
+            false => (0..=from.min(count.saturating_sub(1)))
+                .rev()
+                .find(|index| self.provider.line(*index).contains(query.as_str())),
+        };
+
+        match found {
+            Some(line) => {
+                self.scroll_to(line);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The byte ranges of the search's matches in the line.
+    fn matches(&self, line: &str) -> Vec<Range<usize>> {
+        match &self.query {
+            Some(query) => line
+                .match_indices(query.as_str())
+                .map(|(start, matched)| start..start + matched.len())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The status line's text: the visible lines' range, percentage, and search query.
+    fn status(&self) -> String {
+        let count = self.provider.line_count();
+        let mut status = match count {
+            0 => "(empty)".to_string(),
+            _ => {
+                let bottom = (self.top_line + self.page_height).min(count);
+                format!(
+                    "lines {}-{} of {} ({}%)",
+                    self.top_line + 1,
+                    bottom,
+                    count,
+                    self.percentage()
+                )
+            }
+        };
+
+        if let Some(query) = &self.query {
+            status.push_str(&format!("  /{}", query));
+        }
+
+        status
+    }
+}
+
+/// The line with its tabs replaced by spaces to the next tab stop, measured with the widths.
+fn expand_tabs<'a>(line: &'a str, tab_width: u16, widths: &Widths) -> Cow<'a, str> {
+    if !line.contains('\t') {
+        return Cow::Borrowed(line);
+    }
+
+    let mut expanded = String::new();
+    let mut column = 0;
+    for grapheme in split_graphemes(line) {
+        if grapheme == "\t" {
+            let stop = match tab_width {
+                0 => 0,
+                _ => tab_width - column % tab_width,
+            };
+            expanded.push_str(&" ".repeat(stop as usize));
+            column += stop;
+        } else {
+            expanded.push_str(grapheme);
+            column += widths.grapheme_width(grapheme);
+        }
+    }
+
+    Cow::Owned(expanded)
+}
+
+/// The text truncated or padded with spaces to the width, measured with the widths, and the
+/// length of the text kept.
+fn fit_width(text: &str, width: u16, widths: &Widths) -> (String, usize) {
+    let mut used = 0;
+    let mut fitted = String::new();
+    for grapheme in split_graphemes(text) {
+        let grapheme_width = widths.grapheme_width(grapheme);
+        if used + grapheme_width > width {
+            break;
+        }
+
+        fitted.push_str(grapheme);
+        used += grapheme_width;
+    }
+
+    let kept = fitted.len();
+    fitted.push_str(&" ".repeat((width - used) as usize));
+    (fitted, kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::Widths;
+
+    use super::{expand_tabs, Pager};
+
+    fn pager() -> Pager {
+        let text: String = (0..20).map(|n| format!("line {}\n", n)).collect();
+        let mut pager = Pager::new(&text);
+        pager.page_height = 5;
+        pager
+    }
+
+    #[test]
+    fn pager_scrolling() {
+        let mut pager = pager();
+        assert_eq!(25, pager.percentage());
+
+        pager.scroll_by(-3);
+        assert_eq!(0, pager.top_line());
+        pager.scroll_by(7);
+        assert_eq!(7, pager.top_line());
+        pager.scroll_to(100);
+        assert_eq!(15, pager.top_line());
+        assert_eq!(100, pager.percentage());
+    }
+
+    #[test]
+    fn pager_search() {
+        let mut pager = pager();
+        assert!(pager.search("1"));
+        assert_eq!(1, pager.top_line());
+        assert!(pager.next_match());
+        assert_eq!(10, pager.top_line());
+        assert!(pager.previous_match());
+        assert_eq!(1, pager.top_line());
+        assert!(!pager.previous_match());
+
+        assert!(!pager.search("missing"));
+        assert_eq!(1, pager.top_line());
+        pager.query = Some("ab".to_string());
+        assert_eq!(vec![0..2, 3..5], pager.matches("abcab"));
+    }
+
+    #[test]
+    fn pager_expand_tabs() {
+        assert_eq!("a   b", expand_tabs("a\tb", 4, &Widths::default()));
+        assert_eq!("abcd    e", expand_tabs("abcd\te", 4, &Widths::default()));
+        assert_eq!("ab", expand_tabs("a\tb", 0, &Widths::default()));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{pos, text::split_graphemes, Color, Position, Rect, State, Style};
+
+/// The lines drawn around a panel's edges.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Border {
+    /// No border; content may use the panel's edges.
+    None,
+    /// Thin lines with square corners, e.g. `┌─┐`.
+    Single,
+    /// Thin lines with rounded corners, e.g. `╭─╮`.
+    Rounded,
+    /// Double lines, e.g. `╔═╗`.
+    Double,
+    /// Thick lines, e.g. `┏━┓`.
+    Heavy,
+}
+
+impl Border {
+    /// The graphemes for the top-left, top-right, bottom-left, and bottom-right corners and the
+    /// horizontal and vertical edges, or `None` for no border.
+    fn graphemes(self) -> Option<[&'static str; 6]> {
+        match self {
+            Border::None => None,
+            Border::Single => Some(["┌", "┐", "└", "┘", "─", "│"]),
+            Border::Rounded => Some(["╭", "╮", "╰", "╯", "─", "│"]),
+            Border::Double => Some(["╔", "╗", "╚", "╝", "═", "║"]),
+            Border::Heavy => Some(["┏", "┓", "┗", "┛", "━", "┃"]),
+        }
+    }
+}
+
+/// How a panel drawn with [crate::Interface::draw_panel] is rendered: its fill, border, title, and
+/// shadow. Panels have a single-line border and no fill, title, or shadow by default.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{Border, Color, PanelStyle, Style};
+///
+/// let style = PanelStyle::new()
+///     .set_border(Border::Rounded)
+///     .set_fill(Style::new().set_background(Color::DarkBlue))
+///     .set_title("Confirm")
+///     .set_shadow(true);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PanelStyle {
+    border: Border,
+    border_style: Option<Style>,
+    fill: Option<Style>,
+    title: Option<String>,
+    title_style: Option<Style>,
+    shadow: bool,
+}
+
+impl Default for PanelStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PanelStyle {
+    /// Create a panel style with a single-line border and no fill, title, or shadow.
+    pub fn new() -> PanelStyle {
+        PanelStyle {
+            border: Border::Single,
+            border_style: None,
+            fill: None,
+            title: None,
+            title_style: None,
+            shadow: false,
+        }
+    }
+
+    /// Create a new panel style with the specified border.
+    pub fn set_border(self, border: Border) -> PanelStyle {
+        PanelStyle { border, ..self }
+    }
+
+    /// This panel style's border.
+    pub fn border(&self) -> Border {
+        self.border
+    }
+
+    /// Create a new panel style with the border styled as specified, rather than by the fill.
+    pub fn set_border_style(self, style: Style) -> PanelStyle {
+        PanelStyle {
+            border_style: Some(style),
+            ..self
+        }
+    }
+
+    /// This panel style's border styling, if specified.
+    pub fn border_style(&self) -> Option<Style> {
+        self.border_style
+    }
+
+    /// Create a new panel style with the panel's cells filled with the specified style, usually
+    /// with a background color.
+    pub fn set_fill(self, style: Style) -> PanelStyle {
+        PanelStyle {
+            fill: Some(style),
+            ..self
+        }
+    }
+
+    /// This panel style's fill styling, if specified.
+    pub fn fill(&self) -> Option<Style> {
+        self.fill
+    }
+
+    /// Create a new panel style with the specified title, shown in the top border.
+    pub fn set_title(self, title: &str) -> PanelStyle {
+        PanelStyle {
+            title: Some(title.to_string()),
+            ..self
+        }
+    }
+
+    /// This panel style's title, if specified.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Create a new panel style with the title styled as specified, rather than by the border.
+    pub fn set_title_style(self, style: Style) -> PanelStyle {
+        PanelStyle {
+            title_style: Some(style),
+            ..self
+        }
+    }
+
+    /// This panel style's title styling, if specified.
+    pub fn title_style(&self) -> Option<Style> {
+        self.title_style
+    }
+
+    /// Create a new panel style with the specified shadow value. A shadow darkens the cells one
+    /// line below and one column right of the panel.
+    pub fn set_shadow(self, shadow: bool) -> PanelStyle {
+        PanelStyle { shadow, ..self }
+    }
+
+    /// Whether this panel style casts a shadow.
+    pub fn has_shadow(&self) -> bool {
+        self.shadow
+    }
+}
+
+/// Draws a panel occupying the rectangle, along with its shadow, into the state.
+pub(crate) fn draw_panel(state: &mut State, rect: Rect, style: &PanelStyle) {
+    let (left, top) = (rect.origin().x(), rect.origin().y());
+    let (width, height) = (rect.size().x(), rect.size().y());
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let (right, bottom) = (
+        left.saturating_add(width - 1),
+        top.saturating_add(height - 1),
+    );
+    let set =
+        |state: &mut State, position: Position, grapheme: &str, style: Option<Style>| match style {
+            Some(style) => state.set_styled_text(position, grapheme, style),
+            None => state.set_text(position, grapheme),
+        };
+
+    for y in top..=bottom {
+        for x in left..=right {
+            set(state, pos!(x, y), " ", style.fill);
+        }
+    }
+
+    let border_style = style.border_style.or(style.fill);
+    let (mut inner_left, mut inner_right) = (left, right);
+    if let Some([top_left, top_right, bottom_left, bottom_right, horizontal, vertical]) = style
+        .border
+        .graphemes()
+        .filter(|_| width >= 2 && height >= 2)
+    {
+        for x in left + 1..right {
+            set(state, pos!(x, top), horizontal, border_style);
+            set(state, pos!(x, bottom), horizontal, border_style);
+        }
+
+        for y in top + 1..bottom {
+            set(state, pos!(left, y), vertical, border_style);
+            set(state, pos!(right, y), vertical, border_style);
+        }
+
+        set(state, pos!(left, top), top_left, border_style);
+        set(state, pos!(right, top), top_right, border_style);
+        set(state, pos!(left, bottom), bottom_left, border_style);
+        set(state, pos!(right, bottom), bottom_right, border_style);
+
+        (inner_left, inner_right) = (left + 1, right - 1);
+    }
+
+    if let Some(title) = &style.title {
+        // Padded by a space either side, truncated to fit within the corners
+        let title_style = style.title_style.or(border_style);
+        let mut column = inner_left;
+        for grapheme in split_graphemes(&format!(" {} ", title)) {
+            let width = state.widths().grapheme_width(grapheme);
+            if column as u32 + width as u32 > inner_right as u32 + 1 {
+                break;
+            }
+
+            set(state, pos!(column, top), grapheme, title_style);
+            column += width;
+        }
+    }
+
+    if style.shadow && right < u16::MAX && bottom < u16::MAX {
+        let shadow_style = Style::new()
+            .set_foreground(Color::DarkGrey)
+            .set_background(Color::Black);
+
+        let below = (left + 1..=right + 1).map(|x| pos!(x, bottom + 1));
+        let beside = (top + 1..=bottom).map(|y| pos!(right + 1, y));
+        for position in below.chain(beside) {
+            let grapheme = match state.get_cell(position) {
+                Some(cell) => cell.grapheme().to_string(),
+                None => " ".to_string(),
+            };
+
+            state.set_styled_text(position, &grapheme, shadow_style);
+        }
+    }
+}
@@ -0,0 +1,419 @@
+//! Playback of asciinema cast v2 recordings, such as those written by
+//! [crate::Interface::record_to], through an interface's staging and applying.
+
+use std::{
+    io::{self, Read},
+    thread::sleep,
+    time::Duration,
+};
+
+use crate::{
+    pos, quantize::palette_color, Color, Interface, Position, Quantization, Rect, Result, State,
+    Style, Vector,
+};
+
+/// An event recorded in a cast.
+#[derive(Debug, Clone, PartialEq)]
+enum CastEvent {
+    Output(String),
+    Resize(Vector),
+}
+
+/// Replays a recorded terminal session into an interface, e.g. for demos or to compare a session's
+/// final screen against an expected one. The recording's output is interpreted by a virtual
+/// terminal and each resulting screen is staged and applied, so only changed cells are rendered.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Interface, Player};
+///
+/// let cast = r#"{"version": 2, "width": 80, "height": 24}
+/// [0.5, "o", "Hello, "]
+/// [1.0, "o", "\u001b[1mworld!\u001b[0m"]"#;
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+/// let mut player = Player::new(cast.as_bytes())?;
+///
+/// // Step through the first event, then play the rest without delays
+/// assert!(player.step(&mut interface)?);
+/// player.play(&mut interface, f32::INFINITY)?;
+/// assert_eq!(2, player.position());
+/// # Ok::<(), Error>(())
+/// ```
+pub struct Player {
+    events: Vec<(Duration, CastEvent)>,
+    terminal: vt100::Parser,
+    size: Vector,
+    position: usize,
+    elapsed: Duration,
+}
+
+impl Player {
+    /// Read a cast from the reader, e.g. a file. Fails if it isn't a valid asciinema cast v2.
+    pub fn new(mut cast: impl Read) -> Result<Player> {
+        let mut text = String::new();
+        cast.read_to_string(&mut text)?;
+
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header = lines.next().and_then(Json::parse);
+        let (width, height) = match header {
+            Some(Json::Object(fields)) => (field(&fields, "width"), field(&fields, "height")),
+            _ => (None, None),
+        };
+        let (Some(width), Some(height)) = (width, height) else {
+            return Err(invalid_cast("missing header").into());
+        };
+
+        let mut events = Vec::new();
+        for line in lines {
+            let Some(Json::Array(values)) = Json::parse(line) else {
+                return Err(invalid_cast("malformed event").into());
+            };
+
+            let event = match values.as_slice() {
+                [Json::Number(time), Json::String(code), Json::String(data)] if *time >= 0.0 => {
+                    let time = Duration::from_secs_f64(*time);
+                    match code.as_str() {
+                        "o" => Some((time, CastEvent::Output(data.clone()))),
+                        "r" => parse_size(data).map(|size| (time, CastEvent::Resize(size))),
+                        _ => None,
+                    }
+                }
+                _ => return Err(invalid_cast("malformed event").into()),
+            };
+
+            events.extend(event);
+        }
+
+        Ok(Player {
+            events,
+            terminal: vt100::Parser::new(height, width, 0),
+            size: Vector::new(width, height),
+            position: 0,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    /// The recorded terminal's size as of the last event played.
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    /// The number of output and resize events in the recording.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the recording has no output or resize events.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// The number of events played so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Whether every event has been played.
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.events.len()
+    }
+
+    /// Play the next event and apply the resulting screen to the interface, returning whether
+    /// there was an event to play.
+    pub fn step(&mut self, interface: &mut Interface) -> Result<bool> {
+        let Some((time, event)) = self.events.get(self.position).cloned() else {
+            return Ok(false);
+        };
+
+        let previous_size = self.size;
+        match event {
+            CastEvent::Output(output) => self.terminal.process(output.as_bytes()),
+            CastEvent::Resize(size) => {
+                self.terminal.set_size(size.y(), size.x());
+                self.size = size;
+            }
+        }
+
+        let bounds = Vector::new(
+            previous_size.x().max(self.size.x()),
+            previous_size.y().max(self.size.y()),
+        );
+        interface.restore_region(Rect::new(pos!(0, 0), bounds), &self.screen_state());
+        interface.apply()?;
+
+        self.position += 1;
+        self.elapsed = time;
+        Ok(true)
+    }
+
+    /// Play the remaining events, waiting between them for their recorded delays divided by the
+    /// speed, e.g. 2.0 plays twice as fast. Non-positive or infinite speeds play without delays.
+    pub fn play(&mut self, interface: &mut Interface, speed: f32) -> Result<()> {
+        while let Some((time, _)) = self.events.get(self.position) {
+            let delay = time.saturating_sub(self.elapsed);
+            if speed > 0.0 && speed.is_finite() {
+                sleep(delay.div_f32(speed));
+            }
+
+            self.step(interface)?;
+        }
+
+        Ok(())
+    }
+
+    /// The virtual terminal's screen as interface cells.
+    fn screen_state(&self) -> State {
+        let screen = self.terminal.screen();
+
+        let mut state = State::new();
+        for row in 0..self.size.y() {
+            for column in 0..self.size.x() {
+                let Some(cell) = screen.cell(row, column) else {
+                    continue;
+                };
+
+                if !cell.has_contents() || cell.is_wide_continuation() {
+                    continue;
+                }
+
+                let style = Style::new()
+                    .set_bold(cell.bold())
+                    .set_italic(cell.italic())
+                    .set_underline(cell.underline());
+                let style = match to_color(cell.fgcolor()) {
+                    Some(color) => style.set_foreground(color),
+                    None => style,
+                };
+                let style = match to_color(cell.bgcolor()) {
+                    Some(color) => style.set_background(color),
+                    None => style,
+                };
+
+                let position = pos!(column, row);
+                match style == Style::new() {
+                    true => state.set_text(position, &cell.contents()),
+                    false => state.set_styled_text(position, &cell.contents(), style),
+                }
+            }
+        }
+
+        state
+    }
+}
+
+/// The interface color for a virtual terminal color, or `None` for the default color.
+fn to_color(color: vt100::Color) -> Option<Color> {
+    match color {
+        vt100::Color::Default => None,
+        vt100::Color::Idx(index) => Some(palette_color(index)),
+        vt100::Color::Rgb(red, green, blue) => {
+            Some(Quantization::Nearest.to_color((red, green, blue)))
+        }
+    }
+}
+
+/// The value of a header's numeric field, if present and in range.
+fn field(fields: &[(String, Json)], name: &str) -> Option<u16> {
+    fields.iter().find_map(|(key, value)| match value {
+        Json::Number(number) if key == name && (1.0..=u16::MAX as f64).contains(number) => {
+            Some(*number as u16)
+        }
+        _ => None,
+    })
+}
+
+/// Parse a resize event's size, formatted like "80x24".
+fn parse_size(size: &str) -> Option<Vector> {
+    let (width, height) = size.split_once('x')?;
+    Some(Vector::new(width.parse().ok()?, height.parse().ok()?))
+}
+
+/// An error for an unreadable cast.
+fn invalid_cast(reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid cast: {}", reason),
+    )
+}
+
+/// A JSON value, as needed to read a cast's lines.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse a complete JSON value, or `None` if it's malformed.
+    fn parse(text: &str) -> Option<Json> {
+        let mut characters = text.chars().peekable();
+        let value = Json::parse_value(&mut characters)?;
+        skip_whitespace(&mut characters);
+        characters.peek().is_none().then_some(value)
+    }
+
+    /// Parse the value starting at the next non-whitespace character.
+    fn parse_value(characters: &mut Characters) -> Option<Json> {
+        skip_whitespace(characters);
+        match *characters.peek()? {
+            '"' => parse_string(characters).map(Json::String),
+            '[' => {
+                characters.next();
+                let mut values = Vec::new();
+                loop {
+                    skip_whitespace(characters);
+                    if values.is_empty() && characters.next_if_eq(&']').is_some() {
+                        return Some(Json::Array(values));
+                    }
+
+                    values.push(Json::parse_value(characters)?);
+                    skip_whitespace(characters);
+                    match characters.next()? {
+                        ',' => continue,
+                        ']' => return Some(Json::Array(values)),
+                        _ => return None,
+                    }
+                }
+            }
+            '{' => {
+                characters.next();
+                let mut fields = Vec::new();
+                loop {
+                    skip_whitespace(characters);
+                    if fields.is_empty() && characters.next_if_eq(&'}').is_some() {
+                        return Some(Json::Object(fields));
+                    }
+
+                    let key = parse_string(characters)?;
+                    skip_whitespace(characters);
+                    characters.next_if_eq(&':')?;
+                    fields.push((key, Json::parse_value(characters)?));
+                    skip_whitespace(characters);
+                    match characters.next()? {
+                        ',' => continue,
+                        '}' => return Some(Json::Object(fields)),
+                        _ => return None,
+                    }
+                }
+            }
+            _ => {
+                let mut literal = String::new();
+                while let Some(character) = characters
+                    .next_if(|character| !",]}".contains(*character) && !character.is_whitespace())
+                {
+                    literal.push(character);
+                }
+
+                match literal.as_str() {
+                    "null" => Some(Json::Null),
+                    "true" => Some(Json::Bool(true)),
+                    "false" => Some(Json::Bool(false)),
+                    number => number.parse().ok().map(Json::Number),
+                }
+            }
+        }
+    }
+}
+
+type Characters<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// Skip whitespace between JSON tokens.
+fn skip_whitespace(characters: &mut Characters) {
+    while characters
+        .next_if(|character| character.is_whitespace())
+        .is_some()
+    {}
+}
+
+/// Parse a quoted JSON string, decoding its escapes.
+fn parse_string(characters: &mut Characters) -> Option<String> {
+    characters.next_if_eq(&'"')?;
+
+    let mut string = String::new();
+    loop {
+        match characters.next()? {
+            '"' => return Some(string),
+            '\\' => match characters.next()? {
+                'n' => string.push('\n'),
+                'r' => string.push('\r'),
+                't' => string.push('\t'),
+                'b' => string.push('\u{8}'),
+                'f' => string.push('\u{c}'),
+                'u' => {
+                    let unit = parse_code_unit(characters)?;
+                    let code_point = match unit {
+                        0xD800..=0xDBFF => {
+                            characters.next_if_eq(&'\\')?;
+                            characters.next_if_eq(&'u')?;
+                            let low = parse_code_unit(characters)?;
+                            0x10000 + ((unit - 0xD800) << 10) + (low.checked_sub(0xDC00)? & 0x3FF)
+                        }
+                        unit => unit,
+                    };
+                    string.push(char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER));
+                }
+                escaped => string.push(escaped),
+            },
+            character => string.push(character),
+        }
+    }
+}
+
+/// Parse the four hexadecimal digits of a `\u` escape.
+fn parse_code_unit(characters: &mut Characters) -> Option<u32> {
+    let digits: String = (0..4).filter_map(|_| characters.next()).collect();
+    (digits.len() == 4).then_some(())?;
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Json, Player};
+
+    #[test]
+    fn player_parse_json() {
+        assert_eq!(
+            Some(Json::Array(vec![
+                Json::Number(1.5),
+                Json::String("o".to_string()),
+                Json::String("\x1B[1m\"é😀\"\r\n".to_string()),
+            ])),
+            Json::parse(r#"[1.5, "o", "\u001b[1m\"é😀\"\r\n"]"#)
+        );
+        assert_eq!(
+            Some(Json::Object(vec![
+                ("version".to_string(), Json::Number(2.0)),
+                ("env".to_string(), Json::Object(Vec::new())),
+                ("idle".to_string(), Json::Null),
+                ("tags".to_string(), Json::Array(vec![Json::Bool(true)])),
+            ])),
+            Json::parse(r#"{"version": 2, "env": {}, "idle": null, "tags": [true]}"#)
+        );
+
+        assert_eq!(None, Json::parse(r#"[1, "o""#));
+        assert_eq!(None, Json::parse(r#"[1] 2"#));
+        assert_eq!(None, Json::parse(r#"{"a" 1}"#));
+    }
+
+    #[test]
+    fn player_reads_casts() {
+        let cast = "{\"version\": 2, \"width\": 40, \"height\": 10}\n\n\
+                    [0.5, \"o\", \"a\"]\n\
+                    [0.75, \"i\", \"q\"]\n\
+                    [1.0, \"r\", \"20x5\"]\n";
+        let player = Player::new(cast.as_bytes()).unwrap();
+        assert_eq!(2, player.len());
+        assert_eq!(40, player.size().x());
+
+        assert!(Player::new("".as_bytes()).is_err());
+        assert!(Player::new("{\"version\": 2}".as_bytes()).is_err());
+        assert!(Player::new("{\"width\": 1, \"height\": 1}\n[\"o\"]".as_bytes()).is_err());
+    }
+}
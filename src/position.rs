@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+
+use crate::Vector;
 
 /// Create a new, immutable position (column, line);
 ///
@@ -18,7 +21,8 @@ macro_rules! pos {
 }
 
 /// A coordinate position in the terminal. May be absolute or relative to some buffer's origin.
-#[derive(Eq, PartialEq, Copy, Clone)]
+#[derive(Default, Eq, PartialEq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     x: u16,
     y: u16,
@@ -56,34 +60,139 @@ impl Position {
             y: self.y + diff_y,
         }
     }
+
+    /// This position translated by the specified amount, or `None` if it would lie beyond the
+    /// last column or line.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, pos};
+    ///
+    /// assert_eq!(Some(pos!(3, 5)), pos!(1, 2).checked_translate(2, 3));
+    /// assert_eq!(None, pos!(1, 2).checked_translate(u16::MAX, 0));
+    /// ```
+    pub fn checked_translate(&self, diff_x: u16, diff_y: u16) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add(diff_x)?,
+            y: self.y.checked_add(diff_y)?,
+        })
+    }
+
+    /// This position translated by the specified amount, stopping at the last column and line.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, pos};
+    ///
+    /// assert_eq!(pos!(u16::MAX, 5), pos!(1, 2).saturating_translate(u16::MAX, 3));
+    /// ```
+    pub fn saturating_translate(&self, diff_x: u16, diff_y: u16) -> Self {
+        Self {
+            x: self.x.saturating_add(diff_x),
+            y: self.y.saturating_add(diff_y),
+        }
+    }
+
+    /// This position translated by the specified amount, left or up if negative, stopping at the
+    /// first and last columns and lines.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, pos};
+    ///
+    /// assert_eq!(pos!(1, 5), pos!(3, 2).translate_signed(-2, 3));
+    /// assert_eq!(pos!(0, 0), pos!(3, 2).translate_signed(-5, -5));
+    /// ```
+    pub fn translate_signed(&self, diff_x: i32, diff_y: i32) -> Self {
+        let offset = |value: u16, diff: i32| {
+            (value as i32)
+                .saturating_add(diff)
+                .clamp(0, u16::MAX as i32) as u16
+        };
+
+        Self {
+            x: offset(self.x, diff_x),
+            y: offset(self.y, diff_y),
+        }
+    }
+
+    /// This position translated by the specified amount, left or up if negative, or `None` if it
+    /// would lie beyond the first or last column or line.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, pos};
+    ///
+    /// assert_eq!(Some(pos!(2, 2)), pos!(3, 2).checked_translate_signed(-1, 0));
+    /// assert_eq!(None, pos!(0, 2).checked_translate_signed(-1, 0));
+    /// ```
+    pub fn checked_translate_signed(&self, diff_x: i32, diff_y: i32) -> Option<Self> {
+        let offset = |value: u16, diff: i32| {
+            let value = (value as i32).checked_add(diff)?;
+            u16::try_from(value).ok()
+        };
+
+        Some(Self {
+            x: offset(self.x, diff_x)?,
+            y: offset(self.y, diff_y)?,
+        })
+    }
+}
+
+impl Add<Vector> for Position {
+    type Output = Position;
+
+    fn add(self, offset: Vector) -> Position {
+        self.translate(offset.x(), offset.y())
+    }
+}
+
+impl AddAssign<Vector> for Position {
+    fn add_assign(&mut self, offset: Vector) {
+        *self = *self + offset;
+    }
+}
+
+impl Sub<Vector> for Position {
+    type Output = Position;
+
+    fn sub(self, offset: Vector) -> Position {
+        Position::new(self.x - offset.x(), self.y - offset.y())
+    }
+}
+
+impl SubAssign<Vector> for Position {
+    fn sub_assign(&mut self, offset: Vector) {
+        *self = *self - offset;
+    }
 }
 
 impl PartialOrd for Position {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl Ord for Position {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         match self.y().cmp(&other.y()) {
-            std::cmp::Ordering::Equal => self.x().cmp(&other.x()),
+            core::cmp::Ordering::Equal => self.x().cmp(&other.x()),
             ordering => ordering,
         }
     }
 }
 
 impl Debug for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Position({}, {})", self.x(), self.y())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::cmp::Ordering;
+    use core::cmp::Ordering;
 
-    use crate::{pos, Position};
+    use crate::{Position, Vector};
 
     #[test]
     fn position_initialization() {
@@ -145,4 +254,50 @@ mod tests {
             assert_case(case.0, case.1, case.2);
         }
     }
+
+    #[test]
+    fn position_vector_arithmetic() {
+        let mut position = pos!(2, 3) + Vector::new(4, 1);
+        assert_eq!(pos!(6, 4), position);
+
+        position -= Vector::new(6, 2);
+        assert_eq!(pos!(0, 2), position);
+
+        position += Vector::new(1, 1);
+        assert_eq!(pos!(1, 3), position);
+        assert_eq!(pos!(0, 0), Position::default());
+    }
+
+    #[test]
+    fn position_checked_translation() {
+        let edge = pos!(u16::MAX - 1, 4);
+
+        assert_eq!(Some(pos!(u16::MAX, 5)), edge.checked_translate(1, 1));
+        assert_eq!(None, edge.checked_translate(2, 0));
+        assert_eq!(None, pos!(0, u16::MAX).checked_translate(0, 1));
+
+        assert_eq!(pos!(u16::MAX, 6), edge.saturating_translate(9, 2));
+        assert_eq!(
+            pos!(u16::MAX, u16::MAX),
+            edge.saturating_translate(u16::MAX, u16::MAX)
+        );
+    }
+
+    #[test]
+    fn position_signed_translation() {
+        let position = pos!(2, 3);
+
+        assert_eq!(pos!(0, 4), position.translate_signed(-2, 1));
+        assert_eq!(pos!(0, 0), position.translate_signed(i32::MIN, i32::MIN));
+        assert_eq!(
+            pos!(u16::MAX, u16::MAX),
+            position.translate_signed(i32::MAX, i32::MAX)
+        );
+
+        assert_eq!(Some(pos!(1, 0)), position.checked_translate_signed(-1, -3));
+        assert_eq!(None, position.checked_translate_signed(-3, 0));
+        assert_eq!(None, position.checked_translate_signed(0, -4));
+        assert_eq!(None, position.checked_translate_signed(i32::MAX, 0));
+        assert_eq!(None, position.checked_translate_signed(i32::MIN, 0));
+    }
 }
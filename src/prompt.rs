@@ -0,0 +1,246 @@
+//! Ready-made prompts asking the user a question relative to the cursor, like a command-line
+//! tool's interactive questions. Each prompt renders with a temporary relative interface, reads
+//! keys until answered, and leaves its final rendering in the terminal's buffer. Escape or Ctrl+C
+//! cancels a prompt.
+
+use crate::{pos, Device, Event, Interface, KeyCode, KeyEvent, Position, Result, Style};
+
+/// Ask a yes-or-no question, returning whether it was answered "y". Enter, "n", and cancelling
+/// answer no.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice, Event, KeyCode, KeyEvent, Modifiers};
+/// # let mut device = VirtualDevice::new();
+/// # device.push_event(Event::Key(KeyEvent::new(KeyCode::Char('y'), Modifiers::new())));
+/// use tty_interface::prompt;
+///
+/// if prompt::confirm(&mut device, "Proceed?")? {
+///     // ...
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn confirm(device: &mut dyn Device, message: &str) -> Result<bool> {
+    let mut interface = Interface::new_relative(device)?;
+
+    let question = format!("{} (y/N) ", message);
+    interface.set(pos!(0, 0), &question);
+    interface.set_cursor(Some(pos!(interface.widths().text_width(&question), 0)));
+    interface.apply()?;
+
+    let answer = loop {
+        match read_key(&mut interface)? {
+            None => break false,
+            Some(KeyCode::Char('y' | 'Y')) => break true,
+            Some(KeyCode::Char('n' | 'N') | KeyCode::Enter) => break false,
+            Some(_) => {}
+        }
+    };
+
+    interface.set(
+        pos!(interface.widths().text_width(&question), 0),
+        if answer { "yes" } else { "no" },
+    );
+    interface.set_cursor(None);
+    interface.apply()?;
+    interface.exit()?;
+
+    Ok(answer)
+}
+
+/// Ask the user to choose one of the items with the arrow keys and Enter, returning the chosen
+/// item's index, or `None` if cancelled or there are no items.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice, Event, KeyCode, KeyEvent, Modifiers};
+/// # let mut device = VirtualDevice::new();
+/// # device.push_event(Event::Key(KeyEvent::new(KeyCode::Enter, Modifiers::new())));
+/// use tty_interface::prompt;
+///
+/// let colors = ["Red", "Green", "Blue"];
+/// if let Some(index) = prompt::select(&mut device, &colors)? {
+///     println!("Chose {}", colors[index]);
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub fn select(device: &mut dyn Device, items: &[&str]) -> Result<Option<usize>> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+
+    let mut interface = Interface::new_relative(device)?;
+    let highlight = Style::new().set_bold(true);
+
+    let mut selected = 0;
+    let choice = loop {
+        for (line, item) in (0..).zip(items) {
+            interface.clear_line(line);
+            match line as usize == selected {
+                true => interface.set_styled(pos!(0, line), &format!("> {}", item), highlight),
+                false => interface.set(pos!(0, line), &format!("  {}", item)),
+            };
+        }
+        interface.apply()?;
+
+        match read_key(&mut interface)? {
+            None => break None,
+            Some(KeyCode::Enter) => break Some(selected),
+            Some(KeyCode::Up | KeyCode::Char('k')) => selected = selected.saturating_sub(1),
+            Some(KeyCode::Down | KeyCode::Char('j')) => {
+                selected = (selected + 1).min(items.len() - 1)
+            }
+            Some(KeyCode::Home) => selected = 0,
+            Some(KeyCode::End) => selected = items.len() - 1,
+            Some(_) => {}
+        }
+    };
+
+    interface.exit()?;
+
+    Ok(choice)
+}
+
+/// Ask the user to enter a line of text after the message, returning it once Enter is pressed, or
+/// `None` if cancelled.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice, Event, KeyCode, KeyEvent, Modifiers};
+/// # let mut device = VirtualDevice::new();
+/// # device.push_event(Event::Key(KeyEvent::new(KeyCode::Enter, Modifiers::new())));
+/// use tty_interface::prompt;
+///
+/// let name = prompt::input(&mut device, "Name:")?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn input(device: &mut dyn Device, message: &str) -> Result<Option<String>> {
+    let mut interface = Interface::new_relative(device)?;
+
+    let prefix = format!("{} ", message);
+    let prefix_width = interface.widths().text_width(&prefix);
+    interface.set(pos!(0, 0), &prefix);
+
+    let mut text = String::new();
+    let answer = loop {
+        interface.clear_rest_of_line(pos!(prefix_width, 0));
+        interface.set(pos!(prefix_width, 0), &text);
+        interface.set_cursor(Some(pos!(
+            prefix_width + interface.widths().text_width(&text),
+            0
+        )));
+        interface.apply()?;
+
+        match read_key(&mut interface)? {
+            None => break None,
+            Some(KeyCode::Enter) => break Some(text),
+            Some(KeyCode::Backspace) => {
+                text.pop();
+            }
+            Some(KeyCode::Char(character)) => text.push(character),
+            Some(_) => {}
+        }
+    };
+
+    interface.set_cursor(None);
+    interface.apply()?;
+    interface.exit()?;
+
+    Ok(answer)
+}
+
+/// Wait for the next key press, returning `None` if it cancels the prompt.
+fn read_key(interface: &mut Interface) -> Result<Option<KeyCode>> {
+    loop {
+        if let Event::Key(event) = interface.read_event()? {
+            return Ok((!is_cancel(event)).then_some(event.code()));
+        }
+    }
+}
+
+/// Whether the key press cancels a prompt.
+fn is_cancel(event: KeyEvent) -> bool {
+    match event.code() {
+        KeyCode::Escape => true,
+        KeyCode::Char('c') => event.modifiers().is_control(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{confirm, input, select};
+    use crate::{test::VirtualDevice, Event, KeyCode, KeyEvent, Modifiers};
+
+    /// Create a device with the keys queued as input.
+    fn device_with_keys(keys: &[KeyCode]) -> VirtualDevice {
+        let mut device = VirtualDevice::new();
+        for key in keys {
+            device.push_event(Event::Key(KeyEvent::new(*key, Modifiers::new())));
+        }
+
+        device
+    }
+
+    #[test]
+    fn prompt_confirm() {
+        let mut device = device_with_keys(&[KeyCode::Char('x'), KeyCode::Char('y')]);
+        assert!(confirm(&mut device, "Proceed?").unwrap());
+        assert_eq!("Proceed? (y/N) yes", device.parser().screen().contents());
+
+        let mut device = device_with_keys(&[KeyCode::Enter]);
+        assert!(!confirm(&mut device, "Proceed?").unwrap());
+        assert_eq!("Proceed? (y/N) no", device.parser().screen().contents());
+
+        let mut device = device_with_keys(&[KeyCode::Escape]);
+        assert!(!confirm(&mut device, "Proceed?").unwrap());
+    }
+
+    #[test]
+    fn prompt_select() {
+        let items = ["Red", "Green", "Blue"];
+
+        let mut device = device_with_keys(&[
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Up,
+            KeyCode::Enter,
+        ]);
+        assert_eq!(Some(1), select(&mut device, &items).unwrap());
+        assert_eq!(
+            "  Red\n> Green\n  Blue",
+            device.parser().screen().contents()
+        );
+
+        let mut device = device_with_keys(&[KeyCode::Down, KeyCode::Escape]);
+        assert_eq!(None, select(&mut device, &items).unwrap());
+
+        let mut device = VirtualDevice::new();
+        assert_eq!(None, select(&mut device, &[]).unwrap());
+    }
+
+    #[test]
+    fn prompt_input() {
+        let mut device = device_with_keys(&[
+            KeyCode::Char('A'),
+            KeyCode::Char('d'),
+            KeyCode::Char('x'),
+            KeyCode::Backspace,
+            KeyCode::Char('a'),
+            KeyCode::Enter,
+        ]);
+        assert_eq!(
+            Some("Ada".to_string()),
+            input(&mut device, "Name:").unwrap()
+        );
+        assert_eq!("Name: Ada", device.parser().screen().contents());
+
+        let mut device = device_with_keys(&[KeyCode::Char('A')]);
+        device.push_event(Event::Key(KeyEvent::new(
+            KeyCode::Char('c'),
+            Modifiers::new().set_control(true),
+        )));
+        assert_eq!(None, input(&mut device, "Name:").unwrap());
+    }
+}
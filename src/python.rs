@@ -0,0 +1,374 @@
+//! Python bindings for driving interfaces from scripts. Build the `tty_interface` extension module
+//! with the `python` feature, e.g. `maturin build --release --features python`, and use it as
+//! follows:
+//!
+//! ```python
+//! from tty_interface import Alignment, Color, Interface, Style
+//!
+//! interface = Interface.alternate()
+//! interface.set(0, 0, "Hello", Style(foreground=Color.Red, bold=True))
+//! interface.set_aligned(1, "world", Alignment.Center)
+//! interface.apply()
+//! interface.exit()
+//! ```
+//!
+//! Interfaces render to the process's standard output and may only be used from the thread which
+//! created them. Applying releases the GIL while the frame is written, so other Python threads
+//! continue running during slow terminal writes.
+
+use pyo3::{
+    exceptions::{PyOSError, PyRuntimeError},
+    prelude::*,
+};
+
+use crate::{pos, Alignment, Color, Error, Interface, Position, Style};
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        PyOSError::new_err(error.to_string())
+    }
+}
+
+/// The `tty_interface` Python module.
+#[pymodule]
+fn tty_interface(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyInterface>()?;
+    module.add_class::<Style>()?;
+    module.add_class::<Color>()?;
+    module.add_class::<Alignment>()?;
+    Ok(())
+}
+
+#[pymethods]
+impl Style {
+    #[new]
+    #[pyo3(signature = (foreground=None, background=None, bold=false, italic=false, underline=false))]
+    fn py_new(
+        foreground: Option<Color>,
+        background: Option<Color>,
+        bold: bool,
+        italic: bool,
+        underline: bool,
+    ) -> Style {
+        let mut style = Style::new()
+            .set_bold(bold)
+            .set_italic(italic)
+            .set_underline(underline);
+
+        if let Some(color) = foreground {
+            style = style.set_foreground(color);
+        }
+
+        if let Some(color) = background {
+            style = style.set_background(color);
+        }
+
+        style
+    }
+
+    #[getter(foreground)]
+    fn py_foreground(&self) -> Option<Color> {
+        self.foreground()
+    }
+
+    #[getter(background)]
+    fn py_background(&self) -> Option<Color> {
+        self.background()
+    }
+
+    #[getter(bold)]
+    fn py_bold(&self) -> bool {
+        self.is_bold()
+    }
+
+    #[getter(italic)]
+    fn py_italic(&self) -> bool {
+        self.is_italic()
+    }
+
+    #[getter(underline)]
+    fn py_underline(&self) -> bool {
+        self.is_underlined()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/// A Python-owned interface rendering to standard output. Positions are `(x, y)` tuples.
+#[pyclass(name = "Interface", module = "tty_interface", unsendable)]
+pub struct PyInterface {
+    interface: Option<Interface<'static>>,
+}
+
+#[pymethods]
+impl PyInterface {
+    /// Create an interface on the alternate screen.
+    #[staticmethod]
+    fn alternate() -> PyResult<PyInterface> {
+        let interface = Interface::new_alternate_owned(Box::new(std::io::stdout()))?;
+        Ok(PyInterface::from(interface))
+    }
+
+    /// Create an interface rendering relative to the cursor's current position.
+    #[staticmethod]
+    fn relative() -> PyResult<PyInterface> {
+        let interface = Interface::new_relative_owned(Box::new(std::io::stdout()))?;
+        Ok(PyInterface::from(interface))
+    }
+
+    /// The terminal's size as a `(columns, lines)` tuple.
+    #[getter]
+    fn size(&self) -> PyResult<(u16, u16)> {
+        let size = self.interface()?.size();
+        Ok((size.x(), size.y()))
+    }
+
+    /// Stage text, optionally styled, at the specified position.
+    #[pyo3(signature = (x, y, text, style=None))]
+    fn set(&mut self, x: u16, y: u16, text: &str, style: Option<Style>) -> PyResult<()> {
+        let interface = self.interface_mut()?;
+        match style {
+            Some(style) => interface.set_styled(pos!(x, y), text, style),
+            None => interface.set(pos!(x, y), text),
+        };
+
+        Ok(())
+    }
+
+    /// Stage text, optionally styled, aligned within the specified line.
+    #[pyo3(signature = (line, text, alignment, style=None))]
+    fn set_aligned(
+        &mut self,
+        line: u16,
+        text: &str,
+        alignment: Alignment,
+        style: Option<Style>,
+    ) -> PyResult<()> {
+        let interface = self.interface_mut()?;
+        match style {
+            Some(style) => interface.set_aligned_styled(line, text, alignment, style),
+            None => interface.set_aligned(line, text, alignment),
+        }
+
+        Ok(())
+    }
+
+    /// Stage text, optionally styled, padded or truncated to a fixed-width field.
+    #[pyo3(signature = (x, y, text, width, alignment=Alignment::Left, pad=' ', style=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_field(
+        &mut self,
+        x: u16,
+        y: u16,
+        text: &str,
+        width: u16,
+        alignment: Alignment,
+        pad: char,
+        style: Option<Style>,
+    ) -> PyResult<()> {
+        let interface = self.interface_mut()?;
+        match style {
+            Some(style) => {
+                interface.set_field_styled(pos!(x, y), text, width, alignment, pad, style)
+            }
+            None => interface.set_field(pos!(x, y), text, width, alignment, pad),
+        }
+
+        Ok(())
+    }
+
+    /// Stage a vertical line of a grapheme, optionally styled, downward from the position.
+    #[pyo3(signature = (x, y, length, grapheme, style=None))]
+    fn draw_vline(
+        &mut self,
+        x: u16,
+        y: u16,
+        length: u16,
+        grapheme: &str,
+        style: Option<Style>,
+    ) -> PyResult<()> {
+        let interface = self.interface_mut()?;
+        match style {
+            Some(style) => interface.draw_vline_styled(pos!(x, y), length, grapheme, style),
+            None => interface.draw_vline(pos!(x, y), length, grapheme),
+        }
+
+        Ok(())
+    }
+
+    /// Stage a column ruler, optionally styled, across two lines from the position.
+    #[pyo3(signature = (x, y, length, interval, style=None))]
+    fn draw_ruler(
+        &mut self,
+        x: u16,
+        y: u16,
+        length: u16,
+        interval: u16,
+        style: Option<Style>,
+    ) -> PyResult<()> {
+        let interface = self.interface_mut()?;
+        match style {
+            Some(style) => interface.draw_ruler_styled(pos!(x, y), length, interval, style),
+            None => interface.draw_ruler(pos!(x, y), length, interval),
+        }
+
+        Ok(())
+    }
+
+    /// Stage clearing the specified line.
+    fn clear_line(&mut self, line: u16) -> PyResult<()> {
+        self.interface_mut()?.clear_line(line);
+        Ok(())
+    }
+
+    /// Stage clearing the remainder of the line from the position.
+    fn clear_rest_of_line(&mut self, x: u16, y: u16) -> PyResult<()> {
+        self.interface_mut()?.clear_rest_of_line(pos!(x, y));
+        Ok(())
+    }
+
+    /// Stage clearing the remainder of the interface from the position.
+    fn clear_rest_of_interface(&mut self, x: u16, y: u16) -> PyResult<()> {
+        self.interface_mut()?.clear_rest_of_interface(pos!(x, y));
+        Ok(())
+    }
+
+    /// Stage the cursor's position as an `(x, y)` tuple, or hide it with `None`.
+    #[pyo3(signature = (position))]
+    fn set_cursor(&mut self, position: Option<(u16, u16)>) -> PyResult<()> {
+        let position = position.map(|(x, y)| pos!(x, y));
+        self.interface_mut()?.set_cursor(position);
+        Ok(())
+    }
+
+    /// Apply staged changes to the terminal, releasing the GIL while rendering.
+    fn apply(&mut self, py: Python<'_>) -> PyResult<()> {
+        /// The interface, lent to the closure applying it while the GIL is released.
+        struct Detached<'a>(&'a mut Interface<'static>);
+
+        // SAFETY: `detach` only requires its closure to be `Send` to keep GIL-bound values out of
+        // it, and runs it synchronously on the calling thread. The interface never leaves that
+        // thread, which is the only one the unsendable class is used from, and holds no values
+        // bound to the GIL.
+        unsafe impl Send for Detached<'_> {}
+
+        impl Detached<'_> {
+            // A method, so the closure captures the wrapper rather than only its unsendable field
+            fn apply(self) -> crate::Result<()> {
+                self.0.apply()
+            }
+        }
+
+        let detached = Detached(self.interface_mut()?);
+        py.detach(move || detached.apply())?;
+        Ok(())
+    }
+
+    /// Restore the terminal's configuration. The interface may not be used afterward.
+    fn exit(&mut self) -> PyResult<()> {
+        match self.interface.take() {
+            Some(interface) => Ok(interface.exit()?),
+            None => Err(exited_error()),
+        }
+    }
+}
+
+impl PyInterface {
+    /// The wrapped interface, if it has not exited.
+    fn interface(&self) -> PyResult<&Interface<'static>> {
+        self.interface.as_ref().ok_or_else(exited_error)
+    }
+
+    /// The wrapped interface, mutably, if it has not exited.
+    fn interface_mut(&mut self) -> PyResult<&mut Interface<'static>> {
+        self.interface.as_mut().ok_or_else(exited_error)
+    }
+}
+
+impl From<Interface<'static>> for PyInterface {
+    fn from(interface: Interface<'static>) -> Self {
+        PyInterface {
+            interface: Some(interface),
+        }
+    }
+}
+
+/// The error raised when using an interface after it has exited.
+fn exited_error() -> PyErr {
+    PyRuntimeError::new_err("interface has exited")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use pyo3::types::PyDict;
+
+    use super::*;
+    use crate::test::SharedDevice;
+
+    /// Run a script against an interface for a virtual terminal, returning the screen's contents.
+    fn run_script(script: &str) -> PyResult<String> {
+        Python::initialize();
+
+        let device = SharedDevice::default();
+        let interface = Interface::new_alternate_owned(Box::new(device.clone()))?;
+
+        Python::attach(|py| {
+            let module = PyModule::new(py, "tty_interface")?;
+            tty_interface(&module)?;
+
+            let locals = PyDict::new(py);
+            for name in ["Alignment", "Color", "Style"] {
+                locals.set_item(name, module.getattr(name)?)?;
+            }
+            locals.set_item("interface", Py::new(py, PyInterface::from(interface))?)?;
+
+            let script = CString::new(script).unwrap();
+            py.run(&script, None, Some(&locals))
+        })?;
+
+        let contents = device.device().parser().screen().contents();
+        Ok(contents)
+    }
+
+    #[test]
+    fn python_set_and_apply() {
+        let contents = run_script(
+            "interface.set(1, 0, 'Hello', Style(foreground=Color.Red, bold=True))\n\
+             interface.set_aligned(1, 'mid', Alignment.Right)\n\
+             interface.apply()",
+        )
+        .unwrap();
+
+        assert!(contents.starts_with(" Hello\n"));
+        assert!(contents.contains(&format!("{}mid", " ".repeat(77))));
+    }
+
+    #[test]
+    fn python_style_properties() {
+        run_script(
+            "style = Style(background=Color.Blue, underline=True)\n\
+             assert style.background == Color.Blue\n\
+             assert style.foreground is None\n\
+             assert style.underline and not style.bold\n\
+             assert style == Style(background=Color.Blue, underline=True)",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn python_use_after_exit() {
+        let result = run_script(
+            "interface.exit()\n\
+             interface.set(0, 0, 'late')",
+        );
+
+        Python::attach(|py| {
+            let error = result.unwrap_err();
+            assert!(error.is_instance_of::<PyRuntimeError>(py));
+        });
+    }
+}
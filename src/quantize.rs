@@ -0,0 +1,144 @@
+use crate::Color;
+
+/// The colors of the 16-color palette, in the order of their 256-color palette indices.
+const PALETTE: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// The component levels of the 256-color palette's 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// How a true color is mapped to the nearest color of a smaller palette, so colors defined once
+/// may be displayed by terminals supporting only 256 or 16 colors.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{Color, Quantization};
+///
+/// let orange = (255, 135, 0);
+/// assert_eq!(208, Quantization::Nearest.to_ansi256(orange));
+/// assert_eq!(Color::DarkYellow, Quantization::Perceptual.to_color(orange));
+/// ```
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Quantization {
+    /// The palette color with the least Euclidean distance in RGB space.
+    #[default]
+    Nearest,
+    /// The palette color with the least distance weighted for human perception of red, green,
+    /// and blue (the "redmean" approximation), which better preserves hues.
+    Perceptual,
+}
+
+impl Quantization {
+    /// The nearest color of the 16-color palette, per xterm's default palette.
+    pub fn to_color(self, rgb: (u8, u8, u8)) -> Color {
+        PALETTE
+            .into_iter()
+            .min_by_key(|color| self.distance(rgb, color.to_rgb().unwrap_or_default()))
+            .unwrap_or(Color::Reset)
+    }
+
+    /// The index of the nearest color of the 256-color palette's color cube and grayscale ramp.
+    /// The first 16 indices, whose colors vary by terminal, are excluded.
+    pub fn to_ansi256(self, rgb: (u8, u8, u8)) -> u8 {
+        (16..=255)
+            .min_by_key(|index| self.distance(rgb, ansi256_rgb(*index)))
+            .unwrap_or(16)
+    }
+
+    /// The distance between two colors per this quantization.
+    fn distance(self, (r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+        let red = (r1 as i32 - r2 as i32).pow(2);
+        let green = (g1 as i32 - g2 as i32).pow(2);
+        let blue = (b1 as i32 - b2 as i32).pow(2);
+
+        let distance = match self {
+            Quantization::Nearest => red + green + blue,
+            Quantization::Perceptual => {
+                let red_mean = (r1 as i32 + r2 as i32) / 2;
+                (((512 + red_mean) * red) >> 8) + 4 * green + (((767 - red_mean) * blue) >> 8)
+            }
+        };
+
+        distance as u32
+    }
+}
+
+/// The color displayed for a 256-color palette index, using the nearest 16-color palette color
+/// for indices from 16 onward.
+#[cfg(feature = "std")]
+pub(crate) fn palette_color(index: u8) -> Color {
+    match PALETTE.get(index as usize) {
+        Some(color) => *color,
+        None => Quantization::Nearest.to_color(ansi256_rgb(index)),
+    }
+}
+
+/// The red, green, and blue components of a 256-color palette index from 16 onward.
+fn ansi256_rgb(index: u8) -> (u8, u8, u8) {
+    if index >= 232 {
+        let level = 8 + (index - 232) * 10;
+        return (level, level, level);
+    }
+
+    let offset = index - 16;
+    (
+        CUBE_LEVELS[(offset / 36) as usize],
+        CUBE_LEVELS[(offset / 6 % 6) as usize],
+        CUBE_LEVELS[(offset % 6) as usize],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ansi256_rgb, Quantization};
+    use crate::Color;
+
+    #[test]
+    fn quantize_ansi256() {
+        assert_eq!((0, 0, 0), ansi256_rgb(16));
+        assert_eq!((255, 255, 255), ansi256_rgb(231));
+        assert_eq!((8, 8, 8), ansi256_rgb(232));
+        assert_eq!((238, 238, 238), ansi256_rgb(255));
+
+        for quantization in [Quantization::Nearest, Quantization::Perceptual] {
+            assert_eq!(196, quantization.to_ansi256((255, 0, 0)));
+            assert_eq!(16, quantization.to_ansi256((3, 2, 1)));
+            assert_eq!(244, quantization.to_ansi256((128, 128, 128)));
+            assert_eq!(75, quantization.to_ansi256((95, 175, 255)));
+        }
+    }
+
+    #[test]
+    fn quantize_16_colors() {
+        for quantization in [Quantization::Nearest, Quantization::Perceptual] {
+            assert_eq!(Color::Black, quantization.to_color((10, 10, 10)));
+            assert_eq!(Color::White, quantization.to_color((250, 250, 250)));
+            assert_eq!(Color::DarkRed, quantization.to_color((190, 20, 10)));
+            assert_eq!(Color::DarkGrey, quantization.to_color((120, 120, 130)));
+        }
+    }
+
+    #[test]
+    fn quantize_perceptual_preserves_hue() {
+        // A muted blue is nearer grey in RGB space but perceived as blue
+        let muted_blue = (60, 60, 160);
+        assert_eq!(Color::DarkGrey, Quantization::Nearest.to_color(muted_blue));
+        assert_eq!(Color::Blue, Quantization::Perceptual.to_color(muted_blue));
+    }
+}
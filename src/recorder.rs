@@ -0,0 +1,167 @@
+//! Recording of applied frames in the asciinema cast v2 format, so sessions may be replayed with
+//! `asciinema play` or converted to animations. Started with [crate::Interface::record_to].
+
+use std::{
+    io::{self, Write},
+    time::Instant,
+};
+
+use crate::Vector;
+
+/// Streams an interface's applied frames with their timestamps to a writer as an asciicast. The
+/// first error writing the recording ends it, and is reported once the recording is finished.
+pub(crate) struct Recorder {
+    writer: Box<dyn Write>,
+    start: Instant,
+    error: Option<io::Error>,
+}
+
+impl Recorder {
+    /// Start a recording of a terminal with the specified size, timestamped from the start.
+    pub(crate) fn new(writer: Box<dyn Write>, size: Vector, start: Instant) -> Recorder {
+        let mut recorder = Recorder {
+            writer,
+            start,
+            error: None,
+        };
+
+        recorder.write_line(format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}\n",
+            size.x(),
+            size.y()
+        ));
+
+        recorder
+    }
+
+    /// Record a frame's bytes written to the terminal at the specified time.
+    pub(crate) fn record_frame(&mut self, now: Instant, frame: &[u8]) {
+        self.record_event(now, "o", &String::from_utf8_lossy(frame));
+    }
+
+    /// Record the terminal being resized at the specified time.
+    pub(crate) fn record_resize(&mut self, now: Instant, size: Vector) {
+        self.record_event(now, "r", &format!("{}x{}", size.x(), size.y()));
+    }
+
+    /// Flush the recording, reporting any error which ended it early.
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        self.writer.flush()
+    }
+
+    /// Write an event line with its time since the recording started.
+    fn record_event(&mut self, now: Instant, code: &str, data: &str) {
+        let time = now.saturating_duration_since(self.start);
+        self.write_line(format!(
+            "[{:.6}, \"{}\", \"{}\"]\n",
+            time.as_secs_f64(),
+            code,
+            escape_json(data)
+        ));
+    }
+
+    /// Write a line of the recording unless it has already failed.
+    fn write_line(&mut self, line: String) {
+        if self.error.is_none() {
+            if let Err(error) = self.writer.write_all(line.as_bytes()) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+impl Drop for Recorder {
+    /// Flush what was recorded, such as when an interface is dropped without exiting.
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+/// Escape the text for inclusion in a JSON string.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if character.is_control() => {
+                escaped.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        io::{self, Write},
+        rc::Rc,
+        time::{Duration, Instant},
+    };
+
+    use super::{escape_json, Recorder};
+    use crate::Vector;
+
+    #[test]
+    fn recorder_escape_json() {
+        assert_eq!("plain", escape_json("plain"));
+        assert_eq!(
+            "\\u001b[1m\\\"a\\\\b\\\"\\r\\n",
+            escape_json("\x1B[1m\"a\\b\"\r\n")
+        );
+    }
+
+    #[test]
+    fn recorder_cast() {
+        let cast = Rc::new(RefCell::new(Vec::new()));
+        let start = Instant::now();
+        let mut recorder = Recorder::new(
+            Box::new(SharedCast(cast.clone())),
+            Vector::new(80, 24),
+            start,
+        );
+        recorder.record_frame(start, b"Hello");
+
+        // Events are written as they're recorded
+        let header = "{\"version\": 2, \"width\": 80, \"height\": 24}\n";
+        let streamed = format!("{}[0.000000, \"o\", \"Hello\"]\n", header);
+        assert_eq!(streamed.as_bytes(), cast.borrow().as_slice());
+
+        recorder.record_resize(start + Duration::from_millis(1500), Vector::new(100, 30));
+        recorder.record_frame(start + Duration::from_secs(2), b"\x1B[2J");
+        recorder.finish().unwrap();
+
+        assert_eq!(
+            format!(
+                "{}[1.500000, \"r\", \"100x30\"]\n[2.000000, \"o\", \"\\u001b[2J\"]\n",
+                streamed
+            ),
+            String::from_utf8(cast.borrow().clone()).unwrap()
+        );
+    }
+
+    /// A writer into a buffer shared with the test.
+    struct SharedCast(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedCast {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{pos, Position, Vector};
+
+/// A rectangular region of the terminal described by its top-left origin and size.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect {
+    origin: Position,
+    size: Vector,
+}
+
+impl Rect {
+    /// Create a new, immutable rectangle.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, Rect, Vector, pos};
+    ///
+    /// let rect = Rect::new(pos!(1, 2), Vector::new(3, 4));
+    /// assert_eq!(pos!(1, 2), rect.origin());
+    /// assert_eq!(Vector::new(3, 4), rect.size());
+    /// ```
+    pub fn new(origin: Position, size: Vector) -> Rect {
+        Rect { origin, size }
+    }
+
+    /// This rectangle's top-left position.
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+
+    /// This rectangle's column and line extent.
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    /// Whether the specified position lies within this rectangle.
+    pub fn contains(&self, position: Position) -> bool {
+        let (x, y) = (position.x() as u32, position.y() as u32);
+        let (left, top) = (self.origin.x() as u32, self.origin.y() as u32);
+
+        x >= left && y >= top && x < self.right() && y < self.bottom()
+    }
+
+    /// Whether this rectangle covers no positions.
+    pub fn is_empty(&self) -> bool {
+        self.size.x() == 0 || self.size.y() == 0
+    }
+
+    /// The area both rectangles cover, if they overlap.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, Rect, Vector, pos};
+    ///
+    /// let a = Rect::new(pos!(0, 0), Vector::new(4, 3));
+    /// let b = Rect::new(pos!(2, 1), Vector::new(4, 3));
+    /// assert_eq!(Some(Rect::new(pos!(2, 1), Vector::new(2, 2))), a.intersection(b));
+    ///
+    /// let c = Rect::new(pos!(4, 0), Vector::new(1, 1));
+    /// assert_eq!(None, a.intersection(c));
+    /// ```
+    pub fn intersection(&self, other: Rect) -> Option<Rect> {
+        let left = self.origin.x().max(other.origin.x());
+        let top = self.origin.y().max(other.origin.y());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        if right <= left as u32 || bottom <= top as u32 {
+            return None;
+        }
+
+        Some(Rect::from_edges(left, top, right, bottom))
+    }
+
+    /// The smallest rectangle covering both rectangles. An empty rectangle covers nothing, so
+    /// the other is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, Rect, Vector, pos};
+    ///
+    /// let a = Rect::new(pos!(0, 0), Vector::new(2, 1));
+    /// let b = Rect::new(pos!(3, 2), Vector::new(2, 2));
+    /// assert_eq!(Rect::new(pos!(0, 0), Vector::new(5, 4)), a.union(b));
+    /// ```
+    pub fn union(&self, other: Rect) -> Rect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return other;
+        }
+
+        let left = self.origin.x().min(other.origin.x());
+        let top = self.origin.y().min(other.origin.y());
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::from_edges(left, top, right, bottom)
+    }
+
+    /// Split this rectangle into the specified number of its leftmost columns and the columns
+    /// right of them, each of which is empty if there are none.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, Rect, Vector, pos};
+    ///
+    /// let rect = Rect::new(pos!(1, 1), Vector::new(10, 4));
+    /// let (gutter, content) = rect.split_columns(3);
+    /// assert_eq!(Rect::new(pos!(1, 1), Vector::new(3, 4)), gutter);
+    /// assert_eq!(Rect::new(pos!(4, 1), Vector::new(7, 4)), content);
+    /// ```
+    pub fn split_columns(&self, columns: u16) -> (Rect, Rect) {
+        let columns = columns.min(self.size.x());
+        let left = Rect::new(self.origin, Vector::new(columns, self.size.y()));
+        let right = Rect::new(
+            pos!(self.origin.x().saturating_add(columns), self.origin.y()),
+            Vector::new(self.size.x() - columns, self.size.y()),
+        );
+
+        (left, right)
+    }
+
+    /// Split this rectangle into the specified number of its topmost lines and the lines below
+    /// them, each of which is empty if there are none.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, Rect, Vector, pos};
+    ///
+    /// let rect = Rect::new(pos!(0, 0), Vector::new(10, 4));
+    /// let (header, body) = rect.split_lines(1);
+    /// assert_eq!(Rect::new(pos!(0, 0), Vector::new(10, 1)), header);
+    /// assert_eq!(Rect::new(pos!(0, 1), Vector::new(10, 3)), body);
+    /// ```
+    pub fn split_lines(&self, lines: u16) -> (Rect, Rect) {
+        let lines = lines.min(self.size.y());
+        let top = Rect::new(self.origin, Vector::new(self.size.x(), lines));
+        let bottom = Rect::new(
+            pos!(self.origin.x(), self.origin.y().saturating_add(lines)),
+            Vector::new(self.size.x(), self.size.y() - lines),
+        );
+
+        (top, bottom)
+    }
+
+    /// Shrink this rectangle by the specified number of columns on its left and right and lines
+    /// on its top and bottom, such as for the area within a border. A rectangle too small to be
+    /// inset becomes empty at its center.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Position, Rect, Vector, pos};
+    ///
+    /// let panel = Rect::new(pos!(2, 1), Vector::new(12, 5));
+    /// assert_eq!(Rect::new(pos!(3, 2), Vector::new(10, 3)), panel.inset(1, 1));
+    /// ```
+    pub fn inset(&self, columns: u16, lines: u16) -> Rect {
+        let columns = columns.min(self.size.x() / 2);
+        let lines = lines.min(self.size.y() / 2);
+
+        Rect::new(
+            pos!(
+                self.origin.x().saturating_add(columns),
+                self.origin.y().saturating_add(lines)
+            ),
+            Vector::new(self.size.x() - columns * 2, self.size.y() - lines * 2),
+        )
+    }
+
+    /// The column following this rectangle's last, which may lie beyond the last column.
+    fn right(&self) -> u32 {
+        self.origin.x() as u32 + self.size.x() as u32
+    }
+
+    /// The line following this rectangle's last, which may lie beyond the last line.
+    fn bottom(&self) -> u32 {
+        self.origin.y() as u32 + self.size.y() as u32
+    }
+
+    /// Create the rectangle between its top-left position and the column and line following its
+    /// bottom-right position.
+    fn from_edges(left: u16, top: u16, right: u32, bottom: u32) -> Rect {
+        Rect::new(
+            pos!(left, top),
+            Vector::new(
+                (right - left as u32).min(u16::MAX as u32) as u16,
+                (bottom - top as u32).min(u16::MAX as u32) as u16,
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pos, Position, Vector};
+
+    use super::Rect;
+
+    #[test]
+    fn rect_contains() {
+        let rect = Rect::new(pos!(2, 1), Vector::new(3, 2));
+
+        let cases = [
+            ((2, 1), true),
+            ((4, 2), true),
+            ((3, 1), true),
+            ((1, 1), false),
+            ((5, 1), false),
+            ((2, 0), false),
+            ((2, 3), false),
+        ];
+
+        for ((x, y), expected) in cases {
+            assert_eq!(
+                expected,
+                rect.contains(pos!(x, y)),
+                "contains ({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn rect_empty_contains_nothing() {
+        let rect = Rect::new(pos!(2, 1), Vector::new(0, 0));
+        assert!(!rect.contains(pos!(2, 1)));
+    }
+
+    #[test]
+    fn rect_intersection_and_union() {
+        let a = Rect::new(pos!(1, 1), Vector::new(4, 4));
+        let b = Rect::new(pos!(3, 0), Vector::new(4, 2));
+        let empty = Rect::new(pos!(9, 9), Vector::new(0, 3));
+
+        assert_eq!(
+            Some(Rect::new(pos!(3, 1), Vector::new(2, 1))),
+            a.intersection(b)
+        );
+        assert_eq!(a.intersection(b), b.intersection(a));
+        assert_eq!(Some(a), a.intersection(a));
+        assert_eq!(None, a.intersection(empty));
+
+        assert_eq!(Rect::new(pos!(1, 0), Vector::new(6, 5)), a.union(b));
+        assert_eq!(a, a.union(empty));
+        assert_eq!(a, empty.union(a));
+
+        let far = Rect::new(pos!(u16::MAX - 1, 0), Vector::new(u16::MAX, 1));
+        assert_eq!(
+            Some(Rect::new(pos!(u16::MAX - 1, 0), Vector::new(1, 1))),
+            far.intersection(Rect::new(pos!(0, 0), Vector::new(u16::MAX, u16::MAX)))
+        );
+        assert_eq!(
+            Rect::new(pos!(0, 0), Vector::new(u16::MAX, 1)),
+            far.union(Rect::new(pos!(0, 0), Vector::new(1, 1)))
+        );
+    }
+
+    #[test]
+    fn rect_split_and_inset() {
+        let rect = Rect::new(pos!(2, 1), Vector::new(5, 3));
+
+        let (left, right) = rect.split_columns(9);
+        assert_eq!(rect, left);
+        assert!(right.is_empty());
+        assert_eq!(pos!(7, 1), right.origin());
+
+        let (top, bottom) = rect.split_lines(0);
+        assert!(top.is_empty());
+        assert_eq!(rect, bottom);
+
+        assert_eq!(Rect::new(pos!(4, 2), Vector::new(1, 1)), rect.inset(2, 1));
+        assert_eq!(Rect::new(pos!(4, 2), Vector::new(1, 1)), rect.inset(5, 5));
+        assert_eq!(rect, rect.inset(0, 0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rect_serialize() {
+        let rect = Rect::new(pos!(1, 2), Vector::new(3, 4));
+
+        let json = serde_json::to_string(&rect).unwrap();
+        assert_eq!(r#"{"origin":{"x":1,"y":2},"size":{"x":3,"y":4}}"#, json);
+        assert_eq!(rect, serde_json::from_str(&json).unwrap());
+    }
+}
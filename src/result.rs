@@ -1,3 +1,7 @@
+use std::fmt::{self, Display};
+
+use crate::Position;
+
 /// An interface operation's result containing either a successful value or error.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -5,11 +9,99 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
     /// A low-level terminal interaction error.
-    Terminal(crossterm::ErrorKind),
+    Terminal(std::io::Error),
+    /// Writing an applied frame to the terminal failed, possibly after part of it was written.
+    Apply(ApplyError),
+}
+
+impl Error {
+    /// Whether the error reports that the device doesn't support the operation, such as querying
+    /// the cursor of a device which can't report it.
+    pub(crate) fn is_unsupported(&self) -> bool {
+        matches!(self, Error::Terminal(error) if error.kind() == std::io::ErrorKind::Unsupported)
+    }
 }
 
-impl From<crossterm::ErrorKind> for Error {
-    fn from(err: crossterm::ErrorKind) -> Self {
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
         Error::Terminal(err)
     }
 }
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Terminal(error) => write!(f, "terminal error: {}", error),
+            Error::Apply(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Terminal(error) => Some(error),
+            Error::Apply(error) => Some(error.error()),
+        }
+    }
+}
+
+/// The context in which writing an applied frame failed. The staged changes remain pending, so
+/// the apply may be retried. If part of the frame was written, the terminal's contents and cursor
+/// may no longer match the interface's record of them, and retrying may render text out of place;
+/// the application may prefer to redraw from scratch or abort.
+#[derive(Debug)]
+pub struct ApplyError {
+    error: std::io::Error,
+    position: Option<Position>,
+    bytes_written: usize,
+}
+
+impl ApplyError {
+    /// Create an error for a failed frame write.
+    pub(crate) fn new(
+        error: std::io::Error,
+        position: Option<Position>,
+        bytes_written: usize,
+    ) -> ApplyError {
+        ApplyError {
+            error,
+            position,
+            bytes_written,
+        }
+    }
+
+    /// The underlying terminal error.
+    pub fn error(&self) -> &std::io::Error {
+        &self.error
+    }
+
+    /// The position of the first cell not completely written, if the failure occurred before all of
+    /// the frame's cells were written.
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// How many of the frame's bytes were accepted by the device before the failure.
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
+    /// Whether the terminal's state may no longer match the interface's because part of the frame,
+    /// or all of it if flushing failed, was written.
+    pub fn may_be_inconsistent(&self) -> bool {
+        self.bytes_written > 0
+    }
+}
+
+impl Display for ApplyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "apply failed after {} bytes", self.bytes_written)?;
+
+        if let Some(position) = self.position {
+            write!(f, " at {:?}", position)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
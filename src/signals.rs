@@ -0,0 +1,272 @@
+//! Handling of the Unix signals which affect a terminal application, so resizes, suspension, and
+//! termination leave the terminal usable. Enabled by the `signals` feature and installed with
+//! [crate::InterfaceBuilder::handle_signals] and [crate::InterfaceBuilder::handle_termination].
+
+use std::{
+    mem::MaybeUninit,
+    os::raw::c_int,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use crate::Result;
+
+/// A signal received by the process, as it's handled by an interface.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Signal {
+    /// The terminal was resized.
+    Resize,
+    /// The user asked to suspend the process, e.g. with Ctrl+Z.
+    Suspend,
+    /// The process was asked to terminate, only while termination signals are intercepted.
+    Terminate,
+}
+
+/// How long interfaces wait for input between checks for received signals.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How many of each kind of signal the process has received. Signal handlers can't capture
+/// state, so these are shared by the process, and each installation tracks how many it's taken.
+static RESIZES: AtomicUsize = AtomicUsize::new(0);
+static SUSPENSIONS: AtomicUsize = AtomicUsize::new(0);
+static TERMINATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The kinds of signal reported, each with its count.
+const KINDS: [(Signal, &AtomicUsize); 3] = [
+    (Signal::Resize, &RESIZES),
+    (Signal::Suspend, &SUSPENSIONS),
+    (Signal::Terminate, &TERMINATIONS),
+];
+
+/// The signals handled and the count each increments. The termination signals, which the process
+/// would otherwise terminate on, follow the others and are only handled when intercepted.
+const HANDLED: [(c_int, &AtomicUsize); 5] = [
+    (libc::SIGWINCH, &RESIZES),
+    (libc::SIGTSTP, &SUSPENSIONS),
+    (libc::SIGTERM, &TERMINATIONS),
+    (libc::SIGINT, &TERMINATIONS),
+    (libc::SIGHUP, &TERMINATIONS),
+];
+
+/// How many of the handled signals aren't termination signals.
+const NON_TERMINATION_COUNT: usize = 2;
+
+/// The process's installations, whose handlers are installed with the first and restored with the
+/// last. The termination signals' are likewise installed while any installation intercepts them.
+static INSTALLATIONS: Mutex<Installations> = Mutex::new(Installations {
+    count: 0,
+    intercepting_count: 0,
+    replaced: Vec::new(),
+});
+
+struct Installations {
+    count: usize,
+    intercepting_count: usize,
+    /// The handlers replaced by the installed handlers, to be restored.
+    replaced: Vec<(c_int, libc::sigaction)>,
+}
+
+impl Installations {
+    /// Replace the specified signals' handlers, restoring them if any can't be replaced.
+    fn replace(&mut self, handled: &[(c_int, &AtomicUsize)]) -> Result<()> {
+        for (index, (number, _)) in handled.iter().enumerate() {
+            let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+            action.sa_sigaction = record_signal as extern "C" fn(c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            unsafe { libc::sigemptyset(&mut action.sa_mask) };
+
+            let mut replaced = MaybeUninit::<libc::sigaction>::uninit();
+            if unsafe { libc::sigaction(*number, &action, replaced.as_mut_ptr()) } != 0 {
+                let error = std::io::Error::last_os_error();
+                self.restore(&handled[..index]);
+                return Err(error.into());
+            }
+
+            self.replaced
+                .push((*number, unsafe { replaced.assume_init() }));
+        }
+
+        Ok(())
+    }
+
+    /// Restore the handlers the specified signals' handlers replaced.
+    fn restore(&mut self, handled: &[(c_int, &AtomicUsize)]) {
+        for (number, _) in handled.iter().rev() {
+            if let Some(index) = self
+                .replaced
+                .iter()
+                .rposition(|(replaced, _)| replaced == number)
+            {
+                let (_, action) = self.replaced.remove(index);
+                unsafe { libc::sigaction(*number, &action, std::ptr::null_mut()) };
+            }
+        }
+    }
+}
+
+/// Record a received signal for the interfaces to handle outside of the signal handler.
+extern "C" fn record_signal(number: c_int) {
+    for (handled, count) in HANDLED {
+        if handled == number {
+            count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// An interface's installation of the signals' handlers. Each installation is reported every
+/// signal received while it's installed, and the handlers they replaced are restored once the
+/// last installation is dropped.
+pub(crate) struct Signals {
+    intercepts_termination: bool,
+    /// How many of each kind of signal had been received when this installation last took them.
+    taken: [usize; 3],
+}
+
+impl Signals {
+    /// Install the signals' handlers until dropped, including the termination signals' if they're
+    /// to be intercepted. Signals received earlier are discarded.
+    pub(crate) fn install(intercepts_termination: bool) -> Result<Signals> {
+        let mut installations = INSTALLATIONS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if installations.count == 0 {
+            installations.replace(&HANDLED[..NON_TERMINATION_COUNT])?;
+        }
+
+        if intercepts_termination && installations.intercepting_count == 0 {
+            if let Err(error) = installations.replace(&HANDLED[NON_TERMINATION_COUNT..]) {
+                if installations.count == 0 {
+                    installations.restore(&HANDLED[..NON_TERMINATION_COUNT]);
+                }
+
+                return Err(error);
+            }
+        }
+
+        installations.count += 1;
+        if intercepts_termination {
+            installations.intercepting_count += 1;
+        }
+
+        Ok(Signals {
+            intercepts_termination,
+            taken: KINDS.map(|(_, count)| count.load(Ordering::Relaxed)),
+        })
+    }
+
+    /// Take the first kind of signal received since this installation last took it, if any.
+    pub(crate) fn take_pending(&mut self) -> Option<Signal> {
+        for ((signal, count), taken) in KINDS.into_iter().zip(&mut self.taken) {
+            if signal == Signal::Terminate && !self.intercepts_termination {
+                continue;
+            }
+
+            let received = count.load(Ordering::Relaxed);
+            if received != *taken {
+                *taken = received;
+                return Some(signal);
+            }
+        }
+
+        None
+    }
+}
+
+impl Drop for Signals {
+    fn drop(&mut self) {
+        let mut installations = INSTALLATIONS.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if self.intercepts_termination {
+            installations.intercepting_count -= 1;
+            if installations.intercepting_count == 0 {
+                installations.restore(&HANDLED[NON_TERMINATION_COUNT..]);
+            }
+        }
+
+        installations.count -= 1;
+        if installations.count == 0 {
+            installations.restore(&HANDLED[..NON_TERMINATION_COUNT]);
+        }
+    }
+}
+
+/// Stop the process until it's continued, e.g. by the shell's `fg`.
+pub(crate) fn stop_process() -> Result<()> {
+    if unsafe { libc::raise(libc::SIGSTOP) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Signal, Signals};
+    use crate::{test::VirtualDevice, Event, Interface, Vector};
+
+    /// The handler currently installed for the signal.
+    fn installed_handler(number: libc::c_int) -> libc::sighandler_t {
+        let mut current: libc::sigaction = unsafe { std::mem::zeroed() };
+        unsafe { libc::sigaction(number, std::ptr::null(), &mut current) };
+        current.sa_sigaction
+    }
+
+    // Signals are shared by the process, so they're tested together
+    #[test]
+    fn signals_reported_as_events() {
+        // Termination signals aren't intercepted unless requested
+        let signals = Signals::install(false).unwrap();
+        assert_eq!(libc::SIG_DFL, installed_handler(libc::SIGTERM));
+        drop(signals);
+        assert_eq!(libc::SIG_DFL, installed_handler(libc::SIGWINCH));
+
+        // Overlapping installations each see every signal, and the handlers are restored once the
+        // last installation intercepting them is dropped
+        let mut first = Signals::install(false).unwrap();
+        let mut second = Signals::install(true).unwrap();
+        unsafe { libc::raise(libc::SIGWINCH) };
+        unsafe { libc::raise(libc::SIGWINCH) };
+        unsafe { libc::raise(libc::SIGTERM) };
+        assert_eq!(Some(Signal::Resize), first.take_pending());
+        assert_eq!(None, first.take_pending());
+        assert_eq!(Some(Signal::Resize), second.take_pending());
+        assert_eq!(Some(Signal::Terminate), second.take_pending());
+        assert_eq!(None, second.take_pending());
+
+        drop(second);
+        assert_eq!(libc::SIG_DFL, installed_handler(libc::SIGTERM));
+        assert_ne!(libc::SIG_DFL, installed_handler(libc::SIGWINCH));
+        unsafe { libc::raise(libc::SIGTSTP) };
+        assert_eq!(Some(Signal::Suspend), first.take_pending());
+        drop(first);
+        assert_eq!(libc::SIG_DFL, installed_handler(libc::SIGWINCH));
+        assert_eq!(libc::SIG_DFL, installed_handler(libc::SIGTSTP));
+
+        let mut device = VirtualDevice::new();
+        let mut interface = Interface::builder()
+            .handle_termination(true)
+            .build(&mut device)
+            .unwrap();
+
+        let timeout = Duration::from_millis(1);
+        assert_eq!(None, interface.poll_event(timeout).unwrap());
+
+        unsafe { libc::raise(libc::SIGWINCH) };
+        assert_eq!(
+            Some(Event::Resize(Vector::new(80, 24))),
+            interface.poll_event(timeout).unwrap()
+        );
+        assert_eq!(None, interface.poll_event(timeout).unwrap());
+
+        unsafe { libc::raise(libc::SIGTERM) };
+        unsafe { libc::raise(libc::SIGINT) };
+        assert_eq!(Event::Terminate, interface.read_event().unwrap());
+        assert_eq!(None, interface.poll_event(timeout).unwrap());
+
+        interface.exit().unwrap();
+    }
+}
@@ -1,93 +1,480 @@
-use std::collections::{BTreeMap, BTreeSet};
-
-use crate::{Position, Style};
-
-/// A cell in the terminal's column/line grid composed of text and optional style.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg(feature = "std")]
+use alloc::format;
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use core::hash::Hasher;
+use core::{hash::Hash, ops::RangeInclusive};
+
+use crate::{pos, text::Widths, Position, Rect, Style};
+#[cfg(feature = "std")]
+use crate::{
+    text::{normalize, split_graphemes},
+    Vector,
+};
+
+/// The printable ASCII characters, from which single-character graphemes are borrowed rather than
+/// allocated.
+const PRINTABLE_ASCII: &str =
+    " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+
+/// A cell in the terminal's column/line grid composed of text, an optional base style, and an
+/// optional overlay style composed on top of it.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct Cell {
-    grapheme: String,
+    grapheme: Cow<'static, str>,
     style: Option<Style>,
+    overlay: Option<Style>,
 }
 
 impl Cell {
+    /// Create a new cell with the specified grapheme and optional style. Printable ASCII
+    /// graphemes aren't allocated.
+    pub(crate) fn new(grapheme: &str, style: Option<Style>) -> Cell {
+        let grapheme = match grapheme.as_bytes() {
+            [byte @ b' '..=b'~'] => {
+                let index = (byte - b' ') as usize;
+                Cow::Borrowed(&PRINTABLE_ASCII[index..index + 1])
+            }
+            _ => Cow::Owned(grapheme.to_string()),
+        };
+
+        Cell {
+            grapheme,
+            style,
+            overlay: None,
+        }
+    }
+
+    /// Create a new cell with the specified static grapheme and optional style, without
+    /// allocating.
+    #[cfg(feature = "std")]
+    pub(crate) fn new_static(grapheme: &'static str, style: Option<Style>) -> Cell {
+        Cell {
+            grapheme: Cow::Borrowed(grapheme),
+            style,
+            overlay: None,
+        }
+    }
+
     /// This cell's text content.
     pub(crate) fn grapheme(&self) -> &str {
         &self.grapheme
     }
 
-    /// If available, this cell's styling.
+    /// If available, this cell's base styling.
+    #[cfg(feature = "std")]
     pub(crate) fn style(&self) -> Option<&Style> {
         self.style.as_ref()
     }
+
+    /// If available, this cell's styling as rendered, with its overlay composed on its base.
+    pub(crate) fn rendered_style(&self) -> Option<Style> {
+        match (self.style, self.overlay) {
+            (Some(style), Some(overlay)) => Some(style.overlay(&overlay)),
+            (style, overlay) => style.or(overlay),
+        }
+    }
+
+    /// The number of columns this cell's grapheme occupies, as the terminal displays it.
+    pub(crate) fn width(&self, widths: &Widths) -> u16 {
+        widths.grapheme_width(&self.grapheme)
+    }
 }
 
 /// The terminal interface's contents with comparison capabilities.
 #[derive(Clone)]
 pub(crate) struct State {
     cells: BTreeMap<Position, Cell>,
-    dirty: BTreeSet<Position>,
+    dirty: DirtyRows,
+    /// How many columns the terminal displays the cells' graphemes with.
+    widths: Widths,
 }
 
 impl State {
     /// Initialize a new, empty terminal state.
     pub(crate) fn new() -> State {
+        State::with_widths(Widths::default())
+    }
+
+    /// Initialize a new, empty terminal state whose graphemes are displayed with the widths.
+    pub(crate) fn with_widths(widths: Widths) -> State {
         State {
             cells: BTreeMap::new(),
-            dirty: BTreeSet::new(),
+            dirty: DirtyRows::default(),
+            widths,
+        }
+    }
+
+    /// How many columns the terminal displays this state's graphemes with.
+    #[cfg(feature = "std")]
+    pub(crate) fn widths(&self) -> &Widths {
+        &self.widths
+    }
+
+    /// Update how many columns the terminal displays this state's graphemes with. Cells aren't
+    /// moved; see [State::correct_width].
+    #[cfg(feature = "std")]
+    pub(crate) fn set_widths(&mut self, widths: Widths) {
+        self.widths = widths;
+    }
+
+    /// Create a state from a block of text, placing each line's graphemes from the first column of
+    /// consecutive lines. Spaces are kept verbatim as cells and tabs are expanded to spaces up to
+    /// stops of the specified width. All of the state's cells are dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn from_text(text: &str, tab_width: u16, widths: Widths) -> State {
+        let mut state = State::with_widths(widths);
+
+        for (line, text) in (0..).zip(normalize(text).lines()) {
+            let mut column: u16 = 0;
+            for grapheme in split_graphemes(text) {
+                if grapheme == "\t" {
+                    let tab_length = match tab_width {
+                        0 => 0,
+                        _ => tab_width - column % tab_width,
+                    };
+
+                    for _ in 0..tab_length {
+                        state.set_text(pos!(column, line), " ");
+                        column = column.saturating_add(1);
+                    }
+
+                    continue;
+                }
+
+                state.set_text(pos!(column, line), grapheme);
+                column = column.saturating_add(state.widths.grapheme_width(grapheme));
+            }
         }
+
+        state
     }
 
     /// Update a particular cell's grapheme.
     pub(crate) fn set_text(&mut self, position: Position, grapheme: &str) {
-        self.handle_cell_update(position, grapheme, None);
+        self.set_cell(position, Cell::new(grapheme, None));
     }
 
     /// Update a particular cell's grapheme and styling.
     pub(crate) fn set_styled_text(&mut self, position: Position, grapheme: &str, style: Style) {
-        self.handle_cell_update(position, grapheme, Some(style));
+        self.set_cell(position, Cell::new(grapheme, Some(style)));
     }
 
     /// Updates state and queues dirtied positions, if they've changed.
-    fn handle_cell_update(&mut self, position: Position, grapheme: &str, style: Option<Style>) {
-        let new_cell = Cell {
-            grapheme: grapheme.to_string(),
-            style,
-        };
-
+    pub(crate) fn set_cell(&mut self, position: Position, new_cell: Cell) {
         // If this cell is unchanged, do not mark it dirty
         if Some(&new_cell) == self.cells.get(&position) {
             return;
         }
 
+        // A preceding wide cell overlapping this position is broken by this update
+        if let Some(previous_position) = position.checked_translate_signed(-1, 0) {
+            let previous_cell = self.cells.get(&previous_position);
+            if previous_cell.is_some_and(|cell| cell.width(&self.widths) > 1) {
+                self.cells.remove(&previous_position);
+                self.dirty.insert(previous_position);
+            }
+        }
+
+        // Positions covered by a previous wide cell but not the new one are left empty
+        let previous_width = self
+            .cells
+            .get(&position)
+            .map_or(1, |cell| cell.width(&self.widths));
+        for offset in new_cell.width(&self.widths).max(1)..previous_width {
+            if let Some(column) = position.x().checked_add(offset) {
+                self.dirty.insert(Position::new(column, position.y()));
+            }
+        }
+
+        // Positions covered by a wide cell are rendered by it and hold no content of their own
+        for offset in 1..new_cell.width(&self.widths) {
+            if let Some(column) = position.x().checked_add(offset) {
+                let covered_position = Position::new(column, position.y());
+                self.dirty.remove(&covered_position);
+
+                // A covered wide cell may itself extend beyond the new cell, leaving it empty
+                let covered_width = self
+                    .cells
+                    .remove(&covered_position)
+                    .map_or(1, |cell| cell.width(&self.widths));
+                for covered_offset in new_cell.width(&self.widths) - offset..covered_width {
+                    if let Some(column) = column.checked_add(covered_offset) {
+                        self.dirty.insert(Position::new(column, position.y()));
+                    }
+                }
+            }
+        }
+
         self.dirty.insert(position);
         self.cells.insert(position, new_cell);
     }
 
+    /// Shifts the cells following each occurrence of the grapheme along its line by the difference
+    /// between its width and the width it was staged with, such as after measuring how the
+    /// terminal displays it, marking changed positions dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn correct_width(&mut self, grapheme: &str, staged_width: u16) {
+        let delta = i32::from(self.widths.grapheme_width(grapheme)) - i32::from(staged_width);
+        if delta == 0 {
+            return;
+        }
+
+        let lines: BTreeSet<u16> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| cell.grapheme() == grapheme)
+            .map(|(position, _)| position.y())
+            .collect();
+
+        for line in lines {
+            let cells: Vec<(Position, Cell)> = self
+                .get_line(line)
+                .map(|(position, cell)| (position, cell.clone()))
+                .collect();
+            self.clear_line(line);
+
+            let mut shift = 0;
+            for (position, cell) in cells {
+                let column = i32::from(position.x()) + shift;
+                if cell.grapheme() == grapheme {
+                    shift += delta;
+                }
+
+                if let Ok(column) = u16::try_from(column) {
+                    self.set_cell(Position::new(column, line), cell);
+                }
+            }
+        }
+    }
+
+    /// Copies the other state's cells into this state, marking changed positions dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn set_cells(&mut self, other: &State) {
+        for (position, cell) in &other.cells {
+            if self.cells.get(position) != Some(cell) {
+                self.set_cell(*position, cell.clone());
+            }
+        }
+    }
+
+    /// A copy of this state's cells within the specified rectangle, with none dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn region(&self, rect: Rect) -> State {
+        let cells = self
+            .cells
+            .iter()
+            .filter(|(position, _)| rect.contains(**position))
+            .map(|(position, cell)| (*position, cell.clone()))
+            .collect();
+
+        State {
+            cells,
+            dirty: DirtyRows::default(),
+            widths: self.widths.clone(),
+        }
+    }
+
+    /// Replaces the cells within the specified rectangle with the region's, marking changed
+    /// positions dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn restore_region(&mut self, rect: Rect, region: &State) {
+        self.handle_cell_clears(|position| {
+            rect.contains(**position) && !region.cells.contains_key(position)
+        });
+        self.set_cells(region);
+    }
+
+    /// A copy of this state's cells, with none dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn snapshot(&self) -> State {
+        State {
+            cells: self.cells.clone(),
+            dirty: DirtyRows::default(),
+            widths: self.widths.clone(),
+        }
+    }
+
+    /// Whether this state has the same cells as the other, regardless of which are dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn has_same_cells(&self, other: &State) -> bool {
+        self.cells == other.cells
+    }
+
+    /// Replaces all cells with the snapshot's, marking changed positions dirty.
+    #[cfg(feature = "std")]
+    pub(crate) fn restore(&mut self, snapshot: &State) {
+        self.handle_cell_clears(|position| !snapshot.cells.contains_key(position));
+        self.set_cells(snapshot);
+    }
+
+    /// Clears the cells at the specified positions.
+    #[cfg(feature = "std")]
+    pub(crate) fn clear_positions(&mut self, positions: &BTreeSet<Position>) {
+        self.handle_cell_clears(|position| positions.contains(position));
+    }
+
     /// Clears all cells in the specified line.
     pub(crate) fn clear_line(&mut self, line: u16) {
         self.handle_cell_clears(|position| position.y() == line);
     }
 
     /// Clears cells in the line from the specified position.
+    #[cfg(feature = "std")]
     pub(crate) fn clear_rest_of_line(&mut self, from: Position) {
         self.handle_cell_clears(|position| position.y() == from.y() && position.x() >= from.x());
     }
 
     /// Clears cells in the interface from the specified position.
+    #[cfg(feature = "std")]
     pub(crate) fn clear_rest_of_interface(&mut self, from: Position) {
         self.handle_cell_clears(|position| *position >= &from);
     }
 
+    /// Clears all cells in the specified column.
+    #[cfg(feature = "std")]
+    pub(crate) fn clear_column(&mut self, column: u16) {
+        self.handle_cell_clears(|position| position.x() == column);
+    }
+
+    /// Clears all cells within the specified rectangle.
+    pub(crate) fn clear_rect(&mut self, rect: Rect) {
+        self.handle_cell_clears(|position| rect.contains(**position));
+    }
+
+    /// Updates the overlay of cells within the rectangle, marking changed cells dirty. Empty
+    /// positions are unaffected.
+    #[cfg(feature = "std")]
+    pub(crate) fn set_overlay(&mut self, rect: Rect, overlay: Option<Style>) {
+        for (position, cell) in self.cells.iter_mut() {
+            if rect.contains(*position) && cell.overlay != overlay {
+                cell.overlay = overlay;
+                self.dirty.insert(*position);
+            }
+        }
+    }
+
+    /// Fills a vertical run of cells starting from the specified position with a grapheme.
+    #[cfg(feature = "std")]
+    pub(crate) fn draw_vline(
+        &mut self,
+        from: Position,
+        length: u16,
+        grapheme: &str,
+        style: Option<Style>,
+    ) {
+        for offset in 0..length {
+            let y = match from.y().checked_add(offset) {
+                Some(y) => y,
+                None => break,
+            };
+
+            self.set_cell(Position::new(from.x(), y), Cell::new(grapheme, style));
+        }
+    }
+
+    /// Inserts an empty line at the specified line, shifting it and subsequent lines down.
+    #[cfg(feature = "std")]
+    pub(crate) fn insert_line(&mut self, line: u16) {
+        self.handle_line_shift(line, |y| if y < u16::MAX { Some(y + 1) } else { None });
+    }
+
+    /// Deletes the specified line, shifting subsequent lines up.
+    #[cfg(feature = "std")]
+    pub(crate) fn delete_line(&mut self, line: u16) {
+        self.handle_line_shift(line, |y| if y > line { Some(y - 1) } else { None });
+    }
+
+    /// Moves cells on or after the specified line to the lines produced by the shift function,
+    /// discarding cells for which it produces none, and marks changed positions dirty.
+    #[cfg(feature = "std")]
+    fn handle_line_shift<F: Fn(u16) -> Option<u16>>(&mut self, line: u16, shift: F) {
+        let affected_positions: Vec<Position> = self
+            .cells
+            .keys()
+            .filter(|position| position.y() >= line)
+            .copied()
+            .collect();
+
+        let mut previous_cells = BTreeMap::new();
+        for position in affected_positions {
+            if let Some(cell) = self.cells.remove(&position) {
+                previous_cells.insert(position, cell);
+            }
+        }
+
+        for (position, cell) in &previous_cells {
+            if let Some(y) = shift(position.y()) {
+                self.cells
+                    .insert(Position::new(position.x(), y), cell.clone());
+            }
+        }
+
+        let mut candidate_positions: BTreeSet<Position> = previous_cells.keys().copied().collect();
+        candidate_positions.extend(self.cells.keys().filter(|position| position.y() >= line));
+
+        for position in candidate_positions {
+            if previous_cells.get(&position) != self.cells.get(&position) {
+                self.dirty.insert(position);
+            }
+        }
+    }
+
     /// Clears cells matching the specified predicate, marking them dirtied for re-render.
     fn handle_cell_clears<P: FnMut(&&Position) -> bool>(&mut self, filter_predicate: P) {
         let cells = self.cells.keys();
         let deleted_cells = cells.filter(filter_predicate);
-        let cell_positions: Vec<Position> = deleted_cells.map(|position| *position).collect();
+        let cell_positions: Vec<Position> = deleted_cells.copied().collect();
 
         for position in cell_positions {
-            self.cells.remove(&position);
-            self.dirty.insert(position);
+            // Positions a cleared wide cell covered are left empty
+            let width = self
+                .cells
+                .remove(&position)
+                .map_or(1, |cell| cell.width(&self.widths));
+            for offset in 0..width.max(1) {
+                if let Some(column) = position.x().checked_add(offset) {
+                    self.dirty.insert(Position::new(column, position.y()));
+                }
+            }
+        }
+    }
+
+    /// Check this state's invariants, describing the first which is violated: positions covered
+    /// by a wide cell hold no cell of their own and aren't dirty, since rendering them would
+    /// overwrite the wide cell.
+    #[cfg(feature = "std")]
+    pub(crate) fn check_invariants(&self) -> Result<(), String> {
+        let dirty: BTreeSet<Position> = self.dirty_positions().into_iter().collect();
+        for (position, cell) in &self.cells {
+            for offset in 1..cell.width(&self.widths) {
+                let Some(column) = position.x().checked_add(offset) else {
+                    break;
+                };
+
+                let covered_position = pos!(column, position.y());
+                if self.cells.contains_key(&covered_position) {
+                    return Err(format!(
+                        "cell at {:?} is covered by the wide cell at {:?}",
+                        covered_position, position
+                    ));
+                }
+
+                if dirty.contains(&covered_position) {
+                    return Err(format!(
+                        "dirty position {:?} is covered by the wide cell at {:?}",
+                        covered_position, position
+                    ));
+                }
+            }
         }
+
+        Ok(())
     }
 
     /// Marks any dirty cells as clean.
@@ -95,30 +482,264 @@ impl State {
         self.dirty.clear()
     }
 
-    /// Create an iterator for this state's dirty cells.
-    pub(crate) fn dirty_iter(&self) -> StateIter {
-        StateIter::new(self, self.dirty.clone().into_iter().collect())
+    /// Marks the specified positions dirty for re-render. Positions covered by a preceding wide
+    /// cell are rendered with it, so are skipped.
+    #[cfg(feature = "std")]
+    pub(crate) fn mark_dirty(&mut self, positions: Vec<Position>) {
+        for position in positions {
+            let previous_position = position.checked_translate_signed(-1, 0);
+            let is_covered = previous_position.is_some_and(|previous_position| {
+                self.cells
+                    .get(&previous_position)
+                    .is_some_and(|cell| cell.width(&self.widths) > 1)
+            });
+            if !is_covered {
+                self.dirty.insert(position);
+            }
+        }
+    }
+
+    /// Whether any cells are dirty.
+    pub(crate) fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Create an iterator for this state's dirty cells. Cells are visited in row-major order: each
+    /// line's runs of consecutive dirty cells from left to right, before the following line's.
+    #[cfg(feature = "std")]
+    pub(crate) fn dirty_iter(&self) -> StateIter<'_> {
+        StateIter::new(self, self.dirty_positions())
+    }
+
+    /// The positions of this state's dirty cells, in the order they're visited by
+    /// [State::dirty_iter].
+    pub(crate) fn dirty_positions(&self) -> Vec<Position> {
+        self.dirty
+            .runs()
+            .flat_map(|(line, columns)| columns.map(move |column| pos!(column, line)))
+            .collect()
+    }
+
+    /// Iterate over this state's cells in position order.
+    #[cfg(feature = "std")]
+    pub(crate) fn cells(&self) -> impl Iterator<Item = (Position, &Cell)> {
+        self.cells.iter().map(|(position, cell)| (*position, cell))
+    }
+
+    /// Iterate over the cells on the specified line.
+    pub(crate) fn get_line(&self, line: u16) -> impl Iterator<Item = (Position, &Cell)> {
+        self.cells
+            .range(pos!(0, line)..=pos!(u16::MAX, line))
+            .map(|(position, cell)| (*position, cell))
+    }
+
+    /// The text of the specified line, with empty columns between its cells as spaces.
+    pub(crate) fn line_text(&self, line: u16) -> String {
+        let mut text = String::new();
+        let mut column = 0;
+        for (position, cell) in self.get_line(line) {
+            while column < position.x() {
+                text.push(' ');
+                column += 1;
+            }
+
+            text.push_str(cell.grapheme());
+            column = position.x().saturating_add(cell.width(&self.widths));
+        }
+
+        text
+    }
+
+    /// A hash of the cells on the specified line, for cheaply comparing it with another frame's.
+    #[cfg(feature = "std")]
+    pub(crate) fn line_hash(&self, line: u16) -> u64 {
+        let mut hasher = LineHasher::default();
+        for (position, cell) in self.get_line(line) {
+            position.x().hash(&mut hasher);
+            cell.hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// The cells occupied by each occurrence of the pattern in this state's text, searched line
+    /// by line. Empty columns between cells match spaces, and matches must start and end on
+    /// grapheme boundaries.
+    #[cfg(feature = "std")]
+    pub(crate) fn find_matches(&self, pattern: &str) -> Vec<Rect> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches = Vec::new();
+        let mut cells = self.cells.iter().peekable();
+        while let Some((first, _)) = cells.peek() {
+            let line = first.y();
+
+            // Each grapheme's byte offset in the line's text and the columns it spans
+            let mut text = String::new();
+            let mut columns = BTreeMap::new();
+            let mut column = 0;
+            while let Some((position, cell)) = cells.next_if(|(p, _)| p.y() == line) {
+                while column < position.x() {
+                    columns.insert(text.len(), (column, column + 1));
+                    text.push(' ');
+                    column += 1;
+                }
+
+                let end = position.x().saturating_add(cell.width(&self.widths).max(1));
+                columns.insert(text.len(), (position.x(), end));
+                text.push_str(cell.grapheme());
+                column = end;
+            }
+            columns.insert(text.len(), (column, column));
+
+            for (start, matched) in text.match_indices(pattern) {
+                let end = start + matched.len();
+                let last = columns.range(..end).next_back().map(|(_, columns)| columns);
+                if let (Some((from, _)), Some((_, to)), true) =
+                    (columns.get(&start), last, columns.contains_key(&end))
+                {
+                    let origin = pos!(*from, line);
+                    matches.push(Rect::new(origin, Vector::new(to - from, 1)));
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Get the cell at the specified position, if any.
+    pub(crate) fn get_cell(&self, position: Position) -> Option<&Cell> {
+        self.cells.get(&position)
     }
 
     /// Get the last cell's position.
+    #[cfg(feature = "std")]
     pub(crate) fn get_last_position(&self) -> Option<Position> {
-        self.cells
-            .keys()
-            .last()
-            .and_then(|position| Some(*position))
+        self.cells.keys().last().copied()
+    }
+}
+
+/// A 64-bit FNV-1a hasher for line contents, which hashes their short graphemes more cheaply
+/// than the standard library's SipHash.
+#[cfg(feature = "std")]
+struct LineHasher {
+    hash: u64,
+}
+
+#[cfg(feature = "std")]
+impl Default for LineHasher {
+    fn default() -> Self {
+        LineHasher {
+            hash: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Hasher for LineHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// The positions of a state's dirty cells, grouped by line.
+#[derive(Debug, Clone, Default)]
+struct DirtyRows {
+    rows: BTreeMap<u16, BTreeSet<u16>>,
+}
+
+impl DirtyRows {
+    /// Mark the position dirty.
+    fn insert(&mut self, position: Position) {
+        self.rows
+            .entry(position.y())
+            .or_default()
+            .insert(position.x());
+    }
+
+    /// Mark the position clean.
+    fn remove(&mut self, position: &Position) {
+        if let Some(columns) = self.rows.get_mut(&position.y()) {
+            columns.remove(&position.x());
+            if columns.is_empty() {
+                self.rows.remove(&position.y());
+            }
+        }
+    }
+
+    /// Mark all positions clean.
+    fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Whether no positions are dirty.
+    fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Iterate over each line's runs of consecutive dirty columns, in row-major order.
+    fn runs(&self) -> impl Iterator<Item = (u16, RangeInclusive<u16>)> + '_ {
+        self.rows.iter().flat_map(|(line, columns)| {
+            let mut runs: Vec<RangeInclusive<u16>> = Vec::new();
+            for column in columns.iter().copied() {
+                match runs.last_mut() {
+                    Some(run) if run.end().checked_add(1) == Some(column) => {
+                        *run = *run.start()..=column;
+                    }
+                    _ => runs.push(column..=column),
+                }
+            }
+
+            runs.into_iter().map(move |run| (*line, run))
+        })
+    }
+}
+
+impl Extend<Position> for DirtyRows {
+    fn extend<T: IntoIterator<Item = Position>>(&mut self, positions: T) {
+        for position in positions {
+            self.insert(position);
+        }
+    }
+}
+
+impl IntoIterator for DirtyRows {
+    type Item = Position;
+    type IntoIter = alloc::vec::IntoIter<Position>;
+
+    /// Consume the positions in row-major order.
+    fn into_iter(self) -> Self::IntoIter {
+        let positions: Vec<Position> = self
+            .rows
+            .into_iter()
+            .flat_map(|(line, columns)| columns.into_iter().map(move |column| pos!(column, line)))
+            .collect();
+
+        positions.into_iter()
     }
 }
 
 /// Iterates through a subset of cells in the state.
+#[cfg(feature = "std")]
 pub(crate) struct StateIter<'a> {
     state: &'a State,
     positions: Vec<Position>,
     index: usize,
 }
 
+#[cfg(feature = "std")]
 impl StateIter<'_> {
     /// Create a new state iterator with the specified positions starting from the first position.
-    fn new(state: &State, positions: Vec<Position>) -> StateIter {
+    fn new(state: &State, positions: Vec<Position>) -> StateIter<'_> {
         StateIter {
             state,
             positions,
@@ -127,17 +748,14 @@ impl StateIter<'_> {
     }
 }
 
-impl<'a> Iterator for StateIter<'_> {
+#[cfg(feature = "std")]
+impl Iterator for StateIter<'_> {
     type Item = (Position, Option<Cell>);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.index < self.positions.len() {
             let position = self.positions[self.index];
-            let cell = self
-                .state
-                .cells
-                .get(&position)
-                .and_then(|cell| Some(cell.clone()));
+            let cell = self.state.cells.get(&position).cloned();
 
             self.index += 1;
             Some((position, cell))
@@ -149,10 +767,36 @@ impl<'a> Iterator for StateIter<'_> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{pos, Color, Position, Style};
+    #[cfg(feature = "std")]
+    use alloc::borrow::Cow;
+    use alloc::{vec, vec::Vec};
+
+    #[cfg(feature = "std")]
+    use crate::text::Widths;
+    use crate::{pos, Color, Position, Rect, Style, Vector};
 
     use super::{Cell, State};
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn cell_borrowed_graphemes() {
+        for grapheme in [" ", "a", "~", "\\", "\""] {
+            let cell = Cell::new(grapheme, None);
+            assert!(matches!(cell.grapheme, Cow::Borrowed(_)));
+            assert_eq!(grapheme, cell.grapheme());
+        }
+
+        for grapheme in ["é", "日", "ab", "\t"] {
+            let cell = Cell::new(grapheme, None);
+            assert!(matches!(cell.grapheme, Cow::Owned(_)));
+            assert_eq!(grapheme, cell.grapheme());
+        }
+
+        let cell = Cell::new_static("─", None);
+        assert!(matches!(cell.grapheme, Cow::Borrowed(_)));
+        assert_eq!(Cell::new("─", None), cell);
+    }
+
     #[test]
     fn state_set_text() {
         let mut state = State::new();
@@ -164,22 +808,25 @@ mod tests {
         assert_eq!(3, state.cells.len());
         assert_eq!(
             Cell {
-                grapheme: "A".to_string(),
-                style: None
+                grapheme: "A".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(0, 0)]
         );
         assert_eq!(
             Cell {
-                grapheme: "B".to_string(),
-                style: None
+                grapheme: "B".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(2, 0)]
         );
         assert_eq!(
             Cell {
-                grapheme: "C".to_string(),
-                style: None
+                grapheme: "C".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(1, 1)]
         );
@@ -202,22 +849,25 @@ mod tests {
         assert_eq!(3, state.cells.len());
         assert_eq!(
             Cell {
-                grapheme: "X".to_string(),
+                grapheme: "X".into(),
                 style: Some(Style::new().set_bold(true)),
+                overlay: None,
             },
             state.cells[&pos!(0, 0)],
         );
         assert_eq!(
             Cell {
-                grapheme: "Y".to_string(),
+                grapheme: "Y".into(),
                 style: Some(Style::new().set_italic(true)),
+                overlay: None,
             },
             state.cells[&pos!(1, 3)],
         );
         assert_eq!(
             Cell {
-                grapheme: "Z".to_string(),
+                grapheme: "Z".into(),
                 style: Some(Style::new().set_foreground(Color::Blue)),
+                overlay: None,
             },
             state.cells[&pos!(2, 2)],
         );
@@ -242,29 +892,33 @@ mod tests {
         assert_eq!(4, state.cells.len());
         assert_eq!(
             Cell {
-                grapheme: "A".to_string(),
-                style: None
+                grapheme: "A".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(0, 0)]
         );
         assert_eq!(
             Cell {
-                grapheme: "B".to_string(),
-                style: None
+                grapheme: "B".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(2, 0)]
         );
         assert_eq!(
             Cell {
-                grapheme: "C".to_string(),
-                style: None
+                grapheme: "C".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(1, 1)]
         );
         assert_eq!(
             Cell {
-                grapheme: "D".to_string(),
-                style: None
+                grapheme: "D".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(3, 1)]
         );
@@ -291,35 +945,38 @@ mod tests {
         assert_eq!(3, state.cells.len());
         assert_eq!(
             Cell {
-                grapheme: "A".to_string(),
-                style: None
+                grapheme: "A".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(0, 0)]
         );
         assert_eq!(
             Cell {
-                grapheme: "B".to_string(),
-                style: None
+                grapheme: "B".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(2, 0)]
         );
         assert_eq!(
             Cell {
-                grapheme: "C".to_string(),
-                style: None
+                grapheme: "C".into(),
+                style: None,
+                overlay: None,
             },
             state.cells[&pos!(1, 1)]
         );
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn state_clear_rest_of_line() {
         let mut state = State::new();
 
         let content = ["ABC", "DEF", "GHI"];
 
-        for row in 0..content.len() {
-            let text = content[row];
+        for (row, text) in content.iter().enumerate() {
             for column in 0..text.len() {
                 state.set_text(
                     pos!(column as u16, row as u16),
@@ -345,14 +1002,14 @@ mod tests {
         assert_eq!(1, line_two_cell_count);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn state_clear_rest_of_interface() {
         let mut state = State::new();
 
         let content = ["ABC", "DEF", "GHI"];
 
-        for row in 0..content.len() {
-            let text = content[row];
+        for (row, text) in content.iter().enumerate() {
             for column in 0..text.len() {
                 state.set_text(
                     pos!(column as u16, row as u16),
@@ -378,6 +1035,25 @@ mod tests {
         assert_eq!(pos!(2, 2), dirty_positions[4]);
     }
 
+    #[test]
+    fn state_dirty_runs() {
+        let mut state = State::new();
+
+        state.set_text(pos!(4, 1), "A");
+        state.set_text(pos!(2, 0), "B");
+        state.set_text(pos!(5, 1), "C");
+        state.set_text(pos!(3, 0), "D");
+        state.set_text(pos!(0, 1), "E");
+
+        let runs: Vec<_> = state.dirty.runs().collect();
+        assert_eq!(vec![(0, 2..=3), (1, 0..=0), (1, 4..=5)], runs);
+
+        state.clear_dirty();
+        assert!(!state.is_dirty());
+        assert_eq!(0, state.dirty.runs().count());
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn state_dirty_iter() {
         let mut state = State::new();
@@ -395,8 +1071,9 @@ mod tests {
             Some((
                 pos!(2, 0),
                 Some(Cell {
-                    grapheme: "B".to_string(),
-                    style: None
+                    grapheme: "B".into(),
+                    style: None,
+                    overlay: None,
                 })
             )),
             iter.next()
@@ -406,8 +1083,9 @@ mod tests {
             Some((
                 pos!(0, 2),
                 Some(Cell {
-                    grapheme: "D".to_string(),
-                    style: None
+                    grapheme: "D".into(),
+                    style: None,
+                    overlay: None,
                 })
             )),
             iter.next()
@@ -415,6 +1093,7 @@ mod tests {
         assert_eq!(None, iter.next());
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn state_get_last_position() {
         let mut state = State::new();
@@ -426,4 +1105,335 @@ mod tests {
 
         assert_eq!(pos!(3, 1), state.get_last_position().unwrap());
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_insert_line() {
+        let mut state = State::new();
+
+        state.set_text(pos!(0, 0), "A");
+        state.set_text(pos!(0, 1), "B");
+        state.set_text(pos!(1, 1), "C");
+        state.set_text(pos!(0, 2), "D");
+        state.clear_dirty();
+
+        state.insert_line(1);
+
+        assert_eq!(4, state.cells.len());
+        assert_eq!("A", state.cells[&pos!(0, 0)].grapheme());
+        assert_eq!("B", state.cells[&pos!(0, 2)].grapheme());
+        assert_eq!("C", state.cells[&pos!(1, 2)].grapheme());
+        assert_eq!("D", state.cells[&pos!(0, 3)].grapheme());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(5, dirty_positions.len());
+        assert_eq!(pos!(0, 1), dirty_positions[0]);
+        assert_eq!(pos!(1, 1), dirty_positions[1]);
+        assert_eq!(pos!(0, 2), dirty_positions[2]);
+        assert_eq!(pos!(1, 2), dirty_positions[3]);
+        assert_eq!(pos!(0, 3), dirty_positions[4]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_delete_line() {
+        let mut state = State::new();
+
+        state.set_text(pos!(0, 0), "A");
+        state.set_text(pos!(0, 1), "B");
+        state.set_text(pos!(1, 1), "C");
+        state.set_text(pos!(0, 2), "D");
+        state.clear_dirty();
+
+        state.delete_line(1);
+
+        assert_eq!(2, state.cells.len());
+        assert_eq!("A", state.cells[&pos!(0, 0)].grapheme());
+        assert_eq!("D", state.cells[&pos!(0, 1)].grapheme());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(3, dirty_positions.len());
+        assert_eq!(pos!(0, 1), dirty_positions[0]);
+        assert_eq!(pos!(1, 1), dirty_positions[1]);
+        assert_eq!(pos!(0, 2), dirty_positions[2]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_clear_column() {
+        let mut state = State::new();
+
+        let content = ["ABC", "DEF", "GHI"];
+
+        for (row, text) in content.iter().enumerate() {
+            for column in 0..text.len() {
+                state.set_text(
+                    pos!(column as u16, row as u16),
+                    text.get(column..column + 1).unwrap(),
+                );
+            }
+        }
+
+        state.clear_dirty();
+        state.clear_column(1);
+
+        assert_eq!(6, state.cells.len());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(3, dirty_positions.len());
+        assert_eq!(pos!(1, 0), dirty_positions[0]);
+        assert_eq!(pos!(1, 1), dirty_positions[1]);
+        assert_eq!(pos!(1, 2), dirty_positions[2]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_set_overlay() {
+        let mut state = State::new();
+        let bold = Style::new().set_bold(true);
+        state.set_styled_text(pos!(0, 0), "A", bold);
+        state.set_text(pos!(1, 0), "B");
+        state.set_text(pos!(0, 1), "C");
+        state.clear_dirty();
+
+        let overlay = Style::new().set_background(Color::Blue);
+        state.set_overlay(Rect::new(pos!(0, 0), Vector::new(3, 1)), Some(overlay));
+
+        assert_eq!(3, state.cells.len());
+        assert_eq!(
+            Some(bold.overlay(&overlay)),
+            state.cells[&pos!(0, 0)].rendered_style()
+        );
+        assert_eq!(Some(overlay), state.cells[&pos!(1, 0)].rendered_style());
+        assert_eq!(None, state.cells[&pos!(0, 1)].rendered_style());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(vec![pos!(0, 0), pos!(1, 0)], dirty_positions);
+
+        state.clear_dirty();
+        state.set_overlay(Rect::new(pos!(0, 0), Vector::new(1, 1)), None);
+        assert_eq!(Some(&bold), state.cells[&pos!(0, 0)].style());
+        assert_eq!(Some(bold), state.cells[&pos!(0, 0)].rendered_style());
+        assert_eq!(1, state.dirty.clone().into_iter().count());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_find_matches() {
+        let mut state = State::new();
+        state.set_text(pos!(0, 0), "a");
+        state.set_text(pos!(1, 0), "b");
+        state.set_text(pos!(2, 0), "日");
+        state.set_text(pos!(4, 0), "a");
+        state.set_text(pos!(5, 0), "b");
+        state.set_text(pos!(1, 1), "a");
+        state.set_text(pos!(3, 1), "b");
+
+        let rect = |x, y, width| Rect::new(pos!(x, y), Vector::new(width, 1));
+        assert_eq!(vec![rect(0, 0, 2), rect(4, 0, 2)], state.find_matches("ab"));
+        assert_eq!(vec![rect(1, 0, 3)], state.find_matches("b日"));
+        assert_eq!(vec![rect(1, 1, 3)], state.find_matches("a b"));
+        assert_eq!(vec![rect(0, 1, 2)], state.find_matches(" a"));
+        assert!(state.find_matches("").is_empty());
+    }
+
+    #[test]
+    fn state_line_text() {
+        let mut state = State::new();
+        state.set_text(pos!(1, 0), "a");
+        state.set_text(pos!(2, 0), "日");
+        state.set_styled_text(pos!(5, 0), "b", Color::Red.as_style());
+        state.set_text(pos!(0, 2), "c");
+
+        assert_eq!(" a日 b", state.line_text(0));
+        assert_eq!("", state.line_text(1));
+        assert_eq!("c", state.line_text(2));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_correct_width() {
+        let heart = "❤\u{fe0f}";
+        let mut state = State::new();
+        state.set_text(pos!(0, 0), heart);
+        state.set_text(pos!(2, 0), "a");
+        state.set_text(pos!(3, 0), heart);
+        state.set_text(pos!(5, 0), "b");
+        state.set_text(pos!(0, 1), "c");
+        state.clear_dirty();
+
+        let mut widths = Widths::default();
+        widths.set_measured(heart, 1);
+        state.set_widths(widths);
+        state.correct_width(heart, 2);
+
+        let line: Vec<(Position, &str)> = state
+            .get_line(0)
+            .map(|(position, cell)| (position, cell.grapheme()))
+            .collect();
+        assert_eq!(
+            vec![
+                (pos!(0, 0), heart),
+                (pos!(1, 0), "a"),
+                (pos!(2, 0), heart),
+                (pos!(3, 0), "b")
+            ],
+            line
+        );
+        assert_eq!(Some(&Cell::new("c", None)), state.get_cell(pos!(0, 1)));
+        assert!(state.dirty_iter().all(|(position, _)| position.y() == 0));
+    }
+
+    #[test]
+    fn state_clear_rect() {
+        let mut state = State::new();
+
+        let content = ["ABC", "DEF", "GHI"];
+
+        for (row, text) in content.iter().enumerate() {
+            for column in 0..text.len() {
+                state.set_text(
+                    pos!(column as u16, row as u16),
+                    text.get(column..column + 1).unwrap(),
+                );
+            }
+        }
+
+        state.clear_dirty();
+        state.clear_rect(Rect::new(pos!(1, 1), Vector::new(2, 2)));
+
+        assert_eq!(5, state.cells.len());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(4, dirty_positions.len());
+        assert_eq!(pos!(1, 1), dirty_positions[0]);
+        assert_eq!(pos!(2, 1), dirty_positions[1]);
+        assert_eq!(pos!(1, 2), dirty_positions[2]);
+        assert_eq!(pos!(2, 2), dirty_positions[3]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_restore_region() {
+        let mut state = State::from_text("ABC\nDEF\nGHI", 8, Widths::default());
+        let rect = Rect::new(pos!(1, 1), Vector::new(2, 2));
+        let region = state.region(rect);
+        assert_eq!(4, region.cells.len());
+        assert!(!region.is_dirty());
+
+        state.set_text(pos!(1, 1), "x");
+        state.set_text(pos!(0, 0), "y");
+        state.clear_line(2);
+        state.set_text(pos!(3, 2), "z");
+        state.clear_dirty();
+
+        state.restore_region(rect, &region);
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(vec![pos!(1, 1), pos!(1, 2), pos!(2, 2)], dirty_positions);
+        assert_eq!("y", state.get_cell(pos!(0, 0)).unwrap().grapheme());
+        assert_eq!("E", state.get_cell(pos!(1, 1)).unwrap().grapheme());
+        assert_eq!("I", state.get_cell(pos!(2, 2)).unwrap().grapheme());
+        assert_eq!(None, state.get_cell(pos!(0, 2)));
+        assert_eq!("z", state.get_cell(pos!(3, 2)).unwrap().grapheme());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_draw_vline() {
+        let mut state = State::new();
+
+        state.set_text(pos!(2, 1), "|");
+        state.clear_dirty();
+
+        state.draw_vline(pos!(2, 0), 3, "|", None);
+
+        assert_eq!(3, state.cells.len());
+        assert_eq!("|", state.cells[&pos!(2, 0)].grapheme());
+        assert_eq!("|", state.cells[&pos!(2, 2)].grapheme());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(2, dirty_positions.len());
+        assert_eq!(pos!(2, 0), dirty_positions[0]);
+        assert_eq!(pos!(2, 2), dirty_positions[1]);
+    }
+
+    #[test]
+    fn state_set_wide_text() {
+        let mut state = State::new();
+
+        state.set_text(pos!(0, 0), "A");
+        state.set_text(pos!(1, 0), "B");
+        state.set_text(pos!(2, 0), "C");
+        state.clear_dirty();
+
+        state.set_text(pos!(0, 0), "漢");
+
+        assert_eq!(2, state.cells.len());
+        assert_eq!("漢", state.cells[&pos!(0, 0)].grapheme());
+        assert_eq!(2, state.cells[&pos!(0, 0)].width(&state.widths));
+        assert_eq!("C", state.cells[&pos!(2, 0)].grapheme());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(vec![pos!(0, 0)], dirty_positions);
+    }
+
+    #[test]
+    fn state_overwrite_wide_text_right_half() {
+        let mut state = State::new();
+
+        state.set_text(pos!(0, 0), "漢");
+        state.clear_dirty();
+
+        state.set_text(pos!(1, 0), "B");
+
+        assert_eq!(1, state.cells.len());
+        assert_eq!("B", state.cells[&pos!(1, 0)].grapheme());
+
+        let dirty_positions: Vec<_> = state.dirty.clone().into_iter().collect();
+        assert_eq!(vec![pos!(0, 0), pos!(1, 0)], dirty_positions);
+    }
+
+    #[test]
+    fn state_set_emoji_sequences() {
+        let mut state = State::new();
+
+        state.set_text(pos!(0, 0), "👍🏽");
+        state.set_text(pos!(2, 0), "🇺🇸");
+        state.set_text(pos!(4, 0), "👨\u{200d}👩\u{200d}👧");
+
+        assert_eq!(3, state.cells.len());
+        for position in [pos!(0, 0), pos!(2, 0), pos!(4, 0)] {
+            assert_eq!(2, state.cells[&position].width(&state.widths));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn state_from_text() {
+        let state = State::from_text("  ab  c\n\n\tx界y", 4, Widths::default());
+
+        assert_eq!(
+            vec![
+                (pos!(0, 0), " "),
+                (pos!(1, 0), " "),
+                (pos!(2, 0), "a"),
+                (pos!(3, 0), "b"),
+                (pos!(4, 0), " "),
+                (pos!(5, 0), " "),
+                (pos!(6, 0), "c"),
+                (pos!(0, 2), " "),
+                (pos!(1, 2), " "),
+                (pos!(2, 2), " "),
+                (pos!(3, 2), " "),
+                (pos!(4, 2), "x"),
+                (pos!(5, 2), "界"),
+                (pos!(7, 2), "y"),
+            ],
+            state
+                .cells()
+                .map(|(position, cell)| (position, cell.grapheme()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(14, state.dirty_iter().count());
+    }
 }
@@ -0,0 +1,257 @@
+use crate::{pos, text::Widths, Alignment, Interface, Position, Style};
+
+/// A segment of a status line.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct StatusItem {
+    name: String,
+    slot: Alignment,
+    priority: u8,
+    text: String,
+    style: Option<Style>,
+}
+
+/// A bar of named items rendered on the screen's last line, such as a mode indicator, file name,
+/// and cursor position. Items are placed in left, center, and right slots, separated by a space;
+/// when they don't all fit, the lowest-priority items are omitted.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Alignment, Color, Interface, StatusLine, Style};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let mut status = StatusLine::new().set_style(Style::new().set_background(Color::DarkGrey));
+/// status.set("mode", Alignment::Left, 10, "NORMAL");
+/// status.set("file", Alignment::Center, 5, "src/main.rs");
+/// status.set("position", Alignment::Right, 1, "12:4");
+///
+/// // Each frame, re-rendering if the items or terminal width changed
+/// status.render(&mut interface);
+/// interface.apply()?;
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StatusLine {
+    items: Vec<StatusItem>,
+    style: Option<Style>,
+    rendered: Option<(u16, Vec<(u16, StatusItem)>)>,
+}
+
+impl StatusLine {
+    /// Create a status line with no items.
+    pub fn new() -> StatusLine {
+        StatusLine {
+            items: Vec::new(),
+            style: None,
+            rendered: None,
+        }
+    }
+
+    /// Create a new status line with the specified style filling the line.
+    pub fn set_style(self, style: Style) -> StatusLine {
+        StatusLine {
+            style: Some(style),
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// This status line's style, if specified.
+    pub fn style(&self) -> Option<Style> {
+        self.style
+    }
+
+    /// Update the named item's text, placing it in the slot with the specified priority. Items
+    /// are ordered within a slot by when they were first set.
+    pub fn set(&mut self, name: &str, slot: Alignment, priority: u8, text: &str) {
+        self.update(name, slot, priority, text, None);
+    }
+
+    /// Update the named item's styled text, placing it in the slot with the specified priority.
+    /// Items are ordered within a slot by when they were first set.
+    pub fn set_styled(
+        &mut self,
+        name: &str,
+        slot: Alignment,
+        priority: u8,
+        text: &str,
+        style: Style,
+    ) {
+        self.update(name, slot, priority, text, Some(style));
+    }
+
+    /// Remove the named item, returning whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let count = self.items.len();
+        self.items.retain(|item| item.name != name);
+        self.items.len() != count
+    }
+
+    /// Stage the status line on the interface's last line if its items or the terminal's width
+    /// changed since it was last rendered, returning whether it was staged.
+    pub fn render(&mut self, interface: &mut Interface) -> bool {
+        let width = interface.size().x();
+        let line = interface.scroll_offset() + interface.size().y().saturating_sub(1);
+        let layout = self.layout(width, interface.widths());
+
+        if self.rendered.as_ref() == Some(&(line, layout.clone())) {
+            return false;
+        }
+
+        interface.clear_line(line);
+        if let Some(style) = self.style {
+            interface.set_styled(pos!(0, line), &" ".repeat(width.into()), style);
+        }
+
+        for (column, item) in layout.iter() {
+            match item.style.or(self.style) {
+                Some(style) => interface.set_styled(pos!(*column, line), &item.text, style),
+                None => interface.set(pos!(*column, line), &item.text),
+            };
+        }
+
+        self.rendered = Some((line, layout));
+        true
+    }
+
+    /// Add or replace an item.
+    fn update(
+        &mut self,
+        name: &str,
+        slot: Alignment,
+        priority: u8,
+        text: &str,
+        style: Option<Style>,
+    ) {
+        let item = StatusItem {
+            name: name.to_string(),
+            slot,
+            priority,
+            text: text.lines().next().unwrap_or_default().to_string(),
+            style,
+        };
+
+        match self.items.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = item,
+            None => self.items.push(item),
+        }
+    }
+
+    /// The items shown within the width and the columns they start at. The lowest-priority items
+    /// are omitted until the rest fit, with later items omitted first among equal priorities.
+    fn layout(&self, width: u16, widths: &Widths) -> Vec<(u16, StatusItem)> {
+        let mut shown: Vec<&StatusItem> = self.items.iter().collect();
+        loop {
+            if let Some(layout) = place(&shown, width, widths) {
+                return layout;
+            }
+
+            let lowest = (0..shown.len())
+                .rev()
+                .min_by_key(|index| shown[*index].priority);
+            match lowest {
+                Some(index) => shown.remove(index),
+                None => return Vec::new(),
+            };
+        }
+    }
+}
+
+/// The columns the items start at, if they fit within the width when measured with the widths.
+fn place(items: &[&StatusItem], width: u16, widths: &Widths) -> Option<Vec<(u16, StatusItem)>> {
+    let slot_width = |slot: Alignment| -> u32 {
+        let widths: Vec<u32> = items
+            .iter()
+            .filter(|item| item.slot == slot)
+            .map(|item| widths.text_width(&item.text) as u32)
+            .collect();
+        (widths.iter().sum::<u32>() + widths.len() as u32).saturating_sub(1)
+    };
+
+    let (left, center, right) = (
+        slot_width(Alignment::Left),
+        slot_width(Alignment::Center),
+        slot_width(Alignment::Right),
+    );
+    let gaps = [left, center, right]
+        .iter()
+        .filter(|width| **width > 0)
+        .count() as u32;
+    if left + center + right + gaps.saturating_sub(1) > width as u32 {
+        return None;
+    }
+
+    // The center is centered on the line unless it would overlap the left or right
+    let right_start = width as u32 - right;
+    let center_start = ((width as u32).saturating_sub(center) / 2)
+        .max(left + (left > 0) as u32)
+        .min(right_start.saturating_sub(center + (right > 0) as u32));
+
+    let mut layout = Vec::new();
+    for (slot, start) in [
+        (Alignment::Left, 0),
+        (Alignment::Center, center_start),
+        (Alignment::Right, right_start),
+    ] {
+        let mut column = start as u16;
+        for item in items.iter().filter(|item| item.slot == slot) {
+            layout.push((column, (*item).clone()));
+            column += widths.text_width(&item.text) + 1;
+        }
+    }
+
+    Some(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatusLine;
+    use crate::{text::Widths, Alignment};
+
+    /// The status line's text as laid out within the width.
+    fn render(status: &StatusLine, width: u16) -> String {
+        let mut line = " ".repeat(width.into());
+        for (column, item) in status.layout(width, &Widths::default()) {
+            let column = column as usize;
+            line.replace_range(column..column + item.text.len(), &item.text);
+        }
+
+        line
+    }
+
+    #[test]
+    fn status_slots() {
+        let mut status = StatusLine::new();
+        status.set("mode", Alignment::Left, 0, "NORMAL");
+        status.set("branch", Alignment::Left, 0, "main");
+        status.set("file", Alignment::Center, 0, "lib.rs");
+        status.set("position", Alignment::Right, 0, "1:1");
+        assert_eq!("NORMAL main  lib.rs           1:1", render(&status, 33));
+
+        // The center shifts to avoid overlapping
+        status.set("position", Alignment::Right, 0, "1:1 100%");
+        assert_eq!("NORMAL main lib.rs   1:1 100%", render(&status, 29));
+
+        status.set("mode", Alignment::Left, 0, "INSERT");
+        assert!(status.remove("branch"));
+        assert!(!status.remove("branch"));
+        assert_eq!("INSERT    lib.rs  1:1 100%", render(&status, 26));
+    }
+
+    #[test]
+    fn status_priority_truncation() {
+        let mut status = StatusLine::new();
+        status.set("mode", Alignment::Left, 9, "NORMAL");
+        status.set("file", Alignment::Center, 5, "lib.rs");
+        status.set("encoding", Alignment::Right, 1, "utf-8");
+        status.set("position", Alignment::Right, 1, "1:1");
+
+        assert_eq!("NORMAL lib.rs utf-8 1:1", render(&status, 23));
+        assert_eq!("NORMAL lib.rs   utf-8", render(&status, 21));
+        assert_eq!("NORMAL lib.rs  ", render(&status, 15));
+        assert_eq!("NORMAL  ", render(&status, 8));
+        assert_eq!("    ", render(&status, 4));
+    }
+}
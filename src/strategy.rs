@@ -0,0 +1,15 @@
+/// How an interface translates applied changes into terminal output.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RenderStrategy {
+    /// Only changed cells are rewritten, moving the cursor between them. Produces the least
+    /// output, which suits local terminals.
+    Minimal,
+    /// Suits high-latency links such as SSH sessions. Each changed line is rewritten in a single
+    /// run from its first to its last change rather than jumping between changes, and cleared
+    /// cells at the end of a line are erased with one clear-to-end-of-line.
+    ///
+    /// If `skip_frames` is set, applies made before the previous frame has likely drained, i.e.
+    /// sooner after its write finished than the write took, are skipped. Their changes remain
+    /// pending and are rendered by the next apply, see [crate::Interface::has_deferred_changes].
+    Latency { skip_frames: bool },
+}
@@ -1,5 +1,21 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::Quantization;
+
 /// Colors to be used for foreground and background text formatting.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(from_py_object, eq, eq_int, module = "tty_interface")
+)]
 pub enum Color {
     Black,
     DarkGrey,
@@ -25,6 +41,110 @@ impl Color {
     pub fn as_style(self) -> Style {
         Style::new().set_foreground(self)
     }
+
+    /// The red, green, and blue components this color is displayed with by xterm's default
+    /// palette, or `None` for [Color::Reset]. Terminals' palettes vary, so this is approximate.
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::Black => Some((0, 0, 0)),
+            Color::DarkRed => Some((205, 0, 0)),
+            Color::DarkGreen => Some((0, 205, 0)),
+            Color::DarkYellow => Some((205, 205, 0)),
+            Color::DarkBlue => Some((0, 0, 238)),
+            Color::DarkMagenta => Some((205, 0, 205)),
+            Color::DarkCyan => Some((0, 205, 205)),
+            Color::Grey => Some((229, 229, 229)),
+            Color::DarkGrey => Some((127, 127, 127)),
+            Color::Red => Some((255, 0, 0)),
+            Color::Green => Some((0, 255, 0)),
+            Color::Yellow => Some((255, 255, 0)),
+            Color::Blue => Some((92, 92, 255)),
+            Color::Magenta => Some((255, 0, 255)),
+            Color::Cyan => Some((0, 255, 255)),
+            Color::White => Some((255, 255, 255)),
+            Color::Reset => None,
+        }
+    }
+
+    /// The palette color nearest the point a fraction `t` of the way from `a` to `b`, clamped to
+    /// between them, interpolating their components per [Color::to_rgb]. Interpolating with
+    /// [Color::Reset] gives whichever color is nearer.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::Color;
+    ///
+    /// assert_eq!(Color::Black, Color::lerp(Color::Black, Color::White, 0.1));
+    /// assert_eq!(Color::DarkGrey, Color::lerp(Color::Black, Color::White, 0.5));
+    /// assert_eq!(Color::White, Color::lerp(Color::Black, Color::White, 1.0));
+    /// ```
+    pub fn lerp(a: Color, b: Color, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let (from, to) = match (a.to_rgb(), b.to_rgb()) {
+            (Some(from), Some(to)) => (from, to),
+            _ => return if t < 0.5 { a } else { b },
+        };
+
+        // Components are non-negative, so adding a half before truncating rounds them without the
+        // standard library's float methods
+        let component = |from: u8, to: u8| from as f32 + (to as f32 - from as f32) * t + 0.5;
+        let rgb = (
+            component(from.0, to.0) as u8,
+            component(from.1, to.1) as u8,
+            component(from.2, to.2) as u8,
+        );
+
+        Quantization::Nearest.to_color(rgb)
+    }
+}
+
+impl Color {
+    /// The SGR parameter setting this as the foreground color.
+    fn foreground_sgr(self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::DarkRed => 31,
+            Color::DarkGreen => 32,
+            Color::DarkYellow => 33,
+            Color::DarkBlue => 34,
+            Color::DarkMagenta => 35,
+            Color::DarkCyan => 36,
+            Color::Grey => 37,
+            Color::Reset => 39,
+            Color::DarkGrey => 90,
+            Color::Red => 91,
+            Color::Green => 92,
+            Color::Yellow => 93,
+            Color::Blue => 94,
+            Color::Magenta => 95,
+            Color::Cyan => 96,
+            Color::White => 97,
+        }
+    }
+
+    /// The color set as the foreground by the specified SGR parameter, if any.
+    fn from_foreground_sgr(parameter: u8) -> Option<Color> {
+        match parameter {
+            30 => Some(Color::Black),
+            31 => Some(Color::DarkRed),
+            32 => Some(Color::DarkGreen),
+            33 => Some(Color::DarkYellow),
+            34 => Some(Color::DarkBlue),
+            35 => Some(Color::DarkMagenta),
+            36 => Some(Color::DarkCyan),
+            37 => Some(Color::Grey),
+            39 => Some(Color::Reset),
+            90 => Some(Color::DarkGrey),
+            91 => Some(Color::Red),
+            92 => Some(Color::Green),
+            93 => Some(Color::Yellow),
+            94 => Some(Color::Blue),
+            95 => Some(Color::Magenta),
+            96 => Some(Color::Cyan),
+            97 => Some(Color::White),
+            _ => None,
+        }
+    }
 }
 
 /// Text formatting styles.
@@ -35,15 +155,35 @@ impl Color {
 ///
 /// let style = Color::Red.as_style().set_bold(true);
 /// ```
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(
+    feature = "python",
+    pyo3::pyclass(from_py_object, eq, frozen, module = "tty_interface")
+)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct Style {
+    #[cfg_attr(feature = "serde", serde(rename = "foreground"))]
     foreground_color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(rename = "background"))]
     background_color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(rename = "bold"))]
     is_bold: bool,
+    #[cfg_attr(feature = "serde", serde(rename = "italic"))]
     is_italic: bool,
+    #[cfg_attr(feature = "serde", serde(rename = "underline"))]
     is_underline: bool,
 }
 
+impl Default for Style {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Style {
     /// Create a new style with no colors or styling.
     pub fn new() -> Self {
@@ -114,6 +254,137 @@ impl Style {
     pub fn is_underlined(&self) -> bool {
         self.is_underline
     }
+
+    /// Create a new style composing the overlay on top of this style. The overlay's colors
+    /// replace this style's where specified, and its attributes are added to this style's.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Color, Style};
+    ///
+    /// let base = Style::new().set_foreground(Color::Red).set_bold(true);
+    /// let selection = Style::new().set_background(Color::Blue);
+    ///
+    /// let style = base.overlay(&selection);
+    /// assert_eq!(Some(Color::Red), style.foreground());
+    /// assert_eq!(Some(Color::Blue), style.background());
+    /// assert!(style.is_bold());
+    /// ```
+    pub fn overlay(&self, overlay: &Style) -> Style {
+        Style {
+            foreground_color: overlay.foreground_color.or(self.foreground_color),
+            background_color: overlay.background_color.or(self.background_color),
+            is_bold: self.is_bold || overlay.is_bold,
+            is_italic: self.is_italic || overlay.is_italic,
+            is_underline: self.is_underline || overlay.is_underline,
+        }
+    }
+}
+
+impl Style {
+    /// Convert this style to a semicolon-separated SGR (Select Graphic Rendition) parameter
+    /// string, as used by ANSI escape sequences and `LS_COLORS`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Color, Style};
+    ///
+    /// let style = Color::Red.as_style().set_bold(true);
+    /// assert_eq!("1;91", style.to_sgr());
+    /// ```
+    pub fn to_sgr(&self) -> String {
+        let mut parameters = Vec::new();
+
+        if self.is_bold {
+            parameters.push(1);
+        }
+
+        if self.is_italic {
+            parameters.push(3);
+        }
+
+        if self.is_underline {
+            parameters.push(4);
+        }
+
+        if let Some(color) = self.foreground_color {
+            parameters.push(color.foreground_sgr());
+        }
+
+        if let Some(color) = self.background_color {
+            parameters.push(color.foreground_sgr() + 10);
+        }
+
+        if parameters.is_empty() {
+            return "0".to_string();
+        }
+
+        let parameters: Vec<String> = parameters.iter().map(u8::to_string).collect();
+        parameters.join(";")
+    }
+
+    /// Parse a semicolon-separated SGR (Select Graphic Rendition) parameter string into a style.
+    /// Parameters without an equivalent style, such as blinking or extended colors, are ignored.
+    /// Returns `None` if the string is malformed.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::{Color, Style};
+    ///
+    /// let style = Style::from_sgr("01;34").unwrap();
+    /// assert_eq!(Color::DarkBlue.as_style().set_bold(true), style);
+    /// ```
+    pub fn from_sgr(sgr: &str) -> Option<Style> {
+        let mut style = Style::new();
+
+        if sgr.is_empty() {
+            return Some(style);
+        }
+
+        let mut parameters = sgr.split(';');
+        while let Some(parameter) = parameters.next() {
+            let parameter: u8 = match parameter {
+                "" => 0,
+                parameter => parameter.parse().ok()?,
+            };
+
+            match parameter {
+                0 => style = Style::new(),
+                1 => style = style.set_bold(true),
+                3 => style = style.set_italic(true),
+                4 => style = style.set_underline(true),
+                22 => style = style.set_bold(false),
+                23 => style = style.set_italic(false),
+                24 => style = style.set_underline(false),
+                38 | 48 => {
+                    // Skip extended color arguments, which cannot be represented
+                    match parameters.next()? {
+                        "5" => {
+                            parameters.next()?;
+                        }
+                        "2" => {
+                            for _ in 0..3 {
+                                parameters.next()?;
+                            }
+                        }
+                        _ => return None,
+                    }
+                }
+                parameter => {
+                    if let Some(color) = Color::from_foreground_sgr(parameter) {
+                        style = style.set_foreground(color);
+                    } else if let Some(color) = parameter
+                        .checked_sub(10)
+                        .and_then(Color::from_foreground_sgr)
+                    {
+                        style = style.set_background(color);
+                    }
+                }
+            }
+        }
+
+        Some(style)
+    }
 }
 
 #[cfg(test)]
@@ -147,27 +418,106 @@ mod tests {
     #[test]
     fn style_bold() {
         let mut style = Style::new();
-        assert_eq!(false, style.is_bold());
+        assert!(!style.is_bold());
 
         style = style.set_bold(true);
-        assert_eq!(true, style.is_bold());
+        assert!(style.is_bold());
     }
 
     #[test]
     fn style_italic() {
         let mut style = Style::new();
-        assert_eq!(false, style.is_italic());
+        assert!(!style.is_italic());
 
         style = style.set_italic(true);
-        assert_eq!(true, style.is_italic());
+        assert!(style.is_italic());
     }
 
     #[test]
     fn style_underline() {
         let mut style = Style::new();
-        assert_eq!(false, style.is_underlined());
+        assert!(!style.is_underlined());
 
         style = style.set_underline(true);
-        assert_eq!(true, style.is_underlined());
+        assert!(style.is_underlined());
+    }
+
+    #[test]
+    fn style_to_sgr() {
+        let cases = [
+            (Style::new(), "0"),
+            (Style::new().set_bold(true), "1"),
+            (Color::Red.as_style(), "91"),
+            (Style::new().set_background(Color::DarkBlue), "44"),
+            (Style::new().set_background(Color::Reset), "49"),
+            (
+                Color::Black
+                    .as_style()
+                    .set_background(Color::White)
+                    .set_italic(true)
+                    .set_underline(true),
+                "3;4;30;107",
+            ),
+        ];
+
+        for (style, expected) in cases {
+            assert_eq!(expected, style.to_sgr());
+        }
+    }
+
+    #[test]
+    fn style_from_sgr() {
+        let cases = [
+            ("", Some(Style::new())),
+            ("0", Some(Style::new())),
+            ("00", Some(Style::new())),
+            ("1;31", Some(Color::DarkRed.as_style().set_bold(true))),
+            (
+                "01;34;42",
+                Some(
+                    Color::DarkBlue
+                        .as_style()
+                        .set_background(Color::DarkGreen)
+                        .set_bold(true),
+                ),
+            ),
+            ("1;4;22", Some(Style::new().set_underline(true))),
+            ("31;0;3", Some(Style::new().set_italic(true))),
+            ("38;5;208;1", Some(Style::new().set_bold(true))),
+            ("48;2;1;2;3;96", Some(Color::Cyan.as_style())),
+            ("5;97", Some(Color::White.as_style())),
+            ("1;x", None),
+            ("38;5", None),
+            ("38;9;1", None),
+        ];
+
+        for (sgr, expected) in cases {
+            assert_eq!(expected, Style::from_sgr(sgr), "parsing {:?}", sgr);
+        }
+    }
+
+    #[test]
+    fn style_sgr_round_trip() {
+        let style = Color::Magenta
+            .as_style()
+            .set_background(Color::DarkGrey)
+            .set_bold(true)
+            .set_underline(true);
+
+        assert_eq!(Some(style), Style::from_sgr(&style.to_sgr()));
+    }
+
+    #[test]
+    fn color_lerp() {
+        assert_eq!(Color::DarkRed, Color::lerp(Color::DarkRed, Color::Red, 0.0));
+        assert_eq!(Color::Red, Color::lerp(Color::DarkRed, Color::Red, 0.9));
+        assert_eq!(Color::Red, Color::lerp(Color::DarkRed, Color::Red, 7.0));
+        assert_eq!(
+            Color::DarkGreen,
+            Color::lerp(Color::Green, Color::Black, 0.3)
+        );
+
+        assert_eq!(Color::Reset, Color::lerp(Color::Reset, Color::Blue, 0.4));
+        assert_eq!(Color::Blue, Color::lerp(Color::Reset, Color::Blue, 0.6));
     }
 }
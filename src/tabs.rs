@@ -0,0 +1,318 @@
+use crate::{
+    pos, text::Widths, Event, Interface, KeyCode, MouseButton, MouseEventKind, Position, Style,
+};
+
+/// The layout of a tabs widget as it was last staged.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RenderedTabs {
+    position: Position,
+    /// Each title's starting column and width, including its padding.
+    spans: Vec<(u16, u16)>,
+    active: usize,
+}
+
+/// A row of tab titles separated by dividers with the active tab highlighted. The left and right
+/// arrow keys and clicks switch tabs; when only the active tab changes, re-rendering restages just
+/// the previously and newly active titles.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Event, Interface, KeyCode, KeyEvent, Modifiers, Position, Tabs, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let mut tabs = Tabs::new(vec!["General".to_string(), "Network".to_string()]);
+/// tabs.render(&mut interface, pos!(0, 0));
+/// interface.apply()?;
+///
+/// // Switch to the next tab in response to input
+/// let right = Event::Key(KeyEvent::new(KeyCode::Right, Modifiers::new()));
+/// if tabs.handle_event(&right) {
+///     tabs.render(&mut interface, pos!(0, 0));
+///     interface.apply()?;
+/// }
+///
+/// assert_eq!(1, tabs.active());
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tabs {
+    titles: Vec<String>,
+    active: usize,
+    style: Option<Style>,
+    active_style: Style,
+    divider: String,
+    rendered: Option<RenderedTabs>,
+}
+
+impl Tabs {
+    /// Create tabs with the specified titles, the first active. The active title is bold and
+    /// underlined, and titles are divided by a vertical line.
+    pub fn new(titles: Vec<String>) -> Tabs {
+        Tabs {
+            titles: titles
+                .iter()
+                .map(|title| title.lines().next().unwrap_or_default().to_string())
+                .collect(),
+            active: 0,
+            style: None,
+            active_style: Style::new().set_bold(true).set_underline(true),
+            divider: "│".to_string(),
+            rendered: None,
+        }
+    }
+
+    /// Create new tabs with the specified style for inactive titles and dividers.
+    pub fn set_style(self, style: Style) -> Tabs {
+        Tabs {
+            style: Some(style),
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// Create new tabs with the specified style for the active title.
+    pub fn set_active_style(self, active_style: Style) -> Tabs {
+        Tabs {
+            active_style,
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// Create new tabs with the specified text between titles.
+    pub fn set_divider(self, divider: &str) -> Tabs {
+        Tabs {
+            divider: divider.to_string(),
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// The tabs' titles.
+    pub fn titles(&self) -> &[String] {
+        &self.titles
+    }
+
+    /// The index of the active tab.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Activate the tab at the specified index. Has no effect if there's no such tab.
+    pub fn select(&mut self, index: usize) {
+        if index < self.titles.len() {
+            self.active = index;
+        }
+    }
+
+    /// Activate the tab following the active one, wrapping to the first.
+    pub fn select_next(&mut self) {
+        if !self.titles.is_empty() {
+            self.active = (self.active + 1) % self.titles.len();
+        }
+    }
+
+    /// Activate the tab preceding the active one, wrapping to the last.
+    pub fn select_previous(&mut self) {
+        if !self.titles.is_empty() {
+            let count = self.titles.len();
+            self.active = (self.active + count - 1) % count;
+        }
+    }
+
+    /// The index of the title rendered at the specified position, if any.
+    pub fn title_at(&self, position: Position) -> Option<usize> {
+        let rendered = self.rendered.as_ref()?;
+        if position.y() != rendered.position.y() {
+            return None;
+        }
+
+        let column = position.x().checked_sub(rendered.position.x())?;
+        rendered
+            .spans
+            .iter()
+            .position(|(start, width)| column >= *start && column < start + width)
+    }
+
+    /// Handle an input event, activating the previous or next tab for the left or right arrow
+    /// keys, or the clicked tab for a left click on its rendered title. Returns whether the event
+    /// was consumed.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Key(event) if event.code() == KeyCode::Left => self.select_previous(),
+            Event::Key(event) if event.code() == KeyCode::Right => self.select_next(),
+            Event::Mouse(event) if event.kind() == MouseEventKind::Press(MouseButton::Left) => {
+                match self.title_at(event.position()) {
+                    Some(index) => self.select(index),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        }
+
+        !self.titles.is_empty()
+    }
+
+    /// Stage the tabs with their top-left at the specified position if they changed since they
+    /// were last rendered, returning whether they were staged. If only the active tab changed,
+    /// only the previously and newly active titles are staged.
+    pub fn render(&mut self, interface: &mut Interface, position: Position) -> bool {
+        let spans = self.layout(interface.widths());
+
+        let rendered = self.rendered.take();
+        let is_moved = match &rendered {
+            Some(rendered) => rendered.position != position || rendered.spans != spans,
+            None => true,
+        };
+
+        if is_moved {
+            if let Some(rendered) = &rendered {
+                let width = rendered
+                    .spans
+                    .last()
+                    .map_or(0, |(start, width)| start + width);
+                let clear = " ".repeat(width as usize);
+                interface.set(rendered.position, &clear);
+            }
+
+            for index in 0..self.titles.len() {
+                self.stage_title(interface, position, &spans, index);
+                if index > 0 {
+                    let divider_column =
+                        spans[index].0 - interface.widths().text_width(&self.divider);
+                    let divider_position =
+                        pos!(position.x().saturating_add(divider_column), position.y());
+                    match self.style {
+                        Some(style) => interface.set_styled(divider_position, &self.divider, style),
+                        None => interface.set(divider_position, &self.divider),
+                    };
+                }
+            }
+        } else if let Some(rendered) = &rendered {
+            if rendered.active == self.active {
+                self.rendered = Some(rendered.clone());
+                return false;
+            }
+
+            self.stage_title(interface, position, &spans, rendered.active);
+            self.stage_title(interface, position, &spans, self.active);
+        }
+
+        self.rendered = Some(RenderedTabs {
+            position,
+            spans,
+            active: self.active,
+        });
+        true
+    }
+
+    /// Stage the padded title at the specified index, styled per whether it's active.
+    fn stage_title(
+        &self,
+        interface: &mut Interface,
+        position: Position,
+        spans: &[(u16, u16)],
+        index: usize,
+    ) {
+        let (start, _) = spans[index];
+        let text = format!(" {} ", self.titles[index]);
+        let position = pos!(position.x().saturating_add(start), position.y());
+
+        match (index == self.active, self.style) {
+            (true, _) => interface.set_styled(position, &text, self.active_style),
+            (false, Some(style)) => interface.set_styled(position, &text, style),
+            (false, None) => interface.set(position, &text),
+        };
+    }
+
+    /// Each title's starting column and width, including a space of padding on either side,
+    /// measured with the widths.
+    fn layout(&self, widths: &Widths) -> Vec<(u16, u16)> {
+        let divider_width = widths.text_width(&self.divider);
+
+        let mut column = 0u16;
+        let mut spans = Vec::new();
+        for (index, title) in self.titles.iter().enumerate() {
+            if index > 0 {
+                column = column.saturating_add(divider_width);
+            }
+
+            let width = widths.text_width(title).saturating_add(2);
+            spans.push((column, width));
+            column = column.saturating_add(width);
+        }
+
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pos, text::Widths, Event, KeyCode, KeyEvent, Modifiers, MouseButton, MouseEvent,
+        MouseEventKind, Position,
+    };
+
+    use super::{RenderedTabs, Tabs};
+
+    fn tabs() -> Tabs {
+        Tabs::new(vec![
+            "One".to_string(),
+            "Two".to_string(),
+            "Three".to_string(),
+        ])
+    }
+
+    #[test]
+    fn tabs_layout() {
+        assert_eq!(
+            vec![(0, 5), (6, 5), (12, 7)],
+            tabs().layout(&Widths::default())
+        );
+        assert_eq!(
+            vec![(0, 5), (8, 5), (16, 7)],
+            tabs().set_divider(" | ").layout(&Widths::default())
+        );
+    }
+
+    #[test]
+    fn tabs_selection() {
+        let mut tabs = tabs();
+        tabs.select_previous();
+        assert_eq!(2, tabs.active());
+        tabs.select_next();
+        assert_eq!(0, tabs.active());
+        tabs.select(5);
+        assert_eq!(0, tabs.active());
+    }
+
+    #[test]
+    fn tabs_handle_event() {
+        let mut tabs = tabs();
+        tabs.rendered = Some(RenderedTabs {
+            position: pos!(2, 1),
+            spans: tabs.layout(&Widths::default()),
+            active: 0,
+        });
+
+        let key = |code| Event::Key(KeyEvent::new(code, Modifiers::new()));
+        assert!(tabs.handle_event(&key(KeyCode::Right)));
+        assert_eq!(1, tabs.active());
+        assert!(!tabs.handle_event(&key(KeyCode::Up)));
+
+        let click = |position| {
+            let kind = MouseEventKind::Press(MouseButton::Left);
+            Event::Mouse(MouseEvent::new(kind, position, Modifiers::new()))
+        };
+        assert!(tabs.handle_event(&click(pos!(16, 1))));
+        assert_eq!(2, tabs.active());
+
+        // Dividers and other lines aren't titles
+        assert!(!tabs.handle_event(&click(pos!(7, 1))));
+        assert!(!tabs.handle_event(&click(pos!(3, 0))));
+        assert_eq!(2, tabs.active());
+    }
+}
@@ -0,0 +1,651 @@
+//! A device for a client connected to a server over Telnet, such as a BBS or an administration
+//! console, so the server may drive an interface for each connection. The client's window size is
+//! negotiated with NAWS (RFC 1073) and its changes reported as resize events.
+
+use std::{
+    collections::VecDeque,
+    io::{Read, Write},
+    mem::take,
+    net::{Shutdown, TcpStream},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    pos, CommandEncoder, CursorQuery, Event, EventSource, KeyCode, KeyEvent, Modifiers, Position,
+    RawModeControl, Result, SizedTarget, Vector,
+};
+
+/// Interpret as command.
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+/// Begin subnegotiation.
+const SB: u8 = 250;
+/// End subnegotiation.
+const SE: u8 = 240;
+
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+/// Negotiate about window size.
+const NAWS: u8 = 31;
+
+/// The size assumed until the client reports its own.
+const DEFAULT_SIZE: Vector = Vector::new(80, 24);
+
+/// How long to wait for the client's cursor position report.
+const CURSOR_REPORT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The most subnegotiation parameters kept, beyond which they're dropped. Window sizes need five.
+const MAX_SUBNEGOTIATION_LENGTH: usize = 64;
+
+/// The most data kept for an incomplete escape sequence, beyond which it's discarded.
+const MAX_PENDING_LENGTH: usize = 256;
+
+/// A device for a Telnet client, reading its input from one half of the connection and writing
+/// to the other. The client is asked to report its window size, which is assumed to be 80x24
+/// until it does, and raw mode has the server echo input so keys are sent as they're pressed.
+///
+/// # Examples
+/// ```no_run
+/// # use tty_interface::Error;
+/// use std::{net::TcpListener, time::Duration};
+/// use tty_interface::{telnet::TelnetDevice, Interface, Position, pos};
+///
+/// let listener = TcpListener::bind("0.0.0.0:2323")?;
+/// for stream in listener.incoming() {
+///     let mut device = TelnetDevice::from_stream(stream?)?;
+///     device.wait_for_size(Duration::from_millis(500))?;
+///
+///     let mut interface = Interface::new_alternate(&mut device)?;
+///     interface.set(pos!(0, 0), "Welcome!");
+///     interface.apply()?;
+///     interface.read_event()?;
+///     interface.exit()?;
+/// }
+/// # Ok::<(), Error>(())
+/// ```
+pub struct TelnetDevice<W: Write> {
+    writer: W,
+    input: Receiver<Vec<u8>>,
+    decoder: InputDecoder,
+    events: VecDeque<Event>,
+    cursor_reports: VecDeque<Position>,
+    size: Vector,
+    is_size_reported: bool,
+    is_raw: bool,
+    reader: Option<JoinHandle<()>>,
+    connection: Option<TcpStream>,
+}
+
+impl TelnetDevice<TcpStream> {
+    /// Create a device for the client connected by the TCP stream, asking it to report its window
+    /// size. The connection is shut down when the device is dropped, stopping its reader thread.
+    pub fn from_stream(stream: TcpStream) -> Result<TelnetDevice<TcpStream>> {
+        let connection = stream.try_clone()?;
+        let mut device = TelnetDevice::new(stream.try_clone()?, stream)?;
+        device.connection = Some(connection);
+        Ok(device)
+    }
+}
+
+impl<W: Write> TelnetDevice<W> {
+    /// Create a device for the client connected by the reader and writer, asking it to report its
+    /// window size. The reader is read on another thread, which is stopped when the device is
+    /// dropped once its read in progress finishes, since a read can't be interrupted. See
+    /// [TelnetDevice::from_stream] to stop it immediately for a TCP stream.
+    pub fn new(reader: impl Read + Send + 'static, mut writer: W) -> Result<TelnetDevice<W>> {
+        writer.write_all(&[IAC, DO, NAWS])?;
+        writer.flush()?;
+
+        let (sender, input) = mpsc::channel();
+        let reader = thread::spawn(move || {
+            let mut reader = reader;
+            let mut buffer = [0; 1024];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(length) => {
+                        if sender.send(buffer[..length].to_vec()).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(TelnetDevice {
+            writer,
+            input,
+            decoder: InputDecoder::default(),
+            events: VecDeque::new(),
+            cursor_reports: VecDeque::new(),
+            size: DEFAULT_SIZE,
+            is_size_reported: false,
+            is_raw: false,
+            reader: Some(reader),
+            connection: None,
+        })
+    }
+
+    /// Wait up to the timeout for the client to report its window size, such as before creating
+    /// an interface so it starts at the right size. Returns whether the size has been reported.
+    /// Input received meanwhile is kept for [EventSource::read_event].
+    pub fn wait_for_size(&mut self, timeout: Duration) -> Result<bool> {
+        let deadline = Instant::now() + timeout;
+        while !self.is_size_reported {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.receive(Some(remaining))? {
+                break;
+            }
+        }
+
+        Ok(self.is_size_reported)
+    }
+
+    /// Access the writer to the client.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Wait up to the timeout, or indefinitely if there is none, for input from the client and
+    /// decode it. Returns whether input was received before the timeout.
+    fn receive(&mut self, timeout: Option<Duration>) -> Result<bool> {
+        let received = match timeout {
+            Some(timeout) => self
+                .input
+                .recv_timeout(timeout)
+                .map_err(|error| match error {
+                    RecvTimeoutError::Timeout => None,
+                    RecvTimeoutError::Disconnected => Some(disconnected()),
+                }),
+            None => self.input.recv().map_err(|_| Some(disconnected())),
+        };
+
+        match received {
+            Ok(bytes) => {
+                self.decode(&bytes);
+                Ok(true)
+            }
+            Err(None) => Ok(false),
+            Err(Some(error)) => Err(error.into()),
+        }
+    }
+
+    /// Decode any input already received from the client without waiting for more.
+    fn receive_available(&mut self) {
+        while let Ok(bytes) = self.input.try_recv() {
+            self.decode(&bytes);
+        }
+    }
+
+    fn decode(&mut self, bytes: &[u8]) {
+        for input in self.decoder.decode(bytes) {
+            match input {
+                Input::Event(event) => {
+                    if let Event::Resize(size) = event {
+                        self.size = size;
+                        self.is_size_reported = true;
+                    }
+                    self.events.push_back(event);
+                }
+                Input::CursorReport(position) => self.cursor_reports.push_back(position),
+            }
+        }
+    }
+
+    /// Write the negotiation commands to the client.
+    fn negotiate(&mut self, commands: &[[u8; 3]]) -> Result<()> {
+        self.writer.write_all(&commands.concat())?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for TelnetDevice<W> {
+    fn drop(&mut self) {
+        // Shutting the connection down ends the reader's blocked read, so it may be joined
+        let is_shut_down = self
+            .connection
+            .take()
+            .is_some_and(|connection| connection.shutdown(Shutdown::Both).is_ok());
+
+        // Otherwise, the reader stops once its next read finishes and fails to send the input
+        if let Some(reader) = self.reader.take() {
+            if is_shut_down || reader.is_finished() {
+                let _ = reader.join();
+            }
+        }
+    }
+}
+
+/// The error for a client which closed its connection.
+fn disconnected() -> std::io::Error {
+    std::io::Error::from(std::io::ErrorKind::UnexpectedEof)
+}
+
+impl<W: Write> SizedTarget for TelnetDevice<W> {
+    fn get_terminal_size(&mut self) -> Result<Vector> {
+        self.receive_available();
+        Ok(self.size)
+    }
+}
+
+impl<W: Write> RawModeControl for TelnetDevice<W> {
+    fn enable_raw_mode(&mut self) -> Result<()> {
+        self.negotiate(&[[IAC, WILL, ECHO], [IAC, WILL, SUPPRESS_GO_AHEAD]])?;
+        self.is_raw = true;
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> Result<()> {
+        self.negotiate(&[[IAC, WONT, ECHO], [IAC, WONT, SUPPRESS_GO_AHEAD]])?;
+        self.is_raw = false;
+        Ok(())
+    }
+}
+
+impl<W: Write> CursorQuery for TelnetDevice<W> {
+    fn get_cursor_position(&mut self) -> Result<Position> {
+        self.writer.write_all(b"\x1B[6n")?;
+        self.writer.flush()?;
+
+        let deadline = Instant::now() + CURSOR_REPORT_TIMEOUT;
+        loop {
+            if let Some(position) = self.cursor_reports.pop_front() {
+                return Ok(position);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.receive(Some(remaining))? {
+                return Err(std::io::Error::from(std::io::ErrorKind::TimedOut).into());
+            }
+        }
+    }
+}
+
+impl<W: Write> CommandEncoder for TelnetDevice<W> {}
+
+impl<W: Write> EventSource for TelnetDevice<W> {
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if remaining.is_some_and(|remaining| remaining.is_zero()) || !self.receive(remaining)? {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for TelnetDevice<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // Data bytes equal to IAC are doubled, and line feeds outside of raw mode return the
+        // carriage as a terminal's output processing would
+        let mut output = Vec::with_capacity(buf.len());
+        let mut previous = None;
+        for byte in buf.iter().copied() {
+            match byte {
+                IAC => output.push(IAC),
+                b'\n' if !self.is_raw && previous != Some(b'\r') => output.push(b'\r'),
+                _ => {}
+            }
+            output.push(byte);
+            previous = Some(byte);
+        }
+
+        self.writer.write_all(&output)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Input decoded from the client.
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Input {
+    Event(Event),
+    CursorReport(Position),
+}
+
+/// Where the decoder is within the Telnet protocol's command sequences.
+#[derive(Debug, Default)]
+enum TelnetState {
+    #[default]
+    Data,
+    Command,
+    Negotiation,
+    Subnegotiation(Vec<u8>),
+    SubnegotiationCommand(Vec<u8>),
+}
+
+/// Decodes the client's input, separating Telnet commands from the data and decoding the data's
+/// keys and escape sequences.
+#[derive(Debug, Default)]
+struct InputDecoder {
+    state: TelnetState,
+    is_after_carriage_return: bool,
+    data: Vec<u8>,
+}
+
+impl InputDecoder {
+    /// Decode the bytes received from the client. Incomplete sequences are kept until the rest is
+    /// received.
+    fn decode(&mut self, bytes: &[u8]) -> Vec<Input> {
+        let mut inputs = Vec::new();
+        for byte in bytes.iter().copied() {
+            self.state = match take(&mut self.state) {
+                TelnetState::Data if byte == IAC => TelnetState::Command,
+                TelnetState::Data => {
+                    // A carriage return is sent followed by a line feed or null
+                    let is_line_end = self.is_after_carriage_return && matches!(byte, b'\n' | 0);
+                    if !is_line_end {
+                        self.data.push(byte);
+                    }
+                    self.is_after_carriage_return = byte == b'\r';
+                    TelnetState::Data
+                }
+                TelnetState::Command => match byte {
+                    IAC => {
+                        self.data.push(IAC);
+                        TelnetState::Data
+                    }
+                    WILL | WONT | DO | DONT => TelnetState::Negotiation,
+                    SB => TelnetState::Subnegotiation(Vec::new()),
+                    _ => TelnetState::Data,
+                },
+                TelnetState::Negotiation => TelnetState::Data,
+                TelnetState::Subnegotiation(parameters) if byte == IAC => {
+                    TelnetState::SubnegotiationCommand(parameters)
+                }
+                TelnetState::Subnegotiation(mut parameters) => {
+                    push_parameter(&mut parameters, byte);
+                    TelnetState::Subnegotiation(parameters)
+                }
+                TelnetState::SubnegotiationCommand(parameters) if byte == SE => {
+                    if let [NAWS, width_high, width_low, height_high, height_low] = parameters[..] {
+                        let width = u16::from_be_bytes([width_high, width_low]);
+                        let height = u16::from_be_bytes([height_high, height_low]);
+                        if width > 0 && height > 0 {
+                            self.decode_data(&mut inputs, false);
+                            inputs.push(Input::Event(Event::Resize(Vector::new(width, height))));
+                        }
+                    }
+                    TelnetState::Data
+                }
+                TelnetState::SubnegotiationCommand(mut parameters) => {
+                    push_parameter(&mut parameters, byte);
+                    TelnetState::Subnegotiation(parameters)
+                }
+            };
+        }
+
+        self.decode_data(&mut inputs, true);
+        inputs
+    }
+
+    /// Decode the complete keys and escape sequences in the data received. An escape ending the
+    /// data is the escape key if the data is complete, as sequences arrive together.
+    fn decode_data(&mut self, inputs: &mut Vec<Input>, is_complete: bool) {
+        let data = take(&mut self.data);
+        let mut index = 0;
+        while index < data.len() {
+            let Some((input, length)) = decode_input(&data[index..], is_complete) else {
+                break;
+            };
+
+            inputs.extend(input);
+            index += length;
+        }
+
+        // A client never finishing its escape sequence mustn't grow the data without bound
+        self.data = match data.len() - index > MAX_PENDING_LENGTH {
+            true => Vec::new(),
+            false => data[index..].to_vec(),
+        };
+    }
+}
+
+/// Add the byte to the subnegotiation's parameters, dropping it if there are too many to be a
+/// subnegotiation the decoder understands.
+fn push_parameter(parameters: &mut Vec<u8>, byte: u8) {
+    if parameters.len() < MAX_SUBNEGOTIATION_LENGTH {
+        parameters.push(byte);
+    }
+}
+
+/// Decode the input at the start of the data, returning it, if any, and the number of bytes it
+/// spans, or `None` if the data ends before the input does.
+fn decode_input(data: &[u8], is_complete: bool) -> Option<(Option<Input>, usize)> {
+    let key = |code| {
+        Some(Input::Event(Event::Key(KeyEvent::new(
+            code,
+            Modifiers::new(),
+        ))))
+    };
+
+    let input = match data[0] {
+        b'\r' | b'\n' => key(KeyCode::Enter),
+        b'\t' => key(KeyCode::Tab),
+        0x08 | 0x7F => key(KeyCode::Backspace),
+        0x1B => return decode_escape(data, is_complete),
+        byte @ 0x01..=0x1A => {
+            let modifiers = Modifiers::new().set_control(true);
+            let code = KeyCode::Char((b'a' + byte - 1) as char);
+            Some(Input::Event(Event::Key(KeyEvent::new(code, modifiers))))
+        }
+        0x00..=0x1F => None,
+        byte => {
+            let length = match byte {
+                0xF0.. => 4,
+                0xE0.. => 3,
+                0xC0.. => 2,
+                _ => 1,
+            };
+            if data.len() < length {
+                return None;
+            }
+
+            return match std::str::from_utf8(&data[..length]) {
+                Ok(text) => Some((key(KeyCode::Char(text.chars().next()?)), length)),
+                Err(_) => Some((None, 1)),
+            };
+        }
+    };
+
+    Some((input, 1))
+}
+
+/// Decode the escape sequence at the start of the data.
+fn decode_escape(data: &[u8], is_complete: bool) -> Option<(Option<Input>, usize)> {
+    let key = |code, modifiers| Some(Input::Event(Event::Key(KeyEvent::new(code, modifiers))));
+
+    match data.get(1) {
+        None if is_complete => Some((key(KeyCode::Escape, Modifiers::new()), 1)),
+        None => None,
+        Some(b'[') => {
+            let end = data[2..]
+                .iter()
+                .position(|byte| (0x40..=0x7E).contains(byte))?
+                + 2;
+            let parameters = std::str::from_utf8(&data[2..end]).ok();
+            let parameters: Vec<u16> = parameters
+                .unwrap_or_default()
+                .split(';')
+                .map(|parameter| parameter.parse().unwrap_or(0))
+                .collect();
+
+            let input = match data[end] {
+                b'R' => match parameters[..] {
+                    [line, column] if line > 0 && column > 0 => {
+                        Some(Input::CursorReport(pos!(column - 1, line - 1)))
+                    }
+                    _ => None,
+                },
+                b'Z' => key(KeyCode::BackTab, Modifiers::new()),
+                final_byte => {
+                    let code = match final_byte {
+                        b'~' => match parameters[0] {
+                            1 | 7 => Some(KeyCode::Home),
+                            2 => Some(KeyCode::Insert),
+                            3 => Some(KeyCode::Delete),
+                            4 | 8 => Some(KeyCode::End),
+                            5 => Some(KeyCode::PageUp),
+                            6 => Some(KeyCode::PageDown),
+                            number @ 11..=15 => Some(KeyCode::F((number - 10) as u8)),
+                            number @ 17..=21 => Some(KeyCode::F((number - 11) as u8)),
+                            number @ 23..=24 => Some(KeyCode::F((number - 12) as u8)),
+                            _ => None,
+                        },
+                        final_byte => final_key(final_byte),
+                    };
+
+                    // A second parameter encodes the modifiers held, plus one
+                    let modifiers = parameters.get(1).map_or(0, |bits| bits.saturating_sub(1));
+                    let modifiers = Modifiers::new()
+                        .set_shift(modifiers & 1 != 0)
+                        .set_alt(modifiers & 2 != 0)
+                        .set_control(modifiers & 4 != 0);
+                    code.and_then(|code| key(code, modifiers))
+                }
+            };
+
+            Some((input, end + 1))
+        }
+        Some(b'O') => {
+            let final_byte = *data.get(2)?;
+            let code = match final_byte {
+                b'P'..=b'S' => Some(KeyCode::F(final_byte - b'P' + 1)),
+                final_byte => final_key(final_byte),
+            };
+            Some((code.and_then(|code| key(code, Modifiers::new())), 3))
+        }
+        // An escape preceding a character is the character pressed with alt
+        Some(byte @ 0x20..=0x7E) => {
+            let modifiers = Modifiers::new().set_alt(true);
+            Some((key(KeyCode::Char(*byte as char), modifiers), 2))
+        }
+        Some(_) => Some((key(KeyCode::Escape, Modifiers::new()), 1)),
+    }
+}
+
+/// The key for the final byte of a cursor key's escape sequence.
+fn final_key(final_byte: u8) -> Option<KeyCode> {
+    match final_byte {
+        b'A' => Some(KeyCode::Up),
+        b'B' => Some(KeyCode::Down),
+        b'C' => Some(KeyCode::Right),
+        b'D' => Some(KeyCode::Left),
+        b'H' => Some(KeyCode::Home),
+        b'F' => Some(KeyCode::End),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pos, Event, KeyCode, KeyEvent, Modifiers, Position, Vector};
+
+    use super::{
+        Input, InputDecoder, TelnetState, IAC, MAX_PENDING_LENGTH, MAX_SUBNEGOTIATION_LENGTH, NAWS,
+        SB, SE, WILL,
+    };
+
+    fn key(code: KeyCode, modifiers: Modifiers) -> Input {
+        Input::Event(Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    #[test]
+    fn telnet_decode_keys() {
+        let mut decoder = InputDecoder::default();
+        let none = Modifiers::new();
+
+        let inputs =
+            decoder.decode(b"a\xC3\xA9\r\0\r\n\x7F\t\x03\x1B[A\x1B[1;5C\x1B[3~\x1BOP\x1Bx");
+        assert_eq!(
+            vec![
+                key(KeyCode::Char('a'), none),
+                key(KeyCode::Char('é'), none),
+                key(KeyCode::Enter, none),
+                key(KeyCode::Enter, none),
+                key(KeyCode::Backspace, none),
+                key(KeyCode::Tab, none),
+                key(KeyCode::Char('c'), none.set_control(true)),
+                key(KeyCode::Up, none),
+                key(KeyCode::Right, none.set_control(true)),
+                key(KeyCode::Delete, none),
+                key(KeyCode::F(1), none),
+                key(KeyCode::Char('x'), none.set_alt(true)),
+            ],
+            inputs
+        );
+
+        // Sequences split between reads are decoded once complete, and a lone escape is a key
+        assert!(decoder.decode(b"\x1B[1").is_empty());
+        assert_eq!(vec![key(KeyCode::Left, none)], decoder.decode(b";1D"));
+        assert!(decoder.decode(b"\xE6").is_empty());
+        assert_eq!(
+            vec![key(KeyCode::Char('漢'), none)],
+            decoder.decode(b"\xBC\xA2")
+        );
+        assert_eq!(vec![key(KeyCode::Escape, none)], decoder.decode(b"\x1B"));
+    }
+
+    #[test]
+    fn telnet_decode_commands() {
+        let mut decoder = InputDecoder::default();
+
+        let mut bytes = vec![b'a', IAC, WILL, NAWS, IAC, SB, NAWS, 0, 100, 0, 30, IAC, SE];
+        bytes.extend_from_slice(&[IAC, IAC, b'\x1B', b'[', b'5', b';', b'9', b'R']);
+        assert_eq!(
+            vec![
+                key(KeyCode::Char('a'), Modifiers::new()),
+                Input::Event(Event::Resize(Vector::new(100, 30))),
+                Input::CursorReport(pos!(8, 4)),
+            ],
+            decoder.decode(&bytes)
+        );
+
+        // Subnegotiations may be split between reads, and escape IAC in their parameters
+        assert!(decoder.decode(&[IAC, SB, NAWS, 0, IAC, IAC]).is_empty());
+        assert_eq!(
+            vec![Input::Event(Event::Resize(Vector::new(255, 40)))],
+            decoder.decode(&[0, 40, IAC, SE])
+        );
+    }
+
+    #[test]
+    fn telnet_decode_bounded_buffers() {
+        let mut decoder = InputDecoder::default();
+
+        // Endless subnegotiations keep only as many parameters as could be understood
+        decoder.decode(&[IAC, SB, NAWS]);
+        decoder.decode(&[0; 4096]);
+        match &decoder.state {
+            TelnetState::Subnegotiation(parameters) => {
+                assert_eq!(MAX_SUBNEGOTIATION_LENGTH, parameters.len())
+            }
+            state => panic!("unexpected state: {:?}", state),
+        }
+        assert!(decoder.decode(&[IAC, SE]).is_empty());
+
+        // Endless escape sequences are discarded, and input afterward is decoded as usual
+        assert!(decoder.decode(b"\x1B[").is_empty());
+        assert!(decoder.decode(&[b'1'; 1024]).is_empty());
+        assert!(decoder.data.len() <= MAX_PENDING_LENGTH);
+        assert_eq!(
+            vec![key(KeyCode::Char('a'), Modifiers::new())],
+            decoder.decode(b"a")
+        );
+    }
+}
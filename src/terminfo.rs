@@ -0,0 +1,258 @@
+//! A command encoder consulting the terminfo database for the terminal's control sequences, for
+//! terminals where the default ANSI sequences are wrong or unsupported. Enabled by the `terminfo`
+//! feature and selected with [crate::InterfaceBuilder::encoder].
+
+use std::path::Path;
+
+use ::terminfo::{
+    expand::{Context, Parameter},
+    Database, Expand, Value,
+};
+
+use crate::{encode_ansi, Color, CommandEncoder, DeviceCommand, Result, Style};
+
+/// Encodes commands using the capabilities described by a terminal's terminfo entry. Commands the
+/// entry has no capability for are encoded as ANSI escape sequences, and colors are limited to
+/// those the terminal reports supporting.
+///
+/// # Examples
+/// ```no_run
+/// # use tty_interface::Error;
+/// use tty_interface::{terminfo::TerminfoEncoder, Interface};
+///
+/// let mut stdout = std::io::stdout();
+/// let interface = Interface::builder()
+///     .encoder(Box::new(TerminfoEncoder::from_env()?))
+///     .build(&mut stdout)?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct TerminfoEncoder {
+    database: Database,
+}
+
+impl TerminfoEncoder {
+    /// Create an encoder for the terminal named by the `TERM` environment variable.
+    pub fn from_env() -> Result<TerminfoEncoder> {
+        Ok(TerminfoEncoder::new(
+            Database::from_env().map_err(to_error)?,
+        ))
+    }
+
+    /// Create an encoder for the named terminal, e.g. "screen" or "xterm-256color".
+    pub fn from_name(name: &str) -> Result<TerminfoEncoder> {
+        Ok(TerminfoEncoder::new(
+            Database::from_name(name).map_err(to_error)?,
+        ))
+    }
+
+    /// Create an encoder from a compiled terminfo entry at the specified path.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<TerminfoEncoder> {
+        Ok(TerminfoEncoder::new(
+            Database::from_path(path).map_err(to_error)?,
+        ))
+    }
+
+    /// Create an encoder for the specified terminfo entry.
+    fn new(database: Database) -> TerminfoEncoder {
+        TerminfoEncoder { database }
+    }
+
+    /// The terminal's name, as recorded in its terminfo entry.
+    pub fn name(&self) -> &str {
+        self.database.name()
+    }
+
+    /// How many colors the terminal supports, or zero if it's monochrome.
+    pub fn max_colors(&self) -> u16 {
+        match self.database.raw("colors") {
+            Some(Value::Number(colors)) => u16::try_from(*colors).unwrap_or(u16::MAX),
+            _ => 0,
+        }
+    }
+
+    /// Append the named string capability, expanded with the parameters, to the buffer. Returns
+    /// false if the terminal doesn't have the capability.
+    fn expand(&self, name: &str, parameters: &[Parameter], buffer: &mut Vec<u8>) -> Result<bool> {
+        let capability = match self.database.raw(name) {
+            Some(Value::String(capability)) => capability,
+            _ => return Ok(false),
+        };
+
+        capability
+            .as_slice()
+            .expand(&mut *buffer, parameters, &mut Context::default())
+            .map_err(to_error)?;
+
+        Ok(true)
+    }
+
+    /// Append the sequences enabling the style's attributes and colors to the buffer. Returns false
+    /// if the terminal lacks a capability the style requires.
+    fn expand_style(&self, style: Style, buffer: &mut Vec<u8>) -> Result<bool> {
+        let attributes = [
+            (style.is_bold(), "bold"),
+            (style.is_italic(), "sitm"),
+            (style.is_underlined(), "smul"),
+        ];
+
+        for (is_set, name) in attributes {
+            if is_set && !self.expand(name, &[], buffer)? {
+                return Ok(false);
+            }
+        }
+
+        let colors = [(style.foreground(), "setaf"), (style.background(), "setab")];
+        for (color, name) in colors {
+            if let Some(index) = color.and_then(|color| self.color_index(color)) {
+                if !self.expand(name, &[index.into()], buffer)? {
+                    return Ok(false);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// The terminal's palette index for the color, substituting the normal-intensity variant of
+    /// bright colors on terminals with fewer than 16 colors. Returns `None` for the default color
+    /// or if the terminal is monochrome.
+    fn color_index(&self, color: Color) -> Option<u8> {
+        let index: u8 = match color {
+            Color::Black => 0,
+            Color::DarkRed => 1,
+            Color::DarkGreen => 2,
+            Color::DarkYellow => 3,
+            Color::DarkBlue => 4,
+            Color::DarkMagenta => 5,
+            Color::DarkCyan => 6,
+            Color::Grey => 7,
+            Color::DarkGrey => 8,
+            Color::Red => 9,
+            Color::Green => 10,
+            Color::Yellow => 11,
+            Color::Blue => 12,
+            Color::Magenta => 13,
+            Color::Cyan => 14,
+            Color::White => 15,
+            Color::Reset => return None,
+        };
+
+        match self.max_colors() {
+            0 => None,
+            colors if u16::from(index) >= colors => Some(index - 8),
+            _ => Some(index),
+        }
+    }
+}
+
+impl CommandEncoder for TerminfoEncoder {
+    fn encode(&mut self, command: &DeviceCommand, buffer: &mut Vec<u8>) -> Result<()> {
+        let encoded = match *command {
+            DeviceCommand::MoveTo(position) => {
+                self.expand("cup", &[position.y().into(), position.x().into()], buffer)?
+            }
+            DeviceCommand::MoveLeft(columns) => self.expand("cub", &[columns.into()], buffer)?,
+            DeviceCommand::MoveRight(columns) => self.expand("cuf", &[columns.into()], buffer)?,
+            DeviceCommand::MoveUp(lines) => self.expand("cuu", &[lines.into()], buffer)?,
+            DeviceCommand::PrintStyled(text, style) if style != Style::new() => {
+                let length = buffer.len();
+                if self.expand_style(style, buffer)? && self.database.raw("sgr0").is_some() {
+                    buffer.extend_from_slice(text.as_bytes());
+                    self.expand("sgr0", &[], buffer)?
+                } else {
+                    buffer.truncate(length);
+                    false
+                }
+            }
+            DeviceCommand::HideCursor => self.expand("civis", &[], buffer)?,
+            DeviceCommand::ShowCursor => self.expand("cnorm", &[], buffer)?,
+            DeviceCommand::EnterAlternateScreen => self.expand("smcup", &[], buffer)?,
+            DeviceCommand::LeaveAlternateScreen => self.expand("rmcup", &[], buffer)?,
+            DeviceCommand::ClearAll => self.expand("clear", &[], buffer)?,
+            DeviceCommand::ClearToEndOfLine => self.expand("el", &[], buffer)?,
+            DeviceCommand::InsertCharacters(columns) => {
+                self.expand("ich", &[columns.into()], buffer)?
+            }
+            DeviceCommand::SetScrollRegion(first, last) => {
+                self.expand("csr", &[first.into(), last.into()], buffer)?
+            }
+            _ => false,
+        };
+
+        if !encoded {
+            encode_ansi(command, buffer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Convert a terminfo error into an interface error.
+fn to_error(error: ::terminfo::Error) -> crate::Error {
+    match error {
+        ::terminfo::Error::Io(error) => error.into(),
+        error => std::io::Error::other(error.to_string()).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TerminfoEncoder;
+    use crate::{pos, Color, CommandEncoder, DeviceCommand, Position, Style};
+
+    /// Encode the commands with an encoder for the named terminal.
+    fn encode(name: &str, commands: &[DeviceCommand]) -> Vec<u8> {
+        let mut encoder = TerminfoEncoder::from_name(name).unwrap();
+        let mut buffer = Vec::new();
+        for command in commands {
+            encoder.encode(command, &mut buffer).unwrap();
+        }
+
+        buffer
+    }
+
+    #[test]
+    fn terminfo_encodes_capabilities() {
+        let encoder = TerminfoEncoder::from_name("screen").unwrap();
+        assert_eq!("screen", encoder.name());
+        assert_eq!(8, encoder.max_colors());
+
+        let buffer = encode(
+            "screen",
+            &[
+                DeviceCommand::MoveTo(pos!(2, 3)),
+                DeviceCommand::ShowCursor,
+                DeviceCommand::ClearToEndOfLine,
+                DeviceCommand::PrintStyled("a", Color::Red.as_style()),
+            ],
+        );
+        assert_eq!(
+            b"\x1B[4;3H\x1B[34h\x1B[?25h\x1B[K\x1B[31ma\x1B[m\x0F",
+            buffer.as_slice()
+        );
+    }
+
+    #[test]
+    fn terminfo_uses_full_palette() {
+        let style = Color::Red.as_style().set_background(Color::DarkBlue);
+        let buffer = encode("xterm-256color", &[DeviceCommand::PrintStyled("a", style)]);
+        assert_eq!(b"\x1B[91m\x1B[44ma\x1B(B\x1B[m", buffer.as_slice());
+    }
+
+    #[test]
+    fn terminfo_falls_back_to_ansi() {
+        let buffer = encode(
+            "screen",
+            &[
+                DeviceCommand::Print("a"),
+                DeviceCommand::PrintStyled("b", Style::new().set_italic(true)),
+            ],
+        );
+        assert_eq!(b"a\x1B[3mb\x1B[0m", buffer.as_slice());
+    }
+
+    #[test]
+    fn terminfo_unknown_terminal() {
+        assert!(TerminfoEncoder::from_name("no-such-terminal").is_err());
+    }
+}
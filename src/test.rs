@@ -1,47 +1,561 @@
-use crate::{pos, Device, Position, Result, Vector};
+use std::{
+    cell::{Cell, RefCell, RefMut},
+    collections::VecDeque,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "images")]
+use crate::ImageProtocol;
+use crate::{
+    pos, Clock, Color, CommandEncoder, CursorQuery, Event, EventSource, Interface, Mode, Position,
+    RawModeControl, Rect, Result, SizedTarget, Style, Vector,
+};
 
 /// A virtual testing device based on the vte/vt100 parser. Ideally, this would be hidden from
 /// production builds and only available to functional, documentation, and unit tests, but that does
 /// not seem to be possible currently.
-pub struct VirtualDevice(vt100::Parser);
+///
+/// By default, the device is a terminal reporting its size and cursor, but it may be configured to
+/// behave like other devices, such as a pipe which isn't a terminal or a slow or failing writer.
+pub struct VirtualDevice {
+    parser: vt100::Parser,
+    events: VecDeque<Event>,
+    output: Vec<u8>,
+    is_raw: bool,
+    is_terminal: bool,
+    reports_size: bool,
+    reports_cursor: bool,
+    styling_preference: Option<bool>,
+    write_allowance: Option<usize>,
+    flush_delay: Option<(VirtualClock, Duration)>,
+    #[cfg(feature = "images")]
+    image_protocol: ImageProtocol,
+}
 
 impl VirtualDevice {
     /// Create a new device based around a virtual terminal.
     pub fn new() -> Self {
-        Self(vt100::Parser::default())
+        Self {
+            parser: vt100::Parser::default(),
+            events: VecDeque::new(),
+            output: Vec::new(),
+            is_raw: false,
+            is_terminal: true,
+            reports_size: true,
+            reports_cursor: true,
+            styling_preference: None,
+            write_allowance: None,
+            flush_delay: None,
+            #[cfg(feature = "images")]
+            image_protocol: ImageProtocol::Blocks,
+        }
     }
 
     /// Access this device's underlying parser.
     pub fn parser(&mut self) -> &mut vt100::Parser {
-        &mut self.0
+        &mut self.parser
+    }
+
+    /// Every byte written to this device.
+    pub fn output(&self) -> &[u8] {
+        &self.output
+    }
+
+    /// Whether this device's terminal is in raw mode.
+    pub fn is_raw_mode_enabled(&self) -> bool {
+        self.is_raw
+    }
+
+    /// Update whether this device reports being an interactive terminal.
+    pub fn set_terminal(&mut self, is_terminal: bool) {
+        self.is_terminal = is_terminal;
+    }
+
+    /// Update whether this device reports its size. Without it, size queries fail as unsupported,
+    /// as they do for a file or pipe.
+    pub fn set_size_reported(&mut self, reports_size: bool) {
+        self.reports_size = reports_size;
+    }
+
+    /// Update whether this device reports its cursor. Without it, cursor queries fail as
+    /// unsupported, as they do for a file or pipe.
+    pub fn set_cursor_reported(&mut self, reports_cursor: bool) {
+        self.reports_cursor = reports_cursor;
+    }
+
+    /// Update the styling preference this device reports, such as from the environment.
+    pub fn set_styling_preference(&mut self, preference: Option<bool>) {
+        self.styling_preference = preference;
+    }
+
+    /// Limit the number of further bytes which may be written to this device, or remove the limit.
+    /// Once exhausted, writes fail with a broken pipe, and a write exceeding it is partial.
+    pub fn set_write_allowance(&mut self, allowance: Option<usize>) {
+        self.write_allowance = allowance;
+    }
+
+    /// Make each flush of this device take the specified duration on the clock, such as for a slow
+    /// connection.
+    pub fn set_flush_delay(&mut self, clock: VirtualClock, delay: Duration) {
+        self.flush_delay = Some((clock, delay));
+    }
+
+    /// Queue an input event to be reported by this device.
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+
+    /// Update the image protocol this device reports supporting. Image escape sequences are
+    /// ignored by the virtual terminal.
+    #[cfg(feature = "images")]
+    pub fn set_image_protocol(&mut self, protocol: ImageProtocol) {
+        self.image_protocol = protocol;
+    }
+
+    /// Resize this device's virtual terminal and queue the corresponding resize event.
+    pub fn resize(&mut self, size: Vector) {
+        // A narrower screen would cut a wide cell in its last column in half, which the parser
+        // can't print over, so it's erased first as terminals do
+        let (lines, _) = self.parser.screen().size();
+        let last_column = size.x().saturating_sub(1);
+        for line in 0..lines {
+            let cell = self.parser.screen().cell(line, last_column);
+            if cell.is_some_and(|cell| cell.is_wide()) {
+                let erase = format!("\x1B7\x1B[{};{}H\x1B[X\x1B8", line + 1, last_column + 1);
+                self.parser.process(erase.as_bytes());
+            }
+        }
+
+        self.parser.set_size(size.y(), size.x());
+        self.push_event(Event::Resize(size));
+    }
+}
+
+impl Default for VirtualDevice {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Device for VirtualDevice {
+impl SizedTarget for VirtualDevice {
     fn get_terminal_size(&mut self) -> Result<Vector> {
-        let (lines, columns) = self.0.screen().size();
+        if !self.reports_size {
+            return Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into());
+        }
+
+        let (lines, columns) = self.parser.screen().size();
         Ok(Vector::new(columns, lines))
     }
+}
 
+impl RawModeControl for VirtualDevice {
     fn enable_raw_mode(&mut self) -> Result<()> {
+        self.is_raw = true;
         Ok(())
     }
 
     fn disable_raw_mode(&mut self) -> Result<()> {
+        self.is_raw = false;
         Ok(())
     }
 
+    fn is_terminal(&self) -> bool {
+        self.is_terminal
+    }
+}
+
+impl CommandEncoder for VirtualDevice {
+    fn styling_preference(&self) -> Option<bool> {
+        self.styling_preference
+    }
+
+    #[cfg(feature = "images")]
+    fn image_protocol(&self) -> ImageProtocol {
+        self.image_protocol
+    }
+}
+
+impl EventSource for VirtualDevice {
+    /// Report the next queued event. Without one, a timeout elapses immediately, and waiting
+    /// indefinitely fails since no further input will arrive.
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        match (self.events.pop_front(), timeout) {
+            (Some(event), _) => Ok(Some(event)),
+            (None, Some(_)) => Ok(None),
+            (None, None) => Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into()),
+        }
+    }
+}
+
+impl CursorQuery for VirtualDevice {
     fn get_cursor_position(&mut self) -> Result<Position> {
-        Ok(pos!(0, 0))
+        if !self.reports_cursor {
+            return Err(std::io::Error::from(std::io::ErrorKind::Unsupported).into());
+        }
+
+        let (line, column) = self.parser.screen().cursor_position();
+        Ok(pos!(column, line))
     }
 }
 
 impl std::io::Write for VirtualDevice {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.0.write(buf)
+        let length = buf.len().min(self.write_allowance.unwrap_or(usize::MAX));
+        if length == 0 && !buf.is_empty() {
+            return Err(std::io::ErrorKind::BrokenPipe.into());
+        }
+
+        if let Some(allowance) = &mut self.write_allowance {
+            *allowance -= length;
+        }
+
+        self.output.extend_from_slice(&buf[..length]);
+        self.parser.write(&buf[..length])
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some((clock, delay)) = &self.flush_delay {
+            clock.advance(*delay);
+        }
+
+        self.parser.flush()
+    }
+}
+
+/// A virtual testing clock which only advances when instructed. Clones share the same time, so a
+/// clone may be given to an interface while the original is advanced by the test.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    origin: Instant,
+    elapsed: Rc<Cell<Duration>>,
+}
+
+impl VirtualClock {
+    /// Create a new clock starting at the current instant.
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            elapsed: Rc::new(Cell::new(Duration::ZERO)),
+        }
+    }
+
+    /// Advance this clock, and its clones, by the specified duration.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed.get()
+    }
+}
+
+/// An operation on an interface, for driving one from arbitrary sequences of operations such as
+/// a fuzzer's. See [check_operations].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// Set the text at the position.
+    Set(Position, String),
+    /// Set the styled text at the position.
+    SetStyled(Position, String, Style),
+    /// Clear the line.
+    ClearLine(u16),
+    /// Clear the area.
+    ClearRect(Rect),
+    /// Resize the terminal, with the interface reading the resize event.
+    Resize(Vector),
+    /// Apply the staged changes.
+    Apply,
+}
+
+/// Text for operations decoded from bytes, chosen for their widths: narrow, wide, and combined.
+/// Graphemes terminals disagree on the width of, such as emoji sequences, are avoided, since the
+/// virtual terminal's disagreement isn't the interface's fault.
+const OPERATION_TEXTS: [&str; 8] = ["a", "bc", " ", "漢", "e\u{301}", "👍", "字z", "\u{e9}"];
+
+impl Operation {
+    /// Decode a sequence of operations from arbitrary bytes, such as a fuzzer's input. Every
+    /// input decodes to some sequence, favoring positions and sizes near a small terminal's edges.
+    ///
+    /// # Examples
+    /// ```
+    /// use tty_interface::test::{check_operations, Operation};
+    /// use tty_interface::Mode;
+    ///
+    /// let operations = Operation::decode(&[0, 18, 3, 3, 5, 7, 1]);
+    /// assert!(check_operations(Mode::Alternate, &operations).is_ok());
+    /// ```
+    pub fn decode(data: &[u8]) -> Vec<Operation> {
+        let mut bytes = data.iter().copied();
+        let mut next = || bytes.next();
+        let mut operations = Vec::new();
+
+        while let Some(kind) = next() {
+            let mut position = || Some(pos!((next()? % 24) as u16, (next()? % 8) as u16));
+            let operation = match kind % 6 {
+                0 | 1 => {
+                    let Some(position) = position() else {
+                        break;
+                    };
+
+                    let Some(count) = next() else {
+                        break;
+                    };
+
+                    let mut text = String::new();
+                    for _ in 0..=count % 4 {
+                        match next() {
+                            Some(selector) => {
+                                text.push_str(OPERATION_TEXTS[selector as usize % 8]);
+                            }
+                            None => break,
+                        }
+                    }
+
+                    match kind % 6 {
+                        0 => Operation::Set(position, text),
+                        _ => Operation::SetStyled(position, text, Color::Red.as_style()),
+                    }
+                }
+                2 => match next() {
+                    Some(line) => Operation::ClearLine((line % 8) as u16),
+                    None => break,
+                },
+                3 => match (position(), next()) {
+                    (Some(origin), Some(size)) => {
+                        let size = Vector::new((size % 16) as u16, (size / 16 % 8) as u16);
+                        Operation::ClearRect(Rect::new(origin, size))
+                    }
+                    _ => break,
+                },
+                4 => match next() {
+                    Some(size) => {
+                        let (columns, lines) = (8 + size % 24, 2 + size / 24 % 8);
+                        Operation::Resize(Vector::new(columns as u16, lines as u16))
+                    }
+                    None => break,
+                },
+                _ => Operation::Apply,
+            };
+
+            operations.push(operation);
+        }
+
+        operations
+    }
+}
+
+/// Run the operations against an interface of the specified mode on a virtual device, checking
+/// the interface's invariants after each: cells aren't overlapped by wide cells, only positions
+/// which were changed are staged as dirty, and once applied, nothing is left dirty and the
+/// cursor is within the screen where the interface expects it. Returns a description of the
+/// first violated invariant, or of an error from the interface.
+///
+/// # Examples
+/// ```
+/// use tty_interface::test::{check_operations, Operation};
+/// use tty_interface::{pos, Mode, Position, Vector};
+///
+/// let operations = [
+///     Operation::Set(pos!(78, 0), "漢字".to_string()),
+///     Operation::Apply,
+///     Operation::Resize(Vector::new(40, 10)),
+///     Operation::Set(pos!(39, 9), "👍".to_string()),
+///     Operation::Apply,
+/// ];
+/// assert!(check_operations(Mode::Alternate, &operations).is_ok());
+/// ```
+pub fn check_operations(mode: Mode, operations: &[Operation]) -> std::result::Result<(), String> {
+    let device = SharedDevice::default();
+    let mut shared_device = device.clone();
+    let mut interface = Interface::builder()
+        .mode(mode)
+        .build(&mut shared_device)
+        .map_err(|error| error.to_string())?;
+
+    // The areas changed since the last apply, widened by a column for wide cells they break
+    let mut touched: Vec<Rect> = Vec::new();
+    for (index, operation) in operations.iter().enumerate() {
+        let describe = |violation: String| format!("after operation {}: {}", index, violation);
+
+        match operation {
+            Operation::Set(position, text) => {
+                let rect = interface.set(*position, text);
+                touched.push(widen(rect));
+            }
+            Operation::SetStyled(position, text, style) => {
+                let rect = interface.set_styled(*position, text, *style);
+                touched.push(widen(rect));
+            }
+            Operation::ClearLine(line) => {
+                interface.clear_line(*line);
+                touched.push(Rect::new(pos!(0, *line), Vector::new(u16::MAX, 1)));
+            }
+            Operation::ClearRect(rect) => {
+                interface.clear_rect(*rect);
+                touched.push(widen(*rect));
+            }
+            Operation::Resize(size) => {
+                device.device().resize(*size);
+                interface
+                    .poll_event(Duration::ZERO)
+                    .map_err(|error| describe(error.to_string()))?;
+
+                // Cells the new size reveals are rendered again
+                let revealed = interface.dirty_positions().into_iter();
+                touched.extend(revealed.map(|position| Rect::new(position, Vector::new(1, 1))));
+            }
+            Operation::Apply => {
+                interface
+                    .apply()
+                    .map_err(|error| describe(error.to_string()))?;
+                touched.clear();
+
+                let (line, column) = device.device().parser.screen().cursor_position();
+                interface
+                    .check_applied_invariants(pos!(column, line))
+                    .map_err(describe)?;
+
+                verify_screen(&mut interface, &device).map_err(describe)?;
+            }
+        }
+
+        interface.check_invariants().map_err(describe)?;
+
+        let untouched = interface
+            .dirty_positions()
+            .into_iter()
+            .find(|position| !touched.iter().any(|rect| rect.contains(*position)));
+        if let Some(position) = untouched {
+            return Err(describe(format!(
+                "unchanged position {:?} is dirty",
+                position
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Widen the area by a column to either side, where wide cells changed by changes to the area
+/// begin or end.
+fn widen(rect: Rect) -> Rect {
+    let origin = rect.origin();
+    let widening = origin.x().min(1);
+    let size = Vector::new(
+        rect.size().x().saturating_add(widening + 1),
+        rect.size().y(),
+    );
+    Rect::new(pos!(origin.x() - widening, origin.y()), size)
+}
+
+/// Compare the device's screen with the interface's applied state cell-for-cell, including
+/// styles, returning a description of the first position at which they diverge. The state is
+/// rendered onto a blank screen with the same encoder for comparison. Only alternate interfaces
+/// whose frames are fully applied, without content composited over their state such as ghosts,
+/// are compared.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{test::{verify_screen, SharedDevice}, Interface, Position, pos};
+///
+/// let device = SharedDevice::default();
+/// let mut interface_device = device.clone();
+/// let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+///
+/// interface.set(pos!(0, 0), "Verified");
+/// interface.apply().unwrap();
+/// assert!(verify_screen(&mut interface, &device).is_ok());
+///
+/// device.device().parser().process(b"Overwritten");
+/// assert!(verify_screen(&mut interface, &device).is_err());
+/// ```
+pub fn verify_screen(
+    interface: &mut Interface,
+    device: &SharedDevice,
+) -> std::result::Result<(), String> {
+    let screen = device.device().parser.screen().clone();
+    match interface.find_screen_divergence(&screen) {
+        Ok(None) => Ok(()),
+        Ok(Some(divergence)) => Err(format!("the screen diverged {}", divergence)),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// A virtual device shared between an interface and the test driving it, so the test may inspect
+/// or resize the device while the interface is active. Clones share the same device.
+#[derive(Clone, Default)]
+pub struct SharedDevice(Rc<RefCell<VirtualDevice>>);
+
+impl SharedDevice {
+    /// Share the specified device.
+    pub fn new(device: VirtualDevice) -> Self {
+        Self(Rc::new(RefCell::new(device)))
+    }
+
+    /// Access the shared device. Panics if it's already being accessed.
+    pub fn device(&self) -> RefMut<'_, VirtualDevice> {
+        self.0.borrow_mut()
+    }
+}
+
+impl SizedTarget for SharedDevice {
+    fn get_terminal_size(&mut self) -> Result<Vector> {
+        self.0.borrow_mut().get_terminal_size()
+    }
+}
+
+impl RawModeControl for SharedDevice {
+    fn enable_raw_mode(&mut self) -> Result<()> {
+        self.0.borrow_mut().enable_raw_mode()
+    }
+
+    fn disable_raw_mode(&mut self) -> Result<()> {
+        self.0.borrow_mut().disable_raw_mode()
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.0.borrow().is_terminal()
+    }
+}
+
+impl CommandEncoder for SharedDevice {
+    fn styling_preference(&self) -> Option<bool> {
+        self.0.borrow().styling_preference()
+    }
+
+    #[cfg(feature = "images")]
+    fn image_protocol(&self) -> ImageProtocol {
+        self.0.borrow().image_protocol()
+    }
+}
+
+impl EventSource for SharedDevice {
+    fn read_event(&mut self, timeout: Option<Duration>) -> Result<Option<Event>> {
+        self.0.borrow_mut().read_event(timeout)
+    }
+}
+
+impl CursorQuery for SharedDevice {
+    fn get_cursor_position(&mut self) -> Result<Position> {
+        self.0.borrow_mut().get_cursor_position()
+    }
+}
+
+impl std::io::Write for SharedDevice {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        self.0.flush()
+        self.0.borrow_mut().flush()
     }
 }
@@ -0,0 +1,321 @@
+#[cfg(feature = "std")]
+use alloc::string::ToString;
+#[cfg(not(feature = "graphemes"))]
+use alloc::vec::Vec;
+use alloc::{borrow::Cow, collections::BTreeMap, string::String};
+
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+#[cfg(not(feature = "graphemes"))]
+use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
+
+/// The number of graphemes (user-perceived characters) in the specified text.
+///
+/// # Examples
+/// ```
+/// use tty_interface::grapheme_count;
+///
+/// assert_eq!(5, grapheme_count("héllo"));
+/// assert_eq!(2, grapheme_count("👍🏽🇺🇸"));
+/// ```
+pub fn grapheme_count(text: &str) -> usize {
+    split_graphemes(text).count()
+}
+
+/// Slice the specified text by grapheme indices rather than byte offsets, so grapheme clusters
+/// are never split. The range is start-inclusive and end-exclusive, clamped to the text's length.
+///
+/// # Examples
+/// ```
+/// use tty_interface::slice_graphemes;
+///
+/// assert_eq!("llo", slice_graphemes("héllo", 2, 5));
+/// assert_eq!("🇺🇸", slice_graphemes("👍🏽🇺🇸!", 1, 2));
+/// assert_eq!("", slice_graphemes("abc", 2, 1));
+/// ```
+pub fn slice_graphemes(text: &str, start: usize, end: usize) -> &str {
+    if start >= end {
+        return "";
+    }
+
+    let mut offsets = grapheme_indices(text)
+        .map(|(offset, _)| offset)
+        .chain(core::iter::once(text.len()));
+
+    let start_offset = match offsets.nth(start) {
+        Some(offset) => offset,
+        None => return "",
+    };
+
+    let end_offset = offsets.nth(end - start - 1).unwrap_or(text.len());
+
+    &text[start_offset..end_offset]
+}
+
+/// Split the specified text into its graphemes.
+pub(crate) fn split_graphemes(text: &str) -> impl DoubleEndedIterator<Item = &str> {
+    grapheme_indices(text).map(|(_, grapheme)| grapheme)
+}
+
+/// Split the specified text into its graphemes, each with its byte offset.
+#[cfg(feature = "graphemes")]
+pub(crate) fn grapheme_indices(text: &str) -> impl DoubleEndedIterator<Item = (usize, &str)> {
+    text.grapheme_indices(true)
+}
+
+/// Split the specified text into its graphemes, each with its byte offset. Without Unicode's
+/// segmentation tables, each character starts a grapheme unless it's zero-width, such as a
+/// combining mark or variation selector, follows a zero-width joiner, is an emoji modifier, or
+/// completes a pair of regional indicators. Rarer clusters, such as Hangul syllables composed
+/// of jamo, are split.
+#[cfg(not(feature = "graphemes"))]
+pub(crate) fn grapheme_indices(text: &str) -> impl DoubleEndedIterator<Item = (usize, &str)> {
+    let is_regional_indicator = |character| ('\u{1F1E6}'..='\u{1F1FF}').contains(&character);
+    let is_emoji_modifier = |character| ('\u{1F3FB}'..='\u{1F3FF}').contains(&character);
+
+    let mut starts = Vec::new();
+    let mut previous = None;
+    let mut regional_indicators = 0;
+    for (offset, character) in text.char_indices() {
+        let is_extending = match previous {
+            None => false,
+            Some('\r') => character == '\n',
+            Some('\u{200D}') => true,
+            Some(previous) => {
+                character.width() == Some(0)
+                    || is_emoji_modifier(character)
+                    || is_regional_indicator(previous)
+                        && is_regional_indicator(character)
+                        && regional_indicators % 2 == 1
+            }
+        };
+
+        if !is_extending {
+            starts.push(offset);
+            regional_indicators = 0;
+        }
+        if is_regional_indicator(character) {
+            regional_indicators += 1;
+        }
+        previous = Some(character);
+    }
+
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .copied()
+        .chain(core::iter::once(text.len()))
+        .collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .map(move |(start, end)| (start, &text[start..end]))
+}
+
+/// How many columns a terminal displays graphemes with, which may differ from unicode-width's
+/// widths. Each interface measures its own terminal, so these are kept per interface.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub(crate) struct Widths {
+    /// Whether East Asian ambiguous-width characters occupy two columns.
+    is_ambiguous_wide: bool,
+    /// Widths measured from the terminal for graphemes it displays differently than
+    /// unicode-width reports, such as emoji with variation selectors.
+    measured: BTreeMap<String, u16>,
+}
+
+impl Widths {
+    /// Update whether East Asian ambiguous-width characters occupy two columns.
+    #[cfg(feature = "std")]
+    pub(crate) fn set_ambiguous_wide(&mut self, is_wide: bool) {
+        self.is_ambiguous_wide = is_wide;
+    }
+
+    /// Record the width the terminal was measured displaying the grapheme with, overriding
+    /// unicode-width's.
+    #[cfg(feature = "std")]
+    pub(crate) fn set_measured(&mut self, grapheme: &str, width: u16) {
+        self.measured
+            .insert(grapheme.to_string(), width.clamp(1, 2));
+    }
+
+    /// The number of columns the specified grapheme occupies when displayed. Every grapheme
+    /// occupies at least one column so zero-width sequences (e.g. a lone combining mark) still
+    /// claim a cell.
+    pub(crate) fn grapheme_width(&self, grapheme: &str) -> u16 {
+        if grapheme.len() > 1 {
+            if let Some(width) = self.measured.get(grapheme) {
+                return *width;
+            }
+        }
+
+        let width = match self.is_ambiguous_wide {
+            true => grapheme.width_cjk(),
+            false => grapheme.width(),
+        };
+
+        width.clamp(1, 2) as u16
+    }
+
+    /// The number of columns the specified text occupies when displayed on a single line.
+    #[cfg(feature = "std")]
+    pub(crate) fn text_width(&self, text: &str) -> u16 {
+        split_graphemes(text)
+            .map(|grapheme| self.grapheme_width(grapheme))
+            .sum()
+    }
+}
+
+/// The number of columns the specified grapheme occupies when displayed, by unicode-width's
+/// widths with ambiguous-width characters narrow.
+pub(crate) fn grapheme_width(grapheme: &str) -> u16 {
+    Widths::default().grapheme_width(grapheme)
+}
+
+/// Normalizes the specified text to its canonical composed form (NFC) so that equivalent
+/// sequences, such as a letter followed by a combining accent, are staged identically.
+pub(crate) fn normalize(text: &str) -> Cow<'_, str> {
+    if is_nfc(text) {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.nfc().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    #[cfg(feature = "std")]
+    use super::Widths;
+    use super::{grapheme_count, grapheme_width, normalize, slice_graphemes, split_graphemes};
+
+    #[test]
+    fn text_grapheme_count() {
+        assert_eq!(0, grapheme_count(""));
+        assert_eq!(3, grapheme_count("abc"));
+        assert_eq!(1, grapheme_count("e\u{301}"));
+        assert_eq!(1, grapheme_count("👨\u{200d}👩\u{200d}👧"));
+    }
+
+    #[test]
+    fn text_split_graphemes() {
+        let text = "ae\u{301}👍🏽🇺🇸🇬🇧\r\n👨\u{200D}👩\u{200D}👧❤\u{FE0F}";
+        let graphemes: Vec<&str> = split_graphemes(text).collect();
+        assert_eq!(
+            vec![
+                "a",
+                "e\u{301}",
+                "👍🏽",
+                "🇺🇸",
+                "🇬🇧",
+                "\r\n",
+                "👨\u{200D}👩\u{200D}👧",
+                "❤\u{FE0F}"
+            ],
+            graphemes
+        );
+
+        let reversed: Vec<&str> = split_graphemes("ab\u{301}").rev().collect();
+        assert_eq!(vec!["b\u{301}", "a"], reversed);
+    }
+
+    #[test]
+    fn text_slice_graphemes() {
+        let text = "ae\u{301}👍🏽c";
+
+        let cases = [
+            (0, 4, text),
+            (0, 1, "a"),
+            (1, 2, "e\u{301}"),
+            (2, 3, "👍🏽"),
+            (1, 3, "e\u{301}👍🏽"),
+            (3, 10, "c"),
+            (4, 10, ""),
+            (10, 12, ""),
+            (2, 2, ""),
+            (3, 1, ""),
+        ];
+
+        for (start, end, expected) in cases {
+            assert_eq!(
+                expected,
+                slice_graphemes(text, start, end),
+                "{}..{}",
+                start,
+                end
+            );
+        }
+    }
+
+    #[test]
+    fn text_grapheme_width() {
+        let cases = [
+            ("a", 1),
+            ("é", 1),
+            ("e\u{301}", 1),
+            ("\u{301}", 1),
+            ("漢", 2),
+            ("👍", 2),
+            ("👍🏽", 2),
+            ("🇺🇸", 2),
+            ("👨\u{200d}👩\u{200d}👧", 2),
+            ("❤\u{fe0f}", 2),
+        ];
+
+        for (grapheme, expected) in cases {
+            assert_eq!(
+                expected,
+                grapheme_width(grapheme),
+                "width of {:?}",
+                grapheme
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn text_ambiguous_width() {
+        let mut widths = Widths::default();
+        assert_eq!(1, widths.grapheme_width("→"));
+
+        widths.set_ambiguous_wide(true);
+        assert_eq!(2, widths.grapheme_width("→"));
+        assert_eq!(1, widths.grapheme_width("a"));
+        assert_eq!(5, widths.text_width("°→a"));
+
+        // Other widths, such as another interface's, are unaffected
+        assert_eq!(3, Widths::default().text_width("°→a"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn text_measured_width() {
+        let mut widths = Widths::default();
+        assert_eq!(2, widths.grapheme_width("❤\u{fe0f}"));
+
+        widths.set_measured("❤\u{fe0f}", 1);
+        assert_eq!(1, widths.grapheme_width("❤\u{fe0f}"));
+        assert_eq!(3, widths.text_width("❤\u{fe0f}ab"));
+
+        assert_eq!(2, grapheme_width("❤\u{fe0f}"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn text_text_width() {
+        let widths = Widths::default();
+        assert_eq!(0, widths.text_width(""));
+        assert_eq!(5, widths.text_width("hello"));
+        assert_eq!(5, widths.text_width("漢字!"));
+        assert_eq!(3, widths.text_width("👍🏽a"));
+    }
+
+    #[test]
+    fn text_normalize() {
+        assert_eq!("abc", normalize("abc"));
+        assert_eq!("\u{e9}", normalize("e\u{301}"));
+        assert_eq!("\u{1e69}", normalize("s\u{323}\u{307}"));
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use crate::Style;
+
+/// A set of named styles, such as "error" or "selected", which text may be staged with by name.
+/// Switching an interface's theme, e.g. between light and dark variants, restyles the text staged
+/// with its names. With the `serde` feature, a theme may be loaded from a map of names to styles
+/// in formats such as JSON or TOML.
+///
+/// # Examples
+/// ```
+/// use tty_interface::{Color, Style, Theme};
+///
+/// let mut theme = Theme::new();
+/// theme.define("error", Color::Red.as_style().set_bold(true));
+/// theme.define("muted", Color::DarkGrey.as_style());
+///
+/// assert_eq!(Some(Color::DarkGrey.as_style()), theme.get("muted"));
+/// assert_eq!(None, theme.get("warning"));
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Theme {
+    styles: HashMap<String, Style>,
+}
+
+impl Theme {
+    /// Create a theme with no named styles.
+    pub fn new() -> Theme {
+        Theme {
+            styles: HashMap::new(),
+        }
+    }
+
+    /// Define the style for a name, returning the style it previously had, if any.
+    pub fn define(&mut self, name: &str, style: Style) -> Option<Style> {
+        self.styles.insert(name.to_string(), style)
+    }
+
+    /// Remove a name's style, returning it if it was defined.
+    pub fn remove(&mut self, name: &str) -> Option<Style> {
+        self.styles.remove(name)
+    }
+
+    /// The style defined for a name, if any.
+    pub fn get(&self, name: &str) -> Option<Style> {
+        self.styles.get(name).copied()
+    }
+
+    /// The names with defined styles, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.styles.keys().map(String::as_str)
+    }
+}
+
+impl<N: Into<String>> FromIterator<(N, Style)> for Theme {
+    fn from_iter<T: IntoIterator<Item = (N, Style)>>(styles: T) -> Self {
+        Theme {
+            styles: styles
+                .into_iter()
+                .map(|(name, style)| (name.into(), style))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Theme;
+    use crate::{Color, Style};
+
+    #[test]
+    fn theme_define() {
+        let mut theme: Theme = [("error", Color::Red.as_style())].into_iter().collect();
+        assert_eq!(
+            Some(Color::Red.as_style()),
+            theme.define("error", Color::DarkRed.as_style())
+        );
+        assert_eq!(None, theme.define("title", Style::new().set_bold(true)));
+
+        assert_eq!(Some(Color::DarkRed.as_style()), theme.get("error"));
+        assert_eq!(Some(Style::new().set_bold(true)), theme.remove("title"));
+        assert_eq!(vec!["error"], theme.names().collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn theme_deserialize() {
+        let theme: Theme = serde_json::from_str(
+            r#"{
+                "error": { "foreground": "red", "bold": true },
+                "selected": { "foreground": "black", "background": "dark_cyan" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some(Color::Red.as_style().set_bold(true)),
+            theme.get("error")
+        );
+        assert_eq!(
+            Some(Color::Black.as_style().set_background(Color::DarkCyan)),
+            theme.get("selected")
+        );
+
+        let json = serde_json::to_string(&theme).unwrap();
+        assert_eq!(theme, serde_json::from_str(&json).unwrap());
+    }
+}
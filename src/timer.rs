@@ -0,0 +1,181 @@
+use std::time::{Duration, Instant};
+
+use crate::Style;
+
+/// A clock display showing the time elapsed since a start or remaining until a deadline, formatted
+/// as "HH:MM:SS". Timers are rendered with [crate::Interface::set_timer] using the interface's
+/// clock, so they're typically restaged periodically, e.g. when pumping.
+///
+/// # Examples
+/// ```
+/// use std::time::{Duration, Instant};
+/// use tty_interface::{Color, Timer};
+///
+/// let deadline = Instant::now() + Duration::from_secs(90);
+/// let timer = Timer::countdown(deadline)
+///     .set_warning(Duration::from_secs(10), Color::Red.as_style());
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Timer {
+    kind: TimerKind,
+    style: Option<Style>,
+    warning: Option<(Duration, Style)>,
+}
+
+/// Whether a timer counts up from a start or down to a deadline.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum TimerKind {
+    Elapsed(Instant),
+    Countdown(Instant),
+}
+
+impl Timer {
+    /// Create a timer showing the time elapsed since the specified start.
+    pub fn elapsed(start: Instant) -> Timer {
+        Timer::new(TimerKind::Elapsed(start))
+    }
+
+    /// Create a timer showing the time remaining until the specified deadline, stopping at zero.
+    pub fn countdown(deadline: Instant) -> Timer {
+        Timer::new(TimerKind::Countdown(deadline))
+    }
+
+    /// Create a timer of the specified kind with no styling.
+    fn new(kind: TimerKind) -> Timer {
+        Timer {
+            kind,
+            style: None,
+            warning: None,
+        }
+    }
+
+    /// Create a new timer with the specified style applied to its content.
+    pub fn set_style(self, style: Style) -> Timer {
+        Timer {
+            style: Some(style),
+            ..self
+        }
+    }
+
+    /// Create a new timer which is styled differently once a countdown's remaining time drops to
+    /// the threshold, or once an elapsed timer's time reaches it.
+    pub fn set_warning(self, threshold: Duration, style: Style) -> Timer {
+        Timer {
+            warning: Some((threshold, style)),
+            ..self
+        }
+    }
+
+    /// The duration this timer displays at the specified instant.
+    pub fn duration_at(&self, now: Instant) -> Duration {
+        match self.kind {
+            TimerKind::Elapsed(start) => now.saturating_duration_since(start),
+            TimerKind::Countdown(deadline) => deadline.saturating_duration_since(now),
+        }
+    }
+
+    /// Whether this is a countdown whose deadline has passed at the specified instant.
+    pub fn is_expired_at(&self, now: Instant) -> bool {
+        match self.kind {
+            TimerKind::Elapsed(_) => false,
+            TimerKind::Countdown(deadline) => now >= deadline,
+        }
+    }
+
+    /// This timer's "HH:MM:SS" text at the specified instant. Hours are not limited to two digits.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::{Duration, Instant};
+    /// use tty_interface::Timer;
+    ///
+    /// let start = Instant::now();
+    /// let timer = Timer::elapsed(start);
+    /// assert_eq!("01:02:03", timer.text_at(start + Duration::from_secs(3723)));
+    /// ```
+    pub fn text_at(&self, now: Instant) -> String {
+        let seconds = self.duration_at(now).as_secs();
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+
+    /// This timer's style at the specified instant, if any.
+    pub fn style_at(&self, now: Instant) -> Option<Style> {
+        if let Some((threshold, style)) = self.warning {
+            let duration = self.duration_at(now);
+            let is_warning = match self.kind {
+                TimerKind::Elapsed(_) => duration >= threshold,
+                TimerKind::Countdown(_) => duration <= threshold,
+            };
+
+            if is_warning {
+                return Some(style);
+            }
+        }
+
+        self.style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{Color, Style, Timer};
+
+    #[test]
+    fn timer_elapsed_text() {
+        let start = Instant::now();
+        let timer = Timer::elapsed(start);
+
+        assert_eq!("00:00:00", timer.text_at(start));
+        assert_eq!(
+            "00:00:59",
+            timer.text_at(start + Duration::from_millis(59_999))
+        );
+        assert_eq!("00:01:00", timer.text_at(start + Duration::from_secs(60)));
+        assert_eq!(
+            "100:00:00",
+            timer.text_at(start + Duration::from_secs(360_000))
+        );
+        assert_eq!("00:00:00", timer.text_at(start - Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn timer_countdown_text() {
+        let now = Instant::now();
+        let timer = Timer::countdown(now + Duration::from_secs(90));
+
+        assert_eq!("00:01:30", timer.text_at(now));
+        assert_eq!("00:00:00", timer.text_at(now + Duration::from_secs(120)));
+        assert!(!timer.is_expired_at(now));
+        assert!(timer.is_expired_at(now + Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn timer_warning_style() {
+        let now = Instant::now();
+        let style = Style::new().set_bold(true);
+        let warning = Color::Red.as_style();
+
+        let countdown = Timer::countdown(now + Duration::from_secs(30))
+            .set_style(style)
+            .set_warning(Duration::from_secs(10), warning);
+        assert_eq!(Some(style), countdown.style_at(now));
+        assert_eq!(
+            Some(warning),
+            countdown.style_at(now + Duration::from_secs(20))
+        );
+
+        let elapsed = Timer::elapsed(now).set_warning(Duration::from_secs(10), warning);
+        assert_eq!(None, elapsed.style_at(now));
+        assert_eq!(
+            Some(warning),
+            elapsed.style_at(now + Duration::from_secs(10))
+        );
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{
+    pos,
+    text::{split_graphemes, Widths},
+    Event, Interface, KeyCode, Position, Rect, Style,
+};
+
+/// An item in a tree with its nested items, which are shown when it's expanded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TreeNode {
+    label: String,
+    children: Vec<TreeNode>,
+    is_expanded: bool,
+}
+
+impl TreeNode {
+    /// Create a collapsed node with the specified label and no children.
+    pub fn new(label: &str) -> TreeNode {
+        TreeNode {
+            label: label.lines().next().unwrap_or_default().to_string(),
+            children: Vec::new(),
+            is_expanded: false,
+        }
+    }
+
+    /// Create a new node with the specified children.
+    pub fn with_children(self, children: Vec<TreeNode>) -> TreeNode {
+        TreeNode { children, ..self }
+    }
+
+    /// Create a new node, expanded if specified.
+    pub fn set_expanded(self, is_expanded: bool) -> TreeNode {
+        TreeNode {
+            is_expanded,
+            ..self
+        }
+    }
+
+    /// This node's label.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// This node's children.
+    pub fn children(&self) -> &[TreeNode] {
+        &self.children
+    }
+
+    /// This node's children, for modification.
+    pub fn children_mut(&mut self) -> &mut Vec<TreeNode> {
+        &mut self.children
+    }
+
+    /// Whether this node's children are shown.
+    pub fn is_expanded(&self) -> bool {
+        self.is_expanded
+    }
+}
+
+/// A visible row of a tree.
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct TreeRow {
+    /// The child indices leading from the roots to this row's node.
+    path: Vec<usize>,
+    /// The indentation guides, expansion marker, and label.
+    text: String,
+}
+
+/// A scrollable list of hierarchical items drawn with indentation guides and markers showing
+/// whether each item with children is expanded, one of which is selected. The up and down arrow
+/// keys move the selection, right and left expand and collapse the selected item or move into
+/// and out of it, and enter toggles it. Re-rendering only stages the rows which changed.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use tty_interface::{Interface, Position, Rect, Tree, TreeNode, Vector, pos};
+///
+/// let mut interface = Interface::new_alternate(&mut device)?;
+///
+/// let mut tree = Tree::new(vec![TreeNode::new("src")
+///     .with_children(vec![TreeNode::new("lib.rs"), TreeNode::new("main.rs")])
+///     .set_expanded(true)]);
+///
+/// // src
+/// // ├─ lib.rs
+/// // └─ main.rs
+/// tree.render(&mut interface, Rect::new(pos!(0, 0), Vector::new(20, 5)));
+/// interface.apply()?;
+///
+/// tree.select_next();
+/// assert_eq!(vec![0, 0], tree.selected_path());
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tree {
+    roots: Vec<TreeNode>,
+    selected: usize,
+    scroll: usize,
+    style: Option<Style>,
+    selected_style: Style,
+    rendered: Option<(Rect, Vec<(String, bool)>)>,
+}
+
+impl Tree {
+    /// Create a tree of the specified root nodes with the first selected and shown in bold.
+    pub fn new(roots: Vec<TreeNode>) -> Tree {
+        Tree {
+            roots,
+            selected: 0,
+            scroll: 0,
+            style: None,
+            selected_style: Style::new().set_bold(true),
+            rendered: None,
+        }
+    }
+
+    /// Create a new tree with the specified style for unselected rows.
+    pub fn set_style(self, style: Style) -> Tree {
+        Tree {
+            style: Some(style),
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// Create a new tree with the specified style for the selected row.
+    pub fn set_selected_style(self, selected_style: Style) -> Tree {
+        Tree {
+            selected_style,
+            rendered: None,
+            ..self
+        }
+    }
+
+    /// This tree's root nodes.
+    pub fn roots(&self) -> &[TreeNode] {
+        &self.roots
+    }
+
+    /// This tree's root nodes, for modification. The selection is kept at the same row.
+    pub fn roots_mut(&mut self) -> &mut Vec<TreeNode> {
+        &mut self.roots
+    }
+
+    /// The child indices leading from the roots to the selected node, empty if there are none.
+    pub fn selected_path(&self) -> Vec<usize> {
+        let rows = self.rows();
+        match rows.get(self.selected.min(rows.len().saturating_sub(1))) {
+            Some(row) => row.path.clone(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The selected node, if any.
+    pub fn selected(&self) -> Option<&TreeNode> {
+        self.node(&self.selected_path())
+    }
+
+    /// Select the node following the selected one, if any.
+    pub fn select_next(&mut self) {
+        let last = self.rows().len().saturating_sub(1);
+        self.selected = (self.selected + 1).min(last);
+    }
+
+    /// Select the node preceding the selected one, if any.
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Expand the selected node, showing its children.
+    pub fn expand(&mut self) {
+        self.set_selected_expanded(|_| true);
+    }
+
+    /// Collapse the selected node, hiding its children.
+    pub fn collapse(&mut self) {
+        self.set_selected_expanded(|_| false);
+    }
+
+    /// Expand the selected node if it's collapsed, otherwise collapse it.
+    pub fn toggle(&mut self) {
+        self.set_selected_expanded(|is_expanded| !is_expanded);
+    }
+
+    /// Handle an input event, moving the selection with the up and down arrow keys, expanding
+    /// the selected node or selecting its first child with the right arrow key, collapsing it or
+    /// selecting its parent with the left arrow key, and toggling it with enter. Returns whether
+    /// the event was consumed.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        let Event::Key(event) = event else {
+            return false;
+        };
+
+        let path = self.selected_path();
+        let Some(node) = self.node(&path) else {
+            return false;
+        };
+
+        match event.code() {
+            KeyCode::Up => self.select_previous(),
+            KeyCode::Down => self.select_next(),
+            KeyCode::Right if node.is_expanded && !node.children.is_empty() => self.select_next(),
+            KeyCode::Right => self.expand(),
+            KeyCode::Left if node.is_expanded && !node.children.is_empty() => self.collapse(),
+            KeyCode::Left if path.len() > 1 => {
+                let parent = &path[..path.len() - 1];
+                if let Some(row) = self.rows().iter().position(|row| row.path == parent) {
+                    self.selected = row;
+                }
+            }
+            KeyCode::Enter => self.toggle(),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Stage the visible rows within the rectangle, scrolled to keep the selection in view.
+    /// Only rows which changed since the tree was last rendered are staged, returning whether
+    /// any were.
+    pub fn render(&mut self, interface: &mut Interface, rect: Rect) -> bool {
+        let rows = self.rows();
+        let height = rect.size().y() as usize;
+        self.selected = self.selected.min(rows.len().saturating_sub(1));
+
+        // Scroll the least amount keeping the selection in view
+        self.scroll = self.scroll.min(rows.len().saturating_sub(height));
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if height > 0 && self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let widths = interface.widths();
+        let lines: Vec<(String, bool)> = (0..height)
+            .map(|line| {
+                let index = self.scroll + line;
+                let text = rows.get(index).map_or("", |row| row.text.as_str());
+                (
+                    fit_width(text, rect.size().x(), widths),
+                    index == self.selected,
+                )
+            })
+            .collect();
+
+        let previous = match self.rendered.take() {
+            Some((previous_rect, previous)) if previous_rect == rect => previous,
+            _ => Vec::new(),
+        };
+
+        let mut is_staged = false;
+        for (line, (text, is_selected)) in lines.iter().enumerate() {
+            if previous.get(line) == Some(&(text.clone(), *is_selected)) {
+                continue;
+            }
+
+            let position = pos!(rect.origin().x(), rect.origin().y() + line as u16);
+            match (is_selected, self.style) {
+                (true, _) => interface.set_styled(position, text, self.selected_style),
+                (false, Some(style)) => interface.set_styled(position, text, style),
+                (false, None) => interface.set(position, text),
+            };
+            is_staged = true;
+        }
+
+        self.rendered = Some((rect, lines));
+        is_staged
+    }
+
+    /// The node at the specified path, if any.
+    fn node(&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.roots.get(*first)?, |node, index| {
+                node.children.get(*index)
+            })
+    }
+
+    /// Update the selected node's expansion from whether it's currently expanded.
+    fn set_selected_expanded(&mut self, update: impl Fn(bool) -> bool) {
+        let path = self.selected_path();
+        let Some((first, rest)) = path.split_first() else {
+            return;
+        };
+
+        let mut node = &mut self.roots[*first];
+        for index in rest {
+            node = &mut node.children[*index];
+        }
+
+        if !node.children.is_empty() {
+            node.is_expanded = update(node.is_expanded);
+        }
+    }
+
+    /// The visible rows, descending into expanded nodes.
+    fn rows(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        for (index, root) in self.roots.iter().enumerate() {
+            push_rows(&mut rows, root, vec![index], String::new(), None);
+        }
+
+        rows
+    }
+}
+
+/// Push the node's row and, if it's expanded, its descendants' rows. The guide is the text
+/// indenting the node's ancestors, and whether it's the last of its siblings is unspecified for
+/// roots, which aren't connected by guides.
+fn push_rows(
+    rows: &mut Vec<TreeRow>,
+    node: &TreeNode,
+    path: Vec<usize>,
+    guide: String,
+    is_last: Option<bool>,
+) {
+    let connector = match is_last {
+        Some(true) => "└─ ",
+        Some(false) => "├─ ",
+        None => "",
+    };
+    let marker = match (node.children.is_empty(), node.is_expanded) {
+        (true, _) => "",
+        (false, true) => "▾ ",
+        (false, false) => "▸ ",
+    };
+
+    rows.push(TreeRow {
+        path: path.clone(),
+        text: format!("{}{}{}{}", guide, connector, marker, node.label),
+    });
+
+    if !node.is_expanded {
+        return;
+    }
+
+    let child_guide = match is_last {
+        Some(true) => format!("{}   ", guide),
+        Some(false) => format!("{}│  ", guide),
+        None => guide,
+    };
+
+    let count = node.children.len();
+    for (index, child) in node.children.iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(index);
+        push_rows(
+            rows,
+            child,
+            child_path,
+            child_guide.clone(),
+            Some(index + 1 == count),
+        );
+    }
+}
+
+/// The text truncated or padded with spaces to the width, measured with the widths.
+fn fit_width(text: &str, width: u16, widths: &Widths) -> String {
+    let mut used = 0;
+    let mut fitted = String::new();
+    for grapheme in split_graphemes(text) {
+        used += widths.grapheme_width(grapheme);
+        if used > width {
+            used -= widths.grapheme_width(grapheme);
+            break;
+        }
+
+        fitted.push_str(grapheme);
+    }
+
+    fitted.push_str(&" ".repeat((width - used) as usize));
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{text::Widths, Event, KeyCode, KeyEvent, Modifiers};
+
+    use super::{fit_width, Tree, TreeNode};
+
+    fn tree() -> Tree {
+        Tree::new(vec![
+            TreeNode::new("src")
+                .with_children(vec![
+                    TreeNode::new("widgets")
+                        .with_children(vec![TreeNode::new("tree.rs")])
+                        .set_expanded(true),
+                    TreeNode::new("lib.rs"),
+                ])
+                .set_expanded(true),
+            TreeNode::new("Cargo.toml"),
+        ])
+    }
+
+    #[test]
+    fn tree_rows() {
+        let rows: Vec<String> = tree().rows().into_iter().map(|row| row.text).collect();
+        assert_eq!(
+            vec![
+                "▾ src",
+                "├─ ▾ widgets",
+                "│  └─ tree.rs",
+                "└─ lib.rs",
+                "Cargo.toml"
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn tree_navigation() {
+        let mut tree = tree();
+        let key = |code| Event::Key(KeyEvent::new(code, Modifiers::new()));
+
+        // Right moves into an expanded node, left collapses it and then moves to its parent
+        assert!(tree.handle_event(&key(KeyCode::Right)));
+        assert_eq!(vec![0, 0], tree.selected_path());
+        assert!(tree.handle_event(&key(KeyCode::Left)));
+        assert!(!tree.selected().unwrap().is_expanded());
+        assert!(tree.handle_event(&key(KeyCode::Left)));
+        assert_eq!(vec![0], tree.selected_path());
+
+        // Collapsing hides descendants from the rows
+        tree.toggle();
+        assert_eq!(2, tree.rows().len());
+        tree.select_next();
+        tree.select_next();
+        assert_eq!(vec![1], tree.selected_path());
+
+        // Leaves can't be expanded
+        tree.expand();
+        assert!(!tree.selected().unwrap().is_expanded());
+    }
+
+    #[test]
+    fn tree_fit_width() {
+        assert_eq!("ab  ", fit_width("ab", 4, &Widths::default()));
+        assert_eq!("ab", fit_width("abc", 2, &Widths::default()));
+        assert_eq!("日 ", fit_width("日本", 3, &Widths::default()));
+    }
+}
@@ -1,4 +1,8 @@
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+
 /// A directional vector with no positional information.
+#[derive(Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector {
     x: u16,
     y: u16,
@@ -15,7 +19,7 @@ impl Vector {
     /// assert_eq!(7, size.x());
     /// assert_eq!(4, size.y());
     /// ```
-    pub fn new(x: u16, y: u16) -> Vector {
+    pub const fn new(x: u16, y: u16) -> Vector {
         Vector { x, y }
     }
 
@@ -29,3 +33,62 @@ impl Vector {
         self.y
     }
 }
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, other: Vector) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::collections::HashSet;
+
+    use super::Vector;
+
+    #[test]
+    fn vector_arithmetic() {
+        let mut size = Vector::new(3, 4) + Vector::new(1, 2);
+        assert_eq!(Vector::new(4, 6), size);
+
+        size -= Vector::new(4, 1);
+        assert_eq!(Vector::new(0, 5), size);
+
+        size += Vector::default();
+        assert_eq!(Vector::new(0, 5), size);
+        assert_eq!(Vector::new(0, 0), Vector::default());
+    }
+
+    #[test]
+    fn vector_ordering_and_hashing() {
+        assert!(Vector::new(1, 9) < Vector::new(2, 0));
+        assert!(Vector::new(1, 2) < Vector::new(1, 3));
+
+        let sizes: HashSet<Vector> = [Vector::new(1, 2), Vector::new(1, 2)].into();
+        assert_eq!(1, sizes.len());
+    }
+}
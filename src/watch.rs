@@ -0,0 +1,125 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "crossterm")]
+use {
+    crate::{Event, KeyCode},
+    std::time::Instant,
+};
+
+use crate::{pos, Device, Interface, Position, Result};
+
+/// Repeatedly render the lines produced by a closure at the specified interval, relative to the
+/// cursor, like `watch(1)`. Only changed lines are redrawn. Runs until Ctrl+C, Escape, or "q" is
+/// pressed, then leaves the last lines in the terminal's buffer.
+///
+/// # Examples
+/// ```no_run
+/// # use tty_interface::Error;
+/// use std::time::{Duration, Instant};
+///
+/// let started = Instant::now();
+/// tty_interface::watch(&mut std::io::stdout(), Duration::from_secs(1), || {
+///     vec![format!("Elapsed: {}s", started.elapsed().as_secs())]
+/// })?;
+/// # Ok::<(), Error>(())
+/// ```
+#[cfg(feature = "crossterm")]
+pub fn watch(
+    device: &mut dyn Device,
+    interval: Duration,
+    lines: impl FnMut() -> Vec<String>,
+) -> Result<()> {
+    run_watch(device, lines, |interface| {
+        wait_for_cancel_key(interface, interval)
+    })
+}
+
+/// Repeatedly render the lines produced by a closure at the specified interval, relative to the
+/// cursor, until the cancellation flag is set. Unlike [watch], the terminal's input is not read.
+///
+/// # Examples
+/// ```
+/// # use tty_interface::{Error, test::VirtualDevice};
+/// # let mut device = VirtualDevice::new();
+/// use std::{sync::atomic::{AtomicBool, Ordering}, time::Duration};
+///
+/// let cancel = AtomicBool::new(false);
+/// let mut count = 0;
+/// tty_interface::watch_until(&mut device, Duration::ZERO, &cancel, || {
+///     count += 1;
+///     if count == 3 {
+///         cancel.store(true, Ordering::Relaxed);
+///     }
+///
+///     vec![format!("Count: {}", count)]
+/// })?;
+/// # Ok::<(), Error>(())
+/// ```
+pub fn watch_until(
+    device: &mut dyn Device,
+    interval: Duration,
+    cancel: &AtomicBool,
+    lines: impl FnMut() -> Vec<String>,
+) -> Result<()> {
+    run_watch(device, lines, |_| {
+        thread::sleep(interval);
+        Ok(cancel.load(Ordering::Relaxed))
+    })
+}
+
+/// Render the closure's lines until waiting between renders indicates cancellation.
+fn run_watch(
+    device: &mut dyn Device,
+    mut lines: impl FnMut() -> Vec<String>,
+    mut wait: impl FnMut(&mut Interface) -> Result<bool>,
+) -> Result<()> {
+    let mut interface = Interface::new_relative(device)?;
+
+    loop {
+        let lines = lines();
+        for (line, text) in (0..).zip(&lines) {
+            interface.set(pos!(0, line), text);
+            interface.clear_rest_of_line(pos!(interface.widths().text_width(text), line));
+        }
+
+        let line_count = u16::try_from(lines.len()).unwrap_or(u16::MAX);
+        interface.clear_rest_of_interface(pos!(0, line_count));
+        interface.apply()?;
+
+        if wait(&mut interface)? {
+            break;
+        }
+    }
+
+    interface.exit()
+}
+
+/// Wait for the interval, returning early if a cancellation key is pressed.
+#[cfg(feature = "crossterm")]
+fn wait_for_cancel_key(interface: &mut Interface, interval: Duration) -> Result<bool> {
+    let deadline = Instant::now() + interval;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let event = match interface.poll_event(remaining)? {
+            Some(event) => event,
+            None => return Ok(false),
+        };
+
+        let is_cancel = match event {
+            Event::Key(event) => match event.code() {
+                KeyCode::Char('c') => event.modifiers().is_control(),
+                code => code == KeyCode::Escape || code == KeyCode::Char('q'),
+            },
+            _ => false,
+        };
+
+        if is_cancel {
+            return Ok(true);
+        }
+    }
+}
@@ -0,0 +1,14 @@
+/// How East Asian ambiguous-width characters, such as "→" and "°", are measured. Terminals display
+/// them as one column or two depending on their configuration and locale, typically two for users
+/// in CJK locales.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum AmbiguousWidth {
+    /// Ambiguous characters occupy one column.
+    #[default]
+    Narrow,
+    /// Ambiguous characters occupy two columns.
+    Wide,
+    /// The terminal's width for ambiguous characters is measured by printing one and querying
+    /// the cursor's movement.
+    Auto,
+}
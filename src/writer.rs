@@ -0,0 +1,31 @@
+use std::io::{self, Write};
+
+use crate::Interface;
+
+/// Writes the application's own output through an interface, which prints it around its display.
+/// Created with [Interface::writer].
+pub struct InterfaceWriter<'i, 'a> {
+    interface: &'i mut Interface<'a>,
+}
+
+impl<'i, 'a> InterfaceWriter<'i, 'a> {
+    /// Create a writer printing output through the specified interface.
+    pub(crate) fn new(interface: &'i mut Interface<'a>) -> InterfaceWriter<'i, 'a> {
+        InterfaceWriter { interface }
+    }
+}
+
+impl Write for InterfaceWriter<'_, '_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.interface
+            .write_output(buf)
+            .map_err(|error| io::Error::other(error.to_string()))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.interface
+            .flush_output()
+            .map_err(|error| io::Error::other(error.to_string()))
+    }
+}
@@ -1,4 +1,21 @@
-use tty_interface::{self, pos, test::VirtualDevice, Color, Interface, Position, Style};
+use std::{
+    cell::Cell,
+    io::Write,
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use tty_interface::{
+    self, pos,
+    telnet::TelnetDevice,
+    test::{verify_screen, SharedDevice, VirtualClock, VirtualDevice},
+    Alignment, AmbiguousWidth, BarChart, Border, Broadcaster, Canvas, CanvasMarker, Clock, Color,
+    Corner, CursorMode, CursorQuery, DeviceArbiter, Error, Event, EventRecorder, EventReplayer,
+    EventSource, Gauge, Ghost, Gutter, Interface, KeyCode, KeyEvent, LineProvider, Mode, Modifiers,
+    NotificationLevel, Overflow, Pager, PanelStyle, Player, Position, Rect, RenderStrategy,
+    SizedTarget, Sparkline, StatusLine, Style, Tabs, Timer, Tree, TreeNode, Vector, WriterDevice,
+};
 
 #[test]
 fn basic_write() {
@@ -139,3 +156,3004 @@ fn clearing_rest_of_interface() {
 
     assert_eq!("ABC\nD  \n   ", &device.parser().screen().contents());
 }
+
+#[test]
+fn inserting_lines() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "ABC");
+    interface.set(pos!(0, 1), "DEF");
+    interface.set(pos!(0, 2), "GHI");
+    interface.apply().unwrap();
+
+    interface.insert_line(1);
+    interface.set(pos!(0, 1), "XY");
+    interface.apply().unwrap();
+
+    assert_eq!("ABC\nXY \nDEF\nGHI", &device.parser().screen().contents());
+}
+
+#[test]
+fn deleting_lines() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "ABC");
+    interface.set(pos!(0, 1), "DEF");
+    interface.set(pos!(0, 2), "GH");
+    interface.apply().unwrap();
+
+    interface.delete_line(0);
+    interface.apply().unwrap();
+
+    assert_eq!("DEF\nGH \n  ", &device.parser().screen().contents());
+}
+
+#[test]
+fn clearing_columns() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "ABC");
+    interface.set(pos!(0, 1), "DEF");
+    interface.set(pos!(0, 2), "GHI");
+    interface.apply().unwrap();
+
+    interface.clear_column(1);
+    interface.apply().unwrap();
+
+    assert_eq!("A C\nD F\nG I", &device.parser().screen().contents());
+}
+
+#[test]
+fn clearing_rects() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "ABC");
+    interface.set(pos!(0, 1), "DEF");
+    interface.set(pos!(0, 2), "GHI");
+    interface.apply().unwrap();
+
+    interface.clear_rect(Rect::new(pos!(0, 1), Vector::new(2, 2)));
+    interface.apply().unwrap();
+
+    assert_eq!("ABC\n  F\n  I", &device.parser().screen().contents());
+}
+
+#[test]
+fn drawing_vertical_lines() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "AB");
+    interface.set(pos!(0, 1), "CD");
+    interface.draw_vline(pos!(2, 0), 2, "|");
+    interface.set(pos!(3, 0), "EF");
+    interface.set(pos!(3, 1), "GH");
+    interface.apply().unwrap();
+
+    assert_eq!("AB|EF\nCD|GH", &device.parser().screen().contents());
+}
+
+#[test]
+fn deferring_updates_beyond_byte_budget() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_byte_budget(Some(32));
+
+    interface.set(pos!(0, 0), "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    interface.set(pos!(0, 1), "abcdefghijklmnopqrstuvwxyz");
+    interface.apply().unwrap();
+    assert!(interface.has_deferred_changes());
+
+    while interface.has_deferred_changes() {
+        interface.apply().unwrap();
+    }
+
+    assert_eq!(
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZ\nabcdefghijklmnopqrstuvwxyz",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn deferred_updates_render_first_lines_first() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_byte_budget(Some(33));
+
+    interface.set(pos!(0, 0), "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    interface.set(pos!(0, 1), "abcdefghijklmnopqrstuvwxyz");
+    interface.apply().unwrap();
+
+    drop(interface);
+    let contents = device.parser().screen().contents();
+    assert!(contents.starts_with("ABCDEFGHIJ"));
+    assert!(!contents.contains('a'));
+}
+
+#[test]
+fn expanding_tabs() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "A\tB");
+    interface.set(pos!(3, 1), "C\tD\tE");
+    interface.apply().unwrap();
+
+    assert_eq!(
+        "A       B\n   C    D       E",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn expanding_tabs_with_custom_width() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_tab_width(4);
+
+    interface.set(pos!(0, 0), "AB\tC\t\tD");
+    interface.apply().unwrap();
+
+    assert_eq!("AB  C       D", &device.parser().screen().contents());
+}
+
+#[test]
+fn expanding_tabs_with_placeholder() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_tab_width(4);
+    interface.set_tab_placeholder(Some((">", Style::new().set_italic(true))));
+
+    interface.set(pos!(0, 0), "A\tB");
+    interface.apply().unwrap();
+
+    assert_eq!("A>  B", &device.parser().screen().contents());
+
+    let expected_italic = [false, true, true, true, false];
+    for (column, expected) in expected_italic.iter().enumerate() {
+        let cell = device.parser().screen().cell(0, column as u16).unwrap();
+        assert_eq!(*expected, cell.italic());
+    }
+}
+
+#[test]
+fn introspecting_alternate_interface() {
+    let mut device = VirtualDevice::new();
+    let interface = Interface::new_alternate(&mut device).unwrap();
+
+    assert_eq!(Mode::Alternate, interface.mode());
+    assert_eq!(pos!(0, 0), interface.origin());
+    assert_eq!(Vector::new(80, 24), interface.size());
+    assert!(interface.is_alternate_screen_active());
+}
+
+#[test]
+fn introspecting_relative_interface() {
+    let mut device = VirtualDevice::new();
+    device.parser().process(b"Previous output\r\n\r\n");
+
+    let interface = Interface::new_relative(&mut device).unwrap();
+
+    assert_eq!(Mode::Relative, interface.mode());
+    assert_eq!(pos!(0, 2), interface.origin());
+    assert_eq!(Vector::new(80, 24), interface.size());
+    assert!(!interface.is_alternate_screen_active());
+}
+
+#[test]
+fn reordering_bidirectional_text() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "Hi שלום!");
+    interface.apply().unwrap();
+
+    assert_eq!("Hi םולש!", &device.parser().screen().contents());
+}
+
+#[test]
+fn disabling_bidirectional_reordering() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_bidi_enabled(false);
+
+    interface.set(pos!(0, 0), "Hi שלום!");
+    interface.apply().unwrap();
+
+    assert_eq!("Hi שלום!", &device.parser().screen().contents());
+}
+
+#[test]
+fn writing_wide_graphemes() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "漢字!");
+    interface.set(pos!(0, 1), "漢字!");
+    interface.apply().unwrap();
+
+    interface.set(pos!(2, 1), "ab");
+    interface.apply().unwrap();
+
+    assert_eq!("漢字!\n漢ab!", &device.parser().screen().contents());
+    assert_eq!("!", device.parser().screen().cell(0, 4).unwrap().contents());
+}
+
+#[test]
+fn writing_combining_sequences() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "cafe\u{301}!");
+    interface.apply().unwrap();
+
+    // Re-staging the equivalent precomposed text renders identically
+    interface.set(pos!(0, 0), "caf\u{e9}!");
+    interface.apply().unwrap();
+
+    assert_eq!("caf\u{e9}!", &device.parser().screen().contents());
+    assert_eq!("!", device.parser().screen().cell(0, 4).unwrap().contents());
+}
+
+#[test]
+fn wrapping_text_at_terminal_width() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(78, 0), "ABC");
+    interface.set(pos!(78, 2), "A漢");
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("A", screen.cell(0, 78).unwrap().contents());
+    assert_eq!("B", screen.cell(0, 79).unwrap().contents());
+    assert_eq!("C", screen.cell(1, 0).unwrap().contents());
+    assert_eq!("A", screen.cell(2, 78).unwrap().contents());
+    assert_eq!("漢", screen.cell(3, 0).unwrap().contents());
+}
+
+#[test]
+fn aligning_text() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set_aligned(0, "Left", Alignment::Left);
+    interface.set_aligned(1, "Center", Alignment::Center);
+    interface.set_aligned_styled(2, "Right", Alignment::Right, Style::new().set_bold(true));
+    interface.set_aligned(3, "漢字", Alignment::Right);
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("Left", screen.contents_between(0, 0, 0, 4));
+    assert_eq!("Center", screen.contents_between(1, 37, 1, 43));
+    assert_eq!("Right", screen.contents_between(2, 75, 2, 80));
+    assert!(screen.cell(2, 75).unwrap().bold());
+    assert_eq!("漢", screen.cell(3, 76).unwrap().contents());
+    assert_eq!("字", screen.cell(3, 78).unwrap().contents());
+}
+
+#[test]
+fn drawing_rulers() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.draw_ruler(pos!(2, 0), 22, 5);
+    interface.draw_ruler(pos!(76, 2), 10, 2);
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("0    5    10   15   ", screen.contents_between(0, 2, 0, 22));
+    assert_eq!(
+        "|....|....|....|....|.",
+        screen.contents_between(1, 2, 1, 24)
+    );
+    assert_eq!("0 2 ", screen.contents_between(2, 76, 2, 80));
+    assert_eq!("|.|.", screen.contents_between(3, 76, 3, 80));
+    assert_eq!("", screen.contents_between(4, 0, 4, 80));
+}
+
+#[test]
+fn dragging_ghosts() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "ABCDEF");
+    interface.set(pos!(0, 1), "GHIJKL");
+    interface.set_ghost(
+        pos!(1, 0),
+        Ghost::new("xy").set_style(Style::new().set_bold(true)),
+    );
+    interface.apply().unwrap();
+
+    interface.move_ghost(pos!(3, 1));
+    interface.apply().unwrap();
+
+    interface.set(pos!(0, 1), "ghijkl");
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("ABCDEF\nghixyl", screen.contents());
+    assert!(screen.cell(1, 3).unwrap().bold());
+    assert!(!screen.cell(0, 1).unwrap().bold());
+}
+
+#[test]
+fn clearing_ghosts() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "ABCDEF");
+    interface.set_ghost(pos!(4, 0), Ghost::new("xyz"));
+    interface.apply().unwrap();
+
+    interface.clear_ghost();
+    interface.apply().unwrap();
+
+    assert_eq!("ABCDEF ", &device.parser().screen().contents());
+}
+
+#[test]
+fn formatting_fields() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set_field(pos!(0, 0), "Name", 8, Alignment::Left, '.');
+    interface.set_field(pos!(0, 1), "42", 6, Alignment::Right, ' ');
+    interface.set_field(pos!(0, 2), "Mid", 7, Alignment::Center, '-');
+    interface.set_field(pos!(0, 3), "Truncated", 5, Alignment::Left, ' ');
+    interface.set_field(pos!(0, 4), "漢字", 3, Alignment::Left, '_');
+    interface.set_field_styled(
+        pos!(0, 5),
+        "X",
+        2,
+        Alignment::Left,
+        ' ',
+        Color::Red.as_style(),
+    );
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!(
+        "Name....\n    42\n--Mid--\nTrunc\n漢_\nX ",
+        screen.contents()
+    );
+    assert_eq!(vt100::Color::Idx(9), screen.cell(5, 1).unwrap().fgcolor());
+}
+
+#[test]
+fn overwriting_fields_with_shorter_values() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set_field(pos!(0, 0), "Downloading", 12, Alignment::Left, ' ');
+    interface.set(pos!(12, 0), "|");
+    interface.apply().unwrap();
+
+    interface.set_field(pos!(0, 0), "Done", 12, Alignment::Left, ' ');
+    interface.apply().unwrap();
+
+    assert_eq!("Done        |", &device.parser().screen().contents());
+}
+
+#[test]
+fn automatically_applying_after_debounce() {
+    let clock = VirtualClock::new();
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_clock(Box::new(clock.clone()));
+    interface.set_auto_apply(Some(Duration::from_millis(50)));
+
+    interface.set(pos!(0, 0), "First");
+    assert!(!interface.pump().unwrap());
+
+    clock.advance(Duration::from_millis(30));
+    interface.set(pos!(0, 0), "Second");
+    assert!(!interface.pump().unwrap());
+
+    clock.advance(Duration::from_millis(30));
+    assert!(!interface.pump().unwrap());
+
+    clock.advance(Duration::from_millis(20));
+    assert!(interface.pump().unwrap());
+    assert!(!interface.pump().unwrap());
+
+    assert_eq!("Second", &device.parser().screen().contents());
+}
+
+#[test]
+fn pumping_without_automatic_applies() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "Staged");
+    assert!(!interface.pump().unwrap());
+
+    interface.apply().unwrap();
+    assert_eq!("Staged", &device.parser().screen().contents());
+}
+
+#[test]
+fn building_configured_interface() {
+    let mut device = VirtualDevice::new();
+    device.parser().process(b"Prompt> ");
+
+    let mut interface = Interface::builder()
+        .relative()
+        .default_style(Color::Green.as_style())
+        .tab_width(4)
+        .mouse(true)
+        .build(&mut device)
+        .unwrap();
+
+    assert_eq!(Mode::Relative, interface.mode());
+    assert_eq!(pos!(8, 0), interface.origin());
+    assert_eq!(Some(Color::Green.as_style()), interface.default_style());
+    assert!(interface.is_mouse_captured());
+
+    interface.set(pos!(0, 0), "a\tb");
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("Prompt> a   b", &screen.contents());
+    assert_eq!(vt100::Color::Idx(10), screen.cell(0, 12).unwrap().fgcolor());
+    assert_eq!(
+        vt100::MouseProtocolMode::AnyMotion,
+        screen.mouse_protocol_mode()
+    );
+}
+
+#[cfg(feature = "terminfo")]
+#[test]
+fn building_with_terminfo_encoder() {
+    use tty_interface::terminfo::TerminfoEncoder;
+
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::builder()
+        .encoder(Box::new(TerminfoEncoder::from_name("screen").unwrap()))
+        .build(&mut device)
+        .unwrap();
+
+    interface.set(pos!(2, 1), "plain");
+    interface.set_styled(pos!(2, 2), "bright", Color::Red.as_style());
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("\n  plain\n  bright", &screen.contents());
+
+    // The screen entry only supports eight colors, so bright red is rendered as red
+    assert_eq!(vt100::Color::Idx(1), screen.cell(2, 2).unwrap().fgcolor());
+}
+
+#[test]
+fn clipping_overflowing_text() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::builder()
+        .overflow(Overflow::Clip)
+        .build(&mut device)
+        .unwrap();
+
+    interface.set(pos!(76, 0), "Hello, world!");
+    interface.apply().unwrap();
+
+    assert_eq!(
+        format!("{}Hell", " ".repeat(76)),
+        device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn retrying_failed_apply() {
+    let device = SharedDevice::default();
+    let mut interface_device = device.clone();
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+
+    device.device().set_write_allowance(Some(0));
+    interface.set(pos!(2, 1), "Hello");
+    let error = match interface.apply() {
+        Err(Error::Apply(error)) => error,
+        result => panic!("unexpected result: {:?}", result),
+    };
+
+    assert_eq!(0, error.bytes_written());
+    assert_eq!(Some(pos!(2, 1)), error.position());
+    assert!(!error.may_be_inconsistent());
+
+    device.device().set_write_allowance(None);
+    interface.apply().unwrap();
+
+    assert_eq!("\n  Hello", &device.device().parser().screen().contents());
+}
+
+#[test]
+fn reporting_partially_written_apply() {
+    let device = SharedDevice::default();
+    let mut interface_device = device.clone();
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+
+    // Permit hiding the cursor and printing the first cell
+    device.device().set_write_allowance(Some(7));
+    interface.set(pos!(0, 0), "abc");
+    let error = match interface.apply() {
+        Err(Error::Apply(error)) => error,
+        result => panic!("unexpected result: {:?}", result),
+    };
+
+    assert_eq!(7, error.bytes_written());
+    assert_eq!(Some(pos!(1, 0)), error.position());
+    assert!(error.may_be_inconsistent());
+    assert_eq!(
+        "apply failed after 7 bytes at Position(1, 0): broken pipe",
+        error.to_string()
+    );
+}
+
+#[test]
+fn rendering_to_writer() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "Line 1");
+    interface.set(pos!(0, 1), "Line 2");
+    interface.apply().unwrap();
+    interface.set(pos!(5, 0), "A");
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    let mut parser = vt100::Parser::new(4, 20, 0);
+    parser.process(&device.into_inner());
+    assert_eq!("Line A\nLine 2", &parser.screen().contents());
+}
+
+#[test]
+fn applying_in_row_major_order() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(5, 2), "c");
+    interface.set(pos!(1, 0), "a");
+    interface.set(pos!(3, 2), "b");
+    interface.set(pos!(0, 1), "d");
+    interface.apply().unwrap();
+
+    let output = device.into_inner();
+    let expected = b" a\r\nd\x1B[2C\nb c";
+    assert!(output.ends_with(expected));
+}
+
+#[test]
+fn rendering_for_latency() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::builder()
+        .render_strategy(RenderStrategy::Latency { skip_frames: false })
+        .build(&mut device)
+        .unwrap();
+
+    interface.set(pos!(0, 0), "Hello, world!");
+    interface.set(pos!(0, 1), "Second line");
+    interface.apply().unwrap();
+
+    interface.set(pos!(0, 0), "J");
+    interface.set(pos!(7, 0), "W");
+    interface.clear_rest_of_line(pos!(6, 1));
+    interface.apply().unwrap();
+
+    let output = device.into_inner();
+    assert!(output.ends_with(b"\x1B[?25l\r\x1B[1AJello, W\x1B[2D\n\x1B[K"));
+
+    let mut parser = vt100::Parser::new(4, 20, 0);
+    parser.process(&output);
+    assert_eq!("Jello, World!\nSecond", &parser.screen().contents());
+}
+
+#[test]
+fn repainting_mostly_changed_interface() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(10, 2));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    assert_eq!(Some(0.6), interface.repaint_threshold());
+
+    interface.set(pos!(0, 0), "aaaaaaaaaa");
+    interface.set(pos!(0, 1), "bbbbbbbbbb");
+    interface.apply().unwrap();
+
+    interface.set(pos!(2, 0), "ccc");
+    interface.apply().unwrap();
+
+    interface.set(pos!(0, 1), "dddddddddd");
+    interface.set(pos!(5, 0), "eeeee");
+    interface.apply().unwrap();
+
+    let output = device.into_inner();
+    let clears = output
+        .windows(4)
+        .filter(|bytes| bytes == b"\x1B[2J")
+        .count();
+    assert_eq!(3, clears);
+
+    let mut parser = vt100::Parser::new(2, 10, 0);
+    parser.process(&output);
+    assert_eq!("aaccceeeee\ndddddddddd", &parser.screen().contents());
+}
+
+#[test]
+fn skipping_frames_while_draining() {
+    let clock = VirtualClock::new();
+    let mut device = VirtualDevice::new();
+    device.set_flush_delay(clock.clone(), Duration::from_millis(100));
+    let mut interface = Interface::builder()
+        .render_strategy(RenderStrategy::Latency { skip_frames: true })
+        .clock(Box::new(clock.clone()))
+        .build(&mut device)
+        .unwrap();
+
+    interface.set(pos!(0, 0), "First");
+    interface.apply().unwrap();
+    assert!(!interface.has_deferred_changes());
+
+    // The previous frame took 100ms to write, so it's assumed to still be draining
+    interface.set(pos!(0, 0), "Second");
+    interface.apply().unwrap();
+    assert!(interface.has_deferred_changes());
+
+    clock.advance(Duration::from_millis(100));
+    interface.apply().unwrap();
+    assert!(!interface.has_deferred_changes());
+
+    assert_eq!("Second", &device.parser().screen().contents());
+}
+
+#[test]
+fn resizing_through_events() {
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::Resize(Vector::new(10, 24)));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    assert_eq!(Vector::new(80, 24), interface.size());
+
+    assert_eq!(
+        Event::Resize(Vector::new(10, 24)),
+        interface.read_event().unwrap()
+    );
+    assert_eq!(Vector::new(10, 24), interface.size());
+
+    // Staged text wraps at the new width
+    interface.set(pos!(0, 0), "Hello, world!");
+    interface.apply().unwrap();
+
+    assert!(interface.read_event().is_err());
+    assert_eq!("Hello, wor\nld!", &device.parser().screen().contents());
+}
+
+#[test]
+fn reflowing_wrapped_text_on_resize() {
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::Resize(Vector::new(10, 24)));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_wrapped(pos!(0, 0), "Hello, world! Goodbye");
+    interface.set_wrapped_styled(pos!(0, 3), "Second", Color::Blue.as_style());
+    interface.apply().unwrap();
+
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+
+    // Only the reported size changed, so the virtual screen keeps the columns beyond it
+    let screen = device.parser().screen();
+    assert_eq!(
+        "Hello, world! Goodbye\nld! Goodby\ne\nSecond",
+        &screen.contents()
+    );
+    assert_eq!(vt100::Color::Idx(12), screen.cell(3, 0).unwrap().fgcolor());
+}
+
+#[test]
+fn realigning_aligned_text_on_resize() {
+    let device = SharedDevice::default();
+    let mut interface_device = device.clone();
+
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+    interface.set_aligned(0, "Title", Alignment::Center);
+    interface.set_aligned_styled(1, "Right", Alignment::Right, Color::Blue.as_style());
+    interface.set_aligned(2, "Replaced", Alignment::Right);
+    interface.set_aligned(2, "Last", Alignment::Right);
+    interface.apply().unwrap();
+
+    let rows = || -> Vec<String> {
+        let mut device = device.device();
+        let rows = device.parser().screen().rows(0, 80).take(3);
+        rows.map(|row| row.trim_end().to_string()).collect()
+    };
+    let padded = |columns: usize, text: &str| format!("{}{}", " ".repeat(columns), text);
+    assert_eq!(
+        vec![padded(37, "Title"), padded(75, "Right"), padded(76, "Last")],
+        rows()
+    );
+
+    // Narrowing the terminal moves the text within its new width
+    device.device().resize(Vector::new(40, 24));
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+    assert_eq!(
+        vec![padded(17, "Title"), padded(35, "Right"), padded(36, "Last")],
+        rows()
+    );
+    let color = device
+        .device()
+        .parser()
+        .screen()
+        .cell(1, 35)
+        .unwrap()
+        .fgcolor();
+    assert_eq!(vt100::Color::Idx(12), color);
+
+    // Widening it moves the text back out, clearing its previous cells
+    device.device().resize(Vector::new(60, 24));
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+    assert_eq!(
+        vec![padded(27, "Title"), padded(55, "Right"), padded(56, "Last")],
+        rows()
+    );
+}
+
+#[test]
+fn watching_changing_lines() {
+    let mut device = VirtualDevice::new();
+    device.parser().process(b"$ watch\r\n");
+
+    let cancel = AtomicBool::new(false);
+    let mut frames = vec![
+        vec!["Status: idle".to_string(), "Jobs: 1".to_string()],
+        vec!["Status: ok".to_string()],
+    ]
+    .into_iter();
+
+    tty_interface::watch_until(&mut device, Duration::ZERO, &cancel, || {
+        let frame = frames.next().unwrap();
+        if frames.len() == 0 {
+            cancel.store(true, Ordering::Relaxed);
+        }
+
+        frame
+    })
+    .unwrap();
+
+    assert_eq!(
+        "$ watch\nStatus: ok  \n       ",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn counting_down_timer() {
+    let clock = VirtualClock::new();
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_clock(Box::new(clock.clone()));
+
+    let deadline = clock.now() + Duration::from_secs(75);
+    let timer =
+        Timer::countdown(deadline).set_warning(Duration::from_secs(10), Color::Red.as_style());
+
+    interface.set_timer(pos!(0, 0), &timer);
+    interface.apply().unwrap();
+
+    clock.advance(Duration::from_secs(70));
+    interface.set_timer(pos!(0, 1), &timer);
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("00:01:15\n00:00:05", &screen.contents());
+    assert_eq!(vt100::Color::Default, screen.cell(0, 0).unwrap().fgcolor());
+    assert_eq!(vt100::Color::Idx(9), screen.cell(1, 0).unwrap().fgcolor());
+}
+
+#[test]
+fn setting_blocks() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 1), "xxxxxxxxxxxx");
+    interface.set_block(pos!(1, 0), "+------+\n| Idle |\n+------+");
+    interface.apply().unwrap();
+
+    interface.set(pos!(3, 1), "Busy");
+    interface.set_block(pos!(76, 3), "clipped");
+    interface.apply().unwrap();
+
+    assert_eq!(
+        format!(" +------+\nx| Busy |xxx\n +------+\n{}clip", " ".repeat(76)),
+        device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn scrolling_relative_interface_at_bottom() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 6));
+    device.parser().process(b"prompt\r\n\r\n\r\n\r\n$ ");
+
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    assert_eq!(pos!(2, 4), interface.origin());
+
+    interface.set(pos!(0, 0), "first");
+    interface.set(pos!(0, 1), "second");
+    interface.set(pos!(0, 2), "third");
+    interface.apply().unwrap();
+    assert_eq!(pos!(2, 3), interface.origin());
+
+    interface.set(pos!(0, 0), "FIRST");
+    interface.set(pos!(0, 2), "THIRD");
+    interface.apply().unwrap();
+
+    // The prompt's line scrolled off the screen to make room for the last line
+    drop(interface);
+    assert_eq!(
+        "\n\n\n$ FIRST\n  second\n  THIRD",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn scrolling_relative_interface_off_screen() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 4));
+    device.parser().process(b"\n\n");
+
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    for line in 0..6 {
+        interface.set(pos!(0, line), &format!("Line {}", line));
+    }
+    interface.apply().unwrap();
+    assert_eq!(pos!(0, 0), interface.origin());
+    assert_eq!(2, interface.scrolled_lines());
+
+    // Lines above the screen can't be updated, but those below are unaffected
+    interface.set(pos!(5, 0), "A");
+    interface.set(pos!(5, 3), "B");
+    interface.set_cursor(Some(pos!(0, 1)));
+    interface.apply().unwrap();
+
+    let screen = device.parser().screen();
+    assert_eq!("Line 2\nLine B\nLine 4\nLine 5", &screen.contents());
+    assert_eq!((0, 0), screen.cursor_position());
+}
+
+#[test]
+fn scrolling_content_taller_than_screen() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 4));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    for line in 0..10 {
+        interface.set(pos!(0, line), &format!("Line {}", line));
+    }
+    interface.scroll_to(3);
+    interface.set_cursor(Some(pos!(2, 4)));
+    interface.apply().unwrap();
+
+    interface.set(pos!(5, 5), "E");
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Line 3\nLine 4\nLine E\nLine 6", &screen.contents());
+    assert_eq!((1, 2), screen.cursor_position());
+}
+
+#[test]
+fn scrolling_changes_into_view() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 4));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    for line in 0..10 {
+        interface.set(pos!(0, line), &format!("Line {}", line));
+    }
+    interface.set_cursor(Some(pos!(0, 1)));
+    interface.apply().unwrap();
+
+    // Changes outside the viewport are kept for when they're scrolled into view
+    interface.set(pos!(5, 8), "H");
+    interface.apply().unwrap();
+    interface.scroll_by(7);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Line 7\nLine H\nLine 9", &screen.contents());
+    assert!(screen.hide_cursor());
+}
+
+#[test]
+fn scrolling_relative_content() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "First line");
+    interface.set(pos!(0, 1), "Second");
+    interface.set(pos!(0, 2), "Third");
+    interface.apply().unwrap();
+
+    interface.scroll_by(1);
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!("Second\nThird", &device.parser().screen().contents());
+}
+
+#[test]
+fn panning_wide_content() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 3));
+    let mut interface = Interface::builder()
+        .overflow(Overflow::Extend)
+        .build(&mut device)
+        .unwrap();
+
+    interface.set(pos!(0, 0), "abcdefghijklmnopqrstuvwxyz");
+    interface.set(pos!(12, 1), "Shifted");
+    interface.set(pos!(0, 2), "Short");
+    interface.apply().unwrap();
+
+    interface.pan_to(10);
+    interface.apply().unwrap();
+    interface.pan_by(-2);
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!(
+        "ijklmnopqr\n    Shifte",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn panning_relative_content() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 24));
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    interface.set_overflow(Overflow::Extend);
+
+    interface.set(pos!(0, 0), "Hello");
+    interface.set(pos!(4, 1), "0123456789");
+    interface.apply().unwrap();
+
+    interface.pan_to(3);
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!("lo\n 012345678", &device.parser().screen().contents());
+}
+
+#[test]
+fn polling_events() {
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::FocusGained);
+    device.push_event(Event::Resize(Vector::new(40, 10)));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    let timeout = Duration::from_millis(1);
+    assert_eq!(
+        Some(Event::FocusGained),
+        interface.poll_event(timeout).unwrap()
+    );
+    assert_eq!(
+        Some(Event::Resize(Vector::new(40, 10))),
+        interface.poll_event(timeout).unwrap()
+    );
+    assert_eq!(Vector::new(40, 10), interface.size());
+    assert_eq!(None, interface.poll_event(timeout).unwrap());
+}
+
+#[test]
+fn placing_cursor_after_text() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 5));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    assert_eq!(pos!(3, 1), interface.cursor_after(pos!(0, 1), "abc"));
+    assert_eq!(pos!(4, 0), interface.cursor_after(pos!(0, 0), "日本"));
+    assert_eq!(pos!(2, 1), interface.cursor_after(pos!(6, 0), "abcdef"));
+    assert_eq!(pos!(0, 1), interface.cursor_after(pos!(6, 0), "abcd"));
+    assert_eq!(pos!(2, 1), interface.cursor_after(pos!(8, 0), "a日"));
+    assert_eq!(pos!(9, 0), interface.cursor_after(pos!(2, 0), "a\tb"));
+    assert_eq!(pos!(9, 0), interface.cursor_after(pos!(9, 0), ""));
+
+    interface.set_overflow(Overflow::Clip);
+    assert_eq!(pos!(12, 0), interface.cursor_after(pos!(6, 0), "abcdef"));
+
+    let text = "Name: ";
+    interface.set(pos!(0, 2), text);
+    interface.set_cursor(Some(interface.cursor_after(pos!(0, 2), text)));
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!((2, 6), device.parser().screen().cursor_position());
+}
+
+#[test]
+fn bounding_staged_text() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 5));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let bounds = interface.set(pos!(2, 1), "abc");
+    assert_eq!(Rect::new(pos!(2, 1), Vector::new(3, 1)), bounds);
+
+    let bounds = interface.set(pos!(6, 2), "abcdefgh");
+    assert_eq!(Rect::new(pos!(0, 2), Vector::new(10, 2)), bounds);
+
+    let bounds = interface.set(pos!(8, 0), "a日");
+    assert_eq!(Rect::new(pos!(0, 0), Vector::new(9, 2)), bounds);
+
+    let bounds = interface.set(pos!(4, 4), "");
+    assert_eq!(Rect::new(pos!(4, 4), Vector::new(0, 0)), bounds);
+
+    interface.set_overflow(Overflow::Clip);
+    let bounds = interface.set_styled(pos!(7, 4), "abcdef", Style::new().set_bold(true));
+    assert_eq!(Rect::new(pos!(7, 4), Vector::new(3, 1)), bounds);
+}
+
+#[test]
+fn staging_text_at_the_last_position() {
+    for overflow in [Overflow::Wrap, Overflow::Clip, Overflow::Extend] {
+        let mut device = VirtualDevice::new();
+        let mut interface = Interface::new_alternate(&mut device).unwrap();
+        interface.set_overflow(overflow);
+
+        interface.set(pos!(65535, 65535), "漢字abc");
+        interface.set(pos!(65534, 0), "漢字abc");
+        interface.set_wrapped(pos!(65535, 65535), "漢字\tabc");
+        interface.set_block(pos!(65535, 65534), "漢字\nabc");
+        interface.apply().unwrap();
+    }
+}
+
+#[test]
+fn measuring_text() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 5));
+    let interface = Interface::new_alternate(&mut device).unwrap();
+
+    assert_eq!(Vector::new(17, 1), interface.measure("Hello, 世界!\tx"));
+    assert_eq!(Vector::new(5, 1), interface.measure("e\u{301}👍🏽日"));
+    assert_eq!(Vector::new(0, 0), interface.measure(""));
+
+    assert_eq!(
+        Vector::new(6, 3),
+        interface.measure_wrapped("Hello, world!", 6)
+    );
+    assert_eq!(Vector::new(4, 2), interface.measure_wrapped("日本語", 4));
+    assert_eq!(Vector::new(2, 2), interface.measure_wrapped("日本", 1));
+    assert_eq!(Vector::new(0, 0), interface.measure_wrapped("", 4));
+}
+
+#[test]
+fn restyling_named_text() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.define_style("title", Style::new().set_bold(true));
+    interface.set_named(pos!(0, 0), "Title", "title");
+    interface.set_named(pos!(0, 1), "Error", "error");
+    interface.set_named(pos!(0, 2), "Later", "title");
+    interface.apply().unwrap();
+
+    interface.set(pos!(0, 2), "Plain");
+    interface.define_style("title", Color::Red.as_style());
+    interface.define_style("error", Style::new().set_bold(true));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Title\nError\nPlain", &screen.contents());
+
+    let red = vt100::Color::Idx(9);
+    assert_eq!(red, screen.cell(0, 4).unwrap().fgcolor());
+    assert!(!screen.cell(0, 4).unwrap().bold());
+    assert!(screen.cell(1, 0).unwrap().bold());
+    assert_eq!(vt100::Color::Default, screen.cell(2, 0).unwrap().fgcolor());
+    assert!(!screen.cell(2, 0).unwrap().bold());
+}
+
+#[test]
+fn setting_gradients() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_default_style(Some(Style::new().set_bold(true)));
+
+    let bounds = interface.set_gradient(pos!(1, 0), "abc", Color::Black, Color::White);
+    assert_eq!(Rect::new(pos!(1, 0), Vector::new(3, 1)), bounds);
+    interface.set_gradient(pos!(0, 1), "x", Color::Red, Color::Blue);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(" abc\nx", &screen.contents());
+
+    let colors: Vec<_> = (1..4)
+        .map(|column| screen.cell(0, column).unwrap().fgcolor())
+        .collect();
+    let expected = [0, 8, 15].map(vt100::Color::Idx);
+    assert_eq!(expected.to_vec(), colors);
+    assert!(screen.cell(0, 2).unwrap().bold());
+    assert_eq!(vt100::Color::Idx(9), screen.cell(1, 0).unwrap().fgcolor());
+}
+
+#[test]
+fn drawing_panels() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "..........");
+    interface.set(pos!(0, 1), "..........");
+    interface.set(pos!(0, 2), "..........");
+    interface.set(pos!(0, 3), "..........");
+    interface.set(pos!(0, 4), "..........");
+
+    let style = PanelStyle::new()
+        .set_border(Border::Rounded)
+        .set_title("Long title")
+        .set_shadow(true);
+    interface.draw_panel(Rect::new(pos!(1, 0), Vector::new(8, 4)), &style);
+    interface.set(pos!(2, 1), "Hi");
+
+    let style = PanelStyle::new()
+        .set_border(Border::None)
+        .set_fill(Style::new().set_background(Color::Blue))
+        .set_title("X");
+    interface.draw_panel(Rect::new(pos!(12, 0), Vector::new(5, 2)), &style);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        ".╭ Long ╮.   X   \n.│Hi    │.       \n.│      │.\n.╰──────╯.\n..........",
+        &screen.contents()
+    );
+
+    let shadow = screen.cell(4, 9).unwrap();
+    assert_eq!(vt100::Color::Idx(0), shadow.bgcolor());
+    assert_eq!(".", shadow.contents());
+    assert_eq!(vt100::Color::Default, screen.cell(4, 1).unwrap().bgcolor());
+    assert_eq!(vt100::Color::Idx(0), screen.cell(1, 9).unwrap().bgcolor());
+    assert_eq!(vt100::Color::Default, screen.cell(0, 9).unwrap().bgcolor());
+    assert_eq!(vt100::Color::Idx(12), screen.cell(1, 16).unwrap().bgcolor());
+}
+
+#[test]
+fn restoring_modal_regions() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set(pos!(0, 0), "First line");
+    interface.set(pos!(0, 1), "Second line");
+    interface.apply().unwrap();
+
+    let rect = Rect::new(pos!(2, 0), Vector::new(5, 3));
+    let mut modal = interface.modal(rect, |interface| {
+        interface.draw_panel(rect, &PanelStyle::new());
+    });
+    modal.set(pos!(3, 1), "ok");
+    modal.apply().unwrap();
+    assert_eq!(rect, modal.rect());
+    drop(modal);
+
+    interface.set(pos!(0, 3), "Third line");
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!(
+        "First line\nSecond line\n       \nThird line",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn expiring_notifications() {
+    let clock = VirtualClock::new();
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 3));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_clock(Box::new(clock.clone()));
+
+    interface.set(pos!(0, 0), "Status: running");
+    interface.set(pos!(0, 2), "abcdefghijklmnopqrst");
+    interface.notify("Saved", NotificationLevel::Success, Duration::from_secs(1));
+    interface.notify(
+        "Retrying",
+        NotificationLevel::Warning,
+        Duration::from_secs(3),
+    );
+    interface.apply().unwrap();
+    assert!(!interface.pump().unwrap());
+
+    clock.advance(Duration::from_secs(2));
+    assert!(interface.pump().unwrap());
+    assert_eq!(1, interface.notification_count());
+
+    interface.set_notification_corner(Corner::TopRight);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        "Status: ru Retrying \n                    \nabcdefghijklmnopqrst",
+        &screen.contents()
+    );
+    assert_eq!(vt100::Color::Idx(11), screen.cell(0, 12).unwrap().bgcolor());
+}
+
+#[test]
+fn rendering_status_lines() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 3));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut status = StatusLine::new().set_style(Style::new().set_background(Color::Blue));
+    status.set("mode", Alignment::Left, 9, "NORMAL");
+    status.set("file", Alignment::Center, 1, "lib.rs");
+    status.set("position", Alignment::Right, 5, "1:1");
+
+    interface.set(pos!(0, 0), "Content");
+    assert!(status.render(&mut interface));
+    assert!(!status.render(&mut interface));
+    interface.apply().unwrap();
+
+    status.set("position", Alignment::Right, 5, "12:40");
+    status.set("file", Alignment::Center, 1, "src/interface.rs");
+    assert!(status.render(&mut interface));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Content\n\nNORMAL         12:40", &screen.contents());
+    assert_eq!(vt100::Color::Idx(12), screen.cell(2, 10).unwrap().bgcolor());
+}
+
+#[test]
+fn appending_lines() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 3));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "Build");
+    let first = interface.append_line("Step 1...", None);
+    interface.append_line("Step 2...", None);
+    interface.apply().unwrap();
+    assert_eq!(0, interface.scroll_offset());
+
+    interface.update_line(first, "Step 1 ok", Some(Color::Green.as_style()));
+    let third = interface.append_line("Step 3 is long", None);
+    interface.apply().unwrap();
+    assert_eq!(2, interface.scroll_offset());
+
+    interface.update_line(third, "Step 3 ok", None);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Step 2...\nStep 3 ok \n    ", &screen.contents());
+}
+
+#[test]
+fn updating_moved_lines() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+
+    let first = interface.append_line("first", None);
+    let second = interface.append_line("second", None);
+    let third = interface.append_line("third", None);
+    interface.apply().unwrap();
+
+    interface.insert_line(0);
+    interface.set(pos!(0, 0), "header");
+    interface.delete_line(2);
+    assert_eq!(Some(1), first.line(&interface));
+    assert_eq!(None, second.line(&interface));
+    assert_eq!(Some(2), third.line(&interface));
+
+    first.set_style(&mut interface, Some(Color::Red.as_style()));
+    second.set_text(&mut interface, "ignored");
+    third.set_text(&mut interface, "3rd");
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("header\nfirst \n3rd   \n     ", &screen.contents());
+    assert_eq!(vt100::Color::Idx(9), screen.cell(1, 0).unwrap().fgcolor());
+}
+
+#[test]
+fn reflowing_appended_lines_on_resize() {
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::Resize(Vector::new(10, 24)));
+    device.push_event(Event::Resize(Vector::new(20, 24)));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    let first = interface.append_line("one two three four", None);
+    let second = interface.append_line("five", Some(Color::Blue.as_style()));
+    interface.apply().unwrap();
+
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+    assert_eq!(Some(0), first.line(&interface));
+    assert_eq!(Some(2), second.line(&interface));
+
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+    assert_eq!(Some(1), second.line(&interface));
+
+    drop(interface);
+    let rows: Vec<String> = device.parser().screen().rows(0, 20).take(3).collect();
+    assert_eq!(vec!["one two three four", "five    ", "    "], rows);
+
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::Resize(Vector::new(10, 24)));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.append_line("one two three four", None);
+    interface.append_line("five", Some(Color::Blue.as_style()));
+    interface.apply().unwrap();
+
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    let rows: Vec<String> = screen.rows(0, 10).take(3).collect();
+    assert_eq!(vec!["one two th", "ree four", "five"], rows);
+    assert_eq!(vt100::Color::Idx(12), screen.cell(2, 0).unwrap().fgcolor());
+}
+
+#[test]
+fn undoing_applied_frames() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::builder()
+        .history_limit(2)
+        .build(&mut device)
+        .unwrap();
+    assert!(!interface.undo());
+
+    for step in ["First", "Second", "Third"] {
+        interface.clear_line(0);
+        interface.set(pos!(0, 0), step);
+        interface.set(pos!(0, 1), step);
+        interface.apply().unwrap();
+    }
+
+    interface.set(pos!(0, 2), "Staged");
+    assert!(interface.undo());
+    interface.apply().unwrap();
+    assert!(!interface.undo());
+
+    drop(interface);
+    assert_eq!("Second\nSecond", &device.parser().screen().contents());
+}
+
+#[test]
+fn recording_frames_to_asciicast() {
+    let path = std::env::temp_dir().join(format!("tty-interface-{}.cast", std::process::id()));
+
+    let clock = VirtualClock::new();
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::Resize(Vector::new(40, 10)));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_clock(Box::new(clock.clone()));
+    interface.record_to(&path).unwrap();
+
+    interface.set(pos!(0, 0), "Hello");
+    interface.apply().unwrap();
+
+    clock.advance(Duration::from_millis(250));
+    interface.read_event().unwrap();
+    interface.set(pos!(0, 1), "\"world\"");
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    let cast = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<serde_json::Value> = cast
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(4, lines.len());
+    assert_eq!(
+        serde_json::json!({"version": 2, "width": 80, "height": 24}),
+        lines[0]
+    );
+
+    assert_eq!(serde_json::json!(0.0), lines[1][0]);
+    assert_eq!("o", lines[1][1]);
+    assert!(lines[1][2].as_str().unwrap().contains("Hello"));
+
+    assert_eq!(serde_json::json!([0.25, "r", "40x10"]), lines[2]);
+
+    assert_eq!(serde_json::json!(0.25), lines[3][0]);
+    assert!(lines[3][2].as_str().unwrap().contains("\"world\""));
+}
+
+#[test]
+fn keeping_recordings_of_dropped_interfaces() {
+    let path = std::env::temp_dir().join(format!("tty-interface-drop-{}.cast", std::process::id()));
+
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.record_to(&path).unwrap();
+
+    interface.set(pos!(0, 0), "Hello");
+    interface.apply().unwrap();
+    drop(interface);
+
+    let cast = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = cast.lines().collect();
+    assert_eq!(2, lines.len());
+    assert!(lines[1].contains("Hello"));
+}
+
+#[test]
+fn playing_recorded_sessions() {
+    let path = std::env::temp_dir().join(format!("tty-interface-play-{}.cast", std::process::id()));
+
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.record_to(&path).unwrap();
+    interface.set(pos!(0, 0), "Loading");
+    interface.apply().unwrap();
+    interface.set_styled(pos!(0, 0), "Done", Color::Green.as_style().set_bold(true));
+    interface.clear_rest_of_line(pos!(4, 0));
+    interface.set(pos!(2, 1), "日本");
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    let cast = std::fs::File::open(&path).unwrap();
+    let mut player = Player::new(cast).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(2, player.len());
+
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    assert!(player.step(&mut interface).unwrap());
+    assert_eq!(1, player.position());
+    player.play(&mut interface, f32::INFINITY).unwrap();
+    assert!(player.is_finished());
+    assert!(!player.step(&mut interface).unwrap());
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Done   \n  日本", &screen.contents());
+    assert_eq!(vt100::Color::Idx(10), screen.cell(0, 0).unwrap().fgcolor());
+    assert!(screen.cell(0, 0).unwrap().bold());
+}
+
+#[test]
+fn setting_static_text() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 3));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let bounds = interface.set_static(pos!(0, 0), "┌─ 日本 ─┐", None);
+    assert_eq!(Vector::new(10, 1), bounds.size());
+    interface.set_static(pos!(0, 1), "a\tb", Some(Color::Red.as_style()));
+    interface.set_static(pos!(0, 2), "e\u{301}", None);
+    interface.apply().unwrap();
+
+    interface.set_static(pos!(0, 0), "┌─ 日本 ─┐", None);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("┌─ 日本 ─┐\na       b\né", &screen.contents());
+    assert_eq!(vt100::Color::Idx(9), screen.cell(1, 0).unwrap().fgcolor());
+}
+
+#[test]
+fn skipping_restaged_unchanged_lines() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    // Immediate-mode rendering restages every line each frame
+    for count in [1, 1, 2] {
+        interface.clear_line(0);
+        interface.set(pos!(0, 0), "Status: ok");
+        interface.clear_line(1);
+        interface.set(pos!(0, 1), &format!("Count: {}", count));
+        interface.apply().unwrap();
+    }
+
+    drop(interface);
+    let output = String::from_utf8(device.into_inner()).unwrap();
+    assert_eq!(1, output.matches("Status: ok").count());
+    assert_eq!(2, output.matches("Count").count());
+    assert!(output.ends_with("Count: 2"));
+}
+
+/// A writer discarding its input and counting how many times it's flushed.
+struct FlushCounter(Rc<Cell<usize>>);
+
+impl Write for FlushCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn applying_without_flushing() {
+    let flushes = Rc::new(Cell::new(0));
+    let mut device = WriterDevice::new(FlushCounter(flushes.clone()), Vector::new(20, 4));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    let initial_flushes = flushes.get();
+
+    interface.set(pos!(0, 0), "Header");
+    interface.apply_no_flush().unwrap();
+    interface.set(pos!(0, 1), "Body");
+    interface.apply_no_flush().unwrap();
+    assert_eq!(initial_flushes, flushes.get());
+
+    interface.flush().unwrap();
+    assert_eq!(initial_flushes + 1, flushes.get());
+
+    interface.set(pos!(0, 2), "Footer");
+    interface.apply().unwrap();
+    assert_eq!(initial_flushes + 2, flushes.get());
+}
+
+#[test]
+fn keeping_the_cursor_visible() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    interface.set_cursor_mode(CursorMode::Visible).unwrap();
+    assert_eq!(CursorMode::Visible, interface.cursor_mode());
+
+    interface.set(pos!(0, 0), "Progress: 50%");
+    interface.set(pos!(0, 1), "Loading");
+    interface.apply().unwrap();
+
+    interface.set(pos!(10, 0), "75%");
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Progress: 75%\nLoading", &screen.contents());
+    assert_eq!((0, 0), screen.cursor_position());
+    assert!(!screen.hide_cursor());
+}
+
+#[test]
+fn marking_secondary_cursors() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    let style = Style::new().set_background(Color::Blue);
+
+    interface.set(pos!(0, 0), "let x = 1;");
+    interface.set(pos!(0, 1), "let y = 2;");
+    interface.set_marks(vec![pos!(4, 0), pos!(4, 1), pos!(12, 1)], style);
+    interface.apply().unwrap();
+    assert_eq!(3, interface.marks().len());
+
+    // Marks follow content changes and restore the content beneath them once moved
+    interface.set(pos!(4, 0), "z");
+    interface.set_marks(vec![pos!(4, 0), pos!(5, 1)], style);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("let z = 1;\nlet y = 2;   ", &screen.contents());
+
+    let marked = screen.cell(0, 4).unwrap();
+    assert_eq!("z", marked.contents());
+    assert_eq!(vt100::Color::Idx(12), marked.bgcolor());
+    assert_eq!(vt100::Color::Idx(12), screen.cell(1, 5).unwrap().bgcolor());
+    assert_eq!(vt100::Color::Default, screen.cell(1, 4).unwrap().bgcolor());
+    assert_eq!(vt100::Color::Default, screen.cell(1, 12).unwrap().bgcolor());
+}
+
+#[test]
+fn overlaying_styles() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    let row = Rect::new(pos!(0, 0), Vector::new(6, 1));
+
+    interface.set_styled(
+        pos!(0, 0),
+        "Item 1",
+        Style::new().set_foreground(Color::Red),
+    );
+    interface.set(pos!(0, 1), "Item 2");
+    interface.set_overlay(row, Style::new().set_background(Color::Blue));
+    interface.apply().unwrap();
+
+    interface.clear_overlay(row);
+    interface.set_overlay(
+        Rect::new(pos!(0, 1), Vector::new(6, 1)),
+        Style::new().set_bold(true),
+    );
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Item 1\nItem 2", &screen.contents());
+
+    let deselected = screen.cell(0, 0).unwrap();
+    assert_eq!(vt100::Color::Idx(9), deselected.fgcolor());
+    assert_eq!(vt100::Color::Default, deselected.bgcolor());
+    assert!(screen.cell(1, 0).unwrap().bold());
+}
+
+#[cfg(feature = "images")]
+#[test]
+fn displaying_images_with_blocks() {
+    use tty_interface::ImageSource;
+
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    // Red over blue, approximated with half-blocks
+    let pixels = [[255, 0, 0, 255].repeat(2), [0, 0, 255, 255].repeat(2)].concat();
+    let image = ImageSource::from_rgba(2, 2, pixels).unwrap();
+    interface.set(pos!(0, 0), "Preview:");
+    interface.set_image(Rect::new(pos!(0, 1), Vector::new(2, 1)), image);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("Preview:\n▀▀", &screen.contents());
+
+    let cell = screen.cell(1, 0).unwrap();
+    assert_eq!(vt100::Color::Idx(9), cell.fgcolor());
+    assert_eq!(vt100::Color::Idx(4), cell.bgcolor());
+}
+
+/// A writer sharing what's written to it.
+#[cfg(feature = "images")]
+struct SharedWriter(Rc<std::cell::RefCell<Vec<u8>>>);
+
+#[cfg(feature = "images")]
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "images")]
+#[test]
+fn displaying_images_with_kitty() {
+    use tty_interface::{ImageProtocol, ImageSource};
+
+    let mut device = VirtualDevice::new();
+    device.set_image_protocol(ImageProtocol::Kitty);
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    let cast = Rc::new(std::cell::RefCell::new(Vec::new()));
+    interface.record_to_writer(Box::new(SharedWriter(cast.clone())));
+
+    let image = ImageSource::from_rgba(1, 1, vec![255, 0, 0, 255]).unwrap();
+    let preview = Rect::new(pos!(0, 1), Vector::new(4, 2));
+    interface.set(pos!(0, 0), "Preview:");
+    interface.set(pos!(0, 1), "Loading...");
+    interface.set_image(preview, image);
+    interface.apply().unwrap();
+
+    interface.clear_image(preview);
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    // The image's cells were blanked beneath it, leaving the rest of the content intact
+    let screen = device.parser().screen();
+    assert_eq!("Preview:\n    ing...\n    ", &screen.contents());
+
+    let cast = String::from_utf8(cast.borrow().clone()).unwrap();
+    assert!(cast.contains("\\u001b_Ga=T,f=32,s=1,v=1,c=4,r=2,i=1,C=1,q=2,m=0;/wAA/w==\\u001b\\\\"));
+    assert!(cast.contains("\\u001b_Ga=d,d=I,i=1,q=2\\u001b\\\\"));
+}
+
+#[cfg(feature = "images")]
+#[test]
+fn replacing_sixel_images() {
+    use tty_interface::{ImageProtocol, ImageSource};
+
+    let mut device = VirtualDevice::new();
+    device.set_image_protocol(ImageProtocol::Sixel);
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    let cast = Rc::new(std::cell::RefCell::new(Vec::new()));
+    interface.record_to_writer(Box::new(SharedWriter(cast.clone())));
+
+    let red = ImageSource::from_rgba(1, 1, vec![255, 0, 0, 255]).unwrap();
+    let blue = ImageSource::from_rgba(1, 1, vec![0, 0, 255, 255]).unwrap();
+    let preview = Rect::new(pos!(0, 1), Vector::new(4, 2));
+
+    interface.set(pos!(0, 0), "Preview:");
+    interface.set_image(preview, red);
+    interface.apply().unwrap();
+
+    interface.set_image(preview, blue);
+    interface.set(pos!(0, 3), "Done");
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    // The cursor is restored after each image, so text afterward is placed correctly
+    let screen = device.parser().screen();
+    assert_eq!("Preview:\n    \n    \nDone", &screen.contents());
+
+    // The replaced image's cells are rewritten to paint over it before the new image
+    let cast = String::from_utf8(cast.borrow().clone()).unwrap();
+    let frames: Vec<&str> = cast.lines().skip(1).collect();
+    let blanks = frames[1].find("    \\r\\n    ").unwrap();
+    let sixel = frames[1].find("\\u001bP0;1;0q").unwrap();
+    assert!(blanks < sixel);
+}
+
+#[test]
+fn rendering_canvases() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut canvas = Canvas::new(Vector::new(3, 1));
+    canvas.line((0, 3), (5, 0), Color::Green);
+    canvas.render(&mut interface, pos!(1, 0));
+
+    let mut blocks = Canvas::with_marker(Vector::new(2, 1), CanvasMarker::HalfBlock);
+    blocks.point(0, 0, Color::Red);
+    blocks.point(1, 1, Color::Blue);
+    blocks.render(&mut interface, pos!(1, 1));
+    interface.apply().unwrap();
+
+    // Re-rendering after clearing erases the previous plot's cells
+    canvas.clear();
+    canvas.point(0, 0, Color::Red);
+    canvas.render(&mut interface, pos!(1, 0));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(" ⠁  \n ▀▄", &screen.contents());
+    assert_eq!(vt100::Color::Idx(9), screen.cell(0, 1).unwrap().fgcolor());
+    assert_eq!(vt100::Color::Idx(12), screen.cell(1, 2).unwrap().fgcolor());
+}
+
+#[test]
+fn rendering_charts() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    // The most recent values fitting between the labels, scaled from the minimum to the maximum
+    let sparkline = Sparkline::new().set_labels(true);
+    let values = [1.0, 2.0, 4.0, 8.0, 4.0, 2.5];
+    sparkline.render(
+        &mut interface,
+        Rect::new(pos!(0, 0), Vector::new(8, 1)),
+        &values,
+    );
+
+    // Bars scaled from zero over two lines, omitting those which don't fit
+    let chart = BarChart::new().set_bar_width(2).set_gap(1);
+    let values = [4.0, 8.0, 2.0, 6.0];
+    chart.render(
+        &mut interface,
+        Rect::new(pos!(0, 1), Vector::new(10, 2)),
+        &values,
+    );
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!(
+        "1 ▃█▃▂ 8\n   ██     \n██ ██ ▄▄  ",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn rendering_gauges() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let gauge = Gauge::new().set_filled_style(Style::new().set_background(Color::Blue));
+    gauge.render(
+        &mut interface,
+        Rect::new(pos!(0, 0), Vector::new(10, 1)),
+        0.45,
+    );
+    gauge.render_labeled(
+        &mut interface,
+        Rect::new(pos!(0, 1), Vector::new(6, 3)),
+        0.25,
+        "1/4",
+    );
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("   45%    \n ▌    \n 1/4  \n ▌    ", &screen.contents());
+
+    // The label's cells are styled by whether each is mostly filled, including the fill's edge
+    let filled = |row, column| screen.cell(row, column).unwrap().bgcolor() == vt100::Color::Idx(12);
+    assert!(filled(0, 3) && filled(0, 4) && !filled(0, 5));
+    assert!(filled(2, 0) && filled(2, 1) && !filled(2, 2));
+    assert_eq!(vt100::Color::Idx(12), screen.cell(1, 1).unwrap().fgcolor());
+}
+
+#[test]
+fn switching_tabs() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let titles = vec!["One".to_string(), "Two".to_string(), "Three".to_string()];
+    let mut tabs = Tabs::new(titles);
+    assert!(tabs.render(&mut interface, pos!(0, 0)));
+    interface.apply().unwrap();
+
+    // Unchanged tabs aren't restaged
+    assert!(!tabs.render(&mut interface, pos!(0, 0)));
+
+    let right = Event::Key(KeyEvent::new(KeyCode::Right, Modifiers::new()));
+    assert!(tabs.handle_event(&right));
+    assert!(tabs.render(&mut interface, pos!(0, 0)));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        " One │ Two │ Three ",
+        screen.contents().lines().next().unwrap()
+    );
+
+    let active = |column| {
+        let cell = screen.cell(0, column).unwrap();
+        cell.bold() && cell.underline()
+    };
+    assert!(!active(1) && active(7) && !active(13));
+}
+
+#[test]
+fn rendering_widgets_at_the_last_positions() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut tabs = Tabs::new(vec!["a".to_string(), "漢字".to_string()]);
+    assert!(tabs.render(&mut interface, pos!(65534, 0)));
+
+    let gauge = Gauge::new();
+    let rect = Rect::new(pos!(65530, 65534), Vector::new(10, 3));
+    gauge.render_labeled(&mut interface, rect, 0.5, "漢字");
+
+    let mut canvas = Canvas::new(Vector::new(3, 2));
+    canvas.line((0, 0), (5, 7), Color::Green);
+    canvas.render(&mut interface, pos!(65534, 65534));
+
+    interface.apply().unwrap();
+}
+
+#[test]
+fn browsing_trees() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut tree = Tree::new(vec![
+        TreeNode::new("src")
+            .with_children(vec![
+                TreeNode::new("widgets").with_children(vec![TreeNode::new("tree.rs")]),
+                TreeNode::new("lib.rs"),
+            ])
+            .set_expanded(true),
+        TreeNode::new("Cargo.toml"),
+    ]);
+
+    let rect = Rect::new(pos!(0, 0), Vector::new(16, 3));
+    assert!(tree.render(&mut interface, rect));
+    interface.apply().unwrap();
+    assert!(!tree.render(&mut interface, rect));
+
+    // Expanding the nested node and selecting the last scrolls to keep it in view
+    let key = |code| Event::Key(KeyEvent::new(code, Modifiers::new()));
+    for code in [
+        KeyCode::Down,
+        KeyCode::Right,
+        KeyCode::Down,
+        KeyCode::Down,
+        KeyCode::Down,
+    ] {
+        assert!(tree.handle_event(&key(code)));
+    }
+    assert_eq!(vec![1], tree.selected_path());
+    assert!(tree.render(&mut interface, rect));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        "│  └─ tree.rs   \n└─ lib.rs       \nCargo.toml      ",
+        &screen.contents()
+    );
+    assert!(screen.cell(2, 0).unwrap().bold());
+    assert!(!screen.cell(1, 3).unwrap().bold());
+}
+
+#[test]
+fn rendering_markdown() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.define_style("markdown.code", Color::Green.as_style());
+
+    let text = "## Keys\n\n\n- Press `q` to quit the *whole* program\n- Save with **s**\n\n```\nfn main() {}\n```";
+    let lines = interface.render_markdown(Rect::new(pos!(0, 0), Vector::new(16, 10)), text);
+    assert_eq!(8, lines);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        "Keys\n\n• Press q to\n  quit the whole\n  program\n• Save with s\n\nfn main() {}",
+        screen
+            .contents()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+
+    let cell = |row, column| screen.cell(row, column).unwrap();
+    assert!(cell(0, 0).bold());
+    assert_eq!(vt100::Color::Idx(10), cell(2, 8).fgcolor());
+    assert!(cell(3, 11).italic());
+    assert!(cell(5, 12).bold() && !cell(5, 2).bold());
+    assert_eq!(vt100::Color::Idx(10), cell(7, 0).fgcolor());
+}
+
+#[test]
+fn highlighting_byte_ranges() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    // "é" is two bytes and "日本" six, so spans in bytes are offset from their cells
+    let text = "é = \"日本\"; // x";
+    let spans = [
+        (0..1, Color::Red.as_style()),
+        (5..13, Color::Green.as_style()),
+        (15..19, Color::Blue.as_style()),
+        (18..19, Style::new().set_bold(true)),
+    ];
+    let bounds = interface.set_highlighted(pos!(0, 0), text, &spans);
+    assert_eq!(Vector::new(16, 1), bounds.size());
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("é = \"日本\"; // x", screen.contents().trim_end());
+
+    let color = |column| screen.cell(0, column).unwrap().fgcolor();
+    assert_eq!(vt100::Color::Idx(9), color(0));
+    assert_eq!(vt100::Color::Default, color(2));
+    assert_eq!(vt100::Color::Idx(10), color(4));
+    assert_eq!(vt100::Color::Idx(10), color(7));
+    assert_eq!(vt100::Color::Idx(10), color(9));
+    assert_eq!(vt100::Color::Default, color(10));
+    assert_eq!(vt100::Color::Idx(12), color(12));
+    assert!(!screen.cell(0, 12).unwrap().bold());
+    assert!(screen.cell(0, 15).unwrap().bold());
+}
+
+#[test]
+fn paging_lazy_lines() {
+    struct Numbers;
+
+    impl LineProvider for Numbers {
+        fn line_count(&self) -> usize {
+            1000
+        }
+
+        fn line(&self, index: usize) -> std::borrow::Cow<'_, str> {
+            format!("row {}", index * 7).into()
+        }
+    }
+
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut pager = Pager::with_provider(Box::new(Numbers));
+    let rect = Rect::new(pos!(0, 0), Vector::new(40, 4));
+    assert!(pager.render(&mut interface, rect));
+    interface.apply().unwrap();
+    assert!(!pager.render(&mut interface, rect));
+
+    // Searching scrolls to the first match, paging back moves the match down
+    assert!(pager.search("777"));
+    let up = Event::Key(KeyEvent::new(KeyCode::Char('k'), Modifiers::new()));
+    assert!(pager.handle_event(&up));
+    assert!(pager.render(&mut interface, rect));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        "row 770\nrow 777\nrow 784\nlines 111-113 of 1000 (11%)  /777",
+        screen
+            .contents()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+
+    let highlighted = |column| screen.cell(1, column).unwrap().bgcolor() == vt100::Color::Idx(11);
+    assert!(!highlighted(3) && highlighted(4) && highlighted(6) && !highlighted(7));
+    assert!(screen.cell(3, 0).unwrap().bold());
+}
+
+#[test]
+fn highlighting_displayed_matches() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set(pos!(0, 0), "error: one");
+    interface.set(pos!(2, 1), "no errors");
+    interface.apply().unwrap();
+
+    // Staged but unapplied text isn't searched
+    interface.set(pos!(0, 2), "error");
+    let style = Style::new().set_background(Color::Red);
+    assert_eq!(2, interface.highlight_matches("error", style));
+    interface.apply().unwrap();
+
+    // Clearing restores the previous matches before highlighting others
+    interface.clear_highlights();
+    assert_eq!(1, interface.highlight_matches("one", style));
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    let is_red = |row, column| screen.cell(row, column).unwrap().bgcolor() == vt100::Color::Idx(9);
+    assert!(!is_red(0, 0) && !is_red(1, 5) && !is_red(2, 0));
+    assert!(is_red(0, 7) && is_red(0, 9));
+}
+
+#[test]
+fn numbering_lines_in_a_gutter() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut gutter = Gutter::new(Rect::new(pos!(0, 0), Vector::new(12, 3)));
+    gutter.set_first_number(8);
+    gutter.set_line_count(9);
+    assert!(gutter.render(&mut interface));
+    interface.set(gutter.content_position(pos!(0, 0)), "eight");
+    interface.apply().unwrap();
+    assert!(!gutter.render(&mut interface));
+
+    // Growing to two digits widens the gutter, so the content is restaged
+    gutter.set_line_count(10);
+    if gutter.render(&mut interface) {
+        for (line, text) in ["eight", "nine", "ten"].iter().enumerate() {
+            interface.set(gutter.content_position(pos!(0, line as u16)), text);
+        }
+    }
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!(
+        " 8 eight\n 9 nine\n10 ten",
+        screen
+            .contents()
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    assert_eq!(vt100::Color::Idx(8), screen.cell(0, 1).unwrap().fgcolor());
+}
+
+#[test]
+fn indicating_wrapped_lines() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set_wrap_indicator(Some(("↪ ", Color::Blue.as_style())));
+
+    let bounds = interface.set(pos!(76, 0), "ABCDEF");
+    assert_eq!(Rect::new(pos!(0, 0), Vector::new(80, 2)), bounds);
+    interface.set(pos!(0, 3), "no wrap");
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("ABCD", screen.contents_between(0, 76, 0, 80));
+    assert_eq!("↪ EF", screen.contents_between(1, 0, 1, 4));
+    assert_eq!(vt100::Color::Idx(12), screen.cell(1, 0).unwrap().fgcolor());
+    assert_eq!(vt100::Color::Default, screen.cell(1, 2).unwrap().fgcolor());
+    assert_eq!("no wrap", screen.contents_between(3, 0, 3, 7));
+}
+
+#[test]
+fn measuring_ambiguous_width_characters() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::builder()
+        .ambiguous_width(AmbiguousWidth::Wide)
+        .build(&mut device)
+        .unwrap();
+
+    let bounds = interface.set(pos!(0, 0), "→x");
+    assert_eq!(Vector::new(3, 1), bounds.size());
+
+    // The virtual terminal displays ambiguous characters as narrow
+    interface.set_ambiguous_width(AmbiguousWidth::Auto).unwrap();
+    assert_eq!(AmbiguousWidth::Auto, interface.ambiguous_width());
+    let bounds = interface.set(pos!(0, 1), "→x");
+    assert_eq!(Vector::new(2, 1), bounds.size());
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("→x", screen.contents_between(1, 0, 1, 2));
+    assert_eq!("→ x", screen.contents_between(0, 0, 0, 3));
+}
+
+#[test]
+fn measuring_widths_per_interface() {
+    let mut wide_device = VirtualDevice::new();
+    let mut wide = Interface::builder()
+        .ambiguous_width(AmbiguousWidth::Wide)
+        .build(&mut wide_device)
+        .unwrap();
+
+    // Another interface on the same thread measures with its own widths
+    let mut narrow_device = VirtualDevice::new();
+    let mut narrow = Interface::new_alternate(&mut narrow_device).unwrap();
+    assert_eq!(Vector::new(2, 1), narrow.set(pos!(0, 0), "→x").size());
+    assert_eq!(Vector::new(3, 1), wide.set(pos!(0, 0), "→x").size());
+
+    narrow.set_ambiguous_width(AmbiguousWidth::Wide).unwrap();
+    wide.set_ambiguous_width(AmbiguousWidth::Narrow).unwrap();
+    assert_eq!(Vector::new(3, 1), narrow.measure("→x"));
+    assert_eq!(Vector::new(2, 1), wide.measure("→x"));
+}
+
+#[test]
+fn calibrating_grapheme_widths() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::builder()
+        .width_calibration(true)
+        .build(&mut device)
+        .unwrap();
+    assert!(interface.is_width_calibration_enabled());
+
+    // The virtual terminal displays the heart without its variation selector's emoji presentation
+    let bounds = interface.set(pos!(0, 0), "❤️ab");
+    assert_eq!(Vector::new(4, 1), bounds.size());
+    interface.apply().unwrap();
+    assert_eq!(Vector::new(1, 1), interface.measure("❤️"));
+
+    interface.set(pos!(0, 1), "❤️c");
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("❤️ab", screen.contents_between(0, 0, 0, 3));
+    assert_eq!("❤️c", screen.contents_between(1, 0, 1, 2));
+}
+
+#[test]
+fn calibrating_widths_per_interface() {
+    let mut device = VirtualDevice::new();
+    let mut calibrated = Interface::builder()
+        .width_calibration(true)
+        .build(&mut device)
+        .unwrap();
+    calibrated.set(pos!(0, 0), "❤️");
+    calibrated.apply().unwrap();
+    assert_eq!(Vector::new(1, 1), calibrated.measure("❤️"));
+
+    // Measurements belong to the calibrated terminal, and survive other interfaces' creation
+    let mut other_device = VirtualDevice::new();
+    let other = Interface::new_alternate(&mut other_device).unwrap();
+    assert_eq!(Vector::new(2, 1), other.measure("❤️"));
+    assert_eq!(Vector::new(1, 1), calibrated.measure("❤️"));
+}
+
+#[test]
+fn writing_into_the_last_column() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(10, 3));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set(pos!(0, 0), "┌────────┐");
+    interface.set(pos!(0, 1), "│ Border │");
+    interface.set(pos!(0, 2), "└────────┘");
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!(
+        "┌────────┐\n│ Border │\n└────────┘",
+        &device.parser().screen().contents()
+    );
+
+    // The cursor rests on the corner rather than past it, so printing there doesn't scroll
+    device.parser().process(b"*");
+    assert_eq!(
+        "┌────────┐\n│ Border │\n└────────*",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn writing_wide_characters_into_the_last_column() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(6, 2));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set(pos!(0, 0), "top");
+    interface.set(pos!(0, 1), "ab漢字");
+    interface.apply().unwrap();
+
+    interface.set(pos!(0, 1), "abcd漢");
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!("top\nabcd漢", &device.parser().screen().contents());
+}
+
+#[test]
+fn blanking_wide_characters_cut_by_the_last_column() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(6, 3));
+    device.push_event(Event::Resize(Vector::new(5, 3)));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.read_event().unwrap();
+    interface.set(pos!(0, 0), "abcd漢");
+    interface.apply().unwrap();
+
+    // Once the screen narrows, the wide character would wrap onto the next line if redrawn
+    interface.read_event().unwrap();
+    interface.set_styling_enabled(false);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    assert_eq!("abcd", screen.contents().trim_end());
+    assert!(!screen.cell(0, 4).unwrap().is_wide());
+}
+
+/// The screen's contents after staging changes over "abcd" without rendering in between.
+fn render_restaged(stage: impl Fn(&mut Interface)) -> String {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set(pos!(0, 0), "abcd");
+    interface.apply().unwrap();
+
+    stage(&mut interface);
+    interface.apply().unwrap();
+
+    drop(interface);
+    device.parser().screen().contents()
+}
+
+#[test]
+fn erasing_the_halves_of_replaced_wide_characters() {
+    // The wide characters are never rendered, but the cells they covered are left empty
+    let replaced = render_restaged(|interface| {
+        interface.set(pos!(1, 0), "漢");
+        interface.set(pos!(1, 0), "x");
+    });
+    assert_eq!("ax d", &replaced);
+
+    let cleared = render_restaged(|interface| {
+        interface.set(pos!(1, 0), "漢");
+        interface.clear_rect(Rect::new(pos!(1, 0), Vector::new(1, 1)));
+    });
+    assert_eq!("a  d", &cleared);
+
+    let covered = render_restaged(|interface| {
+        interface.set(pos!(2, 0), "漢");
+        interface.set(pos!(1, 0), "字");
+    });
+    assert_eq!("a字 ", &covered);
+}
+
+#[test]
+fn moving_the_cursor_after_shrinking() {
+    let device = SharedDevice::default();
+    device.device().resize(Vector::new(10, 3));
+
+    let mut interface_device = device.clone();
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+    interface.read_event().unwrap();
+    interface.set(pos!(0, 0), "abcdefghij");
+    interface.apply().unwrap();
+
+    // The terminal keeps its cursor on the screen, so moves start from its last column
+    device.device().resize(Vector::new(6, 3));
+    interface.read_event().unwrap();
+    interface.set(pos!(3, 0), "X");
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!("abcXef", &device.device().parser().screen().contents());
+}
+
+#[test]
+fn redrawing_cells_revealed_by_widening() {
+    let device = SharedDevice::default();
+    device.device().resize(Vector::new(10, 3));
+
+    let mut interface_device = device.clone();
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+    interface.read_event().unwrap();
+    interface.set(pos!(0, 0), "abcdefghij");
+    interface.apply().unwrap();
+
+    // Shrinking the terminal discards the columns beyond it, which widening it again reveals
+    device.device().resize(Vector::new(6, 3));
+    interface.read_event().unwrap();
+    device.device().resize(Vector::new(10, 3));
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!("abcdefghij", &device.device().parser().screen().contents());
+}
+
+#[test]
+fn moving_the_cursor_out_of_wide_characters() {
+    let device = SharedDevice::default();
+    device.device().resize(Vector::new(20, 3));
+
+    let mut interface_device = device.clone();
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+    interface.read_event().unwrap();
+    interface.set(pos!(7, 0), "👍a");
+    interface.apply().unwrap();
+
+    // Shrinking the terminal leaves its cursor within the wide character, which mustn't be
+    // reprinted from there when moving to the revealed character
+    device.device().resize(Vector::new(9, 3));
+    interface.read_event().unwrap();
+    device.device().resize(Vector::new(20, 3));
+    interface.read_event().unwrap();
+    interface.apply().unwrap();
+
+    drop(interface);
+    assert_eq!("       👍a", &device.device().parser().screen().contents());
+}
+
+#[test]
+fn writing_into_the_last_column_of_a_relative_interface() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(8, 3));
+    device.parser().process(b"\n\n$ ");
+
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    interface.set(pos!(0, 0), "123456");
+    interface.apply().unwrap();
+
+    drop(interface);
+    device.parser().process(b"*");
+    assert_eq!("\n\n$ 12345*", &device.parser().screen().contents());
+}
+
+#[test]
+fn reserving_lines_for_a_relative_interface() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 6));
+    device.parser().process(b"prompt\r\n\r\n\r\n\r\n$ ");
+
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    interface.reserve_lines(4).unwrap();
+    assert_eq!(pos!(2, 2), interface.origin());
+
+    // Lines already available needn't be reserved
+    interface.reserve_lines(3).unwrap();
+    assert_eq!(pos!(2, 2), interface.origin());
+
+    for line in 0..4 {
+        interface.set(pos!(0, line), &format!("Line {}", line));
+    }
+    interface.apply().unwrap();
+    assert_eq!(pos!(2, 2), interface.origin());
+    assert_eq!(0, interface.scrolled_lines());
+
+    drop(interface);
+    assert_eq!(
+        "\n\n$ Line 0\n  Line 1\n  Line 2\n  Line 3",
+        &device.parser().screen().contents()
+    );
+}
+
+#[test]
+fn finishing_with_plain_output() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    interface.set_styled(pos!(0, 0), "Done", Color::Green.as_style());
+    interface.set(pos!(2, 2), "日本 ok");
+    interface.apply().unwrap();
+
+    // Staged but unapplied changes aren't printed
+    interface.set(pos!(0, 3), "pending");
+    interface.finish_with_plain_output().unwrap();
+
+    let output = String::from_utf8(device.into_inner()).unwrap();
+    let (_, plain) = output.rsplit_once("\x1B[?1049l").unwrap();
+    assert_eq!("Done\n\n  日本 ok\n", plain);
+}
+
+#[test]
+fn finishing_relative_interfaces_with_plain_output() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+
+    interface.set_styled(pos!(0, 0), "Line 1", Color::Red.as_style());
+    interface.set_styled(pos!(0, 1), "Line 2", Color::Blue.as_style());
+    interface.apply().unwrap();
+    interface.finish_with_plain_output().unwrap();
+
+    let output = String::from_utf8(device.into_inner()).unwrap();
+    assert!(output.ends_with("\x1B[1ALine 1\nLine 2\n"), "{:?}", output);
+}
+
+#[test]
+fn printing_lines_to_non_terminals() {
+    let mut device = VirtualDevice::new();
+    device.set_terminal(false);
+    device.set_size_reported(false);
+    device.set_cursor_reported(false);
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    assert_eq!(Mode::LinePrinter, interface.mode());
+    assert_eq!(Vector::new(80, 24), interface.size());
+
+    interface.set(pos!(0, 0), "Downloading...");
+    interface.set_styled(pos!(0, 1), "0%", Color::Red.as_style());
+    interface.apply().unwrap();
+
+    // Only lines with changed text are printed again
+    interface.set_styled(pos!(0, 0), "Downloading...", Color::Blue.as_style());
+    interface.set(pos!(0, 1), "50%");
+    interface.apply().unwrap();
+
+    interface.clear_line(1);
+    interface.set(pos!(0, 2), "Done");
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    let output = String::from_utf8(device.output().to_vec()).unwrap();
+    assert_eq!("Downloading...\n0%\n50%\nDone\n", output);
+}
+
+#[test]
+fn printing_lines_when_requested() {
+    let mut device = WriterDevice::new(Vec::new(), Vector::new(20, 4));
+    let mut interface = Interface::builder()
+        .line_printer()
+        .mouse(true)
+        .cursor_mode(CursorMode::Visible)
+        .build(&mut device)
+        .unwrap();
+    assert_eq!(Mode::LinePrinter, interface.mode());
+
+    interface.set(pos!(2, 0), "Indented");
+    interface.apply().unwrap();
+    interface.finish_with_plain_output().unwrap();
+
+    assert_eq!(b"  Indented\n", device.into_inner().as_slice());
+}
+
+#[test]
+fn printing_styled_lines_when_forced() {
+    let mut device = VirtualDevice::new();
+    device.set_terminal(false);
+    device.set_size_reported(false);
+    device.set_cursor_reported(false);
+    device.set_styling_preference(Some(true));
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    assert!(interface.is_styling_enabled());
+
+    interface.set_styled(pos!(0, 0), "Error", Color::Red.as_style());
+    interface.set(pos!(6, 0), "ok");
+    interface.apply().unwrap();
+    interface.exit().unwrap();
+
+    let output = String::from_utf8(device.output().to_vec()).unwrap();
+    assert_eq!("\x1B[91mError\x1B[0m ok\n", output);
+}
+
+#[test]
+fn honoring_styling_preferences() {
+    let mut device = VirtualDevice::new();
+    device.set_terminal(false);
+    device.set_styling_preference(Some(false));
+    let mut interface = Interface::builder()
+        .assume_terminal(true)
+        .build(&mut device)
+        .unwrap();
+    assert_eq!(Mode::Alternate, interface.mode());
+    assert!(!interface.is_styling_enabled());
+
+    interface.set_styled(pos!(0, 0), "Error", Color::Red.as_style());
+    interface.apply().unwrap();
+    drop(interface);
+
+    assert_eq!("Error", device.parser().screen().contents());
+    assert_eq!(
+        vt100::Color::Default,
+        device.parser().screen().cell(0, 0).unwrap().fgcolor()
+    );
+
+    // The builder overrides the device's preference, and styles are kept while disabled
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::builder()
+        .styling(false)
+        .build(&mut device)
+        .unwrap();
+    interface.set_styled(pos!(0, 0), "Error", Color::Red.as_style());
+    interface.apply().unwrap();
+    interface.set_styling_enabled(true);
+    interface.apply().unwrap();
+
+    drop(interface);
+    let cell = device.parser().screen().cell(0, 0).unwrap().clone();
+    assert_eq!(vt100::Color::Idx(9), cell.fgcolor());
+}
+
+#[test]
+fn sharing_a_device_between_interfaces() {
+    let device = SharedDevice::default();
+    let mut arbiter_device = device.clone();
+    let arbiter = DeviceArbiter::new(&mut arbiter_device).unwrap();
+
+    let mut content_device = arbiter.claim(0..3).unwrap();
+    let mut footer_device = arbiter.claim(3..4).unwrap();
+    assert!(arbiter.claim(2..5).is_none());
+    assert!(arbiter.claim(4..4).is_none());
+    assert_eq!(vec![0..3, 3..4], arbiter.claimed_rows());
+    assert_eq!(
+        Vector::new(80, 3),
+        content_device.get_terminal_size().unwrap()
+    );
+
+    let mut content = Interface::new_alternate(&mut content_device).unwrap();
+    let mut footer = Interface::new_alternate(&mut footer_device).unwrap();
+
+    content.set(pos!(0, 0), "first");
+    content.set(pos!(0, 4), "clipped");
+    content.apply().unwrap();
+    footer.set(pos!(0, 0), "status");
+    footer.apply().unwrap();
+    content.set(pos!(5, 0), " line");
+    content.set(pos!(0, 2), "third");
+    content.apply().unwrap();
+
+    // Leaving the alternate screen waits for the last interface
+    footer.exit().unwrap();
+    drop(footer_device);
+    assert_eq!(vec![0..3], arbiter.claimed_rows());
+    content.set(pos!(0, 1), "second");
+    content.apply().unwrap();
+
+    drop(content);
+
+    // The interface was dropped without exiting, leaving its device's configuration in place
+    {
+        let mut device = device.device();
+        assert!(device.is_raw_mode_enabled());
+        let screen = device.parser().screen();
+        assert!(screen.alternate_screen());
+        let rows: Vec<String> = screen.rows(0, 80).take(5).collect();
+        assert_eq!(vec!["first line", "second", "third", "status", ""], rows);
+    }
+
+    // Releasing the last claim restores the terminal
+    drop(content_device);
+    assert!(!device.device().is_raw_mode_enabled());
+    assert!(!device.device().parser().screen().alternate_screen());
+    drop(arbiter);
+}
+
+#[test]
+fn scrolling_relative_interfaces_within_their_claims() {
+    let device = SharedDevice::default();
+    let mut arbiter_device = device.clone();
+    let arbiter = DeviceArbiter::new(&mut arbiter_device).unwrap();
+
+    let mut content_device = arbiter.claim(0..20).unwrap();
+    let mut footer_device = arbiter.claim(20..24).unwrap();
+
+    let mut content = Interface::new_relative(&mut content_device).unwrap();
+    let mut footer = Interface::new_alternate(&mut footer_device).unwrap();
+
+    footer.set(pos!(0, 0), "status");
+    footer.set(pos!(0, 3), "help");
+    footer.apply().unwrap();
+
+    content.set(pos!(0, 0), "progress");
+    content.apply().unwrap();
+    for line in 0..30 {
+        writeln!(content.writer(), "line {}", line).unwrap();
+    }
+    content.apply().unwrap();
+
+    // The content scrolled within its rows, leaving the footer in place
+    let rows: Vec<String> = {
+        let mut device = device.device();
+        device.parser().screen().rows(0, 80).collect()
+    };
+    assert_eq!("line 11", rows[0]);
+    assert_eq!("line 29", rows[18]);
+    assert_eq!("progress", rows[19]);
+    assert_eq!(vec!["status", "", "", "help"], rows[20..24]);
+
+    drop(content);
+    drop(content_device);
+
+    // Scrolling spans the screen again once the claimant's released
+    device.device().write_all(b"\x1B[24;1H\n").unwrap();
+    let rows: Vec<String> = {
+        let mut device = device.device();
+        device.parser().screen().rows(0, 80).collect()
+    };
+    assert_eq!(vec!["status", "", "", "help", ""], rows[19..24]);
+
+    drop(footer);
+    drop(footer_device);
+    drop(arbiter);
+}
+
+#[test]
+fn writing_output_above_relative_interfaces() {
+    // Devices which can't report their cursor have it tracked through the printed lines
+    let cases = [(false, true), (true, true), (false, false), (true, false)];
+    for (is_applied, reports_cursor) in cases {
+        let mut device = VirtualDevice::new();
+        device.resize(Vector::new(20, 4));
+        device.set_cursor_reported(reports_cursor);
+
+        let mut interface = Interface::new_relative(&mut device).unwrap();
+        interface.set(pos!(0, 0), "[==  ]");
+        interface.set(pos!(0, 1), "building");
+        interface.apply().unwrap();
+
+        write!(interface.writer(), "first\nsec").unwrap();
+        interface.set(pos!(0, 0), "[=== ]");
+        write!(interface.writer(), "ond\nthird\nfourth\nfif").unwrap();
+        assert_eq!(pos!(0, 2), interface.origin());
+
+        // Staged changes remain pending until applied, and incomplete lines until exiting
+        if !is_applied {
+            drop(interface);
+            let rows: Vec<String> = device.parser().screen().rows(0, 20).collect();
+            assert_eq!(vec!["third", "fourth", "[==  ]", "building"], rows);
+            continue;
+        }
+
+        interface.apply().unwrap();
+        interface.exit().unwrap();
+
+        let rows: Vec<String> = device.parser().screen().rows(0, 20).collect();
+        assert_eq!(vec!["fourth", "[=== ]", "building", "fif"], rows);
+    }
+}
+
+#[test]
+fn writing_output_after_alternate_interfaces() {
+    let mut device = VirtualDevice::new();
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set(pos!(0, 0), "Working...");
+    interface.apply().unwrap();
+
+    writeln!(interface.writer(), "Done").unwrap();
+    interface.exit().unwrap();
+
+    let screen = device.parser().screen();
+    assert!(!screen.alternate_screen());
+    assert_eq!("Done", screen.contents().trim());
+}
+
+#[cfg(unix)]
+#[test]
+fn piping_child_output_above_relative_interfaces() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(20, 5));
+
+    let mut interface = Interface::new_relative(&mut device).unwrap();
+    interface.set(pos!(0, 0), "[==  ]");
+    interface.apply().unwrap();
+
+    let mut command = std::process::Command::new("sh");
+    command.args(["-c", "echo one; printf two; echo failed >&2; exit 3"]);
+    let status = interface
+        .pipe_child_styled(&mut command, None, Some(Color::Red.as_style()))
+        .unwrap();
+    assert_eq!(Some(3), status.code());
+    drop(interface);
+
+    let screen = device.parser().screen();
+    let mut rows: Vec<String> = screen.rows(0, 20).take(3).collect();
+    let failed_row = rows.iter().position(|row| row == "failed").unwrap();
+    assert_eq!(
+        vt100::Color::Idx(9),
+        screen.cell(failed_row as u16, 0).unwrap().fgcolor()
+    );
+    assert_eq!("[==  ]", screen.rows(0, 20).nth(3).unwrap());
+
+    // The streams are interleaved as they're read
+    rows.sort();
+    assert_eq!(vec!["failed", "one", "two"], rows);
+}
+
+#[test]
+fn running_hooks() {
+    let mut device = VirtualDevice::new();
+    device.resize(Vector::new(40, 10));
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+
+    let mut frames = 0;
+    interface.on_before_apply(move |staging| {
+        frames += 1;
+        let line = staging.size().y() - 1;
+        staging.clear_line(line);
+        staging.set(pos!(0, line), &format!("Frame {}", frames));
+    });
+
+    let stats = Rc::new(Cell::new(None));
+    let last_stats = stats.clone();
+    interface.on_after_apply(move |_, stats| last_stats.set(Some(*stats)));
+
+    let sizes = Rc::new(Cell::new(None));
+    let last_size = sizes.clone();
+    interface.on_resize(move |staging, size| {
+        last_size.set(Some(size));
+        staging.set(pos!(0, 0), "Resized");
+    });
+
+    interface.set(pos!(0, 0), "Body");
+    interface.apply().unwrap();
+    let frame = stats.get().unwrap();
+    assert_eq!(11, frame.cells());
+    assert_eq!(0, frame.deferred_cells());
+    assert!(frame.bytes() > 11);
+
+    interface.read_event().unwrap();
+    assert_eq!(Some(Vector::new(40, 10)), sizes.get());
+    interface.apply().unwrap();
+
+    // Hooks no longer run once cleared
+    interface.clear_hooks();
+    interface.apply().unwrap();
+
+    drop(interface);
+    let screen = device.parser().screen();
+    let rows: Vec<String> = screen.rows(0, 80).collect();
+    assert_eq!("Resized", rows[0]);
+    assert_eq!("Frame 2", rows[9]);
+}
+
+#[test]
+fn serving_telnet_clients() {
+    let input = [
+        &[255, 251, 31, 255, 250, 31, 0, 40, 0, 10, 255, 240][..],
+        b"q\x1B[2;3R",
+    ]
+    .concat();
+    let mut device = TelnetDevice::new(std::io::Cursor::new(input), Vec::new()).unwrap();
+    assert!(device.wait_for_size(Duration::from_secs(5)).unwrap());
+    assert_eq!(Vector::new(40, 10), device.get_terminal_size().unwrap());
+    assert_eq!(pos!(2, 1), device.get_cursor_position().unwrap());
+
+    let mut interface = Interface::new_alternate(&mut device).unwrap();
+    interface.set(pos!(0, 0), "Hello");
+    interface.apply().unwrap();
+
+    assert_eq!(
+        Event::Resize(Vector::new(40, 10)),
+        interface.read_event().unwrap()
+    );
+    let key = KeyEvent::new(KeyCode::Char('q'), Modifiers::new());
+    assert_eq!(Event::Key(key), interface.read_event().unwrap());
+    interface.exit().unwrap();
+
+    let output = device.get_ref();
+    assert!(output.starts_with(&[255, 253, 31]));
+    assert!(output.windows(3).any(|window| window == [255, 251, 1]));
+    assert!(output.windows(3).any(|window| window == [255, 252, 1]));
+}
+
+#[test]
+fn closing_telnet_connections_on_drop() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (stream, _) = listener.accept().unwrap();
+
+    let device = TelnetDevice::from_stream(stream).unwrap();
+    let mut negotiation = [0; 3];
+    std::io::Read::read_exact(&mut client, &mut negotiation).unwrap();
+    assert_eq!([255, 253, 31], negotiation);
+
+    // Dropping the device stops its reader and closes the connection without the client's input
+    drop(device);
+    let mut rest = Vec::new();
+    std::io::Read::read_to_end(&mut client, &mut rest).unwrap();
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn broadcasting_to_several_devices() {
+    let mut device = VirtualDevice::new();
+    let mut observer_device = VirtualDevice::new();
+    observer_device.push_event(Event::Resize(Vector::new(20, 5)));
+    let mut late_device = VirtualDevice::new();
+
+    let mut broadcaster = Broadcaster::new();
+    broadcaster.add(Interface::new_alternate(&mut device).unwrap());
+    let observer = broadcaster.add(Interface::new_alternate(&mut observer_device).unwrap());
+    broadcaster
+        .interface_mut(observer)
+        .unwrap()
+        .read_event()
+        .unwrap();
+
+    broadcaster.set(pos!(0, 0), "Hello");
+    broadcaster.set_styled(pos!(30, 10), "far", Color::Red.as_style());
+    broadcaster.apply().unwrap();
+
+    // Devices joining later are given the whole frame along with its changes
+    broadcaster.add(Interface::new_alternate(&mut late_device).unwrap());
+    broadcaster.set(pos!(0, 1), "world");
+    broadcaster.apply().unwrap();
+    assert_eq!(3, broadcaster.interface_count());
+
+    assert!(broadcaster.remove(observer).is_some());
+    assert!(broadcaster.remove(2).is_none());
+    drop(broadcaster);
+
+    for device in [&mut device, &mut late_device] {
+        let screen = device.parser().screen();
+        let rows: Vec<String> = screen.rows(0, 80).collect();
+        assert_eq!("Hello", rows[0]);
+        assert_eq!("world", rows[1]);
+        assert_eq!(format!("{}far", " ".repeat(30)), rows[10]);
+        assert_eq!(vt100::Color::Idx(9), screen.cell(10, 30).unwrap().fgcolor());
+    }
+
+    // The observer's smaller screen is given only the frame's content within it
+    let contents = observer_device.parser().screen().contents();
+    assert!(contents.contains("Hello\nworld"));
+    assert!(!contents.contains("far"));
+}
+
+#[test]
+fn broadcasting_wide_characters_to_narrower_devices() {
+    let mut device = VirtualDevice::new();
+    let mut observer_device = VirtualDevice::new();
+    observer_device.push_event(Event::Resize(Vector::new(20, 5)));
+
+    let mut broadcaster = Broadcaster::new();
+    broadcaster.add(Interface::new_alternate(&mut device).unwrap());
+    let observer = broadcaster.add(Interface::new_alternate(&mut observer_device).unwrap());
+    broadcaster
+        .interface_mut(observer)
+        .unwrap()
+        .read_event()
+        .unwrap();
+
+    // The wide character straddles the observer's last column, so it's blanked there
+    broadcaster.set(pos!(17, 0), "ab漢c");
+    broadcaster.apply().unwrap();
+    drop(broadcaster);
+
+    let rows: Vec<String> = device.parser().screen().rows(0, 80).collect();
+    assert_eq!(format!("{}ab漢c", " ".repeat(17)), rows[0]);
+
+    let rows: Vec<String> = observer_device.parser().screen().rows(0, 20).collect();
+    assert_eq!(format!("{}ab ", " ".repeat(17)), rows[0]);
+    assert_eq!("", rows[1]);
+}
+
+#[test]
+fn recording_and_replaying_events() {
+    let mut device = VirtualDevice::new();
+    device.push_event(Event::Key(KeyEvent::new(
+        KeyCode::Char('j'),
+        Modifiers::new(),
+    )));
+    device.push_event(Event::Resize(Vector::new(60, 20)));
+    device.push_event(Event::Key(KeyEvent::new(KeyCode::Enter, Modifiers::new())));
+
+    // An application reading until enter is pressed, polling between keys
+    fn run(interface: &mut Interface) -> Vec<Option<Event>> {
+        let mut events = Vec::new();
+        loop {
+            let event = interface.poll_event(Duration::from_millis(1)).unwrap();
+            events.push(event.clone());
+            if let Some(Event::Key(key)) = event {
+                interface.set(pos!(0, 0), &format!("{:?}", key.code()));
+                interface.apply().unwrap();
+                if key.code() == KeyCode::Enter {
+                    return events;
+                }
+            }
+        }
+    }
+
+    let mut recording = Vec::new();
+    let mut recorder = EventRecorder::new(&mut device, Box::new(&mut recording));
+    let mut interface = Interface::new_alternate(&mut recorder).unwrap();
+    let recorded_events = run(&mut interface);
+    interface.exit().unwrap();
+    drop(recorder);
+
+    let recording = String::from_utf8(recording).unwrap();
+    let kinds: Vec<&str> = recording
+        .lines()
+        .map(|line| line.split_once(' ').unwrap().1)
+        .collect();
+    assert_eq!(vec!["key j", "resize 60x20", "key enter"], kinds);
+
+    let mut replay_device = VirtualDevice::new();
+    let mut replayer = EventReplayer::new(&mut replay_device, recording.as_bytes()).unwrap();
+    let mut interface = Interface::new_alternate(&mut replayer).unwrap();
+    assert_eq!(recorded_events, run(&mut interface));
+    assert_eq!(Vector::new(60, 20), interface.size());
+    drop(interface);
+    assert_eq!(0, replayer.remaining());
+    assert!(replayer.read_event(None).is_err());
+
+    let invalid = "0.1 key j\n0.2 key hyper+j\n";
+    let error = EventReplayer::new(&mut replay_device, invalid.as_bytes()).err();
+    assert!(error.unwrap().to_string().contains("line 2"));
+}
+
+#[test]
+fn checking_random_operations() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use tty_interface::test::{check_operations, Operation};
+
+    let mut rng = StdRng::seed_from_u64(3663);
+    for _ in 0..300 {
+        let length = rng.gen_range(0..200);
+        let data: Vec<u8> = (0..length).map(|_| rng.gen()).collect();
+        let operations = Operation::decode(&data);
+        for mode in [Mode::Alternate, Mode::Relative] {
+            if let Err(violation) = check_operations(mode, &operations) {
+                panic!("{} in {:?} mode for {:?}", violation, mode, operations);
+            }
+        }
+    }
+}
+
+#[test]
+fn verifying_virtual_screens() {
+    let device = SharedDevice::default();
+    let mut interface_device = device.clone();
+
+    let mut interface = Interface::new_alternate(&mut interface_device).unwrap();
+    interface.set(pos!(0, 0), "漢字 wide");
+    interface.set_styled(pos!(0, 1), "styled", Style::new().set_bold(true));
+    interface.apply().unwrap();
+    verify_screen(&mut interface, &device).unwrap();
+
+    // Narrowing wide cells must clear their second halves
+    interface.set(pos!(0, 0), "ab");
+    interface.set(pos!(3, 0), "c");
+    interface.clear_line(1);
+    interface.apply().unwrap();
+    verify_screen(&mut interface, &device).unwrap();
+
+    // Output the interface didn't render is reported where it diverges
+    device.device().parser().process(b"\x1B[2;3Hx");
+    let divergence = verify_screen(&mut interface, &device).unwrap_err();
+    assert!(divergence.contains("Position(2, 1)"), "{}", divergence);
+}